@@ -0,0 +1,59 @@
+use super::{Announcement, Announcer};
+use crate::x::client::TweetOptions;
+use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+
+/// Announces on the console instead of X: every post is pretty-printed to stdout and logged,
+/// without calling the X API, so no credentials are needed.
+///
+/// It stands in for X, so the posts it prints are replied to and corrected like those made on X.
+#[derive(Default)]
+pub struct ConsoleAnnouncer {
+    posts: AtomicU64,
+}
+
+impl ConsoleAnnouncer {
+    /// Creates a new instance of `ConsoleAnnouncer`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pretty-prints what would be done on X and returns a placeholder ID.
+    ///
+    /// # Arguments
+    /// * `action` - What would be done, e.g. `post` or `delete`.
+    /// * `text` - The text posted, or what the action applies to.
+    pub fn print(&self, action: &str, text: &str) -> String {
+        let id = format!("console-{}", self.posts.fetch_add(1, Ordering::Relaxed) + 1);
+        info!("Console announcer, {} ({}): {:?}", action, id, text);
+        let header = format!("── {} ({}, {} characters) ", action, id, text.chars().count());
+        println!("┌{}", header);
+        for line in text.lines() {
+            println!("│ {}", line);
+        }
+        println!("└{}\n", "─".repeat(header.chars().count()));
+        id
+    }
+}
+
+#[async_trait]
+impl Announcer for ConsoleAnnouncer {
+    fn name(&self) -> &str {
+        "console"
+    }
+
+    fn sink(&self) -> &str {
+        "x"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        announcement.awaits("x")
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        let options = TweetOptions::of(announcement)?;
+        Ok(Some(self.print(&options.action(), announcement.text_for("x"))))
+    }
+}
@@ -0,0 +1,183 @@
+use super::{Announcement, Announcer};
+use crate::audit::log::AuditLog;
+use std::sync::Arc;
+use tokio::{
+    sync::broadcast::error::RecvError,
+    task::JoinSet,
+    time::{sleep, Duration}};
+use anyhow::{anyhow, Result};
+use tracing::{debug, error, info, warn};
+
+/// Attempts at making an announcement on a target before dropping it, by default
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Outcome of an announcement on one target
+#[derive(Debug)]
+pub struct Delivery {
+    /// Name of the target
+    pub target: String,
+    /// Name the post is recorded under, see [Announcer::sink]
+    pub sink: String,
+    /// Attempts made, the last one succeeding unless `result` is an error
+    pub attempts: u32,
+    /// ID of the post made, if the target returns one
+    pub result: Result<Option<String>>,
+}
+
+/// Outcome of an announcement on every target accepting it, in the order the targets were added
+#[derive(Debug, Default)]
+pub struct DispatchReport {
+    pub deliveries: Vec<Delivery>,
+}
+
+impl DispatchReport {
+    /// Returns the deliveries that failed after every attempt
+    pub fn failed(&self) -> impl Iterator<Item = &Delivery> {
+        self.deliveries.iter().filter(|delivery| delivery.result.is_err())
+    }
+}
+
+/// Hands announcements to every target accepting them: X or the console for the announcements
+/// queued in the [Outbox](crate::x::outbox::Outbox), then Matrix, Teams, IRC, NATS and the
+/// outbound webhooks for those made on X.
+///
+/// Each announcement is handed to all the targets accepting it at once, and waits for them
+/// before the next one so they keep the order of the announcements. A failing target is
+/// retried with a growing delay without holding up the others, and the results are logged
+/// together once every target is done.
+pub struct Dispatcher {
+    targets: Vec<Arc<dyn Announcer>>,
+    max_attempts: u32,
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            max_attempts: MAX_ATTEMPTS,
+        }
+    }
+}
+
+impl Dispatcher {
+    /// Creates a dispatcher without targets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tries each target the given number of times, such as once for targets retrying on their own.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Adds a target the announcements are handed to.
+    pub fn add(&mut self, target: impl Announcer + 'static) {
+        self.targets.push(Arc::new(target));
+    }
+
+    /// Adds a target that is also used elsewhere, such as the X client.
+    pub fn add_shared(&mut self, target: Arc<dyn Announcer>) {
+        self.targets.push(target);
+    }
+
+    /// Returns true if there are no targets to hand the announcements to
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Relays the announcements recorded in the audit log from now on, until it's dropped.
+    ///
+    /// The entries missed while relaying fell behind are read back from the audit log, so
+    /// every announcement is relayed in order however long the targets take.
+    ///
+    /// # Arguments
+    /// * `audit_log` - The audit log the announcements are recorded in.
+    pub async fn run(self, audit_log: Arc<AuditLog>) {
+        let names: Vec<&str> = self.targets.iter().map(|target| target.name()).collect();
+        info!("Relaying announcements to {}", names.join(", "));
+        let (mut position, mut recorded) = audit_log.subscribe_at().await;
+        loop {
+            let announcements = match recorded.recv().await {
+                Ok(announcement) => vec![announcement],
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Relaying announcements fell behind by {} entries, reading them from the audit log", missed);
+                    audit_log.recorded_from(position, missed as usize).await
+                }
+                Err(RecvError::Closed) => return,
+            };
+            position += announcements.len();
+            for announcement in announcements {
+                self.relay(announcement).await;
+            }
+        }
+    }
+
+    /// Relays an announcement and logs the outcome
+    async fn relay(&self, announcement: Announcement) {
+        let report = self.dispatch(announcement.clone()).await;
+        if report.deliveries.is_empty() {
+            return;
+        }
+        for delivery in report.failed() {
+            if let Err(e) = &delivery.result {
+                error!("Announcement {} dropped, {} is unavailable: {:?}", announcement.idempotency_key, delivery.target, e);
+            }
+        }
+        debug!(
+            "Relayed {} announcement {} to {}/{} targets",
+            announcement.status.as_str(),
+            announcement.idempotency_key,
+            report.deliveries.len() - report.failed().count(),
+            report.deliveries.len(),
+        );
+    }
+
+    /// Hands an announcement to every target accepting it at once.
+    ///
+    /// # Returns
+    /// The outcome on each of these targets, once they all succeeded or ran out of attempts.
+    pub async fn dispatch(&self, announcement: Announcement) -> DispatchReport {
+        let announcement = Arc::new(announcement);
+        let mut deliveries = JoinSet::new();
+        for (index, target) in self.targets.iter().enumerate() {
+            if !target.accepts(&announcement) {
+                continue;
+            }
+            let target = Arc::clone(target);
+            let announcement = Arc::clone(&announcement);
+            let max_attempts = self.max_attempts;
+            deliveries.spawn(async move { (index, deliver(target.as_ref(), &announcement, max_attempts).await) });
+        }
+
+        let mut report = Vec::new();
+        while let Some(delivery) = deliveries.join_next().await {
+            match delivery {
+                Ok(delivery) => report.push(delivery),
+                Err(e) => error!("Handing announcement {} to a target panicked: {:?}", announcement.idempotency_key, e),
+            }
+        }
+        report.sort_by_key(|(index, _)| *index);
+        DispatchReport { deliveries: report.into_iter().map(|(_, delivery)| delivery).collect() }
+    }
+}
+
+/// Hands an announcement to a target, retrying with a growing delay while it fails
+async fn deliver(target: &dyn Announcer, announcement: &Announcement, max_attempts: u32) -> Delivery {
+    let mut result = Err(anyhow!("No attempt made"));
+    let mut attempts = 0;
+    while attempts < max_attempts {
+        attempts += 1;
+        result = target.announce(announcement).await;
+        match &result {
+            Ok(_) => break,
+            Err(e) => {
+                warn!("Failed to announce on {} (attempt {}/{}): {:?}", target.name(), attempts, max_attempts, e);
+                if attempts < max_attempts {
+                    sleep(Duration::from_secs(attempts as u64 * 2)).await;
+                }
+            }
+        }
+    }
+    Delivery { target: target.name().to_string(), sink: target.sink().to_string(), attempts, result }
+}
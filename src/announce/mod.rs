@@ -0,0 +1,44 @@
+pub mod console;
+pub mod dispatcher;
+
+use crate::audit::log::AuditEntry;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// An announcement, as recorded in the audit log
+pub type Announcement = AuditEntry;
+
+/// A target announcements are made on, such as X, the console, a chat room or a message broker.
+///
+/// Targets are driven by a [Dispatcher](dispatcher::Dispatcher), which hands every announcement
+/// to all of them at once and retries the ones that fail, so a target only has to know how to
+/// announce once. The announcements queued in the audit log are posted on the targets of the
+/// [Outbox](crate::x::outbox::Outbox), then relayed to the other targets once recorded as posted.
+#[async_trait]
+pub trait Announcer: Send + Sync {
+    /// Name of the target in the logs, e.g. `matrix`
+    fn name(&self) -> &str;
+
+    /// Name the posts made on the target are recorded under in the audit log, e.g. `x`.
+    ///
+    /// The name of the target, unless it stands in for another one.
+    fn sink(&self) -> &str {
+        self.name()
+    }
+
+    /// Returns true if the announcement is made on the target.
+    ///
+    /// # Arguments
+    /// * `announcement` - An entry recorded in the audit log, of any status.
+    fn accepts(&self, announcement: &Announcement) -> bool;
+
+    /// Makes an announcement on the target.
+    ///
+    /// # Arguments
+    /// * `announcement` - An announcement the target accepts.
+    ///
+    /// # Returns
+    /// A result containing the ID of the post made, for the targets whose posts are replied to
+    /// or edited later, or an error if the target is unavailable, in which case it is tried again.
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>>;
+}
@@ -0,0 +1,95 @@
+use super::log::AuditEntry;
+use crate::x::client::{Engagement, XClient};
+use std::collections::HashMap;
+use tracing::warn;
+
+const CSV_HEADER: &str = "timestamp,kind,status,repo,source_id,subject,text,x_post_id,likes,reposts,replies,quotes,impressions";
+
+/// Looks up the engagement of the posts of the given entries on X.
+///
+/// Exports go on without engagement if the lookup fails, so a failure is only logged.
+///
+/// # Returns
+/// The engagement of every post still available, keyed by post ID.
+pub async fn engagement(entries: &[AuditEntry], x_client: &XClient) -> HashMap<String, Engagement> {
+    let mut tweet_ids: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| entry.remote_ids.get("x").cloned())
+        .collect();
+    tweet_ids.sort();
+    tweet_ids.dedup();
+
+    match x_client.engagement(&tweet_ids).await {
+        Ok(engagement) => engagement,
+        Err(e) => {
+            warn!("Could not look up the engagement of the announcements: {:?}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Renders entries as CSV, one row per entry with the engagement of its post on X.
+///
+/// # Arguments
+/// * `entries` - The entries to export.
+/// * `engagement` - The engagement of the posts, keyed by post ID. The engagement columns
+///   of entries without any are left empty.
+///
+/// # Returns
+/// The CSV document, header included.
+pub fn to_csv(entries: &[AuditEntry], engagement: &HashMap<String, Engagement>) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push_str("\r\n");
+
+    for entry in entries {
+        let post_id = entry.remote_ids.get("x");
+        let counts = match post_id.and_then(|id| engagement.get(id)) {
+            Some(counts) => [
+                counts.like_count,
+                counts.retweet_count,
+                counts.reply_count,
+                counts.quote_count,
+                counts.impression_count,
+            ]
+            .map(|count| count.to_string()),
+            None => Default::default(),
+        };
+
+        let fields = [
+            entry.timestamp.to_rfc3339(),
+            entry.kind.as_str().to_owned(),
+            entry.status.as_str().to_owned(),
+            entry.repo.clone(),
+            entry.source_id.clone(),
+            entry.subject.clone().unwrap_or_default(),
+            entry.text.clone(),
+            post_id.cloned().unwrap_or_default(),
+        ];
+        let row = fields
+            .iter()
+            .chain(counts.iter())
+            .map(|field| escape(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&row);
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Quotes a CSV field when it holds a separator, a quote or a line break.
+///
+/// Fields a spreadsheet would run as a formula, such as a commit message starting
+/// with `=`, are prefixed with a quote so they are shown as text.
+fn escape(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", field)
+    } else {
+        field.to_owned()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
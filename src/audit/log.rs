@@ -0,0 +1,925 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex};
+use tokio::{
+    fs::OpenOptions,
+    io::AsyncWriteExt,
+    sync::{broadcast, Notify, RwLock},
+    time::{timeout_at, Duration, Instant}};
+use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+use crate::webhook::logging::current_request_id;
+
+/// The kind of announcement an audit entry refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementKind {
+    Release,
+    Contributor,
+    MilestoneTeaser,
+    ProjectItem,
+    DocsDeploy,
+    WeeklyDigest,
+    IssueMilestone,
+    Member,
+    Stargazer,
+    PublicRepo,
+    StarMilestone,
+    Tag,
+    MonthlyStats,
+    Sponsor,
+    SponsorMilestone,
+    Anniversary,
+    BreakingChange,
+    IssueLabel,
+    ContributorMilestone,
+    DownloadMilestone,
+    LongAwaitedFix,
+    ContainerImage,
+    FeedEntry,
+    PackageRelease,
+}
+
+impl AnnouncementKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnnouncementKind::Release => "release",
+            AnnouncementKind::Contributor => "contributor",
+            AnnouncementKind::MilestoneTeaser => "milestone_teaser",
+            AnnouncementKind::ProjectItem => "project_item",
+            AnnouncementKind::DocsDeploy => "docs_deploy",
+            AnnouncementKind::WeeklyDigest => "weekly_digest",
+            AnnouncementKind::IssueMilestone => "issue_milestone",
+            AnnouncementKind::Member => "member",
+            AnnouncementKind::Stargazer => "stargazer",
+            AnnouncementKind::PublicRepo => "public_repo",
+            AnnouncementKind::StarMilestone => "star_milestone",
+            AnnouncementKind::Tag => "tag",
+            AnnouncementKind::MonthlyStats => "monthly_stats",
+            AnnouncementKind::Sponsor => "sponsor",
+            AnnouncementKind::SponsorMilestone => "sponsor_milestone",
+            AnnouncementKind::Anniversary => "anniversary",
+            AnnouncementKind::BreakingChange => "breaking_change",
+            AnnouncementKind::IssueLabel => "issue_label",
+            AnnouncementKind::ContributorMilestone => "contributor_milestone",
+            AnnouncementKind::DownloadMilestone => "download_milestone",
+            AnnouncementKind::LongAwaitedFix => "long_awaited_fix",
+            AnnouncementKind::ContainerImage => "container_image",
+            AnnouncementKind::FeedEntry => "feed_entry",
+            AnnouncementKind::PackageRelease => "package_release",
+        }
+    }
+}
+
+impl FromStr for AnnouncementKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.trim().to_lowercase()))
+            .map_err(|_| anyhow::anyhow!("Unknown announcement kind: {}", s))
+    }
+}
+
+/// What happened to an announcement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// The announcement was posted
+    Posted,
+    /// A correction for a previously posted announcement was posted
+    Corrected,
+    /// A follow-up was posted in the thread of an announcement
+    FollowUp,
+    /// Posting the announcement failed
+    Failed,
+    /// The posts of an announcement were deleted because its subject was withdrawn
+    Retracted,
+    /// The announcement was suppressed as a duplicate
+    Skipped,
+    /// The announcement awaits a confirmation before being posted
+    Pending,
+    /// The announcement was edited in place, its new post replacing the one posted before
+    Edited,
+    /// The announcement was being posted. An entry left in this state means the bot stopped
+    /// before learning the outcome, so the announcement is not attempted again.
+    Posting,
+    /// The announcement is waiting in the outbox to be posted. An entry left in this state
+    /// is posted once the bot is back, so the announcement is made at least once.
+    Queued,
+}
+
+impl AuditStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditStatus::Posted => "posted",
+            AuditStatus::Corrected => "corrected",
+            AuditStatus::FollowUp => "follow_up",
+            AuditStatus::Failed => "failed",
+            AuditStatus::Retracted => "retracted",
+            AuditStatus::Skipped => "skipped",
+            AuditStatus::Pending => "pending",
+            AuditStatus::Edited => "edited",
+            AuditStatus::Posting => "posting",
+            AuditStatus::Queued => "queued",
+        }
+    }
+}
+
+/// A single record of the audit log
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub kind: AnnouncementKind,
+    pub status: AuditStatus,
+    /// Repository the announcement is about, as `owner/name`
+    pub repo: String,
+    /// Stable identifier of the announced object (release id, commit SHA)
+    pub source_id: String,
+    /// Deterministic key of the announcement, see [idempotency_key]
+    #[serde(default)]
+    pub idempotency_key: String,
+    /// Human readable name of the announced object, such as a release tag
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Rendered text of the announcement
+    pub text: String,
+    /// Ids of the posts on each sink, keyed by sink name
+    #[serde(default)]
+    pub remote_ids: BTreeMap<String, String>,
+    /// Normalized identities of the announced subject (login, display name) used for deduplication
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identities: Vec<String>,
+    /// Correlation id of the webhook request the entry was recorded for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// How a queued announcement is posted
+    #[serde(default, skip_serializing_if = "PostPlan::is_empty")]
+    pub plan: PostPlan,
+}
+
+/// How the [Outbox](crate::x::outbox::Outbox) posts a queued announcement, kept with it in the
+/// audit log so an announcement queued before a restart is posted the same way after it.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct PostPlan {
+    /// Sinks the announcement is posted on, X alone when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<String>,
+    /// Texts posted on some sinks instead of the text of the entry, keyed by sink name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub texts: BTreeMap<String, String>,
+    /// Post the announcement replies to on X
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<ReplyTo>,
+    /// IDs of the media attached to the post on X
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub media_ids: Vec<String>,
+    /// Status the announcement is recorded with once posted, `posted` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posted_as: Option<AuditStatus>,
+    /// Handle of the X account followed once the announcement is posted, without the `@`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow: Option<String>,
+    /// Attempts at posting the announcement that failed so far
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failures: u32,
+    /// When the announcement is tried again after a failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// The post a queued announcement replies to on X
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyTo {
+    /// A post already made, by ID
+    Post(String),
+    /// The post of an announcement queued before this one, by idempotency key, once it is made
+    Announcement(String),
+}
+
+impl PostPlan {
+    /// Returns true if the announcement is posted on X alone, the way announcements are by default
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns true if the announcement is posted on the sink.
+    pub fn routes_to(&self, sink: &str) -> bool {
+        if self.sinks.is_empty() {
+            sink == "x"
+        } else {
+            self.sinks.iter().any(|routed| routed == sink)
+        }
+    }
+
+    /// Returns the text posted on the sink, if it differs from the text of the entry.
+    pub fn text(&self, sink: &str) -> Option<&str> {
+        self.texts.get(sink).map(String::as_str)
+    }
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+impl AuditEntry {
+    /// Creates a new audit entry timestamped now.
+    pub fn new(
+        kind: AnnouncementKind,
+        status: AuditStatus,
+        repo: &str,
+        source_id: &str,
+        text: &str,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            kind,
+            status,
+            repo: repo.to_owned(),
+            source_id: source_id.to_owned(),
+            idempotency_key: idempotency_key(kind, repo, source_id),
+            subject: None,
+            text: text.to_owned(),
+            remote_ids: BTreeMap::new(),
+            identities: Vec::new(),
+            request_id: current_request_id(),
+            plan: PostPlan::default(),
+        }
+    }
+
+    /// Adds the id of the post created on `sink`.
+    pub fn with_remote_id(mut self, sink: &str, id: &str) -> Self {
+        self.remote_ids.insert(sink.to_owned(), id.to_owned());
+        self
+    }
+
+    /// Sets the human readable name of the announced object.
+    pub fn with_subject(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.to_owned());
+        self
+    }
+
+    /// Sets the normalized identities of the announced subject.
+    pub fn with_identities(mut self, identities: &[String]) -> Self {
+        self.identities = identities.to_vec();
+        self
+    }
+
+    /// Records the entry under the key of a claim, for announcements made about an object
+    /// more than once, see [AuditLog::claim_key].
+    pub fn with_idempotency_key(mut self, key: &str) -> Self {
+        self.idempotency_key = key.to_owned();
+        self
+    }
+
+    /// Sets how the announcement is posted once queued.
+    pub fn with_plan(mut self, plan: PostPlan) -> Self {
+        self.plan = plan;
+        self
+    }
+
+    /// Returns true if the announcement is queued to be posted on the sink and wasn't posted there yet.
+    pub fn awaits(&self, sink: &str) -> bool {
+        self.status == AuditStatus::Queued && self.plan.routes_to(sink) && !self.remote_ids.contains_key(sink)
+    }
+
+    /// Returns the text posted on the sink.
+    pub fn text_for(&self, sink: &str) -> &str {
+        self.plan.text(sink).unwrap_or(&self.text)
+    }
+}
+
+/// Builds the key identifying an announcement, shared by every attempt to make it.
+///
+/// Retries, restarts and redelivered events derive the same key for the same object, so
+/// at most one of them gets to post.
+pub fn idempotency_key(kind: AnnouncementKind, repo: &str, source_id: &str) -> String {
+    format!("{}:{}:{}", repo, kind.as_str(), source_id)
+}
+
+/// Normalizes a login or display name so that the same person is recognized
+/// regardless of case and spacing.
+pub fn normalize_identity(identity: &str) -> String {
+    identity
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Returns the announcements waiting in the outbox, oldest first: those whose latest entry is queued
+fn queued_entries(entries: &[AuditEntry]) -> Vec<AuditEntry> {
+    let mut settled = HashSet::new();
+    let mut queued: Vec<AuditEntry> = entries
+        .iter()
+        .rev()
+        .filter(|entry| settled.insert(&entry.idempotency_key) && entry.status == AuditStatus::Queued)
+        .cloned()
+        .collect();
+    queued.reverse();
+    queued
+}
+
+/// Returns the idempotency keys of the announcements waiting in the outbox
+fn queued_keys(entries: &[AuditEntry]) -> HashSet<String> {
+    queued_entries(entries).into_iter().map(|entry| entry.idempotency_key).collect()
+}
+
+/// Entries a subscriber can fall behind by before missing some
+const RECORDED_CAPACITY: usize = 256;
+
+/// Append-only log of every announcement made by the bot, stored as JSON lines
+pub struct AuditLog {
+    path: PathBuf,
+    entries: RwLock<Vec<AuditEntry>>,
+    // Announcements being made right now, which aren't recorded yet
+    in_flight: Mutex<HashSet<String>>,
+    // Announcements waiting in the outbox, which are being made as well
+    queued: Mutex<HashSet<String>>,
+    // Notified whenever an announcement stops being in flight
+    settled: Notify,
+    // Every entry recorded from now on, for the consumers of the announcements
+    recorded: broadcast::Sender<AuditEntry>,
+    // Whether new entries are written to the file, or only kept in memory
+    persist: bool,
+}
+
+/// Exclusive right to make an announcement, obtained with [AuditLog::claim].
+///
+/// The claim is released when dropped, which must happen after the outcome of the
+/// announcement is recorded.
+pub struct Claim<'a> {
+    log: &'a AuditLog,
+    kind: AnnouncementKind,
+    repo: String,
+    source_id: String,
+    key: String,
+}
+
+impl Claim<'_> {
+    /// The key the announcement is claimed with.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Records that the announcement is about to be posted, right before sending it.
+    ///
+    /// If the bot stops before the outcome is recorded, the announcement is considered
+    /// handled rather than risking a second post.
+    pub async fn begin(&self, text: &str) {
+        let entry = AuditEntry::new(self.kind, AuditStatus::Posting, &self.repo, &self.source_id, text)
+            .with_idempotency_key(&self.key);
+        if let Err(e) = self.log.record(entry).await {
+            warn!("Failed to record announcement {} as posting: {:?}", self.key, e);
+        }
+    }
+
+    /// Queues the announcement in the outbox, to be posted by the [Outbox](crate::x::outbox::Outbox).
+    ///
+    /// The entry is recorded as queued whatever its status, and the claim released once it is:
+    /// the announcement is handled from then on, even if the bot stops before posting it. An
+    /// entry that can't be written is still queued in memory, so an unwritable log never
+    /// prevents the bot from announcing, only from posting it after a restart.
+    pub async fn enqueue(self, entry: AuditEntry) {
+        self.enqueue_thread(entry, Vec::new()).await
+    }
+
+    /// Queues the announcement along with the replies threaded under it, such as the highlights
+    /// of a release, which are queued under keys of their own.
+    ///
+    /// The replies are queued before the claim is released, so nothing else about the object is
+    /// posted between them, and posted once the post they reply to is made, see [ReplyTo::Announcement].
+    pub async fn enqueue_thread(self, entry: AuditEntry, replies: Vec<AuditEntry>) {
+        let entry = entry.with_idempotency_key(&self.key);
+        for mut entry in std::iter::once(entry).chain(replies) {
+            entry.status = AuditStatus::Queued;
+            if let Err(e) = self.log.record(entry.clone()).await {
+                warn!("Failed to write queued announcement {}, keeping it in memory: {:?}", entry.idempotency_key, e);
+                self.log.keep(entry).await;
+            }
+        }
+    }
+}
+
+impl Drop for Claim<'_> {
+    fn drop(&mut self) {
+        self.log.in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+        self.log.settled.notify_waiters();
+    }
+}
+
+impl AuditLog {
+    /// Opens the audit log at `path`, loading any existing entries.
+    ///
+    /// # Arguments
+    /// * `path` - Location of the JSON lines file. It is created on first write.
+    ///
+    /// # Returns
+    /// A result containing the loaded `AuditLog` or an error if the file cannot be read.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = Vec::new();
+
+        match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => {
+                for (number, line) in contents.lines().enumerate() {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<AuditEntry>(line) {
+                        Ok(mut entry) => {
+                            // Entries recorded before idempotency keys existed
+                            if entry.idempotency_key.is_empty() {
+                                entry.idempotency_key = idempotency_key(entry.kind, &entry.repo, &entry.source_id);
+                            }
+                            entries.push(entry)
+                        }
+                        Err(e) => warn!("Skipping malformed audit log line {}: {}", number + 1, e),
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read audit log {}", path.display()));
+            }
+        }
+
+        info!("Audit log {} loaded with {} entries", path.display(), entries.len());
+
+        Ok(Self {
+            path,
+            queued: Mutex::new(queued_keys(&entries)),
+            entries: RwLock::new(entries),
+            in_flight: Mutex::new(HashSet::new()),
+            settled: Notify::new(),
+            recorded: broadcast::channel(RECORDED_CAPACITY).0,
+            persist: true,
+        })
+    }
+
+    /// Keeps the entries recorded from now on in memory only, leaving the file untouched.
+    ///
+    /// Used to simulate announcements against the history without recording them.
+    pub fn in_memory(mut self) -> Self {
+        self.persist = false;
+        self
+    }
+
+    /// Appends an entry to the log and persists it.
+    pub async fn record(&self, entry: AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+
+        let mut entries = self.entries.write().await;
+        if self.persist {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await
+                .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+        }
+
+        // Nobody may be listening, which is fine
+        let _ = self.recorded.send(entry.clone());
+        self.track_queued(&entry);
+        entries.push(entry);
+        Ok(())
+    }
+
+    /// Adds an entry to the entries in memory and the subscribers without writing it.
+    async fn keep(&self, entry: AuditEntry) {
+        let mut entries = self.entries.write().await;
+        let _ = self.recorded.send(entry.clone());
+        self.track_queued(&entry);
+        entries.push(entry);
+    }
+
+    /// Keeps track of the announcements waiting in the outbox as their entries are recorded
+    fn track_queued(&self, entry: &AuditEntry) {
+        let mut queued = self.queued.lock().unwrap_or_else(|e| e.into_inner());
+        if entry.status == AuditStatus::Queued {
+            queued.insert(entry.idempotency_key.clone());
+        } else if queued.remove(&entry.idempotency_key) {
+            self.settled.notify_waiters();
+        }
+    }
+
+    /// Subscribes to the entries recorded from now on.
+    ///
+    /// A subscriber that falls more than a few hundred entries behind misses the oldest ones.
+    pub fn subscribe(&self) -> broadcast::Receiver<AuditEntry> {
+        self.recorded.subscribe()
+    }
+
+    /// Subscribes to the entries recorded from now on, along with the position of the first one.
+    ///
+    /// Entries are never removed from the log, so a subscriber that falls behind can count the
+    /// entries it received and read the ones it missed with [AuditLog::recorded_from].
+    pub async fn subscribe_at(&self) -> (usize, broadcast::Receiver<AuditEntry>) {
+        let entries = self.entries.read().await;
+        (entries.len(), self.recorded.subscribe())
+    }
+
+    /// Returns up to `count` entries, starting from the entry at the given position.
+    pub async fn recorded_from(&self, position: usize, count: usize) -> Vec<AuditEntry> {
+        let entries = self.entries.read().await;
+        entries.iter().skip(position).take(count).cloned().collect()
+    }
+
+    /// Moves every entry recorded for repository `from` to repository `to` and rewrites the log,
+    /// so announcements stay attached to a repository after it is renamed or transferred.
+    ///
+    /// # Arguments
+    /// * `from` - Previous full name of the repository, as `owner/name`.
+    /// * `to` - New full name of the repository, as `owner/name`.
+    ///
+    /// # Returns
+    /// A result containing the number of entries moved.
+    pub async fn rename_repo(&self, from: &str, to: &str) -> Result<usize> {
+        let mut entries = self.entries.write().await;
+        let mut moved = 0;
+        // Keys of their own, see claim_key, start with the repository as well
+        let rename = |key: &str| key.strip_prefix(from).filter(|rest| rest.starts_with(':')).map(|rest| format!("{}{}", to, rest));
+        for entry in entries.iter_mut().filter(|entry| entry.repo == from) {
+            entry.repo = to.to_owned();
+            entry.idempotency_key = rename(&entry.idempotency_key)
+                .unwrap_or_else(|| idempotency_key(entry.kind, &entry.repo, &entry.source_id));
+            if let Some(ReplyTo::Announcement(key)) = &mut entry.plan.reply_to {
+                if let Some(renamed) = rename(key) {
+                    *key = renamed;
+                }
+            }
+            moved += 1;
+        }
+        if moved > 0 {
+            *self.queued.lock().unwrap_or_else(|e| e.into_inner()) = queued_keys(&entries);
+        }
+        if moved == 0 || !self.persist {
+            return Ok(moved);
+        }
+
+        let mut contents = String::new();
+        for entry in entries.iter() {
+            contents.push_str(&serde_json::to_string(entry)?);
+            contents.push('\n');
+        }
+        let temporary = self.path.with_extension("tmp");
+        tokio::fs::write(&temporary, contents)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.path)
+            .await
+            .with_context(|| format!("Failed to replace audit log {}", self.path.display()))?;
+
+        info!("Moved {} audit log entries from {} to {}", moved, from, to);
+        Ok(moved)
+    }
+
+    /// Claims the announcement of an object, unless it is already being made or was already handled.
+    ///
+    /// The same object may be delivered several times, e.g. when GitHub redelivers a webhook, so
+    /// announcements are keyed on their [idempotency_key]. An object was handled once any outcome
+    /// but a failure or a pending confirmation is recorded for it.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to make.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `source_id` - Stable identifier of the announced object.
+    ///
+    /// # Returns
+    /// The claim to hold until the outcome is recorded, or `None` if the announcement must not be made.
+    pub async fn claim(&self, kind: AnnouncementKind, repo: &str, source_id: &str) -> Option<Claim<'_>> {
+        self.claim_key(kind, repo, source_id, &idempotency_key(kind, repo, source_id)).await
+    }
+
+    /// Claims an announcement under a key of its own rather than the one of the object.
+    ///
+    /// Used for what is announced about an object more than once, such as the correction of
+    /// every edit of a release. The entries of the announcement have to be recorded with
+    /// [AuditEntry::with_idempotency_key] for later claims to see them.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to make.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `source_id` - Stable identifier of the announced object.
+    /// * `key` - The key the announcement is claimed with.
+    pub async fn claim_key(&self, kind: AnnouncementKind, repo: &str, source_id: &str, key: &str) -> Option<Claim<'_>> {
+        let key = key.to_owned();
+        if !self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone()) {
+            return None;
+        }
+        let claim = Claim {
+            log: self,
+            kind,
+            repo: repo.to_owned(),
+            source_id: source_id.to_owned(),
+            key,
+        };
+
+        let entries = self.entries.read().await;
+        let latest = entries.iter().rev().find(|entry| entry.idempotency_key == claim.key);
+        match latest.map(|entry| entry.status) {
+            Some(AuditStatus::Failed | AuditStatus::Pending) | None => {}
+            Some(_) => return None,
+        }
+        drop(entries);
+
+        Some(claim)
+    }
+
+    /// Returns true if an announcement of the given kind is being made for the repository,
+    /// including while it waits in the outbox.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    pub fn is_in_flight(&self, kind: AnnouncementKind, repo: &str) -> bool {
+        let prefix = idempotency_key(kind, repo, "");
+        let in_flight = |keys: &Mutex<HashSet<String>>| {
+            keys.lock().unwrap_or_else(|e| e.into_inner()).iter().any(|key| key.starts_with(&prefix))
+        };
+        in_flight(&self.in_flight) || in_flight(&self.queued)
+    }
+
+    /// Waits until no announcement of the given kind is being made for the repository.
+    ///
+    /// Follow-ups wait on the announcements they belong to, so a docs follow-up or a
+    /// correction is never posted before the release it replies to has been recorded.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to wait for.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `timeout` - How long to wait at most.
+    ///
+    /// # Returns
+    /// `true` once none is in flight, or `false` if one still is after `timeout`.
+    pub async fn wait_until_settled(&self, kind: AnnouncementKind, repo: &str, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // Registered before checking, so an announcement settling in between isn't missed
+            let settled = self.settled.notified();
+            tokio::pin!(settled);
+            settled.as_mut().enable();
+
+            if !self.is_in_flight(kind, repo) {
+                return true;
+            }
+            if timeout_at(deadline, settled).await.is_err() {
+                return false;
+            }
+        }
+    }
+
+    /// Returns when the posts recorded since the given time were made, oldest first.
+    ///
+    /// Announcements that were being posted when the bot stopped are included, since
+    /// they may have reached the sink. Those with a recorded outcome are counted once.
+    ///
+    /// # Arguments
+    /// * `sink` - The sink the posts were made on.
+    /// * `since` - Posts made before this time are left out.
+    pub async fn post_times_since(&self, sink: &str, since: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let entries = self.entries.read().await;
+        let mut with_outcome = HashSet::new();
+        // Every entry is looked at, since the outbox records its posts with the time they were
+        // made rather than in the order of the log
+        let mut times: Vec<DateTime<Utc>> = entries
+            .iter()
+            .rev()
+            .filter(|entry| match entry.status {
+                AuditStatus::Posting => !with_outcome.contains(&entry.idempotency_key),
+                AuditStatus::Posted | AuditStatus::Corrected | AuditStatus::FollowUp | AuditStatus::Edited => {
+                    with_outcome.insert(entry.idempotency_key.clone());
+                    entry.remote_ids.contains_key(sink)
+                }
+                _ => {
+                    with_outcome.insert(entry.idempotency_key.clone());
+                    false
+                }
+            })
+            .map(|entry| entry.timestamp)
+            .filter(|timestamp| *timestamp >= since)
+            .collect();
+        times.sort();
+        times
+    }
+
+    /// Returns the announcements waiting in the outbox, oldest first.
+    ///
+    /// An announcement waits from the moment it is queued until its outcome is recorded,
+    /// across restarts.
+    pub async fn queued(&self) -> Vec<AuditEntry> {
+        let entries = self.entries.read().await;
+        queued_entries(&entries)
+    }
+
+    /// Returns the latest entry recorded under the given idempotency key, if any.
+    pub async fn find_by_key(&self, key: &str) -> Option<AuditEntry> {
+        let entries = self.entries.read().await;
+        entries.iter().rev().find(|entry| entry.idempotency_key == key).cloned()
+    }
+
+    /// Returns the latest entry of any status for the given object, if any.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to look for.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `source_id` - Stable identifier of the announced object.
+    pub async fn find_latest(
+        &self,
+        kind: AnnouncementKind,
+        repo: &str,
+        source_id: &str,
+    ) -> Option<AuditEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.kind == kind && entry.repo == repo && entry.source_id == source_id)
+            .cloned()
+    }
+
+    /// Returns the most recent live announcement of the given kind for the repository.
+    pub async fn find_latest_posted(&self, kind: AnnouncementKind, repo: &str) -> Option<AuditEntry> {
+        let entries = self.entries.read().await;
+        let source_id = entries
+            .iter()
+            .rev()
+            .find(|entry| {
+                entry.kind == kind
+                    && matches!(entry.status, AuditStatus::Posted | AuditStatus::Edited)
+                    && entry.repo == repo
+            })?
+            .source_id
+            .clone();
+        drop(entries);
+
+        // Make sure it hasn't been retracted since
+        self.find_posted(kind, repo, &source_id).await
+    }
+
+    /// Returns true if at least one entry of the given kind was recorded for the repository.
+    pub async fn has_any(&self, kind: AnnouncementKind, repo: &str) -> bool {
+        let entries = self.entries.read().await;
+        entries.iter().any(|entry| entry.kind == kind && entry.repo == repo)
+    }
+
+    /// Returns the entries matching the given filters, oldest first.
+    ///
+    /// # Arguments
+    /// * `kind` - Only entries of this kind of announcement, if set.
+    /// * `repo` - Only entries about this repository, as `owner/name`, if set.
+    /// * `since` - Only entries recorded after this time, if set.
+    pub async fn history(
+        &self,
+        kind: Option<AnnouncementKind>,
+        repo: Option<&str>,
+        since: Option<DateTime<Utc>>,
+    ) -> Vec<AuditEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .iter()
+            .filter(|entry| kind.is_none_or(|kind| entry.kind == kind))
+            .filter(|entry| repo.is_none_or(|repo| entry.repo.eq_ignore_ascii_case(repo)))
+            .filter(|entry| since.is_none_or(|since| entry.timestamp >= since))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the announcements that are still up, newest first.
+    ///
+    /// Every announced object comes once, with its edit if it was edited. Announcements that
+    /// have since been retracted are left out, as are corrections and follow-ups.
+    ///
+    /// # Arguments
+    /// * `kind` - Only announcements of this kind, if set.
+    /// * `repo` - Only announcements about this repository, as `owner/name`, if set.
+    pub async fn announcements(&self, kind: Option<AnnouncementKind>, repo: Option<&str>) -> Vec<AuditEntry> {
+        let entries = self.entries.read().await;
+        let mut settled = HashSet::new();
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| kind.is_none_or(|kind| entry.kind == kind))
+            .filter(|entry| repo.is_none_or(|repo| entry.repo.eq_ignore_ascii_case(repo)))
+            .filter(|entry| match entry.status {
+                // By object rather than idempotency key, which older entries lack
+                AuditStatus::Posted | AuditStatus::Edited => settled.insert((entry.kind, &entry.repo, &entry.source_id)),
+                AuditStatus::Retracted => {
+                    settled.insert((entry.kind, &entry.repo, &entry.source_id));
+                    false
+                }
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the latest live announcement for the given object, if any.
+    ///
+    /// Announcements that have since been retracted are not returned.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to look for.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `source_id` - Stable identifier of the announced object.
+    pub async fn find_posted(
+        &self,
+        kind: AnnouncementKind,
+        repo: &str,
+        source_id: &str,
+    ) -> Option<AuditEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.kind == kind && entry.repo == repo && entry.source_id == source_id)
+            .take_while(|entry| entry.status != AuditStatus::Retracted)
+            .find(|entry| matches!(entry.status, AuditStatus::Posted | AuditStatus::Edited))
+            .cloned()
+    }
+
+    /// Returns the latest entry recording the given post.
+    ///
+    /// # Arguments
+    /// * `sink` - The sink the post was made on, such as `x`.
+    /// * `remote_id` - The ID of the post on the sink.
+    pub async fn find_by_remote_id(&self, sink: &str, remote_id: &str) -> Option<AuditEntry> {
+        let entries = self.entries.read().await;
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.remote_ids.get(sink).is_some_and(|id| id == remote_id))
+            .cloned()
+    }
+
+    /// Returns the latest announcement posted since `since` for a subject sharing any of `identities`.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to look for.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `identities` - Normalized identities of the subject, see [normalize_identity].
+    /// * `since` - Announcements posted before this time are ignored.
+    pub async fn find_recent_by_identity(
+        &self,
+        kind: AnnouncementKind,
+        repo: &str,
+        identities: &[String],
+        since: DateTime<Utc>,
+    ) -> Option<AuditEntry> {
+        let entries = self.entries.read().await;
+        // Every entry is looked at, since entries aren't recorded in the order of their timestamps
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.timestamp >= since)
+            .find(|entry| {
+                entry.kind == kind
+                    // Queued announcements count as made, the outbox posts them
+                    && matches!(entry.status, AuditStatus::Posted | AuditStatus::Queued)
+                    && entry.repo == repo
+                    && entry.identities.iter().any(|identity| identities.contains(identity))
+            })
+            .cloned()
+    }
+
+    /// Returns every post made for the given object that has not been retracted yet,
+    /// including corrections and follow-ups, oldest first.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement to look for.
+    /// * `repo` - Repository the announcement is about, as `owner/name`.
+    /// * `source_id` - Stable identifier of the announced object.
+    pub async fn find_live(
+        &self,
+        kind: AnnouncementKind,
+        repo: &str,
+        source_id: &str,
+    ) -> Vec<AuditEntry> {
+        let entries = self.entries.read().await;
+        // An edit replaces the post the announcement was made with, which is no longer live
+        let mut edited = false;
+        let mut live: Vec<AuditEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.kind == kind && entry.repo == repo && entry.source_id == source_id)
+            .take_while(|entry| entry.status != AuditStatus::Retracted)
+            .filter(|entry| match entry.status {
+                AuditStatus::Edited => !std::mem::replace(&mut edited, true),
+                AuditStatus::Posted => !edited,
+                AuditStatus::Corrected | AuditStatus::FollowUp => true,
+                _ => false,
+            })
+            .cloned()
+            .collect();
+        live.reverse();
+        live
+    }
+}
@@ -0,0 +1,2 @@
+pub mod export;
+pub mod log;
\ No newline at end of file
@@ -0,0 +1,51 @@
+use crate::audit::log::{AnnouncementKind, AuditEntry, AuditStatus};
+use std::collections::BTreeMap;
+use serde::Serialize;
+use chrono::{DateTime, Utc};
+
+/// Identifies the layout of [AnnouncementMessage], bumped on breaking changes
+pub const SCHEMA: &str = "x-bot.announcement.v1";
+
+/// An announcement as published to other systems, such as a website or newsletter tooling
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnouncementMessage {
+    pub schema: &'static str,
+    pub kind: AnnouncementKind,
+    /// `posted`, `corrected`, `follow_up`, `edited` or `retracted`
+    pub status: AuditStatus,
+    /// Repository the announcement is about, as `owner/name`
+    pub repo: String,
+    /// Stable identifier of the announced object, such as a release id or commit SHA
+    pub source_id: String,
+    /// Human readable name of the announced object, such as a release tag
+    pub subject: Option<String>,
+    pub text: String,
+    /// Ids of the posts on each sink, keyed by sink name
+    pub remote_ids: BTreeMap<String, String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AnnouncementMessage {
+    /// Builds the message of an audit log entry.
+    ///
+    /// # Returns
+    /// The message, or `None` for entries that don't change what was announced, such as
+    /// failed, skipped or pending announcements.
+    pub fn from_entry(entry: &AuditEntry) -> Option<Self> {
+        let published = matches!(
+            entry.status,
+            AuditStatus::Posted | AuditStatus::Corrected | AuditStatus::FollowUp | AuditStatus::Edited | AuditStatus::Retracted
+        );
+        published.then(|| Self {
+            schema: SCHEMA,
+            kind: entry.kind,
+            status: entry.status,
+            repo: entry.repo.clone(),
+            source_id: entry.source_id.clone(),
+            subject: entry.subject.clone(),
+            text: entry.text.clone(),
+            remote_ids: entry.remote_ids.clone(),
+            timestamp: entry.timestamp,
+        })
+    }
+}
@@ -0,0 +1,321 @@
+use super::message::AnnouncementMessage;
+use crate::{
+    announce::{Announcement, Announcer},
+    config::env::BrokerConfig,
+    github::types::WebhookEvent,
+    source::events::{EventSource, RepoEvent},
+    webhook::handler::ParseFailures};
+use std::sync::Arc;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream},
+    sync::{mpsc, Mutex},
+    time::{timeout, Duration}};
+use serde_json::json;
+use async_trait::async_trait;
+use anyhow::{Context, Result, anyhow};
+use tracing::{debug, error, info, warn};
+
+/// Time allowed for connecting to the server or for it to acknowledge a message
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time without hearing from the server after which the connection is considered lost,
+/// the server pings its clients every 2 minutes by default
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Header holding the event type of a consumed message, as in the webhook deliveries
+const EVENT_HEADER: &str = "x-github-event";
+
+/// Publishes every announcement to a NATS server, on the subject `<prefix>.<kind>`.
+///
+/// The messages are the JSON [AnnouncementMessage] of the announcements recorded in the
+/// audit log. Only the core NATS protocol over plain TCP is spoken, with the user and password
+/// or the token of the URL.
+pub struct NatsPublisher {
+    url: reqwest::Url,
+    subject_prefix: String,
+    /// Connection to the server, opened once there is something to publish and after it's lost
+    connection: Mutex<Option<Connection>>,
+}
+
+/// A connection to the server
+struct Connection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl NatsPublisher {
+    /// Creates a publisher, which connects once there is something to publish.
+    ///
+    /// # Arguments
+    /// * `url` - The `nats://` URL of the server.
+    /// * `config` - The prefix of the subjects.
+    pub fn new(url: &str, config: &BrokerConfig) -> Result<Self> {
+        Ok(Self {
+            url: reqwest::Url::parse(url).context("NATS_URL must be a URL")?,
+            subject_prefix: config.subject_prefix.clone(),
+            connection: Mutex::new(None),
+        })
+    }
+}
+
+#[async_trait]
+impl Announcer for NatsPublisher {
+    fn name(&self) -> &str {
+        "nats"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        AnnouncementMessage::from_entry(announcement).is_some()
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        let message = AnnouncementMessage::from_entry(announcement).context("Announcement isn't published")?;
+        let subject = format!("{}.{}", self.subject_prefix, message.kind.as_str());
+        let payload = serde_json::to_vec(&message)?;
+
+        let mut connection = self.connection.lock().await;
+        let result = match &mut *connection {
+            Some(connection) => connection.publish(&subject, &payload).await,
+            None => match Connection::open(&self.url).await {
+                Ok(connected) => connection.insert(connected).publish(&subject, &payload).await,
+                Err(e) => Err(e),
+            },
+        };
+        if result.is_err() {
+            *connection = None;
+        }
+        result?;
+        debug!("Published {} announcement {} to NATS", message.status.as_str(), announcement.idempotency_key);
+        Ok(None)
+    }
+}
+
+/// Consumes GitHub events forwarded to a NATS subject, such as by a webhook gateway.
+///
+/// Messages hold the webhook payload, with the event type in an `X-GitHub-Event` header or,
+/// without headers, as the last token of the subject, as in `github.events.release`. Events
+/// go through the same handling as the webhook route.
+pub struct NatsSubscriber {
+    url: reqwest::Url,
+    subject: String,
+    queue_group: Option<String>,
+    /// Number of payloads that failed to parse, per event type, shared with the webhook route
+    parse_failures: Arc<ParseFailures>,
+}
+
+/// A message received on a subscription
+struct Message {
+    subject: String,
+    headers: Vec<(String, String)>,
+    payload: Vec<u8>,
+}
+
+impl NatsSubscriber {
+    /// Creates a subscriber, which connects once it runs.
+    ///
+    /// # Arguments
+    /// * `url` - The `nats://` URL of the server.
+    /// * `config` - The subject and queue group to consume from.
+    /// * `parse_failures` - The count of payloads that failed to parse, see [AppState](crate::webhook::handler::AppState).
+    ///
+    /// # Returns
+    /// The subscriber, or `None` when no subject is configured.
+    pub fn new(url: &str, config: &BrokerConfig, parse_failures: Arc<ParseFailures>) -> Result<Option<Self>> {
+        let Some(subject) = config.ingest_subject.clone() else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            url: reqwest::Url::parse(url).context("NATS_URL must be a URL")?,
+            subject,
+            queue_group: config.ingest_queue_group.clone(),
+            parse_failures,
+        }))
+    }
+}
+
+#[async_trait]
+impl EventSource for NatsSubscriber {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn run(&self, events: mpsc::Sender<RepoEvent>) -> Result<()> {
+        let mut connection = Connection::open(&self.url).await?;
+        let subscribe = match &self.queue_group {
+            Some(group) => format!("SUB {} {} 1\r\n", self.subject, group),
+            None => format!("SUB {} 1\r\n", self.subject),
+        };
+        // Not waiting for a PONG, messages may come first, and errors show up while reading them
+        connection.writer.write_all(subscribe.as_bytes()).await?;
+        connection.writer.flush().await?;
+        info!("Consuming GitHub events from NATS on {}", self.subject);
+
+        loop {
+            let message = connection.next_message().await?;
+            let event_type = message.headers.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(EVENT_HEADER))
+                .map(|(_, value)| value.as_str())
+                .unwrap_or_else(|| message.subject.rsplit('.').next().unwrap_or_default())
+                .to_owned();
+            let payload = match String::from_utf8(message.payload) {
+                Ok(payload) => payload,
+                Err(_) => {
+                    error!("Dropped {} event from {}, its payload isn't UTF-8", event_type, message.subject);
+                    continue;
+                }
+            };
+
+            let event = match WebhookEvent::parse(&event_type, &payload) {
+                Some(Ok(event)) => event,
+                Some(Err(e)) => {
+                    *self.parse_failures.lock().unwrap().entry(event_type.clone()).or_default() += 1;
+                    error!("Failed to parse {} event from {}: {}", event_type, message.subject, e);
+                    continue;
+                }
+                None => {
+                    warn!("Unsupported event type {} from {}", event_type, message.subject);
+                    continue;
+                }
+            };
+
+            let event = RepoEvent { event_type, origin: message.subject, event };
+            if events.send(event).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Connection {
+    /// Connects to the server and authenticates with the credentials of the URL.
+    async fn open(url: &reqwest::Url) -> Result<Self> {
+        let host = url.host_str().ok_or_else(|| anyhow!("NATS_URL has no host"))?;
+        let port = url.port().unwrap_or(4222);
+        let stream = timeout(IO_TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| anyhow!("Timed out connecting to NATS at {}:{}", host, port))??;
+        let (reader, writer) = stream.into_split();
+        let mut connection = Self {
+            reader: BufReader::new(reader),
+            writer,
+        };
+
+        // The server greets with its INFO before anything else
+        let info = connection.read_line().await?;
+        if !info.starts_with("INFO") {
+            return Err(anyhow!("Unexpected greeting from NATS: {}", info));
+        }
+
+        let mut options = json!({ "verbose": false, "pedantic": false, "headers": true, "name": "x-bot", "lang": "rust" });
+        match (url.username(), url.password()) {
+            ("", _) => {}
+            (token, None) => options["auth_token"] = json!(token),
+            (user, Some(password)) => {
+                options["user"] = json!(user);
+                options["pass"] = json!(password);
+            }
+        }
+        connection.writer.write_all(format!("CONNECT {}\r\n", options).as_bytes()).await?;
+        connection.flush_and_wait().await?;
+
+        info!("Connected to NATS at {}:{}", host, port);
+        Ok(connection)
+    }
+
+    /// Publishes a message, and waits for the server to process it.
+    async fn publish(&mut self, subject: &str, payload: &[u8]) -> Result<()> {
+        let mut message = format!("PUB {} {}\r\n", subject, payload.len()).into_bytes();
+        message.extend_from_slice(payload);
+        message.extend_from_slice(b"\r\n");
+        self.writer.write_all(&message).await?;
+        self.flush_and_wait().await
+    }
+
+    /// Sends a PING and waits for its PONG, which the server sends once it processed
+    /// everything sent before, or the error it ran into.
+    async fn flush_and_wait(&mut self) -> Result<()> {
+        self.writer.write_all(b"PING\r\n").await?;
+        self.writer.flush().await?;
+        loop {
+            let line = self.read_line().await?;
+            match line.split_whitespace().next().unwrap_or_default() {
+                "PONG" => return Ok(()),
+                "PING" => self.writer.write_all(b"PONG\r\n").await?,
+                "-ERR" => return Err(anyhow!("NATS error: {}", line)),
+                // Such as +OK or updates of the server INFO
+                _ => {}
+            }
+        }
+    }
+
+    /// Waits for the next message of the subscriptions, answering the server's pings meanwhile.
+    async fn next_message(&mut self) -> Result<Message> {
+        loop {
+            let line = self.read_line_within(IDLE_TIMEOUT).await?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.first().copied().unwrap_or_default() {
+                // MSG <subject> <sid> [reply-to] <bytes>
+                "MSG" if (4..=5).contains(&fields.len()) => {
+                    let subject = fields[1].to_owned();
+                    let size: usize = fields[fields.len() - 1].parse().context("Invalid MSG size")?;
+                    let payload = self.read_payload(size).await?;
+                    return Ok(Message { subject, headers: Vec::new(), payload });
+                }
+                // HMSG <subject> <sid> [reply-to] <header bytes> <total bytes>
+                "HMSG" if (5..=6).contains(&fields.len()) => {
+                    let subject = fields[1].to_owned();
+                    let header_size: usize = fields[fields.len() - 2].parse().context("Invalid HMSG header size")?;
+                    let size: usize = fields[fields.len() - 1].parse().context("Invalid HMSG size")?;
+                    if header_size > size {
+                        return Err(anyhow!("Invalid HMSG: {}", line));
+                    }
+                    let mut payload = self.read_payload(size).await?;
+                    let headers = String::from_utf8_lossy(&payload[..header_size])
+                        .lines()
+                        // The first line is the NATS/1.0 version
+                        .skip(1)
+                        .filter_map(|header| header.split_once(':'))
+                        .map(|(name, value)| (name.trim().to_owned(), value.trim().to_owned()))
+                        .collect();
+                    payload.drain(..header_size);
+                    return Ok(Message { subject, headers, payload });
+                }
+                "PING" => {
+                    self.writer.write_all(b"PONG\r\n").await?;
+                    self.writer.flush().await?;
+                }
+                "-ERR" => return Err(anyhow!("NATS error: {}", line)),
+                // Such as +OK, PONG or updates of the server INFO
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the payload of a message, followed by its CRLF.
+    async fn read_payload(&mut self, size: usize) -> Result<Vec<u8>> {
+        let mut payload = vec![0; size + 2];
+        timeout(IO_TIMEOUT, self.reader.read_exact(&mut payload))
+            .await
+            .map_err(|_| anyhow!("Timed out reading a message from NATS"))??;
+        payload.truncate(size);
+        Ok(payload)
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        self.read_line_within(IO_TIMEOUT).await
+    }
+
+    async fn read_line_within(&mut self, within: Duration) -> Result<String> {
+        let mut line = String::new();
+        let read = timeout(within, self.reader.read_line(&mut line))
+            .await
+            .map_err(|_| anyhow!("Timed out waiting for NATS"))??;
+        if read == 0 {
+            return Err(anyhow!("NATS closed the connection"));
+        }
+        Ok(line.trim_end().to_string())
+    }
+}
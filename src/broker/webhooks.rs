@@ -0,0 +1,92 @@
+use super::message::AnnouncementMessage;
+use crate::{
+    announce::{Announcement, Announcer},
+    config::{env::HttpConfig, tls},
+    webhook::signature::hmac_sha256_hex};
+use reqwest::Certificate;
+use async_trait::async_trait;
+use anyhow::{Context, Result};
+use tracing::debug;
+
+/// Header holding the HMAC of the body, as `sha256=<hex>`
+const SIGNATURE_HEADER: &str = "X-XBot-Signature-256";
+
+/// Header holding the kind of the announcement
+const EVENT_HEADER: &str = "X-XBot-Event";
+
+/// Header identifying the delivery, the same across retries
+const DELIVERY_HEADER: &str = "X-XBot-Delivery";
+
+/// Delivers every announcement to a URL as JSON, for dashboards or automation tools such as Zapier or n8n.
+///
+/// The body is the [AnnouncementMessage] of the announcement, as published to NATS. With a
+/// secret, it is signed like GitHub signs its webhooks, in `X-XBot-Signature-256`.
+pub struct WebhookPublisher {
+    http: reqwest::Client,
+    url: String,
+    /// Name of the target in the logs, with its URL
+    name: String,
+    secret: Option<String>,
+}
+
+impl WebhookPublisher {
+    /// Creates a new instance of `WebhookPublisher`.
+    ///
+    /// # Arguments
+    /// * `url` - The URL the announcements are posted to.
+    /// * `secret` - The secret signing the deliveries, unsigned without one.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the publisher, or an error if the extra root certificates cannot be read.
+    pub fn new(url: &str, secret: Option<&str>, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        Ok(Self {
+            http: http.build()?,
+            url: url.to_string(),
+            name: format!("webhook {}", url),
+            secret: secret.map(str::to_owned),
+        })
+    }
+}
+
+#[async_trait]
+impl Announcer for WebhookPublisher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        AnnouncementMessage::from_entry(announcement).is_some()
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        let message = AnnouncementMessage::from_entry(announcement).context("Announcement isn't published")?;
+        let body = serde_json::to_vec(&message)?;
+
+        let delivery = format!("{}:{}", announcement.idempotency_key, message.status.as_str());
+        let mut request = self.http
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header(EVENT_HEADER, message.kind.as_str())
+            .header(DELIVERY_HEADER, &delivery)
+            .body(body.clone());
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+        }
+        request.send().await?.error_for_status()?;
+        debug!("Delivered {} announcement {} to {}", message.status.as_str(), announcement.idempotency_key, self.url);
+        Ok(None)
+    }
+}
+
+/// Signs a body like GitHub does in `X-Hub-Signature-256`
+fn sign(secret: &str, body: &[u8]) -> String {
+    format!("sha256={}", hmac_sha256_hex(secret.as_bytes(), body))
+}
@@ -0,0 +1,67 @@
+use super::exit::Failure;
+use anyhow::{Context, Result, anyhow};
+
+/// Command line arguments of a subcommand, consumed as the options are read.
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    /// Wraps the arguments following the subcommand name.
+    pub fn new(args: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            args: args.into_iter().collect(),
+        }
+    }
+
+    /// Takes a flag such as `--json`.
+    ///
+    /// # Returns
+    /// True if the flag was given.
+    pub fn flag(&mut self, name: &str) -> bool {
+        match self.args.iter().position(|arg| arg == name) {
+            Some(index) => {
+                self.args.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes an option with a value, given either as `--name value` or `--name=value`.
+    ///
+    /// # Returns
+    /// A result containing the value if the option was given, or an error if its value is missing.
+    pub fn value(&mut self, name: &str) -> Result<Option<String>> {
+        let prefix = format!("{}=", name);
+        if let Some(index) = self.args.iter().position(|arg| arg.starts_with(&prefix)) {
+            let arg = self.args.remove(index);
+            return Ok(Some(arg[prefix.len()..].to_string()));
+        }
+
+        let Some(index) = self.args.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if index + 1 >= self.args.len() {
+            return Err(anyhow!("Missing value for {}", name)).context(Failure::Usage);
+        }
+        self.args.remove(index);
+        Ok(Some(self.args.remove(index)))
+    }
+
+    /// Takes the next argument that isn't an option, such as the tag in `post-release v1.0.0`.
+    ///
+    /// Read after the options, so their values aren't mistaken for it.
+    pub fn positional(&mut self) -> Option<String> {
+        let index = self.args.iter().position(|arg| !arg.starts_with("--"))?;
+        Some(self.args.remove(index))
+    }
+
+    /// Checks that every argument was consumed.
+    pub fn finish(self) -> Result<()> {
+        match self.args.first() {
+            Some(arg) => Err(anyhow!("Unexpected argument: {}", arg)).context(Failure::Usage),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::{args::Args, exit::Failure};
+use crate::{config::duration::parse_duration, webhook::signature::hmac_sha256_hex};
+use std::{
+    collections::BTreeMap,
+    env::var,
+    sync::{Arc, Mutex}};
+use tokio::{
+    task::JoinSet,
+    time::{interval, Duration, Instant, MissedTickBehavior}};
+use anyhow::{Context, Result, anyhow};
+
+pub const USAGE: &str = "x-bot bench-webhook [--url <url>] [--rps <requests>] [--duration <duration>] [--file <payload.json> --event <type>]";
+
+/// Synthetic ping sent by default, which the server acknowledges without announcing anything
+const PING_PAYLOAD: &str = r#"{"zen":"Keep it logically awesome.","hook_id":0,"hook":{"id":0,"active":true}}"#;
+
+/// Time after which a request counts as dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a request sent by the benchmark
+enum Outcome {
+    /// The server answered with this status, after this long
+    Answered(u16, Duration),
+    /// The request failed or timed out
+    Dropped,
+}
+
+/// Fires webhook deliveries at a running instance at a steady rate and reports how it keeps up.
+///
+/// Deliveries are signed with `WEBHOOK_SECRET` in `X-Hub-Signature-256` when it is set, like
+/// GitHub does. The server rate limits clients per IP, so the address the benchmark runs from
+/// should be in `RATE_LIMIT_ALLOWLIST` unless the limit itself is being measured.
+///
+/// # Arguments
+/// * `args` - The `--url` of the webhook route, the `--rps` requests per second to send for the
+///   `--duration`, and optionally a payload `--file` sent as the `--event` type instead of a ping.
+///
+/// # Returns
+/// A result indicating success, or an error if an argument is invalid.
+pub async fn run(mut args: Args) -> Result<()> {
+    let url = args.value("--url")?.unwrap_or_else(|| "http://127.0.0.1:7878/webhook".to_string());
+    let rps: u32 = args.value("--rps")?
+        .unwrap_or_else(|| "50".to_string())
+        .parse()
+        .context("--rps must be a positive integer")
+        .context(Failure::Usage)?;
+    let duration = parse_duration(&args.value("--duration")?.unwrap_or_else(|| "10s".to_string()))
+        .context(Failure::Usage)?
+        .to_std()?;
+    let file = args.value("--file")?;
+    let event_type = args.value("--event")?;
+    args.finish()?;
+    if rps == 0 {
+        return Err(anyhow!("--rps must be greater than 0")).context(Failure::Usage);
+    }
+
+    let (event_type, payload) = match (file, event_type) {
+        (None, None) => ("ping".to_string(), PING_PAYLOAD.to_string()),
+        (Some(file), Some(event_type)) => {
+            let payload = tokio::fs::read_to_string(&file)
+                .await
+                .with_context(|| format!("Failed to read {}", file))?;
+            (event_type, payload)
+        }
+        _ => return Err(anyhow!("--file and --event go together\nUsage: {}", USAGE)).context(Failure::Usage),
+    };
+    let signature = var("WEBHOOK_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty())
+        .map(|secret| sign(&secret, &payload));
+
+    let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+    let payload = Arc::new(payload);
+    let in_flight = Arc::new(Mutex::new(0usize));
+    let mut max_in_flight = 0;
+    let mut requests = JoinSet::new();
+
+    println!("Sending {} {} events per second to {} for {}s", rps, event_type, url, duration.as_secs());
+    let mut ticker = interval(Duration::from_secs(1) / rps);
+    // A generator that can't keep up sends fewer requests instead of bursting
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let started = Instant::now();
+    let mut sent: u64 = 0;
+    while started.elapsed() < duration {
+        ticker.tick().await;
+        sent += 1;
+
+        let mut request = client
+            .post(&url)
+            .header("content-type", "application/json")
+            .header("x-github-event", &event_type)
+            .header("x-github-delivery", format!("bench-{}", sent))
+            .body(payload.as_str().to_owned());
+        if let Some(signature) = &signature {
+            request = request.header("x-hub-signature-256", signature);
+        }
+
+        {
+            let mut in_flight = in_flight.lock().unwrap();
+            *in_flight += 1;
+            max_in_flight = max_in_flight.max(*in_flight);
+        }
+        let in_flight = Arc::clone(&in_flight);
+        requests.spawn(async move {
+            let sent_at = Instant::now();
+            let outcome = match request.send().await {
+                Ok(response) => Outcome::Answered(response.status().as_u16(), sent_at.elapsed()),
+                Err(_) => Outcome::Dropped,
+            };
+            *in_flight.lock().unwrap() -= 1;
+            outcome
+        });
+    }
+    let elapsed = started.elapsed();
+
+    let mut statuses = BTreeMap::new();
+    let mut latencies = Vec::new();
+    let mut dropped = 0;
+    while let Some(outcome) = requests.join_next().await {
+        match outcome? {
+            Outcome::Answered(status, latency) => {
+                *statuses.entry(status).or_insert(0u64) += 1;
+                latencies.push(latency);
+            }
+            Outcome::Dropped => dropped += 1,
+        }
+    }
+    latencies.sort();
+
+    println!(
+        "Sent {} requests in {:.1}s ({:.1}/s, {} requested), at most {} in flight",
+        sent, elapsed.as_secs_f64(), sent as f64 / elapsed.as_secs_f64(), rps, max_in_flight
+    );
+    for (status, count) in &statuses {
+        let note = match status {
+            202 => " (accepted, held or queued)",
+            413 | 415 => " (rejected payload)",
+            429 => " (rate limited)",
+            _ => "",
+        };
+        println!("  {}: {}{}", status, count, note);
+    }
+    println!("  dropped: {} (failed or no answer within {}s)", dropped, REQUEST_TIMEOUT.as_secs());
+    if !latencies.is_empty() {
+        println!(
+            "Latency: p50 {}  p90 {}  p99 {}  max {}",
+            format_latency(percentile(&latencies, 50)),
+            format_latency(percentile(&latencies, 90)),
+            format_latency(percentile(&latencies, 99)),
+            format_latency(latencies[latencies.len() - 1]),
+        );
+    }
+    Ok(())
+}
+
+/// Signs a payload like GitHub does in `X-Hub-Signature-256`
+fn sign(secret: &str, payload: &str) -> String {
+    format!("sha256={}", hmac_sha256_hex(secret.as_bytes(), payload.as_bytes()))
+}
+
+/// Returns the latency below which `percent` of the sorted latencies fall
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let index = (sorted.len() * percent).div_ceil(100).saturating_sub(1);
+    sorted[index.min(sorted.len() - 1)]
+}
+
+fn format_latency(latency: Duration) -> String {
+    format!("{:.1}ms", latency.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,93 @@
+use super::{args::Args, exit::Failure};
+use crate::{
+    audit::log::{AuditEntry, AuditLog, AuditStatus},
+    config::env::Config,
+    x::client::XClient};
+use anyhow::{Context, Result, anyhow};
+use tracing_subscriber::{
+    layer::SubscriberExt,
+    util::SubscriberInitExt};
+
+pub const USAGE: &str = "x-bot edit-post <post-id> --text <text> [--dry-run]";
+
+/// Edits a recent announcement in place, e.g. to fix a typo, on X accounts with edit access.
+///
+/// Only the live post of an announcement can be edited, within `X_EDIT_WINDOW_MINUTES` of
+/// being posted. X counts the window from the first post, so an announcement is edited once;
+/// later corrections delete and repost it. The edit is recorded in the audit log, where it
+/// replaces the earlier post.
+///
+/// # Arguments
+/// * `args` - The ID of the post, `--text` with its new text, and `--dry-run` to print the
+///   edit instead of making it.
+///
+/// # Returns
+/// A result indicating success, or an error if the post can't be edited or the edit fails.
+pub async fn run(mut args: Args) -> Result<()> {
+    let dry_run = args.flag("--dry-run");
+    let text = args.value("--text")?
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| anyhow!("Missing the new text of the post\nUsage: {}", USAGE))
+        .context(Failure::Usage)?;
+    let post_id = args.positional()
+        .ok_or_else(|| anyhow!("Missing the ID of the post\nUsage: {}", USAGE))
+        .context(Failure::Usage)?;
+    args.finish()?;
+
+    let config = Config::from_env().context(Failure::Config)?;
+    if !config.edit.enabled {
+        return Err(anyhow!("Editing posts requires X_EDIT_ACCESS=true")).context(Failure::Config);
+    }
+
+    // Logged to stderr, so the edit printed to stdout in dry-run mode stands out
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string())
+        ))
+        .with(tracing_subscriber::fmt::layer().with_target(true).with_writer(std::io::stderr))
+        .init();
+
+    let mut x_client = XClient::new(
+        config.secrets.x_api_key().to_owned(),
+        config.secrets.x_api_secret().to_owned(),
+        config.secrets.x_access_token().to_owned(),
+        config.secrets.x_access_secret().to_owned(),
+        config.queue.clone(),
+        config.fallback.clone(),
+        &config.http,
+    ).await.context(Failure::Config)?
+    .with_edit_access(config.edit.clone());
+    let mut audit_log = AuditLog::open(&config.audit.path).await.context(Failure::StateStore)?;
+    if dry_run {
+        x_client = x_client.dry_run();
+        audit_log = audit_log.in_memory();
+    }
+    x_client.restore_rate_limit(&audit_log).await;
+
+    let entry = audit_log.find_by_remote_id("x", &post_id).await
+        .ok_or_else(|| anyhow!("Post {} is not an announcement of the bot", post_id))?;
+    let live = audit_log.find_posted(entry.kind, &entry.repo, &entry.source_id).await
+        .filter(|live| live.remote_ids.get("x") == Some(&post_id))
+        .ok_or_else(|| anyhow!("Post {} was retracted or replaced since", post_id))?;
+    if live.status != AuditStatus::Posted {
+        return Err(anyhow!("Post {} is already an edit of an earlier post", post_id));
+    }
+    if !x_client.can_edit(live.timestamp) {
+        return Err(anyhow!(
+            "Post {} is older than the edit window of {} minutes",
+            post_id, config.edit.window_minutes
+        ));
+    }
+
+    let edited_id = x_client.edit_tweet(live.kind, &post_id, &text).await
+        .with_context(|| format!("Failed to edit post {}", post_id))?;
+    let mut edited = AuditEntry::new(live.kind, AuditStatus::Edited, &live.repo, &live.source_id, &text)
+        .with_remote_id("x", &edited_id);
+    if let Some(subject) = &live.subject {
+        edited = edited.with_subject(subject);
+    }
+    audit_log.record(edited).await.context(Failure::StateStore)?;
+
+    eprintln!("Post {} is edited, its new ID is {}", post_id, edited_id);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::{
+    fmt::{Display, Formatter},
+    process::ExitCode};
+
+/// Why the bot stopped, which sets its exit code.
+///
+/// The codes follow `sysexits.h`, so restart policies and alerts can tell a bot that needs
+/// fixing from one that can simply be restarted. A clean shutdown exits with 0, and any
+/// other error is [Failure::Fatal].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// The command line is invalid (64)
+    Usage,
+    /// A listener or another part of the bot failed while running (70)
+    Fatal,
+    /// The audit log or another state file can't be read or written (74)
+    StateStore,
+    /// GitHub or X rejected the credentials (77)
+    Auth,
+    /// The configuration is missing or invalid (78)
+    Config,
+}
+
+impl Failure {
+    pub fn code(&self) -> u8 {
+        match self {
+            Failure::Usage => 64,
+            Failure::Fatal => 70,
+            Failure::StateStore => 74,
+            Failure::Auth => 77,
+            Failure::Config => 78,
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let description = match self {
+            Failure::Usage => "Invalid command line",
+            Failure::Fatal => "Fatal error",
+            Failure::StateStore => "State store unavailable",
+            Failure::Auth => "Authentication failed",
+            Failure::Config => "Invalid configuration",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Returns the exit code of an error, set by the [Failure] it was given as context.
+pub fn exit_code(error: &anyhow::Error) -> ExitCode {
+    let failure = error.downcast_ref::<Failure>().copied().unwrap_or(Failure::Fatal);
+    ExitCode::from(failure.code())
+}
+
+/// Returns true if an error is GitHub rejecting the credentials.
+pub fn is_github_auth_error(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<octocrab::Error>(),
+        Some(octocrab::Error::GitHub { source, .. })
+            if source.status_code == http::StatusCode::UNAUTHORIZED
+    )
+}
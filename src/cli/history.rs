@@ -0,0 +1,136 @@
+use super::args::Args;
+use crate::{
+    audit::{
+        export,
+        log::{AnnouncementKind, AuditEntry, AuditLog}},
+    config::{
+        duration::parse_duration,
+        env::{AuditConfig, Config}},
+    x::client::XClient};
+use std::{collections::HashMap, str::FromStr};
+use chrono::Utc;
+use anyhow::{Result, anyhow};
+
+pub const USAGE: &str = "x-bot history [--type <kind>] [--since <duration>] [--repo <owner/name>] [--format text|json|csv]";
+
+/// How the history is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    /// One line per announcement
+    Text,
+    /// One JSON object per announcement
+    Json,
+    /// A CSV document with the engagement of every post
+    Csv,
+}
+
+impl FromStr for HistoryFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(HistoryFormat::Text),
+            "json" => Ok(HistoryFormat::Json),
+            "csv" => Ok(HistoryFormat::Csv),
+            _ => Err(anyhow!("Unknown history format: {}", s)),
+        }
+    }
+}
+
+/// Prints the announcements recorded in the audit log.
+///
+/// # Arguments
+/// * `args` - The filters: `--type` kind of announcement, `--since` duration such as `7d`,
+///   `--repo` repository, and the `--format`: `json` prints the raw entries as JSON lines and
+///   `csv` adds the engagement of the posts, which needs the X credentials. `--json` is short
+///   for `--format json`.
+///
+/// # Returns
+/// A result indicating success, or an error if an argument is invalid or the log can't be read.
+pub async fn run(mut args: Args) -> Result<()> {
+    let kind = args
+        .value("--type")?
+        .map(|kind| kind.parse::<AnnouncementKind>())
+        .transpose()?;
+    let since = args
+        .value("--since")?
+        .map(|since| parse_duration(&since))
+        .transpose()?
+        .map(|duration| Utc::now() - duration);
+    let repo = args.value("--repo")?;
+    let format = match args.flag("--json") {
+        true => Some(HistoryFormat::Json),
+        false => None,
+    };
+    let format = match args.value("--format")? {
+        Some(format) => format.parse()?,
+        None => format.unwrap_or(HistoryFormat::Text),
+    };
+    args.finish()?;
+
+    let audit_log = AuditLog::open(AuditConfig::from_env().path).await?;
+    let entries = audit_log.history(kind, repo.as_deref(), since).await;
+
+    match format {
+        HistoryFormat::Text => {
+            for entry in &entries {
+                println!("{}", format_entry(entry));
+            }
+            if entries.is_empty() {
+                println!("No announcements found");
+            }
+        }
+        HistoryFormat::Json => {
+            for entry in &entries {
+                println!("{}", serde_json::to_string(entry)?);
+            }
+        }
+        HistoryFormat::Csv => {
+            let engagement = match x_client().await {
+                Ok(x_client) => export::engagement(&entries, &x_client).await,
+                Err(e) => {
+                    eprintln!("Exporting without engagement: {}", e);
+                    HashMap::new()
+                }
+            };
+            print!("{}", export::to_csv(&entries, &engagement));
+        }
+    }
+    Ok(())
+}
+
+/// Creates the X client used to look up engagement from the configuration
+async fn x_client() -> Result<XClient> {
+    let config = Config::from_env()?;
+    XClient::new(
+        config.secrets.x_api_key().to_owned(),
+        config.secrets.x_api_secret().to_owned(),
+        config.secrets.x_access_token().to_owned(),
+        config.secrets.x_access_secret().to_owned(),
+        config.queue.clone(),
+        config.fallback.clone(),
+        &config.http,
+    ).await
+}
+
+/// Formats an entry as a single line: when, what, where and the posts made
+fn format_entry(entry: &AuditEntry) -> String {
+    let remote_ids = if entry.remote_ids.is_empty() {
+        "-".to_string()
+    } else {
+        entry.remote_ids
+            .iter()
+            .map(|(sink, id)| format!("{}:{}", sink, id))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    format!(
+        "{}  {:<16}  {:<10}  {}  {}  {}",
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        entry.kind.as_str(),
+        entry.status.as_str(),
+        entry.repo,
+        entry.subject.as_deref().unwrap_or(&entry.source_id),
+        remote_ids,
+    )
+}
@@ -0,0 +1,8 @@
+pub mod args;
+pub mod bench;
+pub mod edit_post;
+pub mod exit;
+pub mod history;
+pub mod post_contributor;
+pub mod post_release;
+pub mod simulate;
\ No newline at end of file
@@ -0,0 +1,112 @@
+use super::{args::Args, exit::{is_github_auth_error, Failure}};
+use crate::{
+    audit::log::{AnnouncementKind, AuditLog},
+    config::env::Config,
+    github::client::GitHubClient,
+    webhook::handler::WebhookHandler,
+    x::{client::XClient, follows::Follows, outbox::Outbox}};
+use std::{path::Path, sync::Arc};
+use anyhow::{Context, Result, anyhow};
+use tracing_subscriber::{
+    layer::SubscriberExt,
+    util::SubscriberInitExt};
+
+pub const USAGE: &str = "x-bot post-contributor <login> [--branch <name>] [--dry-run]";
+
+/// Announces a contributor by hand, e.g. one whose contributions predate the bot.
+///
+/// The announcement credits the contributor's latest commit on the branch, rendered with the
+/// branch's contributor template. It is recorded in the audit log like any other announcement,
+/// so the contributor isn't announced again within the dedupe window.
+///
+/// # Arguments
+/// * `args` - The login of the contributor, the `--branch` to look for their commits on,
+///   the first monitored branch by default, and `--dry-run` to print the post instead of making it.
+///
+/// # Returns
+/// A result indicating success, or an error if the contributor has no commits or wasn't announced.
+pub async fn run(mut args: Args) -> Result<()> {
+    let dry_run = args.flag("--dry-run");
+    let branch_name = args.value("--branch")?;
+    let username = args.positional()
+        .ok_or_else(|| anyhow!("Missing the login of the contributor\nUsage: {}", USAGE))
+        .context(Failure::Usage)?;
+    args.finish()?;
+
+    let config = Config::from_env().context(Failure::Config)?;
+    let branch = match &branch_name {
+        Some(name) => config.branches.iter().find(|branch| &branch.name == name),
+        None => config.branches.first(),
+    }
+        .cloned()
+        .ok_or_else(|| anyhow!("{} is not a monitored branch", branch_name.unwrap_or_default()))
+        .context(Failure::Usage)?;
+
+    // Logged to stderr, so the post printed to stdout in dry-run mode stands out
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string())
+        ))
+        .with(tracing_subscriber::fmt::layer().with_target(true).with_writer(std::io::stderr))
+        .init();
+
+    let github_client = Arc::new(GitHubClient::new(
+        config.github_auth(),
+        config.repo_owner.clone(),
+        config.repo_name.clone(),
+        config.branches.iter().map(|branch| branch.name.clone()).collect(),
+        Path::new(&config.contributor.state_dir),
+        &config.http,
+    ).await.context(Failure::Config)?);
+
+    let mut x_client = XClient::new(
+        config.secrets.x_api_key().to_owned(),
+        config.secrets.x_api_secret().to_owned(),
+        config.secrets.x_access_token().to_owned(),
+        config.secrets.x_access_secret().to_owned(),
+        config.queue.clone(),
+        config.fallback.clone(),
+        &config.http,
+    ).await.context(Failure::Config)?;
+    let mut audit_log = AuditLog::open(&config.audit.path).await.context(Failure::StateStore)?;
+    if dry_run {
+        x_client = x_client.dry_run();
+        audit_log = audit_log.in_memory();
+    }
+    let x_client = Arc::new(x_client);
+    let audit_log = Arc::new(audit_log);
+    x_client.restore_rate_limit(&audit_log).await;
+
+    let commit = match github_client.latest_commit_by(&branch.name, &username).await {
+        Ok(Some(commit)) => commit,
+        Ok(None) => return Err(anyhow!("{} has no commits on {}", username, branch.name)),
+        Err(e) if is_github_auth_error(&e) => return Err(e).context(Failure::Auth),
+        Err(e) => return Err(e).with_context(|| format!("Failed to look up the commits of {}", username)),
+    };
+    let repo_name = github_client.repo().await.full_name();
+
+    let outbox = Outbox::to_x(Arc::clone(&x_client), Arc::clone(&audit_log), config.poll_backoff.clone())
+        .with_follows(Arc::clone(&x_client), Follows::for_contributors(&config.contributor));
+    let webhook_handler = WebhookHandler::new(
+        github_client,
+        x_client,
+        Arc::clone(&audit_log),
+        config.branches.clone(),
+        config.release.clone(),
+        config.contributor.clone(),
+        config.member.clone(),
+    )?;
+    webhook_handler.announce_contributor(&repo_name, &branch, &username, &commit).await;
+    // No outbox runs in the background, the announcement is posted before exiting
+    outbox.drain().await;
+
+    // The announcement logs why a contributor is skipped or fails rather than returning an error
+    let announcement = audit_log.find_posted(AnnouncementKind::Contributor, &repo_name, &commit.id).await;
+    match announcement.and_then(|entry| entry.remote_ids.get("x").cloned()) {
+        Some(post_id) => {
+            eprintln!("{} is announced in post {}", username, post_id);
+            Ok(())
+        }
+        None => Err(anyhow!("{} was not announced, see the log above for why", username)),
+    }
+}
@@ -0,0 +1,130 @@
+use super::{args::Args, exit::{is_github_auth_error, Failure}};
+use crate::{
+    audit::log::{AnnouncementKind, AuditLog},
+    config::env::{Config, SINKS},
+    github::client::GitHubClient,
+    linkedin::client::LinkedInClient,
+    webhook::handler::WebhookHandler,
+    x::{client::XClient, outbox::Outbox}};
+use std::{path::Path, sync::Arc};
+use anyhow::{Context, Result, anyhow};
+use tracing_subscriber::{
+    layer::SubscriberExt,
+    util::SubscriberInitExt};
+
+pub const USAGE: &str = "x-bot post-release <tag> [--dry-run] [--sink <name,...>]";
+
+/// Announces a release the bot missed, e.g. because it was down when it was published.
+///
+/// The release is looked up on GitHub and goes through the same channel routing, templates
+/// and major release flow as a `published` webhook. It is recorded in the audit log like
+/// any other announcement, so a release that was already announced isn't posted again.
+///
+/// # Arguments
+/// * `args` - The tag of the release, `--dry-run` to print the posts instead of making them,
+///   and `--sink` to post to these sinks instead of the ones the release channel routes to.
+///
+/// # Returns
+/// A result indicating success, or an error if the release can't be found or wasn't announced.
+pub async fn run(mut args: Args) -> Result<()> {
+    let dry_run = args.flag("--dry-run");
+    let sinks: Option<Vec<String>> = args.value("--sink")?.map(|sinks| {
+        sinks.split(',').map(|sink| sink.trim().to_string()).filter(|sink| !sink.is_empty()).collect()
+    });
+    let tag = args.positional()
+        .ok_or_else(|| anyhow!("Missing the tag of the release\nUsage: {}", USAGE))
+        .context(Failure::Usage)?;
+    args.finish()?;
+
+    if let Some(sink) = sinks.iter().flatten().find(|sink| !SINKS.contains(&sink.as_str())) {
+        return Err(anyhow!("Unknown sink {}, expected one of {}", sink, SINKS.join(", "))).context(Failure::Usage);
+    }
+
+    let mut config = Config::from_env().context(Failure::Config)?;
+    if sinks.iter().flatten().any(|sink| sink == "linkedin") && !config.linkedin.enabled {
+        return Err(anyhow!("Posting to linkedin requires LINKEDIN_ENABLED")).context(Failure::Usage);
+    }
+    if let Some(sinks) = sinks {
+        for channel in &mut config.release.channels {
+            channel.sinks = sinks.clone();
+        }
+    }
+
+    // Logged to stderr, so the posts printed to stdout in dry-run mode stand out
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string())
+        ))
+        .with(tracing_subscriber::fmt::layer().with_target(true).with_writer(std::io::stderr))
+        .init();
+
+    let github_client = Arc::new(GitHubClient::new(
+        config.github_auth(),
+        config.repo_owner.clone(),
+        config.repo_name.clone(),
+        config.branches.iter().map(|branch| branch.name.clone()).collect(),
+        Path::new(&config.contributor.state_dir),
+        &config.http,
+    ).await.context(Failure::Config)?);
+
+    let mut x_client = XClient::new(
+        config.secrets.x_api_key().to_owned(),
+        config.secrets.x_api_secret().to_owned(),
+        config.secrets.x_access_token().to_owned(),
+        config.secrets.x_access_secret().to_owned(),
+        config.queue.clone(),
+        config.fallback.clone(),
+        &config.http,
+    ).await.context(Failure::Config)?;
+    let mut linkedin_client = config.linkedin.enabled
+        .then(|| LinkedInClient::new(&config.linkedin, &config.secrets, &config.http))
+        .transpose()
+        .context(Failure::Config)?;
+    let mut audit_log = AuditLog::open(&config.audit.path).await.context(Failure::StateStore)?;
+    if dry_run {
+        x_client = x_client.dry_run();
+        linkedin_client = linkedin_client.map(LinkedInClient::dry_run);
+        audit_log = audit_log.in_memory();
+    }
+    let x_client = Arc::new(x_client);
+    let linkedin_client = linkedin_client.map(Arc::new);
+    let audit_log = Arc::new(audit_log);
+    x_client.restore_rate_limit(&audit_log).await;
+
+    let event = match github_client.published_release_event(&tag).await {
+        Ok(event) => event,
+        Err(e) if is_github_auth_error(&e) => return Err(e).context(Failure::Auth),
+        Err(e) => return Err(e).with_context(|| format!("Failed to look up the release tagged {}", tag)),
+    };
+    let repo_name = event.repository.full_name.clone();
+    let release_id = event.release.id.to_string();
+
+    let outbox = Outbox::to_x(Arc::clone(&x_client), Arc::clone(&audit_log), config.poll_backoff.clone())
+        .with_linkedin(linkedin_client.clone());
+    let webhook_handler = WebhookHandler::new(
+        github_client,
+        x_client,
+        Arc::clone(&audit_log),
+        config.branches.clone(),
+        config.release.clone(),
+        config.contributor.clone(),
+        config.member.clone(),
+    )?
+    .with_opengraph_images(&config.http)?
+    .with_linkedin(linkedin_client, config.linkedin.clone());
+    webhook_handler.handle_release(event).await?;
+    // No outbox runs in the background, the release and its thread are posted before exiting
+    outbox.drain().await;
+
+    // The pipeline logs why a release is skipped or fails rather than returning an error
+    let announcement = audit_log.find_posted(AnnouncementKind::Release, &repo_name, &release_id).await;
+    match announcement.filter(|entry| !entry.remote_ids.is_empty()) {
+        Some(entry) => {
+            for (sink, post_id) in &entry.remote_ids {
+                eprintln!("Release {} is announced in {} post {}", tag, sink, post_id);
+            }
+            Ok(())
+        }
+        None => Err(anyhow!("Release {} was not announced, see the log above for why", tag)),
+    }
+}
@@ -0,0 +1,111 @@
+use super::{args::Args, exit::Failure};
+use crate::{
+    audit::log::AuditLog,
+    config::env::Config,
+    github::{client::GitHubClient, types::WebhookEvent},
+    linkedin::client::LinkedInClient,
+    webhook::handler::WebhookHandler,
+    x::{client::XClient, follows::Follows, outbox::Outbox}};
+use std::{path::Path, sync::Arc};
+use anyhow::{Context, Result, anyhow};
+use tracing_subscriber::{
+    layer::SubscriberExt,
+    util::SubscriberInitExt};
+
+pub const USAGE: &str = "x-bot simulate --file <payload.json> --event <type>";
+
+/// Runs a saved webhook payload through the same handling as the server, without posting anything.
+///
+/// Every post the event would make is printed to stdout instead, while the filter decisions
+/// taken along the way are logged to stderr. Announcements are checked against the audit
+/// log, but nothing is recorded in it.
+///
+/// # Arguments
+/// * `args` - The `--file` holding the payload, and the `--event` type GitHub sends in `X-GitHub-Event`.
+///
+/// # Returns
+/// A result indicating success, or an error if the payload can't be read or handled.
+pub async fn run(mut args: Args) -> Result<()> {
+    let file = args.value("--file")?
+        .ok_or_else(|| anyhow!("Missing --file\nUsage: {}", USAGE))
+        .context(Failure::Usage)?;
+    let event_type = args.value("--event")?
+        .ok_or_else(|| anyhow!("Missing --event\nUsage: {}", USAGE))
+        .context(Failure::Usage)?;
+    args.finish()?;
+
+    let payload = tokio::fs::read_to_string(&file)
+        .await
+        .with_context(|| format!("Failed to read {}", file))?;
+    let event = WebhookEvent::parse(&event_type, &payload)
+        .ok_or_else(|| anyhow!("Unsupported event type: {}", event_type))?
+        .with_context(|| format!("Failed to parse {} as a {} event", file, event_type))?;
+
+    let config = Config::from_env()?;
+
+    // Logged to stderr, so the posts printed to stdout stand out
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("LOG_LEVEL").unwrap_or_else(|_| "debug".to_string())
+        ))
+        .with(tracing_subscriber::fmt::layer().with_target(true).with_writer(std::io::stderr))
+        .init();
+
+    let github_client = Arc::new(GitHubClient::new(
+        config.github_auth(),
+        config.repo_owner.clone(),
+        config.repo_name.clone(),
+        config.branches.iter().map(|branch| branch.name.clone()).collect(),
+        Path::new(&config.contributor.state_dir),
+        &config.http,
+    ).await?);
+
+    let x_client = Arc::new(XClient::new(
+        config.secrets.x_api_key().to_owned(),
+        config.secrets.x_api_secret().to_owned(),
+        config.secrets.x_access_token().to_owned(),
+        config.secrets.x_access_secret().to_owned(),
+        config.queue.clone(),
+        config.fallback.clone(),
+        &config.http,
+    ).await?.with_edit_access(config.edit.clone()).dry_run());
+
+    let linkedin_client = config.linkedin.enabled
+        .then(|| LinkedInClient::new(&config.linkedin, &config.secrets, &config.http))
+        .transpose()?
+        .map(|client| Arc::new(client.dry_run()));
+
+    let audit_log = Arc::new(AuditLog::open(&config.audit.path).await?.in_memory());
+
+    // Contributor announcements wait for the contributors like they do in the server
+    tokio::spawn({
+        let github_client = Arc::clone(&github_client);
+        async move { github_client.bootstrap_contributors().await }
+    });
+
+    let outbox = Outbox::to_x(Arc::clone(&x_client), Arc::clone(&audit_log), config.poll_backoff.clone())
+        .with_linkedin(linkedin_client.clone())
+        .with_follows(Arc::clone(&x_client), Follows::for_contributors(&config.contributor));
+    let webhook_handler = WebhookHandler::new(
+        github_client,
+        x_client,
+        audit_log,
+        config.branches.clone(),
+        config.release.clone(),
+        config.contributor.clone(),
+        config.member.clone(),
+    )?
+    .with_opengraph_images(&config.http)?
+    .with_public_repos(config.public_repo.clone())
+    .with_tags(config.tags.clone())?
+    .with_sponsors(config.sponsors.clone())
+    .with_breaking_changes(config.breaking_changes.clone())
+    .with_issue_labels(config.issue_labels.clone())
+    .with_linkedin(linkedin_client, config.linkedin.clone())
+    .with_long_awaited_fixes(config.long_awaited_fixes.clone());
+    let result = webhook_handler.handle(event).await;
+
+    // The announcements queued by the event are printed like the ones posted right away
+    outbox.drain().await;
+    result
+}
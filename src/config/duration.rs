@@ -0,0 +1,29 @@
+use chrono::Duration;
+use anyhow::{anyhow, Result};
+
+/// Parses a short duration such as `30s`, `15m`, `12h`, `7d` or `2w`.
+///
+/// # Arguments
+/// * `value` - A number followed by a unit: `s`, `m`, `h`, `d` or `w`.
+///
+/// # Returns
+/// A result containing the parsed `Duration` or an error if the value is malformed.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Duration {} is missing a unit (s, m, h, d or w)", value))?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration: {}", value))?;
+
+    match unit {
+        "s" => Ok(Duration::seconds(amount)),
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(anyhow!("Invalid duration unit in {}, expected s, m, h, d or w", value)),
+    }
+}
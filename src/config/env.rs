@@ -1,9 +1,20 @@
 use std::{
+    collections::HashMap,
     env::var,
+    net::IpAddr,
     str::FromStr,
+    time::Duration,
     fmt::{Display, Formatter}};
 use serde::Deserialize;
 use anyhow::Context;
+use chrono::Weekday;
+use ipnet::IpNet;
+use crate::{
+    audit::log::AnnouncementKind,
+    config::duration::parse_duration,
+    github::{client::GitHubAuth, repository::RepoName},
+    release::channel::ReleaseChannel,
+    template::{locale::Locale, render::unescape}};
 
 
 /// Runtime environment for the application
@@ -42,12 +53,86 @@ impl FromStr for Environment {
     }
 }
 
+/// Where the announcements are made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Announcer {
+    /// Posted on X
+    X,
+    /// Printed to stdout and the logs, to try out templates and filters without X credentials
+    Console,
+}
+
+// convert string from env var file to Announcer
+impl FromStr for Announcer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "x" => Ok(Announcer::X),
+            "console" => Ok(Announcer::Console),
+            _ => Err(anyhow::anyhow!("Invalid announcer: {}", s)),
+        }
+    }
+}
+
+/// Address the webhook server listens on
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum BindAddress {
+    /// A TCP address such as `0.0.0.0:7878`, `[::]:7878` or `localhost:7878`
+    Tcp(String),
+    /// A Unix domain socket, e.g. `unix:/run/x-bot.sock`
+    Unix(String),
+    /// The sockets passed by systemd socket activation
+    Systemd,
+}
+
+// convert string from env var file to BindAddress
+impl FromStr for BindAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("systemd") {
+            return Ok(BindAddress::Systemd);
+        }
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(anyhow::anyhow!("Unix socket address is missing a path: {}", s));
+            }
+            return Ok(BindAddress::Unix(path.to_string()));
+        }
+        match s.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(BindAddress::Tcp(s.to_string())),
+            _ => Err(anyhow::anyhow!("Invalid listen address, expected host:port, unix:<path> or systemd: {}", s)),
+        }
+    }
+}
+
+impl Display for BindAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindAddress::Tcp(address) => write!(f, "{}", address),
+            BindAddress::Unix(path) => write!(f, "unix:{}", path),
+            BindAddress::Systemd => write!(f, "systemd"),
+        }
+    }
+}
+
 /// Server configuration settings
 #[derive(Debug, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub webhook_path: String,
+    /// Addresses the server listens on, defaulting to the host and port
+    pub listen: Vec<BindAddress>,
+    /// Largest webhook payload accepted, in bytes
+    pub max_body_bytes: usize,
+    /// Whether webhooks configured with the `application/x-www-form-urlencoded` content type are accepted
+    pub accept_form_payloads: bool,
+    /// Whether the signature of the GitHub webhooks is checked against `WEBHOOK_SECRET`, only
+    /// turned off for local testing
+    pub verify_signatures: bool,
 }
 
 // impl Default for ServerConfig {
@@ -60,6 +145,37 @@ pub struct ServerConfig {
 //     }
 // }
 
+/// Browser facing security settings of the admin routes, the webhook route is left out
+#[derive(Debug, Clone, Deserialize)]
+pub struct SecurityConfig {
+    /// Origins allowed to call the admin routes from a browser, `*` for any; none by default
+    pub cors_allowed_origins: Vec<String>,
+    /// Value of the `Content-Security-Policy` header
+    pub content_security_policy: String,
+    /// Max age of the `Strict-Transport-Security` header, 0 to leave it out
+    pub hsts_max_age_seconds: u64,
+}
+
+/// Read-only public API of the announcements, such as for the project website
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublicApiConfig {
+    /// Serve the announcements under `/api`
+    pub enabled: bool,
+}
+
+/// Atom feed of the announcements, for people following the project without an X account
+#[derive(Debug, Clone, Deserialize)]
+pub struct AtomFeedConfig {
+    /// Title of the feed
+    pub title: String,
+    /// Public URL of the feed, its id and self link
+    pub url: Option<String>,
+    /// File the feed is written to whenever an announcement is made, for a static web server
+    pub path: Option<String>,
+    /// Number of latest announcements in the feed
+    pub entries: usize,
+}
+
 /// Rate limiting configuration
 #[derive(Debug, Deserialize)]
 pub struct RateLimitConfig {
@@ -67,6 +183,10 @@ pub struct RateLimitConfig {
     pub max_requests: u32,
     /// Time window in seconds
     pub window_seconds: u64,
+    /// Whether the ranges GitHub delivers webhooks from are exempt from the limit
+    pub allow_github_hooks: bool,
+    /// Networks exempt from the limit
+    pub allowlist: Vec<IpNet>,
 }
 
 // impl Default for RateLimitConfig {
@@ -87,6 +207,26 @@ pub struct RetryConfig {
     pub initial_delay_ms: u64,
     /// Maximum delay between retries in milliseconds
     pub max_delay_ms: u64,
+    /// Retries allowed per minute across the GitHub and X APIs, 0 for no limit
+    pub budget_per_minute: u32,
+}
+
+/// Backoff of the scheduled polls after they fail
+#[derive(Debug, Clone, Deserialize)]
+pub struct PollBackoffConfig {
+    /// Delay before retrying a poll after its first failure, in seconds
+    pub initial_seconds: u64,
+    /// Maximum delay between retries of a failing poll, in seconds
+    pub max_seconds: u64,
+}
+
+/// Pacing of the background scans against GitHub's rate limit
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestBudgetConfig {
+    /// Spread the requests of background scans over the rate limit window
+    pub pacing: bool,
+    /// Requests kept for handling webhooks, which background scans don't spend
+    pub reserve: u32,
 }
 
 // impl Default for RetryConfig {
@@ -99,7 +239,7 @@ pub struct RetryConfig {
 //     }
 // }
 
-/// Cache configuration
+// /// Cache configuration
 // #[derive(Debug, Deserialize)]
 // pub struct CacheConfig {
 //     /// Enable caching
@@ -121,14 +261,47 @@ pub struct RetryConfig {
 // }
 
 /// API timeout configuration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TimeoutConfig {
+    /// Timeouts of the requests made to GitHub, including the release pages
+    pub github: ApiTimeoutConfig,
+    /// Timeouts of the requests made to X
+    pub x: ApiTimeoutConfig,
+}
+
+/// Timeouts of the requests made to an API
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiTimeoutConfig {
     /// Connect timeout in seconds
     pub connect_seconds: u64,
-    /// Read timeout in seconds
+    /// Timeout in seconds of every read of the response, only applied by the GitHub client
     pub read_seconds: u64,
-    /// Write timeout in seconds
+    /// Timeout in seconds of every write of the request, only applied by the GitHub client
     pub write_seconds: u64,
+    /// Deadline in seconds of a whole request, retries and redirects included
+    pub total_seconds: u64,
+}
+
+impl ApiTimeoutConfig {
+    /// Returns the connect timeout.
+    pub fn connect(&self) -> Duration {
+        Duration::from_secs(self.connect_seconds)
+    }
+
+    /// Returns the read timeout.
+    pub fn read(&self) -> Duration {
+        Duration::from_secs(self.read_seconds)
+    }
+
+    /// Returns the write timeout.
+    pub fn write(&self) -> Duration {
+        Duration::from_secs(self.write_seconds)
+    }
+
+    /// Returns the deadline of a whole request.
+    pub fn total(&self) -> Duration {
+        Duration::from_secs(self.total_seconds)
+    }
 }
 
 // impl Default for TimeoutConfig {
@@ -141,260 +314,2681 @@ pub struct TimeoutConfig {
 //     }
 // }
 
-/// Sensitive configuration that should never be logged or displayed
-#[derive(Debug,Deserialize)]
-pub struct Secrets {
-    /// GitHub personal access token for API authentication
-    github_token: String,
-        
-    /// X API key for API authentication
-    x_api_key: String,
-    
-    /// X API secret for API authentication
-    x_api_secret: String,
-    
-    /// X access token for API authentication
-    x_access_token: String,
-    
-    /// X access secret for API authentication
-    x_access_secret: String,
+/// Policy applied when an already announced release is edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseEditedPolicy {
+    /// Leave the original announcement untouched
+    Ignore,
+    /// Reply to the original announcement with a correction note
+    Reply,
+    /// Delete the original announcement and post a corrected one
+    Repost,
 }
 
-impl Display for Secrets {
-    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "[REDACTED]")
+// convert string from env var file to ReleaseEditedPolicy
+impl FromStr for ReleaseEditedPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ignore" => Ok(ReleaseEditedPolicy::Ignore),
+            "reply" => Ok(ReleaseEditedPolicy::Reply),
+            "repost" => Ok(ReleaseEditedPolicy::Repost),
+            _ => Err(anyhow::anyhow!("Invalid release edited policy: {}", s)),
+        }
     }
 }
 
-/// Secret tokens getters in a controlled manner
-impl Secrets {
-    pub fn github_token(&self) -> &str {
-        &self.github_token
-    }
+/// Which releases are announced, depending on whether they are pre-releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrereleasePolicy {
+    /// Announce stable releases and pre-releases, each with the template of its channel
+    Include,
+    /// Only announce stable releases
+    Exclude,
+    /// Only announce pre-releases
+    Only,
+}
 
-    pub fn x_api_key(&self) -> &str {
-        &self.x_api_key
-    }
+// convert string from env var file to PrereleasePolicy
+impl FromStr for PrereleasePolicy {
+    type Err = anyhow::Error;
 
-    pub fn x_api_secret(&self) -> &str {
-        &self.x_api_secret
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "include" => Ok(PrereleasePolicy::Include),
+            "exclude" => Ok(PrereleasePolicy::Exclude),
+            "only" => Ok(PrereleasePolicy::Only),
+            _ => Err(anyhow::anyhow!("Invalid prerelease policy: {}", s)),
+        }
     }
+}
 
-    pub fn x_access_token(&self) -> &str {
-        &self.x_access_token
+impl PrereleasePolicy {
+    /// Returns true if a release of the given channel is announced.
+    pub fn announces(&self, channel: ReleaseChannel) -> bool {
+        let prerelease = channel != ReleaseChannel::Stable;
+        match self {
+            PrereleasePolicy::Include => true,
+            PrereleasePolicy::Exclude => !prerelease,
+            PrereleasePolicy::Only => prerelease,
+        }
     }
+}
 
-    pub fn x_access_secret(&self) -> &str {
-        &self.x_access_secret
-    }
+/// What counts as the first contribution of a contributor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirstContributionPolicy {
+    /// Their first commit on the monitored branch
+    FirstCommit,
+    /// Their first merged pull request
+    FirstMergedPr,
+    /// Their first interaction of any kind: issue, pull request, comment or commit
+    FirstInteraction,
+    /// Their pull request is marked by GitHub as coming from a first-time contributor
+    PrAuthorAssociation,
+}
 
-    /// Validate all secrets
-    pub fn validate(&self) -> anyhow::Result<()> {
-        if self.github_token.is_empty() {
-            return Err(anyhow::anyhow!("GITHUB_TOKEN must be set"));
-        }
-        if self.github_token.len() != 40 {
-            return Err(anyhow::anyhow!("GitHub token must be exactly 40 characters long"));
-        } 
-        if self.x_api_key.is_empty() {
-            return Err(anyhow::anyhow!("X_API_KEY must be set"));
-        }
-        if self.x_api_key.len() < 25 {
-            return Err(anyhow::anyhow!("X_API_KEY must be at least 32 characters long"));
-        }
-        if self.x_api_secret.is_empty() {
-            return Err(anyhow::anyhow!("X_API_SECRET must be set"));
-        }
-        if self.x_api_secret.len() < 32 {
-            return Err(anyhow::anyhow!("X_API_SECRET must be at least 32 characters long"));
-        }
-        if self.x_access_token.is_empty() {
-            return Err(anyhow::anyhow!("X_ACCESS_TOKEN must be set"));
-        }
-        if self.x_access_token.len() < 32 {
-            return Err(anyhow::anyhow!("X_ACCESS_TOKEN must be at least 32 characters long"));
-        }
-        if self.x_access_secret.is_empty() {
-            return Err(anyhow::anyhow!("X_ACCESS_SECRET must be set"));
-        }
-        if self.x_access_secret.len() < 32 {
-            return Err(anyhow::anyhow!("X_ACCESS_SECRET must be at least 32 characters long"));
+// convert string from env var file to FirstContributionPolicy
+impl FromStr for FirstContributionPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "first_commit" => Ok(FirstContributionPolicy::FirstCommit),
+            "first_merged_pr" => Ok(FirstContributionPolicy::FirstMergedPr),
+            "first_interaction" => Ok(FirstContributionPolicy::FirstInteraction),
+            "pr_author_association" => Ok(FirstContributionPolicy::PrAuthorAssociation),
+            _ => Err(anyhow::anyhow!("Invalid first contribution policy: {}", s)),
         }
-        Ok(())
     }
 }
 
-/// Configuration structure for the application
-#[derive(Debug, Deserialize)]
-pub struct Config {
-    /// Current runtime environment
-    // #[serde(default)]
-    pub environment: Environment,
+/// Alternate endpoint tried once posting through the v2 tweets endpoint keeps failing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum FallbackEndpoint {
+    /// The v1.1 `statuses/update` endpoint
+    #[serde(rename = "v1.1")]
+    V1,
+}
 
-    /// Server configuration
-    // #[serde(default)]
-    pub server: ServerConfig,
+// convert string from env var file to FallbackEndpoint
+impl FromStr for FallbackEndpoint {
+    type Err = anyhow::Error;
 
-    /// Rate limiting configuration
-    // #[serde(default)]
-    pub rate_limit: RateLimitConfig,
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "v1" | "v1.1" => Ok(FallbackEndpoint::V1),
+            _ => Err(anyhow::anyhow!("Invalid X fallback endpoint: {}", s)),
+        }
+    }
+}
 
-    /// Retry configuration
-    // #[serde(default)]
-    pub retry: RetryConfig,
+/// Names of the sinks announcements can be routed to
+pub const SINKS: &[&str] = &["x", "linkedin"];
 
-    /// API timeout configuration
-    // #[serde(default)]
-    pub timeout: TimeoutConfig,
+/// Settings for a release channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelConfig {
+    pub channel: ReleaseChannel,
+    /// Regex matched against release tags to detect the channel
+    pub tag_pattern: Option<String>,
+    /// Template of the release announcement.
+    /// Supports the `{tag}`, `{name}`, `{body}`, `{url}`, `{repo}` and `{channel}` placeholders.
+    pub template: String,
+    /// Hashtags appended to the announcement, without the leading `#`
+    pub hashtags: Vec<String>,
+    /// Sinks the announcement is posted to; an empty list disables announcing the channel
+    pub sinks: Vec<String>,
+}
 
-    /// Sensitive configuration values
-    pub secrets: Secrets,
-    
-    /// GitHub repository owner (username or organization)
-    pub repo_owner: String,
-    
-    /// GitHub repository name
-    pub repo_name: String,
+impl ChannelConfig {
+    /// Returns true if announcements of this channel are routed to `sink`.
+    pub fn routes_to(&self, sink: &str) -> bool {
+        self.sinks.iter().any(|name| name == sink)
+    }
+}
 
-    /// Log level for the application
-    #[serde(default = "default_log_level")]
-    pub log_level: String,
+/// Settings of the richer announcement flow used for major releases
+#[derive(Debug, Clone, Deserialize)]
+pub struct MajorReleaseConfig {
+    /// Use the major release flow for stable `X.0.0` releases
+    pub enabled: bool,
+    /// Also treat `0.Y.0` releases as major
+    pub zero_minor_is_major: bool,
+    /// Template of the announcement, supporting the same placeholders as the channel templates
+    pub template: String,
+    /// Path of an image card attached to the announcement
+    pub image_path: Option<String>,
+    /// Number of release note highlights posted as a thread under the announcement
+    pub highlights: usize,
 }
 
-fn default_log_level() -> String {
-    "info".to_string()
+/// Settings for delaying release announcements until their assets are uploaded
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssetWaitConfig {
+    /// Minimum number of assets the release must have (0 disables the minimum)
+    pub min_assets: usize,
+    /// Regexes that must each match the name of at least one asset
+    pub patterns: Vec<String>,
+    /// The release is announced anyway once this many minutes have passed
+    pub timeout_minutes: u64,
+    /// Seconds between two checks of the release assets
+    pub poll_interval_seconds: u64,
 }
 
-impl Config {
-    /// Loads configuration from environment variables
-    ///
-    /// # Returns
-    /// A Result containing the Config if successful, or an error if any required
-    /// environment variables are missing
-    pub fn from_env() -> anyhow::Result<Self> {
-        dotenv::dotenv().ok();
+/// Settings for holding release announcements until the CI of their tag succeeds
+#[derive(Debug, Clone, Deserialize)]
+pub struct CiWaitConfig {
+    /// Wait for the check runs of the release tag before announcing it
+    pub enabled: bool,
+    /// Names of the check runs that must succeed; every check run of the tag when empty
+    pub checks: Vec<String>,
+    /// Releases whose CI is still running after this many minutes aren't announced
+    pub timeout_minutes: u64,
+    /// Seconds between two checks of the CI
+    pub poll_interval_seconds: u64,
+}
 
-        // Load environment-specific settings
-        let environment = var("ENVIRONMENT")
-            .unwrap_or_else(|_| "development".to_string())
-            .parse()?;
+/// Settings for following up release announcements once the docs site is deployed
+#[derive(Debug, Clone, Deserialize)]
+pub struct DocsDeployConfig {
+    /// Reply to the latest release announcement when the docs site is deployed
+    pub enabled: bool,
+    /// Deployment environment of the docs site
+    pub environment: String,
+    /// URL of the docs site, used when the deployment doesn't report one
+    pub url: Option<String>,
+    /// Template of the follow-up. Supports the `{tag}` and `{url}` placeholders.
+    pub template: String,
+    /// Deployments later than this many hours after the release announcement are not announced
+    pub window_hours: u64,
+}
 
-        // Load secrets first and validate them
-        let secrets = Secrets {
-            github_token: var("GITHUB_TOKEN")
-                .context("GITHUB_TOKEN must be set")?,
-            x_api_key: var("X_API_KEY")
-                .context("X_API_KEY must be set")?,
-            x_api_secret: var("X_API_SECRET")
-                .context("X_API_SECRET must be set")?,
-            x_access_token: var("X_ACCESS_TOKEN")
-                .context("X_ACCESS_TOKEN must be set")?,
-            x_access_secret: var("X_ACCESS_SECRET")
-                .context("X_ACCESS_SECRET must be set")?,
-        };
-        secrets.validate()?;
+/// Release announcement configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseConfig {
+    /// What to do when an announced release is edited
+    pub edited_policy: ReleaseEditedPolicy,
+    /// Edits made later than this many minutes after the announcement are ignored
+    pub edit_window_minutes: u64,
+    /// Share of the lines of the release notes, in percent, an edit has to change to be
+    /// announced, so that typo fixes don't make noise (0 announces every edit)
+    pub notes_min_change_percent: u32,
+    /// Delete the announcement posts when the release is deleted
+    pub retract_on_delete: bool,
+    /// Whether pre-releases, the releases of the beta and nightly channels, are announced
+    pub prereleases: PrereleasePolicy,
+    /// Settings of every release channel
+    pub channels: Vec<ChannelConfig>,
+    /// Settings of the major release flow
+    pub major: MajorReleaseConfig,
+    /// Attach the OpenGraph image of the release page, GitHub's generated social card,
+    /// to announcements that don't have an image card of their own
+    pub opengraph_image: bool,
+    /// Settings for waiting on release assets
+    pub asset_wait: AssetWaitConfig,
+    /// Settings for waiting on the CI of the release tag
+    pub ci_wait: CiWaitConfig,
+    /// Settings of the docs deploy follow-ups
+    pub docs: DocsDeployConfig,
+}
 
-        // Load server configuration
-        let server = ServerConfig {
-            host: var("SERVER_HOST")
-                .unwrap_or_else(|_| "127.0.0.1".to_string()),
-            port: var("SERVER_PORT")
-                .unwrap_or_else(|_| "7878".to_string())
-                .parse()
-                .context("SERVER_PORT must be a valid port number")?,
-            webhook_path: var("WEBHOOK_PATH")
-                .unwrap_or_else(|_| "/webhook".to_string()),
-        };
+/// Default template of the new contributor announcement
+pub const DEFAULT_CONTRIBUTOR_TEMPLATE: &str = "Delta got a new contributor {username}!\nDetails: {message}\nLink: {url}";
 
-        // Load rate limit configuration
-        let rate_limit = RateLimitConfig {
-            max_requests: var("RATE_LIMIT_MAX_REQUESTS")
-                .unwrap_or_else(|_| "100".to_string())
-                .parse()
-                .context("RATE_LIMIT_MAX_REQUESTS must be a positive integer")?,
-            window_seconds: var("RATE_LIMIT_WINDOW_SECONDS")
-                .unwrap_or_else(|_| "3600".to_string())
-                .parse()
-                .context("RATE_LIMIT_WINDOW_SECONDS must be a positive integer")?,
-        };
+/// Settings for a monitored branch
+#[derive(Debug, Clone, Deserialize)]
+pub struct BranchConfig {
+    /// Branch name, without the `refs/heads/` prefix
+    pub name: String,
+    /// Template of the new contributor announcement for commits on this branch.
+    /// Supports the `{username}`, `{message}`, `{url}` and `{branch}` placeholders.
+    pub contributor_template: String,
+}
 
-        // Load retry configuration
-        let retry = RetryConfig {
-            max_attempts: var("RETRY_MAX_ATTEMPTS")
-                .unwrap_or_else(|_| "3".to_string())
-                .parse()
-                .context("RETRY_MAX_ATTEMPTS must be a positive integer")?,
-            initial_delay_ms: var("RETRY_INITIAL_DELAY_MS")
-                .unwrap_or_else(|_| "1000".to_string())
-                .parse()
-                .context("RETRY_INITIAL_DELAY_MS must be a positive integer")?,
-            max_delay_ms: var("RETRY_MAX_DELAY_MS")
-                .unwrap_or_else(|_| "5000".to_string())
-                .parse()
-                .context("RETRY_MAX_DELAY_MS must be a positive integer")?,
-        };
+/// Authentication to GitHub as a GitHub App, with the `GITHUB_APP_PRIVATE_KEY` secret, rather
+/// than with the personal access token of `GITHUB_TOKEN`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubAppConfig {
+    /// ID of the app, the personal access token is used without one
+    pub app_id: Option<u64>,
+    /// Installation of the app the bot acts as, the one on each repository unless set
+    pub installation_id: Option<u64>,
+}
 
-        // Load timeout configuration
-        let timeout = TimeoutConfig {
-            connect_seconds: var("TIMEOUT_CONNECT_SECONDS")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .context("TIMEOUT_CONNECT_SECONDS must be a positive integer")?,
-            read_seconds: var("TIMEOUT_READ_SECONDS")
-                .unwrap_or_else(|_| "30".to_string())
-                .parse()
-                .context("TIMEOUT_READ_SECONDS must be a positive integer")?,
-            write_seconds: var("TIMEOUT_WRITE_SECONDS")
-                .unwrap_or_else(|_| "30".to_string())
-                .parse()
-                .context("TIMEOUT_WRITE_SECONDS must be a positive integer")?,
-        };
+/// Polling of the commits and releases with GraphQL, for bots GitHub can't deliver webhooks to
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubPollConfig {
+    /// Poll every repository, alongside the webhooks
+    pub enabled: bool,
+    /// Seconds between two polls of a repository
+    pub interval_seconds: u64,
+}
 
-        let config = Config {
-            environment,
-            server,
-            rate_limit,
-            retry,
-            timeout,
-            secrets,
-            repo_owner: var("REPO_OWNER")
-                .context("REPO_OWNER must be set")?,
-            repo_name: var("REPO_NAME")
-                .context("REPO_NAME must be set")?,
-            log_level: var("LOG_LEVEL")
-                .unwrap_or_else(|_| default_log_level()),
-        };
+/// A GitHub repository announced alongside the one of `REPO_OWNER` and `REPO_NAME`
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepositoryConfig {
+    pub owner: String,
+    pub name: String,
+    /// Monitored branches, the ones of `BRANCHES` unless the repository is listed with its own
+    pub branches: Vec<BranchConfig>,
+}
 
-        config.validate()?;
-        Ok(config)
+impl RepositoryConfig {
+    /// Returns the name of the repository as `owner/name`
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
     }
+}
 
-    /// Get secrets safely inside Config
-    pub fn github_token(&self) -> &str {
-        self.secrets.github_token()
-    }
+/// Announcement of every public repository of a GitHub organization, found as they are created
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrganizationConfig {
+    /// Announce the repositories of the organization, through its organization webhook
+    pub enabled: bool,
+    /// Login of the organization, `REPO_OWNER` unless set
+    pub name: String,
+    /// Regex a repository name must match to be announced, all of them when unset
+    pub include: Option<String>,
+    /// Regex of the repository names left out, such as `^(\.github|sandbox-.*)$`
+    pub exclude: Option<String>,
+    /// Announce the forks of the organization as well
+    pub include_forks: bool,
+    /// Minutes between two lookups of the repositories of the organization
+    pub discovery_interval_minutes: u64,
+}
 
-    pub fn x_api_key(&self) -> &str {
-        self.secrets.x_api_key()
-    }
+/// New contributor announcement configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContributorConfig {
+    /// What counts as a first contribution
+    pub first_contribution: FirstContributionPolicy,
+    /// A contributor announced less than this many hours ago is not announced again (0 disables)
+    pub dedupe_window_hours: u64,
+    /// Directory where the known contributors of every branch are persisted between runs
+    pub state_dir: String,
+    /// Follow announced contributors whose GitHub profile links to their X account
+    pub follow_on_x: bool,
+    /// Accounts followed per day at most
+    pub follow_daily_cap: u32,
+}
 
-    pub fn x_api_secret(&self) -> &str {
-        self.secrets.x_api_secret()
-    }
+/// New collaborator announcement configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemberConfig {
+    /// Announce people added as collaborators of the repository
+    pub enabled: bool,
+    /// Logins announced as soon as they are added; anyone else waits for a confirmation
+    pub allowlist: Vec<String>,
+    /// Template of the announcement. Supports the `{username}` and `{permission}` placeholders.
+    pub template: String,
+}
 
-    pub fn x_access_token(&self) -> &str {
-        self.secrets.x_access_token()
-    }
+/// Announcement of repositories made public, for organization webhooks
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublicRepoConfig {
+    /// Announce repositories of the organization that are made public
+    pub enabled: bool,
+    /// Template of the announcement.
+    /// Supports the `{name}`, `{repo}`, `{description}`, `{topics}`, `{language}` and `{url}` placeholders.
+    pub template: String,
+}
 
-    pub fn x_access_secret(&self) -> &str {
-        self.secrets.x_access_secret()
-    }
+/// Announcement of tags pushed without a GitHub release
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagConfig {
+    /// Announce new tags
+    pub enabled: bool,
+    /// Regex a tag must match to be announced
+    pub pattern: String,
+    /// Template of the announcement. Supports the `{tag}`, `{repo}` and `{url}` placeholders.
+    pub template: String,
+    /// Minutes to wait for a release of the tag, which is announced instead of the tag
+    pub release_wait_minutes: u64,
+}
 
-    /// Validates the configuration values
-    fn validate(&self) -> anyhow::Result<()> {
-        if self.repo_owner.is_empty() || self.repo_name.is_empty() {
-            return Err(anyhow::anyhow!("Repository owner and name cannot be empty"));
-        }
+/// Heads-up on merged pull requests labeled as breaking changes
+#[derive(Debug, Clone, Deserialize)]
+pub struct BreakingChangeConfig {
+    /// Announce breaking changes merged to a monitored branch
+    pub enabled: bool,
+    /// Labels marking a pull request as a breaking change, compared case-insensitively
+    pub labels: Vec<String>,
+    /// Template of the heads-up.
+    /// Supports the `{number}`, `{title}`, `{author}`, `{branch}`, `{repo}` and `{url}` placeholders.
+    pub template: String,
+}
+
+/// Settings for an announced issue label
+#[derive(Debug, Clone, Deserialize)]
+pub struct LabelConfig {
+    /// Label name, compared case-insensitively
+    pub name: String,
+    /// Template of the announcement of issues given this label.
+    /// Supports the `{number}`, `{title}`, `{label}`, `{repo}` and `{url}` placeholders.
+    pub template: String,
+}
+
+/// Announcement of open issues given a label such as `good first issue`
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueLabelConfig {
+    /// Announce issues when they are given one of the labels
+    pub enabled: bool,
+    /// Settings of every announced label
+    pub labels: Vec<LabelConfig>,
+    /// Issues announced per hour at most, so that labeling many issues at once doesn't flood the timeline
+    pub max_per_hour: usize,
+}
+
+/// Default template of the labeled issue announcement
+pub const DEFAULT_ISSUE_LABEL_TEMPLATE: &str = "🌱 Looking for a way to contribute to Delta? Check out this {label} issue:\n{title}\n{url}";
+
+/// Announcement of long-standing issues closed as completed
+#[derive(Debug, Clone, Deserialize)]
+pub struct LongAwaitedFixConfig {
+    /// Announce old issues once they are fixed
+    pub enabled: bool,
+    /// Issues opened at least this many days before being closed are announced
+    pub min_age_days: u64,
+    /// Template of the announcement.
+    /// Supports the `{number}`, `{title}`, `{years}`, `{repo}` and `{url}` placeholders.
+    pub template: String,
+}
+
+/// Milestone teaser scheduler configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeaserConfig {
+    /// Post teasers for open milestones with a due date
+    pub enabled: bool,
+    /// How long before the due date each teaser is posted, e.g. `7d`
+    pub offsets: Vec<String>,
+    /// Template of the teaser.
+    /// Supports the `{title}`, `{when}`, `{due_date}`, `{open_issues}` and `{url}` placeholders.
+    pub template: String,
+    /// Locale of the due date, the time left and the issue count
+    pub locale: Locale,
+    /// Minutes between two checks of the milestones
+    pub check_interval_minutes: u64,
+}
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// TLS from the start, usually on port 465
+    Tls,
+    /// Plain text upgraded to TLS with `STARTTLS`, usually on port 587
+    StartTls,
+    /// Plain text, only for a relay on the same host or network
+    None,
+}
+
+impl SmtpSecurity {
+    /// Returns the port usually serving this kind of connection
+    pub fn default_port(&self) -> u16 {
+        match self {
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::None => 25,
+        }
+    }
+}
+
+// convert string from env var file to SmtpSecurity
+impl FromStr for SmtpSecurity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tls" => Ok(SmtpSecurity::Tls),
+            "starttls" | "start_tls" => Ok(SmtpSecurity::StartTls),
+            "none" => Ok(SmtpSecurity::None),
+            _ => Err(anyhow::anyhow!("Invalid SMTP security: {}", s)),
+        }
+    }
+}
+
+/// Email digest of the announcements configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailDigestConfig {
+    /// Email a periodic digest of the announcements
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_security: SmtpSecurity,
+    /// Username to sign in to the SMTP server with, paired with the `SMTP_PASSWORD` secret
+    pub smtp_username: Option<String>,
+    /// Sender of the digest, such as `Delta <announcements@delta.io>`
+    pub from: String,
+    /// Recipients of the digest, such as a mailing list address
+    pub to: Vec<String>,
+    /// Day of the week the digest is sent on, every day when unset
+    pub weekday: Option<Weekday>,
+    /// Hour of the day (UTC) from which the digest is sent
+    pub hour: u32,
+    /// Template of the subject. Supports the `{count}` and `{since}` placeholders.
+    pub subject: String,
+    /// Template of the plain text body. Supports the `{count}`, `{since}` and `{announcements}` placeholders.
+    pub text_template: String,
+    /// Template of the HTML body, with the same placeholders, `{announcements}` being an HTML list
+    pub html_template: String,
+}
+
+/// Weekly merged pull request digest configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct DigestConfig {
+    /// Post a weekly summary of the merged pull requests
+    pub enabled: bool,
+    /// Day of the week the digest is posted on
+    pub weekday: Weekday,
+    /// Hour of the day (UTC) from which the digest is posted
+    pub hour: u32,
+    /// Template of the digest.
+    /// Supports the `{count}`, `{notable}`, `{week}`, `{date}` and `{repo}` placeholders, and
+    /// `{views}`, `{views_change}`, `{clones}`, `{clones_change}` and `{top_referrer}` with traffic.
+    pub template: String,
+    /// Locale of the count and the date
+    pub locale: Locale,
+    /// Labels marking notable pull requests, listed by title in the digest, in order of priority
+    pub notable_labels: Vec<String>,
+    /// Maximum number of notable pull requests listed
+    pub max_notable: usize,
+    /// Read the repository traffic for the digest, which requires push access
+    pub traffic: bool,
+}
+
+/// Monthly repository stats configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct MonthlyStatsConfig {
+    /// Post the stats of the past month
+    pub enabled: bool,
+    /// Day of the month the stats are posted on
+    pub day: u32,
+    /// Hour of the day (UTC) from which the stats are posted
+    pub hour: u32,
+    /// Template of the stats.
+    /// Supports the `{month}`, `{year}`, `{pulls}`, `{releases}`, `{contributors}` and `{repo}` placeholders.
+    pub template: String,
+    /// Locale of the counts and the month
+    pub locale: Locale,
+}
+
+/// Repository anniversary configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnniversaryConfig {
+    /// Post on the anniversary of the creation of the repository
+    pub enabled: bool,
+    /// Hour of the day (UTC) from which the anniversary is posted
+    pub hour: u32,
+    /// Template of the anniversary.
+    /// Supports the `{years}`, `{since}`, `{releases}`, `{contributors}` and `{repo}` placeholders.
+    pub template: String,
+    /// Locale of the counts
+    pub locale: Locale,
+}
+
+/// Closed issue milestone configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueMilestoneConfig {
+    /// Announce when the number of closed issues reaches a round number
+    pub enabled: bool,
+    /// A milestone is announced every time this many more issues are closed
+    pub every: u64,
+    /// Template of the announcement. Supports the `{count}` and `{repo}` placeholders.
+    pub template: String,
+    /// Locale of the count
+    pub locale: Locale,
+    /// Minutes between two counts of the closed issues
+    pub check_interval_minutes: u64,
+}
+
+/// Star count milestone configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct StarMilestoneConfig {
+    /// Announce when the number of stars crosses a milestone
+    pub enabled: bool,
+    /// Star counts announced once reached, in increasing order
+    pub milestones: Vec<u64>,
+    /// Template of the announcement. Supports the `{count}` and `{repo}` placeholders.
+    pub template: String,
+    /// Locale of the count
+    pub locale: Locale,
+    /// Minutes between two counts of the stars
+    pub check_interval_minutes: u64,
+}
+
+/// GitHub Sponsors announcement configuration, for webhooks of the sponsored account
+#[derive(Debug, Clone, Deserialize)]
+pub struct SponsorsConfig {
+    /// Thank new sponsors
+    pub enabled: bool,
+    /// Template of the thanks to public sponsors.
+    /// Supports the `{sponsor}`, `{sponsorable}`, `{tier}` and `{amount}` placeholders.
+    pub template: String,
+    /// Template of the thanks to private sponsors, who aren't named.
+    /// Supports the `{sponsorable}`, `{tier}` and `{amount}` placeholders.
+    pub private_template: String,
+    /// Sponsor counts announced once reached, in increasing order, none to disable
+    pub milestones: Vec<u64>,
+    /// Template of the milestone announcement. Supports the `{count}` and `{sponsorable}` placeholders.
+    pub milestone_template: String,
+    /// Locale of the sponsor count
+    pub locale: Locale,
+}
+
+/// Release download milestone configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct DownloadMilestoneConfig {
+    /// Announce when the downloads of the assets of a release cross a milestone
+    pub enabled: bool,
+    /// Download counts announced once reached by a release, in increasing order
+    pub milestones: Vec<u64>,
+    /// Template of the announcement. Supports the `{tag}`, `{count}`, `{repo}` and `{url}` placeholders.
+    pub template: String,
+    /// Locale of the count
+    pub locale: Locale,
+    /// Minutes between two counts of the downloads
+    pub check_interval_minutes: u64,
+}
+
+/// A container registry images are published to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Registry {
+    /// Docker Hub, with images named `namespace/name`
+    DockerHub,
+    /// GitHub Container Registry, with images named `owner/name`
+    Ghcr,
+}
+
+// convert string from env var file to Registry
+impl FromStr for Registry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "docker_hub" | "dockerhub" => Ok(Registry::DockerHub),
+            "ghcr" => Ok(Registry::Ghcr),
+            _ => Err(anyhow::anyhow!("Invalid registry: {}", s)),
+        }
+    }
+}
+
+/// Container image publish announcement configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageConfig {
+    /// Announce new tags of a container image
+    pub enabled: bool,
+    /// Registry the image is published to
+    pub registry: Registry,
+    /// Name of the image in the registry, such as `delta-io/delta-rs`
+    pub image: String,
+    /// Regex a tag must match to be announced
+    pub tag_pattern: String,
+    /// Template of the announcement. Supports the `{image}`, `{tag}`, `{pull}` and `{url}` placeholders.
+    pub template: String,
+    /// Username to sign in to the registry with, paired with the `REGISTRY_TOKEN` secret.
+    /// Public images are read anonymously without one.
+    pub username: Option<String>,
+    /// Minutes between two checks of the tags
+    pub check_interval_minutes: u64,
+}
+
+/// A package registry of another language ecosystem, where bindings are published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Npm,
+    PyPi,
+}
+
+impl Ecosystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPi => "pypi",
+        }
+    }
+}
+
+/// Package release announcement configuration, for one ecosystem
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageConfig {
+    /// Announce the new versions of the package
+    pub enabled: bool,
+    pub ecosystem: Ecosystem,
+    /// Name of the package, such as `deltalake`
+    pub package: String,
+    /// Template of the announcement. Supports the `{package}`, `{version}`, `{install}` and `{url}` placeholders.
+    pub template: String,
+    /// Minutes between two checks of the latest version
+    pub check_interval_minutes: u64,
+}
+
+/// RSS and Atom feed announcement configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    /// Announce the new entries of the feeds
+    pub enabled: bool,
+    /// URLs of the RSS or Atom feeds, such as a blog's or `https://github.com/<owner>/<repo>/releases.atom`
+    pub urls: Vec<String>,
+    /// Template of the announcement. Supports the `{title}`, `{url}`, `{summary}` and `{feed}` placeholders.
+    pub template: String,
+    /// Minutes between two fetches of the feeds
+    pub check_interval_minutes: u64,
+}
+
+/// Contributor count milestone configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContributorMilestoneConfig {
+    /// Announce when the number of unique contributors crosses a milestone
+    pub enabled: bool,
+    /// Contributor counts announced once reached, in increasing order
+    pub milestones: Vec<u64>,
+    /// Template of the announcement. Supports the `{count}` and `{repo}` placeholders.
+    pub template: String,
+    /// Locale of the count
+    pub locale: Locale,
+    /// Minutes between two counts of the contributors
+    pub check_interval_minutes: u64,
+}
+
+/// Notable stargazer announcement configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct StargazerConfig {
+    /// Announce notable accounts starring the repository
+    pub enabled: bool,
+    /// Accounts with at least this many followers are notable
+    pub min_followers: u64,
+    /// Logins that are notable regardless of their followers
+    pub allowlist: Vec<String>,
+    /// Template of the announcement. Supports the `{username}`, `{followers}` and `{url}` placeholders.
+    pub template: String,
+    /// Locale of the follower count
+    pub locale: Locale,
+    /// Minutes between two checks of the stargazers
+    pub poll_interval_minutes: u64,
+}
+
+/// Projects (v2) board watcher configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProjectConfig {
+    /// Announce items of the project board moving to the done status
+    pub enabled: bool,
+    /// Organization or user owning the project
+    pub owner: String,
+    /// Number of the project, as shown in its URL
+    pub number: u64,
+    /// Name of the single select field holding the item status
+    pub status_field: String,
+    /// Status value meaning an item is complete
+    pub done_status: String,
+    /// Template of the announcement. Supports the `{title}` and `{url}` placeholders.
+    pub template: String,
+    /// Minutes between two polls of the project board
+    pub poll_interval_minutes: u64,
+}
+
+/// Priorities of the announcements waiting on the X rate limit
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueueConfig {
+    /// Base priority of every kind of announcement, higher goes first; unlisted kinds get 0
+    pub priorities: HashMap<AnnouncementKind, u32>,
+    /// How much the priority of a waiting announcement grows every minute
+    pub aging_per_minute: u32,
+}
+
+impl QueueConfig {
+    /// Returns the base priority of a kind of announcement.
+    pub fn priority(&self, kind: AnnouncementKind) -> u32 {
+        self.priorities.get(&kind).copied().unwrap_or_default()
+    }
+}
+
+/// Editing of posts, for X accounts with edit access
+#[derive(Debug, Clone, Deserialize)]
+pub struct EditConfig {
+    /// The account can edit its posts
+    pub enabled: bool,
+    /// Posts can be edited for this many minutes after being posted
+    pub window_minutes: u64,
+}
+
+/// Fallback chain used when the v2 tweets endpoint fails every retry
+#[derive(Debug, Clone, Deserialize)]
+pub struct FallbackConfig {
+    /// Endpoints tried in order after the v2 endpoint, empty to give up right away
+    pub chain: Vec<FallbackEndpoint>,
+    /// Base URL of the v1.1 API
+    pub v1_base_url: String,
+}
+
+/// Outgoing HTTP configuration shared by the GitHub and X clients
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpConfig {
+    /// Path of a PEM bundle of root certificates trusted on top of the system ones
+    pub ca_bundle: Option<String>,
+    /// Base URL of the GitHub API, for GitHub Enterprise or a mock server
+    pub github_api_url: Option<String>,
+    /// Base URL of the X v2 API
+    pub x_api_url: String,
+    /// Timeouts of the requests made to GitHub and X
+    pub timeout: TimeoutConfig,
+}
+
+/// Publishing of the announcements to a message broker, and consuming of GitHub events from it
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerConfig {
+    /// Prefix of the subjects announcements are published on, followed by their kind
+    pub subject_prefix: String,
+
+    /// Subject GitHub events are consumed from, alongside the webhook route
+    pub ingest_subject: Option<String>,
+
+    /// Queue group sharing the consumed events between instances
+    pub ingest_queue_group: Option<String>,
+
+    /// URLs every announcement is posted to as JSON, signed with the `OUTBOUND_WEBHOOK_SECRET` secret
+    pub webhook_urls: Vec<String>,
+}
+
+/// Mirroring of the announcements to a Matrix room
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatrixConfig {
+    /// Post the announcements to the room as well
+    pub enabled: bool,
+    /// Base URL of the homeserver, such as `https://matrix.org`
+    pub homeserver_url: String,
+    /// Id of the room, such as `!abcdef:matrix.org`, which the account of `MATRIX_ACCESS_TOKEN` has joined
+    pub room_id: String,
+    /// Kinds of announcements posted to the room, all of them when empty
+    pub kinds: Vec<AnnouncementKind>,
+}
+
+impl MatrixConfig {
+    /// Returns true if announcements of this kind are posted to the room.
+    pub fn posts(&self, kind: AnnouncementKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// Mirroring of the announcements to a Microsoft Teams channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamsConfig {
+    /// Post the announcements to the channel of `TEAMS_WEBHOOK_URL` as well
+    pub enabled: bool,
+    /// Kinds of announcements posted to the channel
+    pub kinds: Vec<AnnouncementKind>,
+}
+
+/// Relaying of the announcements to an IRC channel
+#[derive(Debug, Clone, Deserialize)]
+pub struct IrcConfig {
+    /// Relay the announcements to the channel as well
+    pub enabled: bool,
+    /// Host name of the IRC server, such as `irc.libera.chat`
+    pub server: String,
+    pub port: u16,
+    /// Connect over TLS, which most networks offer on port 6697
+    pub tls: bool,
+    /// Nickname of the bot, registered with the `IRC_PASSWORD` secret when set
+    pub nickname: String,
+    /// Channel the announcements are relayed to, such as `#delta-rs`
+    pub channel: String,
+    /// Kinds of announcements relayed to the channel, all of them when empty
+    pub kinds: Vec<AnnouncementKind>,
+    /// Time between two messages sent to the server, so it doesn't disconnect the bot for flooding
+    pub message_interval_ms: u64,
+}
+
+impl IrcConfig {
+    /// Returns true if announcements of this kind are relayed to the channel.
+    pub fn posts(&self, kind: AnnouncementKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// Posting of the release announcements to a LinkedIn organization page, for the channels routed to the `linkedin` sink
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkedInConfig {
+    /// Post to the organization page
+    pub enabled: bool,
+    /// Numeric id of the organization, as in `https://www.linkedin.com/company/<id>/admin/`
+    pub organization_id: String,
+    /// Client id of the OAuth2 application, paired with the `LINKEDIN_CLIENT_SECRET` secret
+    pub client_id: String,
+    /// Template of the release announcement, longer than on X.
+    /// Supports the same placeholders as the release templates.
+    pub template: String,
+}
+
+/// Archiving of the audit log and state to an S3 compatible bucket
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArchiveConfig {
+    /// Upload snapshots of the audit log and state periodically
+    pub enabled: bool,
+    /// Endpoint of the object storage, such as `https://s3.eu-west-1.amazonaws.com`
+    pub endpoint: String,
+    /// Bucket the snapshots are uploaded to, addressed in the path of the endpoint
+    pub bucket: String,
+    /// Region requests are signed for
+    pub region: String,
+    /// Access key id, paired with the `ARCHIVE_S3_SECRET_ACCESS_KEY` secret
+    pub access_key_id: String,
+    /// Prefix of the keys, followed by the time of the snapshot
+    pub prefix: String,
+    /// Minutes between two snapshots
+    pub interval_minutes: u64,
+}
+
+/// Watching of a GitLab project alongside the GitHub repository
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitLabConfig {
+    /// Announce the contributors and releases of the GitLab project, reported by its webhooks
+    pub enabled: bool,
+    /// Base URL of the GitLab API, for self-managed instances
+    pub api_url: String,
+    /// Path of the project, such as `delta-io/delta-rs`
+    pub project: String,
+}
+
+/// Watching of a Gitea or Forgejo repository, such as a mirror on Codeberg
+#[derive(Debug, Clone, Deserialize)]
+pub struct GiteaConfig {
+    /// Announce the contributors and releases of the repository, reported by its webhooks
+    pub enabled: bool,
+    /// Base URL of the instance, such as `https://codeberg.org`
+    pub url: String,
+    /// The repository, as `owner/name`
+    pub repo: String,
+}
+
+/// Audit log configuration
+#[derive(Debug, Deserialize)]
+pub struct AuditConfig {
+    /// Path of the JSON lines file recording every announcement
+    pub path: String,
+}
+
+impl AuditConfig {
+    /// Loads the audit log configuration on its own, for commands that only read the log.
+    pub fn from_env() -> Self {
+        Self {
+            path: var("AUDIT_LOG_PATH")
+                .unwrap_or_else(|_| "audit.jsonl".to_string()),
+        }
+    }
+}
+
+/// Sensitive configuration that should never be logged or displayed
+#[derive(Debug,Deserialize)]
+pub struct Secrets {
+    /// GitHub personal access token for API authentication, unused when authenticating as a GitHub App
+    github_token: String,
+
+    /// Private key of the GitHub App, in PEM
+    github_app_private_key: Option<String>,
+        
+    /// X API key for API authentication
+    x_api_key: String,
+    
+    /// X API secret for API authentication
+    x_api_secret: String,
+    
+    /// X access token for API authentication
+    x_access_token: String,
+    
+    /// X access secret for API authentication
+    x_access_secret: String,
+
+    /// Bearer token required to confirm pending collaborator announcements
+    member_confirm_token: Option<String>,
+
+    /// Bearer token required to export the announcement history
+    history_token: Option<String>,
+
+    /// URL of the NATS server announcements are published to, which may hold credentials
+    nats_url: Option<String>,
+
+    /// Secret access key of the bucket the state is archived to
+    archive_secret_access_key: Option<String>,
+
+    /// GitLab access token for API authentication
+    gitlab_token: Option<String>,
+
+    /// Secret the GitHub webhooks are signed with in `X-Hub-Signature-256`
+    webhook_secret: Option<String>,
+
+    /// Secret token GitLab sends with the webhooks of the project
+    gitlab_webhook_secret: Option<String>,
+
+    /// Gitea access token for API authentication, only needed for private repositories
+    gitea_token: Option<String>,
+
+    /// Secret the webhooks of the Gitea repository are signed with
+    gitea_webhook_secret: Option<String>,
+
+    /// Access token of the container registry, such as a Docker Hub personal access token
+    registry_token: Option<String>,
+
+    /// Access token of the Matrix account posting to the room
+    matrix_access_token: Option<String>,
+
+    /// Client secret of the LinkedIn OAuth2 application
+    linkedin_client_secret: Option<String>,
+
+    /// Refresh token granted to the LinkedIn application, exchanged for access tokens
+    linkedin_refresh_token: Option<String>,
+
+    /// LinkedIn access token to start with, refreshed once it expires
+    linkedin_access_token: Option<String>,
+
+    /// URL of the incoming webhook of the Teams channel, which anyone knowing it can post to
+    teams_webhook_url: Option<String>,
+
+    /// Password of the SMTP server the email digest is sent through
+    smtp_password: Option<String>,
+
+    /// Password of the IRC server, which networks such as Libera.Chat check against the account of the nickname
+    irc_password: Option<String>,
+
+    /// Secret the deliveries to the outbound webhooks are signed with
+    outbound_webhook_secret: Option<String>,
+}
+
+impl Display for Secrets {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+/// Secret tokens getters in a controlled manner
+impl Secrets {
+    pub fn github_token(&self) -> &str {
+        &self.github_token
+    }
+
+    pub fn github_app_private_key(&self) -> Option<&str> {
+        self.github_app_private_key.as_deref()
+    }
+
+    pub fn x_api_key(&self) -> &str {
+        &self.x_api_key
+    }
+
+    pub fn x_api_secret(&self) -> &str {
+        &self.x_api_secret
+    }
+
+    pub fn x_access_token(&self) -> &str {
+        &self.x_access_token
+    }
+
+    pub fn x_access_secret(&self) -> &str {
+        &self.x_access_secret
+    }
+
+    pub fn member_confirm_token(&self) -> Option<&str> {
+        self.member_confirm_token.as_deref()
+    }
+
+    pub fn history_token(&self) -> Option<&str> {
+        self.history_token.as_deref()
+    }
+
+    pub fn nats_url(&self) -> Option<&str> {
+        self.nats_url.as_deref()
+    }
+
+    pub fn archive_secret_access_key(&self) -> Option<&str> {
+        self.archive_secret_access_key.as_deref()
+    }
+
+    pub fn gitlab_token(&self) -> Option<&str> {
+        self.gitlab_token.as_deref()
+    }
+
+    pub fn webhook_secret(&self) -> Option<&str> {
+        self.webhook_secret.as_deref()
+    }
+
+    pub fn gitlab_webhook_secret(&self) -> Option<&str> {
+        self.gitlab_webhook_secret.as_deref()
+    }
+
+    pub fn gitea_token(&self) -> Option<&str> {
+        self.gitea_token.as_deref()
+    }
+
+    pub fn gitea_webhook_secret(&self) -> Option<&str> {
+        self.gitea_webhook_secret.as_deref()
+    }
+
+    pub fn registry_token(&self) -> Option<&str> {
+        self.registry_token.as_deref()
+    }
+
+    pub fn matrix_access_token(&self) -> Option<&str> {
+        self.matrix_access_token.as_deref()
+    }
+
+    pub fn linkedin_client_secret(&self) -> Option<&str> {
+        self.linkedin_client_secret.as_deref()
+    }
+
+    pub fn linkedin_refresh_token(&self) -> Option<&str> {
+        self.linkedin_refresh_token.as_deref()
+    }
+
+    pub fn linkedin_access_token(&self) -> Option<&str> {
+        self.linkedin_access_token.as_deref()
+    }
+
+    pub fn teams_webhook_url(&self) -> Option<&str> {
+        self.teams_webhook_url.as_deref()
+    }
+
+    pub fn smtp_password(&self) -> Option<&str> {
+        self.smtp_password.as_deref()
+    }
+
+    pub fn irc_password(&self) -> Option<&str> {
+        self.irc_password.as_deref()
+    }
+
+    pub fn outbound_webhook_secret(&self) -> Option<&str> {
+        self.outbound_webhook_secret.as_deref()
+    }
+
+    /// Validate all secrets, the X credentials only when announcing on X and the GitHub token
+    /// only without the private key of a GitHub App
+    pub fn validate(&self, announcer: Announcer) -> anyhow::Result<()> {
+        if self.github_app_private_key.is_none() {
+            if self.github_token.is_empty() {
+                return Err(anyhow::anyhow!("GITHUB_TOKEN must be set"));
+            }
+            if self.github_token.len() != 40 {
+                return Err(anyhow::anyhow!("GitHub token must be exactly 40 characters long"));
+            }
+        }
+        if announcer == Announcer::X {
+            if self.x_api_key.is_empty() {
+                return Err(anyhow::anyhow!("X_API_KEY must be set"));
+            }
+            if self.x_api_key.len() < 25 {
+                return Err(anyhow::anyhow!("X_API_KEY must be at least 32 characters long"));
+            }
+            if self.x_api_secret.is_empty() {
+                return Err(anyhow::anyhow!("X_API_SECRET must be set"));
+            }
+            if self.x_api_secret.len() < 32 {
+                return Err(anyhow::anyhow!("X_API_SECRET must be at least 32 characters long"));
+            }
+            if self.x_access_token.is_empty() {
+                return Err(anyhow::anyhow!("X_ACCESS_TOKEN must be set"));
+            }
+            if self.x_access_token.len() < 32 {
+                return Err(anyhow::anyhow!("X_ACCESS_TOKEN must be at least 32 characters long"));
+            }
+            if self.x_access_secret.is_empty() {
+                return Err(anyhow::anyhow!("X_ACCESS_SECRET must be set"));
+            }
+            if self.x_access_secret.len() < 32 {
+                return Err(anyhow::anyhow!("X_ACCESS_SECRET must be at least 32 characters long"));
+            }
+        }
+        if self.member_confirm_token.as_ref().is_some_and(|token| token.len() < 32) {
+            return Err(anyhow::anyhow!("MEMBER_CONFIRM_TOKEN must be at least 32 characters long"));
+        }
+        if self.history_token.as_ref().is_some_and(|token| token.len() < 32) {
+            return Err(anyhow::anyhow!("HISTORY_TOKEN must be at least 32 characters long"));
+        }
+        if self.webhook_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            return Err(anyhow::anyhow!("WEBHOOK_SECRET must be at least 32 characters long"));
+        }
+        if self.gitlab_webhook_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            return Err(anyhow::anyhow!("GITLAB_WEBHOOK_SECRET must be at least 32 characters long"));
+        }
+        if self.gitea_webhook_secret.as_ref().is_some_and(|secret| secret.len() < 32) {
+            return Err(anyhow::anyhow!("GITEA_WEBHOOK_SECRET must be at least 32 characters long"));
+        }
+        if let Some(url) = &self.nats_url {
+            let url = reqwest::Url::parse(url).context("NATS_URL must be a URL")?;
+            if url.scheme() != "nats" || url.host_str().is_none() {
+                return Err(anyhow::anyhow!("NATS_URL must be a nats://host:port URL"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Configuration structure for the application
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    /// Current runtime environment
+    // #[serde(default)]
+    pub environment: Environment,
+
+    /// Where the announcements are made
+    pub announcer: Announcer,
+
+    /// Server configuration
+    // #[serde(default)]
+    pub server: ServerConfig,
+
+    /// Rate limiting configuration
+    // #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Admin route security configuration
+    pub security: SecurityConfig,
+
+    /// Retry configuration
+    // #[serde(default)]
+    pub retry: RetryConfig,
+
+    /// Backoff of failing scheduled polls
+    pub poll_backoff: PollBackoffConfig,
+
+    /// Pacing of the background scans against GitHub's rate limit
+    pub request_budget: RequestBudgetConfig,
+
+    /// Branches whose pushes are checked for new contributors
+    pub branches: Vec<BranchConfig>,
+
+    /// Release announcement configuration
+    pub release: ReleaseConfig,
+
+    /// New contributor announcement configuration
+    pub contributor: ContributorConfig,
+
+    /// New collaborator announcement configuration
+    pub member: MemberConfig,
+
+    /// Publicized repository announcement configuration
+    pub public_repo: PublicRepoConfig,
+
+    /// Tag announcement configuration
+    pub tags: TagConfig,
+
+    /// Breaking change announcement configuration
+    pub breaking_changes: BreakingChangeConfig,
+
+    /// Labeled issue announcement configuration
+    pub issue_labels: IssueLabelConfig,
+
+    /// Long-awaited fix announcement configuration
+    pub long_awaited_fixes: LongAwaitedFixConfig,
+
+    /// Milestone teaser scheduler configuration
+    pub teaser: TeaserConfig,
+
+    /// Weekly merged pull request digest configuration
+    pub digest: DigestConfig,
+
+    /// Monthly repository stats configuration
+    pub monthly_stats: MonthlyStatsConfig,
+
+    /// Repository anniversary configuration
+    pub anniversary: AnniversaryConfig,
+
+    /// Closed issue milestone configuration
+    pub issue_milestones: IssueMilestoneConfig,
+
+    /// Star count milestone configuration
+    pub star_milestones: StarMilestoneConfig,
+
+    /// Release download milestone configuration
+    pub download_milestones: DownloadMilestoneConfig,
+
+    /// Contributor count milestone configuration
+    pub contributor_milestones: ContributorMilestoneConfig,
+
+    /// Container image publish announcement configuration
+    pub images: ImageConfig,
+
+    /// RSS and Atom feed announcement configuration
+    pub feeds: FeedConfig,
+
+    /// npm package release announcement configuration
+    pub npm: PackageConfig,
+
+    /// PyPI package release announcement configuration
+    pub pypi: PackageConfig,
+
+    /// GitHub Sponsors announcement configuration
+    pub sponsors: SponsorsConfig,
+
+    /// Notable stargazer announcement configuration
+    pub stargazers: StargazerConfig,
+
+    /// Projects (v2) board watcher configuration
+    pub project: ProjectConfig,
+
+    /// Announcement queue configuration
+    pub queue: QueueConfig,
+
+    /// X posting fallback configuration
+    pub fallback: FallbackConfig,
+
+    /// X post editing configuration
+    pub edit: EditConfig,
+
+    /// Outgoing HTTP configuration
+    pub http: HttpConfig,
+
+    /// Audit log configuration
+    pub audit: AuditConfig,
+
+    /// Message broker configuration
+    pub broker: BrokerConfig,
+
+    /// Matrix room configuration
+    pub matrix: MatrixConfig,
+
+    /// LinkedIn organization page configuration
+    pub linkedin: LinkedInConfig,
+
+    /// Microsoft Teams channel configuration
+    pub teams: TeamsConfig,
+
+    /// IRC channel configuration
+    pub irc: IrcConfig,
+
+    /// Public API configuration
+    pub public_api: PublicApiConfig,
+
+    /// Atom feed configuration
+    pub atom_feed: AtomFeedConfig,
+
+    /// Email digest configuration
+    pub email_digest: EmailDigestConfig,
+
+    /// Archive configuration
+    pub archive: ArchiveConfig,
+
+    /// GitLab project configuration
+    pub gitlab: GitLabConfig,
+
+    /// Gitea or Forgejo repository configuration
+    pub gitea: GiteaConfig,
+
+    /// Sensitive configuration values
+    pub secrets: Secrets,
+
+    /// Authentication as a GitHub App
+    pub github_app: GitHubAppConfig,
+
+    /// Polling of the repositories with GraphQL
+    pub github_poll: GitHubPollConfig,
+    
+    /// GitHub repository owner (username or organization)
+    pub repo_owner: String,
+    
+    /// GitHub repository name
+    pub repo_name: String,
+
+    /// Other GitHub repositories announced by the same bot, such as the other crates of the project
+    pub repositories: Vec<RepositoryConfig>,
+
+    /// Announcement of the other repositories of the organization
+    pub organization: OrganizationConfig,
+
+    /// Log level for the application
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+/// Loads the settings of a release channel from its `RELEASE_<CHANNEL>_*` variables
+fn load_channel_config(channel: ReleaseChannel) -> ChannelConfig {
+    let prefix = format!("RELEASE_{}", channel.as_str().to_uppercase());
+    let (default_pattern, default_template) = match channel {
+        ReleaseChannel::Stable => (
+            None,
+            "New release ({tag}) of Delta out! 🎉\nLink to release notes: {url}",
+        ),
+        ReleaseChannel::Beta => (
+            Some("(?i)(alpha|beta|rc|pre)"),
+            "New pre-release ({tag}) of Delta out for testing! 🧪\nLink to release notes: {url}",
+        ),
+        ReleaseChannel::Nightly => (
+            Some("(?i)(nightly|dev)"),
+            "New nightly build ({tag}) of Delta available.\nLink: {url}",
+        ),
+    };
+
+    ChannelConfig {
+        channel,
+        tag_pattern: var(format!("{}_TAG_PATTERN", prefix))
+            .ok()
+            .or_else(|| default_pattern.map(str::to_string))
+            .filter(|pattern| !pattern.is_empty()),
+        template: var(format!("{}_TEMPLATE", prefix))
+            .map(|template| unescape(&template))
+            .unwrap_or_else(|_| default_template.to_string()),
+        hashtags: list_var(&format!("{}_HASHTAGS", prefix), "")
+            .into_iter()
+            .map(|hashtag| hashtag.trim_start_matches('#').to_string())
+            .collect(),
+        sinks: list_var(&format!("{}_SINKS", prefix), "x"),
+    }
+}
+
+/// Loads the settings of the package releases of an ecosystem from its `NPM_*` or `PYPI_*` variables
+fn load_package_config(ecosystem: Ecosystem) -> anyhow::Result<PackageConfig> {
+    let prefix = ecosystem.as_str().to_uppercase();
+    let default_template = match ecosystem {
+        Ecosystem::Npm => "📦 {package} {version} is out on npm!\n{install}\n{url}",
+        Ecosystem::PyPi => "🐍 {package} {version} is out on PyPI!\n{install}\n{url}",
+    };
+
+    Ok(PackageConfig {
+        enabled: var(format!("{}_ENABLED", prefix))
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .with_context(|| format!("{}_ENABLED must be true or false", prefix))?,
+        ecosystem,
+        package: var(format!("{}_PACKAGE", prefix)).unwrap_or_default(),
+        template: var(format!("{}_TEMPLATE", prefix))
+            .map(|template| unescape(&template))
+            .unwrap_or_else(|_| default_template.to_string()),
+        check_interval_minutes: var(format!("{}_CHECK_INTERVAL_MINUTES", prefix))
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .with_context(|| format!("{}_CHECK_INTERVAL_MINUTES must be a positive integer", prefix))?,
+    })
+}
+
+/// Reads a comma separated list, ignoring blank items
+fn list_var(name: &str, default: &str) -> Vec<String> {
+    var(name)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parses a network such as `10.0.0.0/8`, or a single address
+fn parse_network(network: &str) -> anyhow::Result<IpNet> {
+    network
+        .parse()
+        .or_else(|_| network.parse::<IpAddr>().map(IpNet::from))
+        .with_context(|| format!("Invalid network in RATE_LIMIT_ALLOWLIST: {}", network))
+}
+
+/// Parses the priority of a kind of announcement, e.g. `release=100`
+fn parse_priority(priority: &str) -> anyhow::Result<(AnnouncementKind, u32)> {
+    let (kind, value) = priority
+        .split_once('=')
+        .with_context(|| format!("Invalid queue priority {}, expected kind=priority", priority))?;
+    let kind = serde_json::from_value(serde_json::Value::String(kind.trim().to_string()))
+        .with_context(|| format!("Unknown announcement kind in QUEUE_PRIORITIES: {}", kind))?;
+    let value = value
+        .trim()
+        .parse()
+        .context("QUEUE_PRIORITIES values must be positive integers")?;
+    Ok((kind, value))
+}
+
+/// Loads the timeouts of an API, e.g. `GITHUB_TIMEOUT_READ_SECONDS` falling back to `TIMEOUT_READ_SECONDS`.
+fn load_api_timeouts(api: &str) -> anyhow::Result<ApiTimeoutConfig> {
+    let seconds = |name: &str, default: &str| -> anyhow::Result<u64> {
+        let specific = format!("{}_TIMEOUT_{}_SECONDS", api, name);
+        var(&specific)
+            .or_else(|_| var(format!("TIMEOUT_{}_SECONDS", name)))
+            .unwrap_or_else(|_| default.to_string())
+            .parse()
+            .with_context(|| format!("{} must be a positive integer", specific))
+    };
+
+    Ok(ApiTimeoutConfig {
+        connect_seconds: seconds("CONNECT", "10")?,
+        read_seconds: seconds("READ", "30")?,
+        write_seconds: seconds("WRITE", "30")?,
+        total_seconds: seconds("TOTAL", "60")?,
+    })
+}
+
+/// Name of a per-branch environment variable, e.g. `BRANCH_V1_X_CONTRIBUTOR_TEMPLATE` for `v1.x`
+fn branch_var(branch: &str, setting: &str) -> String {
+    let branch: String = branch
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("BRANCH_{}_{}", branch, setting)
+}
+
+/// Name of a per-label environment variable, e.g. `LABEL_GOOD_FIRST_ISSUE_TEMPLATE` for `good first issue`
+fn label_var(label: &str, setting: &str) -> String {
+    let label: String = label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("LABEL_{}_{}", label, setting)
+}
+
+impl Config {
+    /// Loads configuration from environment variables
+    ///
+    /// # Returns
+    /// A Result containing the Config if successful, or an error if any required
+    /// environment variables are missing
+    pub fn from_env() -> anyhow::Result<Self> {
+        dotenv::dotenv().ok();
+
+        // Load environment-specific settings
+        let environment = var("ENVIRONMENT")
+            .unwrap_or_else(|_| "development".to_string())
+            .parse()?;
+
+        // The X credentials are only needed to post on X
+        let announcer: Announcer = var("ANNOUNCER")
+            .unwrap_or_else(|_| "x".to_string())
+            .parse()?;
+        let x_var = |name: &str| match announcer {
+            Announcer::X => var(name).with_context(|| format!("{} must be set", name)),
+            Announcer::Console => Ok(var(name).unwrap_or_default()),
+        };
+
+        // Load secrets first and validate them
+        let secrets = Secrets {
+            github_token: var("GITHUB_TOKEN")
+                .unwrap_or_default(),
+            // Line breaks can be written as \n, for environments without multiline values
+            github_app_private_key: var("GITHUB_APP_PRIVATE_KEY")
+                .ok()
+                .filter(|key| !key.is_empty())
+                .map(|key| unescape(&key)),
+            x_api_key: x_var("X_API_KEY")?,
+            x_api_secret: x_var("X_API_SECRET")?,
+            x_access_token: x_var("X_ACCESS_TOKEN")?,
+            x_access_secret: x_var("X_ACCESS_SECRET")?,
+            member_confirm_token: var("MEMBER_CONFIRM_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            history_token: var("HISTORY_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            nats_url: var("NATS_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            archive_secret_access_key: var("ARCHIVE_S3_SECRET_ACCESS_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+            gitlab_token: var("GITLAB_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            webhook_secret: var("WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            gitlab_webhook_secret: var("GITLAB_WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            gitea_token: var("GITEA_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            gitea_webhook_secret: var("GITEA_WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            registry_token: var("REGISTRY_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            matrix_access_token: var("MATRIX_ACCESS_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            linkedin_client_secret: var("LINKEDIN_CLIENT_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            linkedin_refresh_token: var("LINKEDIN_REFRESH_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            linkedin_access_token: var("LINKEDIN_ACCESS_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            teams_webhook_url: var("TEAMS_WEBHOOK_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            smtp_password: var("SMTP_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            irc_password: var("IRC_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            outbound_webhook_secret: var("OUTBOUND_WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+        };
+        secrets.validate(announcer)?;
+
+        // Load GitHub App configuration
+        let github_app = GitHubAppConfig {
+            app_id: var("GITHUB_APP_ID")
+                .ok()
+                .filter(|id| !id.is_empty())
+                .map(|id| id.parse())
+                .transpose()
+                .context("GITHUB_APP_ID must be a positive integer")?,
+            installation_id: var("GITHUB_APP_INSTALLATION_ID")
+                .ok()
+                .filter(|id| !id.is_empty())
+                .map(|id| id.parse())
+                .transpose()
+                .context("GITHUB_APP_INSTALLATION_ID must be a positive integer")?,
+        };
+
+        // Load GitHub polling configuration
+        let github_poll = GitHubPollConfig {
+            enabled: var("GITHUB_POLL_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("GITHUB_POLL_ENABLED must be true or false")?,
+            interval_seconds: var("GITHUB_POLL_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("GITHUB_POLL_INTERVAL_SECONDS must be a positive integer")?,
+        };
+
+        // Load server configuration
+        let host = var("SERVER_HOST")
+            .unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port: u16 = var("SERVER_PORT")
+            .unwrap_or_else(|_| "7878".to_string())
+            .parse()
+            .context("SERVER_PORT must be a valid port number")?;
+        // IPv6 hosts need brackets to be told apart from the port
+        let default_listen = if host.contains(':') && !host.starts_with('[') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        };
+        let server = ServerConfig {
+            listen: list_var("SERVER_LISTEN", &default_listen)
+                .iter()
+                .map(|address| address.parse())
+                .collect::<anyhow::Result<_>>()?,
+            host,
+            port,
+            webhook_path: var("WEBHOOK_PATH")
+                .unwrap_or_else(|_| "/webhook".to_string()),
+            max_body_bytes: var("WEBHOOK_MAX_BODY_BYTES")
+                .unwrap_or_else(|_| "5242880".to_string())
+                .parse()
+                .context("WEBHOOK_MAX_BODY_BYTES must be a positive integer")?,
+            accept_form_payloads: var("WEBHOOK_ACCEPT_FORM")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("WEBHOOK_ACCEPT_FORM must be true or false")?,
+            verify_signatures: var("WEBHOOK_VERIFY_SIGNATURE")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .context("WEBHOOK_VERIFY_SIGNATURE must be true or false")?,
+        };
+
+        // Load rate limit configuration
+        let rate_limit = RateLimitConfig {
+            max_requests: var("RATE_LIMIT_MAX_REQUESTS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .context("RATE_LIMIT_MAX_REQUESTS must be a positive integer")?,
+            window_seconds: var("RATE_LIMIT_WINDOW_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .context("RATE_LIMIT_WINDOW_SECONDS must be a positive integer")?,
+            allow_github_hooks: var("RATE_LIMIT_ALLOW_GITHUB_HOOKS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .context("RATE_LIMIT_ALLOW_GITHUB_HOOKS must be true or false")?,
+            allowlist: list_var("RATE_LIMIT_ALLOWLIST", "")
+                .iter()
+                .map(|network| parse_network(network))
+                .collect::<anyhow::Result<_>>()?,
+        };
+
+        // Load admin route security configuration
+        let security = SecurityConfig {
+            cors_allowed_origins: list_var("CORS_ALLOWED_ORIGINS", ""),
+            content_security_policy: var("CONTENT_SECURITY_POLICY")
+                .unwrap_or_else(|_| "default-src 'none'; frame-ancestors 'none'".to_string()),
+            hsts_max_age_seconds: var("HSTS_MAX_AGE_SECONDS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("HSTS_MAX_AGE_SECONDS must be a positive integer")?,
+        };
+
+        // Load retry configuration
+        let retry = RetryConfig {
+            max_attempts: var("RETRY_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .context("RETRY_MAX_ATTEMPTS must be a positive integer")?,
+            initial_delay_ms: var("RETRY_INITIAL_DELAY_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .context("RETRY_INITIAL_DELAY_MS must be a positive integer")?,
+            max_delay_ms: var("RETRY_MAX_DELAY_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .context("RETRY_MAX_DELAY_MS must be a positive integer")?,
+            budget_per_minute: var("RETRY_BUDGET_PER_MINUTE")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("RETRY_BUDGET_PER_MINUTE must be a positive integer")?,
+        };
+
+        // Load poll backoff configuration
+        let poll_backoff = PollBackoffConfig {
+            initial_seconds: var("POLL_BACKOFF_INITIAL_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("POLL_BACKOFF_INITIAL_SECONDS must be a positive integer")?,
+            max_seconds: var("POLL_BACKOFF_MAX_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .context("POLL_BACKOFF_MAX_SECONDS must be a positive integer")?,
+        };
+
+        // Load background request pacing configuration
+        let request_budget = RequestBudgetConfig {
+            pacing: var("GITHUB_BACKGROUND_PACING")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .context("GITHUB_BACKGROUND_PACING must be true or false")?,
+            reserve: var("GITHUB_RATE_LIMIT_RESERVE")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .context("GITHUB_RATE_LIMIT_RESERVE must be a positive integer")?,
+        };
+
+        // Load timeout configuration, the TIMEOUT_* values apply to both APIs unless overridden
+        let timeout = TimeoutConfig {
+            github: load_api_timeouts("GITHUB")?,
+            x: load_api_timeouts("X")?,
+        };
+
+        // Load monitored branches, each with its own announcement template
+        let branches = list_var("BRANCHES", "main,master")
+            .into_iter()
+            .map(|name| BranchConfig {
+                contributor_template: var(branch_var(&name, "CONTRIBUTOR_TEMPLATE"))
+                    .map(|template| unescape(&template))
+                    .unwrap_or_else(|_| DEFAULT_CONTRIBUTOR_TEMPLATE.to_string()),
+                name,
+            })
+            .collect::<Vec<_>>();
+
+        // Load the other repositories, listed as owner/name or owner/name/branch, a repository
+        // listed with several branches monitors all of them
+        let mut repositories: Vec<RepositoryConfig> = Vec::new();
+        for entry in list_var("REPOSITORIES", "") {
+            let mut parts = entry.splitn(3, '/');
+            let (Some(owner), Some(name)) = (parts.next(), parts.next()) else {
+                return Err(anyhow::anyhow!("REPOSITORIES must list repositories as owner/name or owner/name/branch, got {}", entry));
+            };
+            let branch = parts.next().map(|branch| branches.iter()
+                .find(|configured| configured.name == branch)
+                .cloned()
+                .unwrap_or_else(|| BranchConfig {
+                    name: branch.to_string(),
+                    contributor_template: var(branch_var(branch, "CONTRIBUTOR_TEMPLATE"))
+                        .map(|template| unescape(&template))
+                        .unwrap_or_else(|_| DEFAULT_CONTRIBUTOR_TEMPLATE.to_string()),
+                }));
+            let repository = match repositories.iter_mut().find(|repository| repository.owner == owner && repository.name == name) {
+                Some(repository) => repository,
+                None => {
+                    repositories.push(RepositoryConfig {
+                        owner: owner.to_string(),
+                        name: name.to_string(),
+                        branches: Vec::new(),
+                    });
+                    repositories.last_mut().expect("just pushed")
+                }
+            };
+            if let Some(branch) = branch {
+                repository.branches.push(branch);
+            }
+        }
+        for repository in &mut repositories {
+            if repository.branches.is_empty() {
+                repository.branches = branches.clone();
+            }
+        }
+
+        // Load organization configuration
+        let organization = OrganizationConfig {
+            enabled: var("ORG_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ORG_ENABLED must be true or false")?,
+            name: var("ORG_NAME")
+                .ok()
+                .filter(|name| !name.is_empty())
+                .or_else(|| var("REPO_OWNER").ok())
+                .unwrap_or_default(),
+            include: var("ORG_INCLUDE").ok().filter(|pattern| !pattern.is_empty()),
+            exclude: var("ORG_EXCLUDE").ok().filter(|pattern| !pattern.is_empty()),
+            include_forks: var("ORG_INCLUDE_FORKS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ORG_INCLUDE_FORKS must be true or false")?,
+            discovery_interval_minutes: var("ORG_DISCOVERY_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("ORG_DISCOVERY_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load release announcement configuration
+        let release = ReleaseConfig {
+            edited_policy: var("RELEASE_EDITED_POLICY")
+                .unwrap_or_else(|_| "ignore".to_string())
+                .parse()?,
+            edit_window_minutes: var("RELEASE_EDIT_WINDOW_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("RELEASE_EDIT_WINDOW_MINUTES must be a positive integer")?,
+            notes_min_change_percent: var("RELEASE_NOTES_MIN_CHANGE_PERCENT")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .context("RELEASE_NOTES_MIN_CHANGE_PERCENT must be a positive integer")?,
+            retract_on_delete: var("RELEASE_RETRACT_ON_DELETE")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .context("RELEASE_RETRACT_ON_DELETE must be true or false")?,
+            prereleases: var("RELEASE_PRERELEASES")
+                .unwrap_or_else(|_| "include".to_string())
+                .parse()?,
+            channels: ReleaseChannel::ALL
+                .iter()
+                .map(|&channel| load_channel_config(channel))
+                .collect(),
+            major: MajorReleaseConfig {
+                enabled: var("RELEASE_MAJOR_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .context("RELEASE_MAJOR_ENABLED must be true or false")?,
+                zero_minor_is_major: var("RELEASE_MAJOR_ZERO_MINOR")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .context("RELEASE_MAJOR_ZERO_MINOR must be true or false")?,
+                template: var("RELEASE_MAJOR_TEMPLATE")
+                    .map(|template| unescape(&template))
+                    .unwrap_or_else(|_| {
+                        "Delta {tag} is here! 🚀 A new major release, with highlights in the thread below 🧵\nRelease notes: {url}".to_string()
+                    }),
+                image_path: var("RELEASE_MAJOR_IMAGE_PATH")
+                    .ok()
+                    .filter(|path| !path.is_empty()),
+                highlights: var("RELEASE_MAJOR_HIGHLIGHTS")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .context("RELEASE_MAJOR_HIGHLIGHTS must be a positive integer")?,
+            },
+            opengraph_image: var("RELEASE_OPENGRAPH_IMAGE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("RELEASE_OPENGRAPH_IMAGE must be true or false")?,
+            asset_wait: AssetWaitConfig {
+                min_assets: var("RELEASE_WAIT_MIN_ASSETS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .context("RELEASE_WAIT_MIN_ASSETS must be a positive integer")?,
+                patterns: list_var("RELEASE_WAIT_ASSET_PATTERNS", ""),
+                timeout_minutes: var("RELEASE_WAIT_TIMEOUT_MINUTES")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("RELEASE_WAIT_TIMEOUT_MINUTES must be a positive integer")?,
+                poll_interval_seconds: var("RELEASE_WAIT_POLL_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .context("RELEASE_WAIT_POLL_SECONDS must be a positive integer")?,
+            },
+            ci_wait: CiWaitConfig {
+                enabled: var("RELEASE_WAIT_CI")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .context("RELEASE_WAIT_CI must be true or false")?,
+                checks: list_var("RELEASE_WAIT_CI_CHECKS", ""),
+                timeout_minutes: var("RELEASE_WAIT_CI_TIMEOUT_MINUTES")
+                    .unwrap_or_else(|_| "120".to_string())
+                    .parse()
+                    .context("RELEASE_WAIT_CI_TIMEOUT_MINUTES must be a positive integer")?,
+                poll_interval_seconds: var("RELEASE_WAIT_CI_POLL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("RELEASE_WAIT_CI_POLL_SECONDS must be a positive integer")?,
+            },
+            docs: DocsDeployConfig {
+                enabled: var("DOCS_DEPLOY_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .context("DOCS_DEPLOY_ENABLED must be true or false")?,
+                environment: var("DOCS_DEPLOY_ENVIRONMENT").unwrap_or_else(|_| "github-pages".to_string()),
+                url: var("DOCS_URL")
+                    .ok()
+                    .filter(|url| !url.is_empty()),
+                template: var("DOCS_DEPLOY_TEMPLATE")
+                    .map(|template| unescape(&template))
+                    .unwrap_or_else(|_| "📚 The docs are up to date with Delta {tag}: {url}".to_string()),
+                window_hours: var("DOCS_DEPLOY_WINDOW_HOURS")
+                    .unwrap_or_else(|_| "24".to_string())
+                    .parse()
+                    .context("DOCS_DEPLOY_WINDOW_HOURS must be a positive integer")?,
+            },
+        };
+
+        // Load contributor announcement configuration
+        let contributor = ContributorConfig {
+            first_contribution: var("FIRST_CONTRIBUTION")
+                .unwrap_or_else(|_| "first_commit".to_string())
+                .parse()?,
+            dedupe_window_hours: var("CONTRIBUTOR_DEDUPE_WINDOW_HOURS")
+                .unwrap_or_else(|_| "720".to_string())
+                .parse()
+                .context("CONTRIBUTOR_DEDUPE_WINDOW_HOURS must be a positive integer")?,
+            state_dir: var("CONTRIBUTOR_STATE_DIR")
+                .unwrap_or_else(|_| "state".to_string()),
+            follow_on_x: var("CONTRIBUTOR_FOLLOW_ON_X")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("CONTRIBUTOR_FOLLOW_ON_X must be true or false")?,
+            follow_daily_cap: var("CONTRIBUTOR_FOLLOW_DAILY_CAP")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .context("CONTRIBUTOR_FOLLOW_DAILY_CAP must be a positive integer")?,
+        };
+
+        // Load new collaborator announcement configuration
+        let member = MemberConfig {
+            enabled: var("MEMBER_ANNOUNCE_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("MEMBER_ANNOUNCE_ENABLED must be true or false")?,
+            allowlist: list_var("MEMBER_ALLOWLIST", ""),
+            template: var("MEMBER_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "Welcome @{username} to the delta maintainer team! 🎉".to_string()),
+        };
+
+        // Load publicized repository announcement configuration
+        let public_repo = PublicRepoConfig {
+            enabled: var("PUBLIC_REPO_ANNOUNCE_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("PUBLIC_REPO_ANNOUNCE_ENABLED must be true or false")?,
+            template: var("PUBLIC_REPO_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "{name} is now open source! 🎉\n{description}\n{topics}\n{url}".to_string()),
+        };
+
+        // Load tag announcement configuration
+        let tags = TagConfig {
+            enabled: var("TAG_ANNOUNCE_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("TAG_ANNOUNCE_ENABLED must be true or false")?,
+            pattern: var("TAG_PATTERN")
+                .unwrap_or_else(|_| r"^v?\d+\.\d+\.\d+$".to_string()),
+            template: var("TAG_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "🏷️ {repo} {tag} has been tagged\n{url}".to_string()),
+            release_wait_minutes: var("TAG_RELEASE_WAIT_MINUTES")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .context("TAG_RELEASE_WAIT_MINUTES must be a positive integer")?,
+        };
+
+        // Load breaking change announcement configuration
+        let breaking_changes = BreakingChangeConfig {
+            enabled: var("BREAKING_CHANGES_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("BREAKING_CHANGES_ENABLED must be true or false")?,
+            labels: list_var("BREAKING_CHANGES_LABELS", "breaking-change"),
+            template: var("BREAKING_CHANGES_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| {
+                    "⚠️ Heads-up: a breaking change just landed on {branch} and ships with the next release\n\
+                     {title} (#{number})\n{url}".to_string()
+                }),
+        };
+
+        // Load labeled issue announcement configuration, each label with its own template
+        let issue_labels = IssueLabelConfig {
+            enabled: var("ISSUE_LABELS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ISSUE_LABELS_ENABLED must be true or false")?,
+            labels: list_var("ISSUE_LABELS", "good first issue")
+                .into_iter()
+                .map(|name| LabelConfig {
+                    template: var(label_var(&name, "TEMPLATE"))
+                        .map(|template| unescape(&template))
+                        .unwrap_or_else(|_| DEFAULT_ISSUE_LABEL_TEMPLATE.to_string()),
+                    name,
+                })
+                .collect(),
+            max_per_hour: var("ISSUE_LABELS_MAX_PER_HOUR")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .context("ISSUE_LABELS_MAX_PER_HOUR must be a positive integer")?,
+        };
+
+        // Load long-awaited fix announcement configuration
+        let long_awaited_fixes = LongAwaitedFixConfig {
+            enabled: var("LONG_AWAITED_FIXES_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("LONG_AWAITED_FIXES_ENABLED must be true or false")?,
+            min_age_days: var("LONG_AWAITED_FIXES_MIN_AGE_DAYS")
+                .unwrap_or_else(|_| "730".to_string())
+                .parse()
+                .context("LONG_AWAITED_FIXES_MIN_AGE_DAYS must be a positive integer")?,
+            template: var("LONG_AWAITED_FIXES_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "🕰️ Long-awaited fix: an issue opened over {years} years ago is finally resolved!\n{title}\n{url}".to_string()),
+        };
+
+        // Locale of the numbers and dates in scheduled announcements
+        let locale: Locale = var("LOCALE")
+            .unwrap_or_else(|_| "en".to_string())
+            .parse()
+            .context("LOCALE must be one of en, de, fr or es")?;
+
+        // Load milestone teaser configuration
+        let teaser = TeaserConfig {
+            enabled: var("TEASER_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("TEASER_ENABLED must be true or false")?,
+            offsets: list_var("TEASER_OFFSETS", "7d,1d"),
+            template: var("TEASER_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "Delta {title} lands {when} — {open_issues} issues to go! 👀\n{url}".to_string()),
+            locale,
+            check_interval_minutes: var("TEASER_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("TEASER_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load weekly digest configuration
+        let digest = DigestConfig {
+            enabled: var("DIGEST_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("DIGEST_ENABLED must be true or false")?,
+            weekday: var("DIGEST_WEEKDAY")
+                .unwrap_or_else(|_| "fri".to_string())
+                .parse()
+                .context("DIGEST_WEEKDAY must be a day of the week")?,
+            hour: var("DIGEST_HOUR")
+                .unwrap_or_else(|_| "16".to_string())
+                .parse()
+                .context("DIGEST_HOUR must be a positive integer")?,
+            template: var("DIGEST_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "This week in Delta: {count} pull requests merged 🛠️\n{notable}".to_string()),
+            locale,
+            notable_labels: list_var("DIGEST_NOTABLE_LABELS", "enhancement,bug"),
+            max_notable: var("DIGEST_MAX_NOTABLE")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .context("DIGEST_MAX_NOTABLE must be a positive integer")?,
+            traffic: var("DIGEST_TRAFFIC")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("DIGEST_TRAFFIC must be true or false")?,
+        };
+
+        // Load monthly stats configuration
+        let monthly_stats = MonthlyStatsConfig {
+            enabled: var("MONTHLY_STATS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("MONTHLY_STATS_ENABLED must be true or false")?,
+            day: var("MONTHLY_STATS_DAY")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .context("MONTHLY_STATS_DAY must be a positive integer")?,
+            hour: var("MONTHLY_STATS_HOUR")
+                .unwrap_or_else(|_| "16".to_string())
+                .parse()
+                .context("MONTHLY_STATS_HOUR must be a positive integer")?,
+            template: var("MONTHLY_STATS_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| {
+                    "📊 {month} in Delta: {pulls} pull requests merged and {releases} releases shipped, \
+                     by {contributors} contributors all-time. Thank you!".to_string()
+                }),
+            locale,
+        };
+
+        // Load repository anniversary configuration
+        let anniversary = AnniversaryConfig {
+            enabled: var("ANNIVERSARY_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ANNIVERSARY_ENABLED must be true or false")?,
+            hour: var("ANNIVERSARY_HOUR")
+                .unwrap_or_else(|_| "16".to_string())
+                .parse()
+                .context("ANNIVERSARY_HOUR must be a positive integer")?,
+            template: var("ANNIVERSARY_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| {
+                    "🎉 Delta turns {years} today, {releases} releases and {contributors} contributors later. \
+                     Thank you all!".to_string()
+                }),
+            locale,
+        };
+
+        // Load closed issue milestone configuration
+        let issue_milestones = IssueMilestoneConfig {
+            enabled: var("ISSUE_MILESTONES_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ISSUE_MILESTONES_ENABLED must be true or false")?,
+            every: var("ISSUE_MILESTONES_EVERY")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .context("ISSUE_MILESTONES_EVERY must be a positive integer")?,
+            template: var("ISSUE_MILESTONES_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "{count} issues closed in {repo} 🎉 Thanks to everyone who reported, triaged and fixed them!".to_string()),
+            locale,
+            check_interval_minutes: var("ISSUE_MILESTONES_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("ISSUE_MILESTONES_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load notable stargazer configuration
+        let mut milestones = list_var("GITHUB_STAR_MILESTONES", "1000,5000,10000,25000,50000,100000")
+            .iter()
+            .map(|milestone| milestone.parse())
+            .collect::<Result<Vec<u64>, _>>()
+            .context("GITHUB_STAR_MILESTONES must be a comma separated list of positive integers")?;
+        milestones.sort_unstable();
+        milestones.dedup();
+        let star_milestones = StarMilestoneConfig {
+            enabled: var("STAR_MILESTONES_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("STAR_MILESTONES_ENABLED must be true or false")?,
+            milestones,
+            template: var("STAR_MILESTONES_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "🌟 {repo} just passed {count} stars on GitHub! Thank you all for the support!".to_string()),
+            locale,
+            check_interval_minutes: var("STAR_MILESTONES_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("STAR_MILESTONES_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load release download milestone configuration
+        let mut milestones = list_var("DOWNLOAD_MILESTONES", "1000,10000,100000,1000000")
+            .iter()
+            .map(|milestone| milestone.parse())
+            .collect::<Result<Vec<u64>, _>>()
+            .context("DOWNLOAD_MILESTONES must be a comma separated list of positive integers")?;
+        milestones.sort_unstable();
+        milestones.dedup();
+        let download_milestones = DownloadMilestoneConfig {
+            enabled: var("DOWNLOAD_MILESTONES_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("DOWNLOAD_MILESTONES_ENABLED must be true or false")?,
+            milestones,
+            template: var("DOWNLOAD_MILESTONES_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "📦 {repo} {tag} has been downloaded {count} times! Thank you for using it!\n{url}".to_string()),
+            locale,
+            check_interval_minutes: var("DOWNLOAD_MILESTONES_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "360".to_string())
+                .parse()
+                .context("DOWNLOAD_MILESTONES_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load container image publish announcement configuration
+        let images = ImageConfig {
+            enabled: var("REGISTRY_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("REGISTRY_ENABLED must be true or false")?,
+            registry: var("REGISTRY")
+                .unwrap_or_else(|_| "ghcr".to_string())
+                .parse()?,
+            image: var("REGISTRY_IMAGE")
+                .unwrap_or_default(),
+            tag_pattern: var("REGISTRY_TAG_PATTERN")
+                .unwrap_or_else(|_| r"^v?\d+\.\d+\.\d+$".to_string()),
+            template: var("REGISTRY_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "🐳 The {image}:{tag} image is out!\n{pull}\n{url}".to_string()),
+            username: var("REGISTRY_USERNAME")
+                .ok()
+                .filter(|username| !username.is_empty()),
+            check_interval_minutes: var("REGISTRY_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("REGISTRY_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load feed announcement configuration
+        let feeds = FeedConfig {
+            enabled: var("FEEDS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("FEEDS_ENABLED must be true or false")?,
+            urls: list_var("FEED_URLS", ""),
+            template: var("FEED_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "📰 {title}\n{url}".to_string()),
+            check_interval_minutes: var("FEED_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("FEED_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load contributor count milestone configuration
+        let mut milestones = list_var("CONTRIBUTOR_MILESTONES", "100,250,500,1000")
+            .iter()
+            .map(|milestone| milestone.parse())
+            .collect::<Result<Vec<u64>, _>>()
+            .context("CONTRIBUTOR_MILESTONES must be a comma separated list of positive integers")?;
+        milestones.sort_unstable();
+        milestones.dedup();
+        let contributor_milestones = ContributorMilestoneConfig {
+            enabled: var("CONTRIBUTOR_MILESTONES_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("CONTRIBUTOR_MILESTONES_ENABLED must be true or false")?,
+            milestones,
+            template: var("CONTRIBUTOR_MILESTONES_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "🎉 {repo} just welcomed its {count}th contributor! Thank you all for building it with us!".to_string()),
+            locale,
+            check_interval_minutes: var("CONTRIBUTOR_MILESTONES_CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("CONTRIBUTOR_MILESTONES_CHECK_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load GitHub Sponsors configuration
+        let mut milestones = list_var("SPONSORS_MILESTONES", "10,25,50,100,250,500")
+            .iter()
+            .map(|milestone| milestone.parse())
+            .collect::<Result<Vec<u64>, _>>()
+            .context("SPONSORS_MILESTONES must be a comma separated list of positive integers")?;
+        milestones.sort_unstable();
+        milestones.dedup();
+        let sponsors = SponsorsConfig {
+            enabled: var("SPONSORS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("SPONSORS_ENABLED must be true or false")?,
+            template: var("SPONSORS_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "💜 Thank you {sponsor} for sponsoring {sponsorable} on GitHub Sponsors!".to_string()),
+            private_template: var("SPONSORS_PRIVATE_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "💜 A new sponsor just backed {sponsorable} on GitHub Sponsors. Thank you!".to_string()),
+            milestones,
+            milestone_template: var("SPONSORS_MILESTONE_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "🎉 {sponsorable} now has {count} sponsors on GitHub Sponsors! Thank you all for the support!".to_string()),
+            locale,
+        };
+
+        let stargazers = StargazerConfig {
+            enabled: var("STARGAZERS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("STARGAZERS_ENABLED must be true or false")?,
+            min_followers: var("STARGAZERS_MIN_FOLLOWERS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .context("STARGAZERS_MIN_FOLLOWERS must be a positive integer")?,
+            allowlist: list_var("STARGAZERS_ALLOWLIST", ""),
+            template: var("STARGAZERS_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "⭐ {username} just starred Delta, welcome aboard!\n{url}".to_string()),
+            locale,
+            poll_interval_minutes: var("STARGAZERS_POLL_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("STARGAZERS_POLL_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load project board watcher configuration
+        let project = ProjectConfig {
+            enabled: var("PROJECT_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("PROJECT_ENABLED must be true or false")?,
+            owner: var("PROJECT_OWNER")
+                .or_else(|_| var("REPO_OWNER"))
+                .unwrap_or_default(),
+            number: var("PROJECT_NUMBER")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .context("PROJECT_NUMBER must be a positive integer")?,
+            status_field: var("PROJECT_STATUS_FIELD")
+                .unwrap_or_else(|_| "Status".to_string()),
+            done_status: var("PROJECT_DONE_STATUS")
+                .unwrap_or_else(|_| "Done".to_string()),
+            template: var("PROJECT_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "Roadmap progress for Delta ✅ {title} is done!\n{url}".to_string()),
+            poll_interval_minutes: var("PROJECT_POLL_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .context("PROJECT_POLL_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        // Load announcement queue configuration
+        let queue = QueueConfig {
+            priorities: list_var(
+                "QUEUE_PRIORITIES",
+                "release=100,docs_deploy=90,tag=80,breaking_change=70,member=60,long_awaited_fix=50,\
+                 contributor=50,package_release=50,sponsor=50,milestone_teaser=40,project_item=40,\
+                 container_image=40,issue_milestone=30,star_milestone=30,contributor_milestone=30,\
+                 download_milestone=30,feed_entry=30,weekly_digest=30,monthly_stats=30,anniversary=30,\
+                 sponsor_milestone=30,public_repo=30,issue_label=20,stargazer=20",
+            )
+            .iter()
+            .map(|priority| parse_priority(priority))
+            .collect::<anyhow::Result<_>>()?,
+            aging_per_minute: var("QUEUE_AGING_PER_MINUTE")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .context("QUEUE_AGING_PER_MINUTE must be a positive integer")?,
+        };
+
+        // Load X posting fallback configuration
+        let fallback = FallbackConfig {
+            chain: list_var("X_FALLBACK", "v1.1")
+                .iter()
+                .map(|endpoint| endpoint.parse())
+                .collect::<anyhow::Result<_>>()?,
+            v1_base_url: var("X_V1_BASE_URL")
+                .unwrap_or_else(|_| "https://api.twitter.com/1.1".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        };
+
+        // Load X post editing configuration
+        let edit = EditConfig {
+            enabled: var("X_EDIT_ACCESS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("X_EDIT_ACCESS must be true or false")?,
+            window_minutes: var("X_EDIT_WINDOW_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("X_EDIT_WINDOW_MINUTES must be a positive integer")?,
+        };
+
+        // Load outgoing HTTP configuration
+        let http = HttpConfig {
+            ca_bundle: var("HTTP_CA_BUNDLE").ok(),
+            github_api_url: var("GITHUB_API_URL").ok(),
+            x_api_url: var("X_API_URL")
+                .unwrap_or_else(|_| "https://api.twitter.com/2".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            timeout,
+        };
+
+        // Load audit log configuration
+        let audit = AuditConfig::from_env();
+
+        // Load message broker configuration
+        let broker = BrokerConfig {
+            subject_prefix: var("NATS_SUBJECT_PREFIX")
+                .unwrap_or_else(|_| "xbot.announcements".to_string()),
+            ingest_subject: var("NATS_INGEST_SUBJECT")
+                .ok()
+                .filter(|subject| !subject.is_empty()),
+            ingest_queue_group: var("NATS_INGEST_QUEUE_GROUP")
+                .ok()
+                .filter(|group| !group.is_empty()),
+            webhook_urls: list_var("OUTBOUND_WEBHOOK_URLS", ""),
+        };
+
+        // Load Matrix room configuration
+        let matrix = MatrixConfig {
+            enabled: var("MATRIX_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("MATRIX_ENABLED must be true or false")?,
+            homeserver_url: var("MATRIX_HOMESERVER_URL")
+                .unwrap_or_else(|_| "https://matrix.org".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            room_id: var("MATRIX_ROOM_ID")
+                .unwrap_or_default(),
+            kinds: list_var("MATRIX_KINDS", "")
+                .iter()
+                .map(|kind| kind.parse())
+                .collect::<anyhow::Result<_>>()
+                .context("MATRIX_KINDS must be a comma separated list of announcement types")?,
+        };
+
+        // Load Microsoft Teams channel configuration
+        let teams = TeamsConfig {
+            enabled: var("TEAMS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("TEAMS_ENABLED must be true or false")?,
+            kinds: list_var("TEAMS_KINDS", "release,contributor")
+                .iter()
+                .map(|kind| kind.parse())
+                .collect::<anyhow::Result<_>>()
+                .context("TEAMS_KINDS must be a comma separated list of announcement types")?,
+        };
+
+        // Load IRC channel configuration
+        let irc_tls = var("IRC_TLS")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .context("IRC_TLS must be true or false")?;
+        let irc = IrcConfig {
+            enabled: var("IRC_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("IRC_ENABLED must be true or false")?,
+            server: var("IRC_SERVER")
+                .unwrap_or_else(|_| "irc.libera.chat".to_string()),
+            port: match var("IRC_PORT") {
+                Ok(port) => port.parse().context("IRC_PORT must be a port number")?,
+                Err(_) if irc_tls => 6697,
+                Err(_) => 6667,
+            },
+            tls: irc_tls,
+            nickname: var("IRC_NICKNAME")
+                .unwrap_or_else(|_| "x-bot".to_string()),
+            channel: var("IRC_CHANNEL")
+                .unwrap_or_default(),
+            kinds: list_var("IRC_KINDS", "")
+                .iter()
+                .map(|kind| kind.parse())
+                .collect::<anyhow::Result<_>>()
+                .context("IRC_KINDS must be a comma separated list of announcement types")?,
+            message_interval_ms: var("IRC_MESSAGE_INTERVAL_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse()
+                .context("IRC_MESSAGE_INTERVAL_MS must be a number of milliseconds")?,
+        };
+
+        // Load LinkedIn organization page configuration
+        let linkedin = LinkedInConfig {
+            enabled: var("LINKEDIN_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("LINKEDIN_ENABLED must be true or false")?,
+            organization_id: var("LINKEDIN_ORGANIZATION_ID")
+                .unwrap_or_default(),
+            client_id: var("LINKEDIN_CLIENT_ID")
+                .unwrap_or_default(),
+            template: var("LINKEDIN_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| {
+                    "🚀 {name} of {repo} is out!\n\n{body|strip_markdown|truncate(2000)}\n\nRead the full release notes: {url}".to_string()
+                }),
+        };
+
+        // Load public API configuration
+        let public_api = PublicApiConfig {
+            enabled: var("PUBLIC_API_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("PUBLIC_API_ENABLED must be true or false")?,
+        };
+
+        // Load email digest configuration
+        let smtp_security: SmtpSecurity = var("SMTP_SECURITY")
+            .unwrap_or_else(|_| "starttls".to_string())
+            .parse()?;
+        let email_digest = EmailDigestConfig {
+            enabled: var("EMAIL_DIGEST_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("EMAIL_DIGEST_ENABLED must be true or false")?,
+            smtp_host: var("SMTP_HOST")
+                .unwrap_or_default(),
+            smtp_port: match var("SMTP_PORT") {
+                Ok(port) => port.parse().context("SMTP_PORT must be a port number")?,
+                Err(_) => smtp_security.default_port(),
+            },
+            smtp_security,
+            smtp_username: var("SMTP_USERNAME")
+                .ok()
+                .filter(|username| !username.is_empty()),
+            from: var("EMAIL_DIGEST_FROM")
+                .unwrap_or_default(),
+            to: list_var("EMAIL_DIGEST_TO", ""),
+            weekday: Some(var("EMAIL_DIGEST_WEEKDAY").unwrap_or_else(|_| "mon".to_string()))
+                .filter(|weekday| !weekday.is_empty())
+                .map(|weekday| weekday.parse())
+                .transpose()
+                .context("EMAIL_DIGEST_WEEKDAY must be a day of the week, or empty for a daily digest")?,
+            hour: var("EMAIL_DIGEST_HOUR")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()
+                .context("EMAIL_DIGEST_HOUR must be a positive integer")?,
+            subject: var("EMAIL_DIGEST_SUBJECT")
+                .unwrap_or_else(|_| "Delta digest: {count} announcements since {since}".to_string()),
+            text_template: var("EMAIL_DIGEST_TEXT_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "Here is what Delta announced since {since}:\n\n{announcements}\n".to_string()),
+            html_template: var("EMAIL_DIGEST_HTML_TEMPLATE")
+                .map(|template| unescape(&template))
+                .unwrap_or_else(|_| "<p>Here is what Delta announced since {since}:</p>\n{announcements}".to_string()),
+        };
+
+        // Load Atom feed configuration
+        let atom_feed = AtomFeedConfig {
+            title: var("ATOM_FEED_TITLE")
+                .unwrap_or_else(|_| "Delta announcements".to_string()),
+            url: var("ATOM_FEED_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            path: var("ATOM_FEED_PATH")
+                .ok()
+                .filter(|path| !path.is_empty()),
+            entries: var("ATOM_FEED_ENTRIES")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .context("ATOM_FEED_ENTRIES must be a positive integer")?,
+        };
+
+        // Load archive configuration
+        let archive = ArchiveConfig {
+            enabled: var("ARCHIVE_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ARCHIVE_ENABLED must be true or false")?,
+            endpoint: var("ARCHIVE_S3_ENDPOINT")
+                .unwrap_or_else(|_| "https://s3.amazonaws.com".to_string()),
+            bucket: var("ARCHIVE_S3_BUCKET")
+                .unwrap_or_default(),
+            region: var("ARCHIVE_S3_REGION")
+                .unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key_id: var("ARCHIVE_S3_ACCESS_KEY_ID")
+                .unwrap_or_default(),
+            prefix: var("ARCHIVE_S3_PREFIX")
+                .unwrap_or_else(|_| "x-bot/".to_string()),
+            interval_minutes: var("ARCHIVE_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "1440".to_string())
+                .parse()
+                .context("ARCHIVE_INTERVAL_MINUTES must be a positive integer")?,
+        };
+
+        let gitlab = GitLabConfig {
+            enabled: var("GITLAB_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("GITLAB_ENABLED must be true or false")?,
+            api_url: var("GITLAB_API_URL")
+                .unwrap_or_else(|_| "https://gitlab.com/api/v4".to_string()),
+            project: var("GITLAB_PROJECT")
+                .unwrap_or_default(),
+        };
+
+        let gitea = GiteaConfig {
+            enabled: var("GITEA_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("GITEA_ENABLED must be true or false")?,
+            url: var("GITEA_URL")
+                .unwrap_or_else(|_| "https://codeberg.org".to_string()),
+            repo: var("GITEA_REPO")
+                .unwrap_or_default(),
+        };
+
+        let config = Config {
+            environment,
+            announcer,
+            server,
+            rate_limit,
+            security,
+            retry,
+            poll_backoff,
+            request_budget,
+            branches,
+            release,
+            contributor,
+            member,
+            public_repo,
+            tags,
+            breaking_changes,
+            issue_labels,
+            long_awaited_fixes,
+            teaser,
+            digest,
+            monthly_stats,
+            anniversary,
+            issue_milestones,
+            star_milestones,
+            download_milestones,
+            contributor_milestones,
+            images,
+            feeds,
+            npm: load_package_config(Ecosystem::Npm)?,
+            pypi: load_package_config(Ecosystem::PyPi)?,
+            sponsors,
+            stargazers,
+            project,
+            queue,
+            fallback,
+            edit,
+            http,
+            audit,
+            broker,
+            matrix,
+            linkedin,
+            teams,
+            irc,
+            public_api,
+            atom_feed,
+            email_digest,
+            archive,
+            gitlab,
+            gitea,
+            secrets,
+            github_app,
+            github_poll,
+            repo_owner: var("REPO_OWNER")
+                .context("REPO_OWNER must be set")?,
+            repo_name: var("REPO_NAME")
+                .context("REPO_NAME must be set")?,
+            repositories,
+            organization,
+            log_level: var("LOG_LEVEL")
+                .unwrap_or_else(|_| default_log_level()),
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Get secrets safely inside Config
+    pub fn github_token(&self) -> &str {
+        self.secrets.github_token()
+    }
+
+    pub fn x_api_key(&self) -> &str {
+        self.secrets.x_api_key()
+    }
+
+    pub fn x_api_secret(&self) -> &str {
+        self.secrets.x_api_secret()
+    }
+
+    pub fn x_access_token(&self) -> &str {
+        self.secrets.x_access_token()
+    }
+
+    pub fn x_access_secret(&self) -> &str {
+        self.secrets.x_access_secret()
+    }
+
+    /// Returns the credentials of the GitHub API, the ones of the GitHub App when set
+    pub fn github_auth(&self) -> GitHubAuth {
+        match (self.github_app.app_id, self.secrets.github_app_private_key()) {
+            (Some(app_id), Some(private_key)) => GitHubAuth::App {
+                app_id,
+                private_key: private_key.to_owned(),
+                installation_id: self.github_app.installation_id,
+            },
+            _ => GitHubAuth::Token(self.secrets.github_token().to_owned()),
+        }
+    }
+
+    /// Validates the configuration values
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.github_app.app_id.is_some() != self.secrets.github_app_private_key().is_some() {
+            return Err(anyhow::anyhow!("GITHUB_APP_ID and GITHUB_APP_PRIVATE_KEY must be set together"));
+        }
+        if let Some(private_key) = self.secrets.github_app_private_key() {
+            jsonwebtoken::EncodingKey::from_rsa_pem(private_key.as_bytes())
+                .context("GITHUB_APP_PRIVATE_KEY must be the private key of the GitHub App, in PEM")?;
+        }
+        if self.repo_owner.is_empty() || self.repo_name.is_empty() {
+            return Err(anyhow::anyhow!("Repository owner and name cannot be empty"));
+        }
+        for repository in &self.repositories {
+            if repository.owner.is_empty() || repository.name.is_empty() || repository.branches.iter().any(|branch| branch.name.is_empty()) {
+                return Err(anyhow::anyhow!("REPOSITORIES must list repositories as owner/name or owner/name/branch, got {}", repository.full_name()));
+            }
+            if repository.owner.eq_ignore_ascii_case(&self.repo_owner) && repository.name.eq_ignore_ascii_case(&self.repo_name) {
+                return Err(anyhow::anyhow!("REPOSITORIES must not list {}, it is already REPO_OWNER/REPO_NAME", repository.full_name()));
+            }
+        }
+
+        if self.server.max_body_bytes == 0 {
+            return Err(anyhow::anyhow!("Webhook max body bytes must be greater than 0"));
+        }
+        if self.server.listen.is_empty() {
+            return Err(anyhow::anyhow!("SERVER_LISTEN must contain at least one address"));
+        }
+        if self.server.verify_signatures && self.secrets.webhook_secret().is_none() {
+            return Err(anyhow::anyhow!(
+                "WEBHOOK_SECRET must be set to the secret of the GitHub webhook, or WEBHOOK_VERIFY_SIGNATURE=false for local testing"
+            ));
+        }
 
         match self.log_level.to_lowercase().as_str() {
             "error" | "warn" | "info" | "debug" | "trace" => Ok(()),
@@ -420,15 +3014,419 @@ impl Config {
             return Err(anyhow::anyhow!("Retry max delay must be greater than or equal to initial delay"));
         }
 
+        // Validate poll backoff configuration
+        if self.poll_backoff.initial_seconds == 0 {
+            return Err(anyhow::anyhow!("Poll backoff initial delay must be greater than 0"));
+        }
+        if self.poll_backoff.max_seconds < self.poll_backoff.initial_seconds {
+            return Err(anyhow::anyhow!("Poll backoff max delay must be greater than or equal to initial delay"));
+        }
+
         // Validate timeout configuration
-        if self.timeout.connect_seconds == 0 {
-            return Err(anyhow::anyhow!("Connect timeout must be greater than 0"));
+        for (api, timeout) in [("GitHub", &self.http.timeout.github), ("X", &self.http.timeout.x)] {
+            if timeout.connect_seconds == 0 {
+                return Err(anyhow::anyhow!("{} connect timeout must be greater than 0", api));
+            }
+            if timeout.read_seconds == 0 {
+                return Err(anyhow::anyhow!("{} read timeout must be greater than 0", api));
+            }
+            if timeout.write_seconds == 0 {
+                return Err(anyhow::anyhow!("{} write timeout must be greater than 0", api));
+            }
+            if timeout.total_seconds < timeout.connect_seconds {
+                return Err(anyhow::anyhow!("{} total timeout must be at least the connect timeout", api));
+            }
+        }
+
+        // Validate release edit configuration
+        if self.release.notes_min_change_percent > 100 {
+            return Err(anyhow::anyhow!("RELEASE_NOTES_MIN_CHANGE_PERCENT must be between 0 and 100"));
+        }
+
+        // Validate release channel configuration
+        for channel in &self.release.channels {
+            if let Some(pattern) = &channel.tag_pattern {
+                regex::Regex::new(pattern)
+                    .with_context(|| format!("Invalid tag pattern for the {} release channel", channel.channel))?;
+            }
+            if let Some(sink) = channel.sinks.iter().find(|sink| !SINKS.contains(&sink.as_str())) {
+                return Err(anyhow::anyhow!("Unknown sink {} for the {} release channel", sink, channel.channel));
+            }
+        }
+
+        // Validate contributor follow configuration
+        if self.contributor.follow_on_x && self.contributor.follow_daily_cap == 0 {
+            return Err(anyhow::anyhow!("CONTRIBUTOR_FOLLOW_DAILY_CAP must be greater than 0"));
+        }
+
+        // Validate X post editing configuration, X only allows edits within an hour
+        if self.edit.enabled && !(1..=60).contains(&self.edit.window_minutes) {
+            return Err(anyhow::anyhow!("X_EDIT_WINDOW_MINUTES must be between 1 and 60"));
+        }
+
+        // Validate release asset wait configuration
+        for pattern in &self.release.asset_wait.patterns {
+            regex::Regex::new(pattern)
+                .with_context(|| format!("Invalid release asset pattern: {}", pattern))?;
+        }
+        if self.release.asset_wait.poll_interval_seconds == 0 {
+            return Err(anyhow::anyhow!("Release asset poll interval must be greater than 0"));
+        }
+        if self.release.ci_wait.poll_interval_seconds == 0 {
+            return Err(anyhow::anyhow!("Release CI poll interval must be greater than 0"));
+        }
+
+        // Validate branch configuration
+        if self.branches.is_empty() {
+            return Err(anyhow::anyhow!("BRANCHES must list at least one branch"));
+        }
+
+        // Validate milestone teaser configuration
+        for offset in &self.teaser.offsets {
+            parse_duration(offset).context("TEASER_OFFSETS must be a list of durations such as 7d")?;
+        }
+        if self.teaser.check_interval_minutes == 0 {
+            return Err(anyhow::anyhow!("Teaser check interval must be greater than 0"));
+        }
+
+        // Validate weekly digest configuration
+        if self.digest.hour > 23 {
+            return Err(anyhow::anyhow!("DIGEST_HOUR must be between 0 and 23"));
+        }
+
+        // Validate monthly stats configuration, every month has the first 28 days
+        if !(1..=28).contains(&self.monthly_stats.day) {
+            return Err(anyhow::anyhow!("MONTHLY_STATS_DAY must be between 1 and 28"));
+        }
+        if self.monthly_stats.hour > 23 {
+            return Err(anyhow::anyhow!("MONTHLY_STATS_HOUR must be between 0 and 23"));
+        }
+
+        // Validate repository anniversary configuration
+        if self.anniversary.hour > 23 {
+            return Err(anyhow::anyhow!("ANNIVERSARY_HOUR must be between 0 and 23"));
+        }
+
+        // Validate closed issue milestone configuration
+        if self.issue_milestones.every == 0 {
+            return Err(anyhow::anyhow!("ISSUE_MILESTONES_EVERY must be greater than 0"));
+        }
+        if self.issue_milestones.check_interval_minutes == 0 {
+            return Err(anyhow::anyhow!("Issue milestone check interval must be greater than 0"));
+        }
+
+        // Validate GitHub polling configuration
+        if self.github_poll.enabled && self.github_poll.interval_seconds == 0 {
+            return Err(anyhow::anyhow!("GITHUB_POLL_INTERVAL_SECONDS must be greater than 0"));
+        }
+
+        // Validate organization configuration
+        if self.organization.enabled {
+            if self.organization.name.is_empty() {
+                return Err(anyhow::anyhow!("ORG_NAME must be set when ORG_ENABLED is true"));
+            }
+            for (name, pattern) in [("ORG_INCLUDE", &self.organization.include), ("ORG_EXCLUDE", &self.organization.exclude)] {
+                if let Some(pattern) = pattern {
+                    regex::Regex::new(pattern).with_context(|| format!("Invalid {}: {}", name, pattern))?;
+                }
+            }
+            if self.organization.discovery_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("ORG_DISCOVERY_INTERVAL_MINUTES must be greater than 0"));
+            }
+        }
+
+        // Validate tag announcement configuration
+        if self.tags.enabled {
+            regex::Regex::new(&self.tags.pattern)
+                .with_context(|| format!("Invalid TAG_PATTERN: {}", self.tags.pattern))?;
+        }
+
+        // Validate breaking change announcement configuration
+        if self.breaking_changes.enabled && self.breaking_changes.labels.is_empty() {
+            return Err(anyhow::anyhow!("BREAKING_CHANGES_LABELS must list at least one label"));
+        }
+
+        // Validate labeled issue announcement configuration
+        if self.issue_labels.enabled {
+            if self.issue_labels.labels.is_empty() {
+                return Err(anyhow::anyhow!("ISSUE_LABELS must list at least one label"));
+            }
+            if self.issue_labels.max_per_hour == 0 {
+                return Err(anyhow::anyhow!("ISSUE_LABELS_MAX_PER_HOUR must be greater than 0"));
+            }
+        }
+
+        // Validate long-awaited fix announcement configuration
+        if self.long_awaited_fixes.enabled && self.long_awaited_fixes.min_age_days == 0 {
+            return Err(anyhow::anyhow!("LONG_AWAITED_FIXES_MIN_AGE_DAYS must be greater than 0"));
+        }
+
+        // Validate star milestone configuration
+        if self.star_milestones.enabled {
+            if self.star_milestones.milestones.is_empty() || self.star_milestones.milestones.contains(&0) {
+                return Err(anyhow::anyhow!("GITHUB_STAR_MILESTONES must list star counts greater than 0"));
+            }
+            if self.star_milestones.check_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Star milestone check interval must be greater than 0"));
+            }
+        }
+
+        // Validate release download milestone configuration
+        if self.download_milestones.enabled {
+            if self.download_milestones.milestones.is_empty() || self.download_milestones.milestones.contains(&0) {
+                return Err(anyhow::anyhow!("DOWNLOAD_MILESTONES must list download counts greater than 0"));
+            }
+            if self.download_milestones.check_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Download milestone check interval must be greater than 0"));
+            }
+        }
+
+        // Validate container image publish announcement configuration
+        if self.images.enabled {
+            if RepoName::from_full_name(&self.images.image).is_none() {
+                return Err(anyhow::anyhow!("REGISTRY_IMAGE must be set to an image, such as owner/name"));
+            }
+            regex::Regex::new(&self.images.tag_pattern)
+                .with_context(|| format!("Invalid REGISTRY_TAG_PATTERN: {}", self.images.tag_pattern))?;
+            if self.images.username.is_some() != self.secrets.registry_token().is_some() {
+                return Err(anyhow::anyhow!("REGISTRY_USERNAME and REGISTRY_TOKEN must be set together"));
+            }
+            if self.images.check_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Registry check interval must be greater than 0"));
+            }
+        }
+
+        // Validate feed announcement configuration
+        if self.feeds.enabled {
+            if self.feeds.urls.is_empty() {
+                return Err(anyhow::anyhow!("FEED_URLS must list at least one feed when FEEDS_ENABLED is true"));
+            }
+            for url in &self.feeds.urls {
+                let parsed = reqwest::Url::parse(url).with_context(|| format!("Invalid feed URL: {}", url))?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    return Err(anyhow::anyhow!("Feed URLs must be http(s) URLs: {}", url));
+                }
+            }
+            if self.feeds.check_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Feed check interval must be greater than 0"));
+            }
+        }
+
+        // Validate package release announcement configuration
+        for packages in [&self.npm, &self.pypi] {
+            if !packages.enabled {
+                continue;
+            }
+            let prefix = packages.ecosystem.as_str().to_uppercase();
+            if packages.package.is_empty() {
+                return Err(anyhow::anyhow!("{}_PACKAGE must be set when {}_ENABLED is true", prefix, prefix));
+            }
+            if packages.check_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("{}_CHECK_INTERVAL_MINUTES must be greater than 0", prefix));
+            }
+        }
+
+        // Validate contributor milestone configuration
+        if self.contributor_milestones.enabled {
+            if self.contributor_milestones.milestones.is_empty() || self.contributor_milestones.milestones.contains(&0) {
+                return Err(anyhow::anyhow!("CONTRIBUTOR_MILESTONES must list contributor counts greater than 0"));
+            }
+            if self.contributor_milestones.check_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Contributor milestone check interval must be greater than 0"));
+            }
+        }
+
+        // Validate GitHub Sponsors configuration
+        if self.sponsors.enabled && self.sponsors.milestones.contains(&0) {
+            return Err(anyhow::anyhow!("SPONSORS_MILESTONES must list sponsor counts greater than 0"));
+        }
+
+        // Validate notable stargazer configuration
+        if self.stargazers.poll_interval_minutes == 0 {
+            return Err(anyhow::anyhow!("Stargazer poll interval must be greater than 0"));
         }
-        if self.timeout.read_seconds == 0 {
-            return Err(anyhow::anyhow!("Read timeout must be greater than 0"));
+
+        // Validate project board watcher configuration
+        if self.project.enabled {
+            if self.project.number == 0 {
+                return Err(anyhow::anyhow!("PROJECT_NUMBER must be set when PROJECT_ENABLED is true"));
+            }
+            if self.project.owner.is_empty() {
+                return Err(anyhow::anyhow!("Project owner cannot be empty"));
+            }
+            if self.project.poll_interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Project poll interval must be greater than 0"));
+            }
+        }
+
+        // Validate message broker configuration, subjects are dot separated tokens without wildcards
+        let prefix = &self.broker.subject_prefix;
+        if prefix.split('.').any(|token| token.is_empty() || token.contains(['*', '>']) || token.contains(char::is_whitespace)) {
+            return Err(anyhow::anyhow!("NATS_SUBJECT_PREFIX must be a NATS subject without wildcards"));
+        }
+        if let Some(subject) = &self.broker.ingest_subject {
+            if self.secrets.nats_url().is_none() {
+                return Err(anyhow::anyhow!("NATS_INGEST_SUBJECT requires NATS_URL"));
+            }
+            // Wildcards are whole tokens, and > only ends a subject
+            let tokens: Vec<&str> = subject.split('.').collect();
+            let invalid = tokens.iter().enumerate().any(|(i, token)| {
+                token.is_empty()
+                    || token.contains(char::is_whitespace)
+                    || (token.contains(['*', '>']) && token.len() > 1)
+                    || (*token == ">" && i + 1 < tokens.len())
+            });
+            if invalid {
+                return Err(anyhow::anyhow!("NATS_INGEST_SUBJECT must be a NATS subject, such as github.events.>"));
+            }
+        }
+        if self.broker.ingest_queue_group.as_deref().is_some_and(|group| group.contains(char::is_whitespace)) {
+            return Err(anyhow::anyhow!("NATS_INGEST_QUEUE_GROUP cannot contain whitespace"));
+        }
+        for url in &self.broker.webhook_urls {
+            let parsed = reqwest::Url::parse(url).with_context(|| format!("Invalid URL {} in OUTBOUND_WEBHOOK_URLS", url))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err(anyhow::anyhow!("OUTBOUND_WEBHOOK_URLS must be http(s) URLs, got {}", url));
+            }
+        }
+
+        // Validate Matrix room configuration
+        if self.matrix.enabled {
+            let homeserver = reqwest::Url::parse(&self.matrix.homeserver_url).context("MATRIX_HOMESERVER_URL must be a URL")?;
+            if !matches!(homeserver.scheme(), "http" | "https") || homeserver.host_str().is_none() {
+                return Err(anyhow::anyhow!("MATRIX_HOMESERVER_URL must be an http(s) URL"));
+            }
+            if !self.matrix.room_id.starts_with('!') || !self.matrix.room_id.contains(':') {
+                return Err(anyhow::anyhow!("MATRIX_ROOM_ID must be a room id, such as !abcdef:matrix.org, when MATRIX_ENABLED is true"));
+            }
+            if self.secrets.matrix_access_token().is_none() {
+                return Err(anyhow::anyhow!("MATRIX_ACCESS_TOKEN must be set when MATRIX_ENABLED is true"));
+            }
+        }
+
+        // Validate Microsoft Teams channel configuration
+        if self.teams.enabled {
+            let url = self.secrets.teams_webhook_url()
+                .ok_or_else(|| anyhow::anyhow!("TEAMS_WEBHOOK_URL must be set when TEAMS_ENABLED is true"))?;
+            if !reqwest::Url::parse(url).is_ok_and(|url| url.scheme() == "https") {
+                return Err(anyhow::anyhow!("TEAMS_WEBHOOK_URL must be an https URL"));
+            }
+            if self.teams.kinds.is_empty() {
+                return Err(anyhow::anyhow!("TEAMS_KINDS must list at least one announcement type"));
+            }
+        }
+
+        // Validate IRC channel configuration
+        if self.irc.enabled {
+            if self.irc.server.is_empty() {
+                return Err(anyhow::anyhow!("IRC_SERVER must be set when IRC_ENABLED is true"));
+            }
+            let channel = &self.irc.channel;
+            if !channel.starts_with(['#', '&']) || channel.len() < 2 || channel.contains([' ', ',', '\x07']) {
+                return Err(anyhow::anyhow!("IRC_CHANNEL must be a channel, such as #delta-rs, when IRC_ENABLED is true"));
+            }
+            let nickname = &self.irc.nickname;
+            if nickname.is_empty() || nickname.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+                || nickname.contains(|c: char| c.is_whitespace() || matches!(c, ',' | '*' | '?' | '!' | '@' | '#' | ':')) {
+                return Err(anyhow::anyhow!("IRC_NICKNAME must be a valid nickname, got {}", nickname));
+            }
+            if self.irc.message_interval_ms == 0 {
+                return Err(anyhow::anyhow!("IRC_MESSAGE_INTERVAL_MS must be greater than 0"));
+            }
+        }
+
+        // Validate LinkedIn organization page configuration
+        if self.linkedin.enabled {
+            if self.linkedin.organization_id.is_empty() || !self.linkedin.organization_id.chars().all(|c| c.is_ascii_digit()) {
+                return Err(anyhow::anyhow!("LINKEDIN_ORGANIZATION_ID must be the numeric id of the organization when LINKEDIN_ENABLED is true"));
+            }
+            if self.linkedin.client_id.is_empty() {
+                return Err(anyhow::anyhow!("LINKEDIN_CLIENT_ID must be set when LINKEDIN_ENABLED is true"));
+            }
+            if self.secrets.linkedin_client_secret().is_none() || self.secrets.linkedin_refresh_token().is_none() {
+                return Err(anyhow::anyhow!("LINKEDIN_CLIENT_SECRET and LINKEDIN_REFRESH_TOKEN must be set when LINKEDIN_ENABLED is true"));
+            }
+        } else if let Some(channel) = self.release.channels.iter().find(|channel| channel.routes_to("linkedin")) {
+            return Err(anyhow::anyhow!("The {} release channel is routed to linkedin, which requires LINKEDIN_ENABLED", channel.channel));
+        }
+
+        // Validate email digest configuration
+        if self.email_digest.enabled {
+            if self.email_digest.smtp_host.is_empty() {
+                return Err(anyhow::anyhow!("SMTP_HOST must be set when EMAIL_DIGEST_ENABLED is true"));
+            }
+            if self.email_digest.from.is_empty() || self.email_digest.to.is_empty() {
+                return Err(anyhow::anyhow!("EMAIL_DIGEST_FROM and EMAIL_DIGEST_TO must be set when EMAIL_DIGEST_ENABLED is true"));
+            }
+            if self.email_digest.smtp_username.is_some() != self.secrets.smtp_password().is_some() {
+                return Err(anyhow::anyhow!("SMTP_USERNAME and SMTP_PASSWORD must be set together"));
+            }
+            if self.email_digest.hour > 23 {
+                return Err(anyhow::anyhow!("EMAIL_DIGEST_HOUR must be between 0 and 23"));
+            }
         }
-        if self.timeout.write_seconds == 0 {
-            return Err(anyhow::anyhow!("Write timeout must be greater than 0"));
+
+        // Validate Atom feed configuration
+        if self.atom_feed.entries == 0 {
+            return Err(anyhow::anyhow!("ATOM_FEED_ENTRIES must be greater than 0"));
+        }
+        if let Some(url) = &self.atom_feed.url {
+            reqwest::Url::parse(url).context("ATOM_FEED_URL must be a URL")?;
+        }
+
+        // Validate archive configuration
+        if self.archive.enabled {
+            let endpoint = reqwest::Url::parse(&self.archive.endpoint).context("ARCHIVE_S3_ENDPOINT must be a URL")?;
+            if !matches!(endpoint.scheme(), "http" | "https") || endpoint.host_str().is_none() {
+                return Err(anyhow::anyhow!("ARCHIVE_S3_ENDPOINT must be an http(s) URL"));
+            }
+            if self.archive.bucket.is_empty() || self.archive.bucket.contains('/') {
+                return Err(anyhow::anyhow!("ARCHIVE_S3_BUCKET must be set to a bucket name when ARCHIVE_ENABLED is true"));
+            }
+            if self.archive.region.is_empty() {
+                return Err(anyhow::anyhow!("ARCHIVE_S3_REGION cannot be empty"));
+            }
+            if self.archive.access_key_id.is_empty() || self.secrets.archive_secret_access_key().is_none() {
+                return Err(anyhow::anyhow!(
+                    "ARCHIVE_S3_ACCESS_KEY_ID and ARCHIVE_S3_SECRET_ACCESS_KEY must be set when ARCHIVE_ENABLED is true"
+                ));
+            }
+            if self.archive.interval_minutes == 0 {
+                return Err(anyhow::anyhow!("Archive interval must be greater than 0"));
+            }
+        }
+
+        // Validate GitLab configuration
+        if self.gitlab.enabled {
+            let api_url = reqwest::Url::parse(&self.gitlab.api_url).context("GITLAB_API_URL must be a URL")?;
+            if !matches!(api_url.scheme(), "http" | "https") || api_url.host_str().is_none() {
+                return Err(anyhow::anyhow!("GITLAB_API_URL must be an http(s) URL"));
+            }
+            if !self.gitlab.project.contains('/') {
+                return Err(anyhow::anyhow!("GITLAB_PROJECT must be set to the path of a project, such as group/project"));
+            }
+            if self.secrets.gitlab_token().is_none() || self.secrets.gitlab_webhook_secret().is_none() {
+                return Err(anyhow::anyhow!("GITLAB_TOKEN and GITLAB_WEBHOOK_SECRET must be set when GITLAB_ENABLED is true"));
+            }
+        }
+
+        // Validate Gitea configuration
+        if self.gitea.enabled {
+            let url = reqwest::Url::parse(&self.gitea.url).context("GITEA_URL must be a URL")?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(anyhow::anyhow!("GITEA_URL must be an http(s) URL"));
+            }
+            if RepoName::from_full_name(&self.gitea.repo).is_none() {
+                return Err(anyhow::anyhow!("GITEA_REPO must be set to a repository, such as owner/name"));
+            }
+            if self.secrets.gitea_webhook_secret().is_none() {
+                return Err(anyhow::anyhow!("GITEA_WEBHOOK_SECRET must be set when GITEA_ENABLED is true"));
+            }
+        }
+
+        // Validate audit log configuration
+        if self.audit.path.is_empty() {
+            return Err(anyhow::anyhow!("Audit log path cannot be empty"));
         }
 
         Ok(())
@@ -444,21 +3442,6 @@ impl Config {
     //     matches!(self.environment, Environment::Production)
     // }
 
-    // /// Get connect timeout as Duration
-    // pub fn connect_timeout(&self) -> Duration {
-    //     Duration::from_secs(self.timeout.connect_seconds)
-    // }
-
-    // /// Get read timeout as Duration
-    // pub fn read_timeout(&self) -> Duration {
-    //     Duration::from_secs(self.timeout.read_seconds)
-    // }
-
-    // /// Get write timeout as Duration
-    // pub fn write_timeout(&self) -> Duration {
-    //     Duration::from_secs(self.timeout.write_seconds)
-    // }
-
     /// Get the full webhook URL path
     pub fn webhook_url(&self) -> String {
         format!("http://{}{}",self.server.host, self.server.webhook_path)
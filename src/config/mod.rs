@@ -1 +1,3 @@
-pub mod env;
\ No newline at end of file
+pub mod duration;
+pub mod env;
+pub mod tls;
\ No newline at end of file
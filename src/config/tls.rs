@@ -0,0 +1,60 @@
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+/// Loads the extra root certificates trusted on top of the system ones.
+///
+/// # Arguments
+/// * `ca_bundle` - The path of a PEM bundle, if one is configured.
+///
+/// # Returns
+/// A result containing the certificates of the bundle, empty when none is configured,
+/// or an error if the bundle can't be read.
+pub fn extra_root_certificates(ca_bundle: Option<&str>) -> Result<Vec<CertificateDer<'static>>> {
+    let Some(path) = ca_bundle else {
+        return Ok(Vec::new());
+    };
+
+    let certificates = CertificateDer::pem_file_iter(path)
+        .with_context(|| format!("Failed to read CA bundle {}", path))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid certificate in CA bundle {}", path))?;
+    if certificates.is_empty() {
+        return Err(anyhow::anyhow!("CA bundle {} contains no certificates", path));
+    }
+
+    info!("Loaded {} extra root certificates from {}", certificates.len(), path);
+    Ok(certificates)
+}
+
+/// Builds a TLS client configuration trusting the system roots and the given extra certificates.
+///
+/// # Arguments
+/// * `extra` - The extra root certificates, e.g. from [extra_root_certificates].
+///
+/// # Returns
+/// A result containing the TLS configuration or an error if no usable root is left.
+pub fn client_config(extra: &[CertificateDer<'static>]) -> Result<rustls::ClientConfig> {
+    let mut roots = rustls::RootCertStore::empty();
+
+    let native = rustls_native_certs::load_native_certs();
+    for error in &native.errors {
+        warn!("Failed to load a system root certificate: {}", error);
+    }
+    roots.add_parsable_certificates(native.certs);
+
+    for certificate in extra {
+        roots
+            .add(certificate.clone())
+            .context("Invalid root certificate in CA bundle")?;
+    }
+    if roots.is_empty() {
+        return Err(anyhow::anyhow!("No root certificates available for TLS"));
+    }
+
+    let config = rustls::ClientConfig::builder_with_provider(rustls::crypto::ring::default_provider().into())
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(config)
+}
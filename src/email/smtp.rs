@@ -0,0 +1,194 @@
+use crate::config::{env::{EmailDigestConfig, HttpConfig, SmtpSecurity}, tls};
+use std::sync::Arc;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use rand::Rng;
+use rustls::pki_types::ServerName;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufStream},
+    net::TcpStream,
+    time::{timeout, Duration}};
+use tokio_rustls::TlsConnector;
+use anyhow::{anyhow, Context, Result};
+use tracing::debug;
+
+/// Time allowed for connecting to the server or for it to answer a command
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Length of the lines of base64 encoded bodies, the most MIME allows
+const BASE64_LINE_LENGTH: usize = 76;
+
+/// A connection to the server, in plain text or TLS
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// An email with a plain text and an HTML version
+pub struct Email {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Sends emails through an SMTP server, speaking just enough of the protocol for a digest:
+/// implicit TLS or STARTTLS, `AUTH PLAIN`, and a single message per connection.
+pub struct SmtpClient {
+    host: String,
+    port: u16,
+    security: SmtpSecurity,
+    /// Username and password to sign in with, anonymous without them
+    credentials: Option<(String, String)>,
+    from: String,
+    to: Vec<String>,
+    tls: TlsConnector,
+}
+
+impl SmtpClient {
+    /// Creates a new instance of `SmtpClient`.
+    ///
+    /// # Arguments
+    /// * `config` - The server, the sender and the recipients of the digest.
+    /// * `password` - The password paired with the username of the settings.
+    /// * `http_config` - The extra root certificates to trust.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if no root certificates are usable.
+    pub fn new(config: &EmailDigestConfig, password: Option<&str>, http_config: &HttpConfig) -> Result<Self> {
+        let extra = tls::extra_root_certificates(http_config.ca_bundle.as_deref())?;
+        Ok(Self {
+            host: config.smtp_host.clone(),
+            port: config.smtp_port,
+            security: config.smtp_security,
+            credentials: config.smtp_username.clone().zip(password.map(str::to_owned)),
+            from: config.from.clone(),
+            to: config.to.clone(),
+            tls: TlsConnector::from(Arc::new(tls::client_config(&extra)?)),
+        })
+    }
+
+    /// Sends an email to the recipients.
+    ///
+    /// # Returns
+    /// A result containing the `Message-ID` of the email, or an error if the server refused it.
+    pub async fn send(&self, email: &Email) -> Result<String> {
+        let tcp = timeout(IO_TIMEOUT, TcpStream::connect((self.host.as_str(), self.port)))
+            .await
+            .context("Timed out connecting to the SMTP server")??;
+        let stream: Box<dyn Stream> = match self.security {
+            SmtpSecurity::Tls => Box::new(self.tls.connect(self.server_name()?, tcp).await?),
+            SmtpSecurity::StartTls | SmtpSecurity::None => Box::new(tcp),
+        };
+        let mut stream = BufStream::new(stream);
+
+        expect(&mut stream, 220).await?;
+        command(&mut stream, "EHLO x-bot", 250).await?;
+        if self.security == SmtpSecurity::StartTls {
+            command(&mut stream, "STARTTLS", 220).await?;
+            let tls = self.tls.connect(self.server_name()?, stream.into_inner()).await?;
+            stream = BufStream::new(Box::new(tls));
+            command(&mut stream, "EHLO x-bot", 250).await?;
+        }
+        if let Some((username, password)) = &self.credentials {
+            let plain = BASE64.encode(format!("\0{}\0{}", username, password));
+            command(&mut stream, &format!("AUTH PLAIN {}", plain), 235).await
+                .context("The SMTP server rejected the credentials")?;
+        }
+
+        command(&mut stream, &format!("MAIL FROM:<{}>", address(&self.from)), 250).await?;
+        for recipient in &self.to {
+            command(&mut stream, &format!("RCPT TO:<{}>", address(recipient)), 250).await?;
+        }
+        command(&mut stream, "DATA", 354).await?;
+
+        let message_id = format!("<{:016x}.{}@x-bot>", rand::thread_rng().gen::<u64>(), Utc::now().timestamp());
+        let message = self.message(email, &message_id);
+        // Lines starting with a dot are escaped, a dot alone ends the message
+        for line in message.split("\r\n") {
+            if line.starts_with('.') {
+                stream.write_all(b".").await?;
+            }
+            stream.write_all(line.as_bytes()).await?;
+            stream.write_all(b"\r\n").await?;
+        }
+        command(&mut stream, ".", 250).await?;
+        // The email is accepted, a failed goodbye doesn't matter
+        let _ = command(&mut stream, "QUIT", 221).await;
+
+        Ok(message_id)
+    }
+
+    fn server_name(&self) -> Result<ServerName<'static>> {
+        ServerName::try_from(self.host.clone()).map_err(|_| anyhow!("Invalid SMTP host {}", self.host))
+    }
+
+    /// Builds the MIME message, with both versions of the email as base64 encoded UTF-8.
+    fn message(&self, email: &Email, message_id: &str) -> String {
+        let boundary = format!("x-bot-{:016x}", rand::thread_rng().gen::<u64>());
+        let headers = [
+            format!("From: {}", self.from),
+            format!("To: {}", self.to.join(", ")),
+            format!("Subject: =?utf-8?B?{}?=", BASE64.encode(&email.subject)),
+            format!("Date: {}", Utc::now().to_rfc2822()),
+            format!("Message-ID: {}", message_id),
+            "MIME-Version: 1.0".to_string(),
+            format!("Content-Type: multipart/alternative; boundary=\"{}\"", boundary),
+        ];
+
+        let mut message = headers.join("\r\n");
+        message.push_str("\r\n\r\n");
+        for (content_type, body) in [("text/plain", &email.text), ("text/html", &email.html)] {
+            message.push_str(&format!("--{}\r\n", boundary));
+            message.push_str(&format!("Content-Type: {}; charset=utf-8\r\n", content_type));
+            message.push_str("Content-Transfer-Encoding: base64\r\n\r\n");
+            let encoded = BASE64.encode(body);
+            for chunk in encoded.as_bytes().chunks(BASE64_LINE_LENGTH) {
+                message.push_str(std::str::from_utf8(chunk).expect("base64 is ASCII"));
+                message.push_str("\r\n");
+            }
+        }
+        message.push_str(&format!("--{}--", boundary));
+        message
+    }
+}
+
+/// Sends a command and checks the reply code
+async fn command(stream: &mut BufStream<Box<dyn Stream>>, command: &str, code: u16) -> Result<()> {
+    stream.write_all(command.as_bytes()).await?;
+    stream.write_all(b"\r\n").await?;
+    stream.flush().await?;
+    let verb = command.split(' ').next().unwrap_or_default();
+    expect(stream, code).await.with_context(|| format!("SMTP command {} failed", verb))
+}
+
+/// Reads a reply, made of lines `<code>-<text>` and ending with `<code> <text>`, and checks its code
+async fn expect(stream: &mut BufStream<Box<dyn Stream>>, code: u16) -> Result<()> {
+    loop {
+        let mut line = String::new();
+        let read = timeout(IO_TIMEOUT, stream.read_line(&mut line))
+            .await
+            .context("Timed out waiting for the SMTP server")??;
+        if read == 0 {
+            return Err(anyhow!("The SMTP server closed the connection"));
+        }
+        let line = line.trim_end();
+        debug!("SMTP: {}", line);
+
+        let reply: u16 = line.get(..3).and_then(|reply| reply.parse().ok())
+            .ok_or_else(|| anyhow!("Malformed SMTP reply: {}", line))?;
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        if reply != code {
+            return Err(anyhow!("Unexpected SMTP reply: {}", line));
+        }
+        return Ok(());
+    }
+}
+
+/// Returns the address of a mailbox such as `Delta <bot@delta.io>`
+fn address(mailbox: &str) -> &str {
+    match (mailbox.rfind('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+        _ => mailbox.trim(),
+    }
+}
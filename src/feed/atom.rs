@@ -0,0 +1,78 @@
+use crate::{
+    audit::log::{idempotency_key, AuditEntry},
+    config::env::AtomFeedConfig};
+use std::sync::LazyLock;
+use chrono::{SecondsFormat, Utc};
+use regex::Regex;
+
+/// Links in the text of an announcement
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"https?://[^\s<>"]+"#).expect("valid URL regex")
+});
+
+/// Id of the feed when its public URL isn't configured
+const DEFAULT_FEED_ID: &str = "urn:x-bot:announcements";
+
+/// Renders an Atom feed of announcements, for people following the project without an X account.
+///
+/// # Arguments
+/// * `config` - The title and public URL of the feed.
+/// * `announcements` - The announcements that are still up, newest first, as returned by
+///   [AuditLog::announcements](crate::audit::log::AuditLog::announcements).
+///
+/// # Returns
+/// The XML document of the feed, with at most `config.entries` entries.
+pub fn render(config: &AtomFeedConfig, announcements: &[AuditEntry]) -> String {
+    let announcements = &announcements[..announcements.len().min(config.entries)];
+    let updated = announcements.first().map(|entry| entry.timestamp).unwrap_or_else(Utc::now);
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str(&format!("  <title>{}</title>\n", escape(&config.title)));
+    feed.push_str(&format!("  <id>{}</id>\n", escape(config.url.as_deref().unwrap_or(DEFAULT_FEED_ID))));
+    if let Some(url) = &config.url {
+        feed.push_str(&format!("  <link rel=\"self\" href=\"{}\"/>\n", escape(url)));
+    }
+    feed.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339_opts(SecondsFormat::Secs, true)));
+    feed.push_str("  <generator>x-bot</generator>\n");
+    for entry in announcements {
+        feed.push_str(&render_entry(entry));
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+/// Renders an announcement as an entry, linking to its post on X or else to the first link of its text
+fn render_entry(entry: &AuditEntry) -> String {
+    let title = match &entry.subject {
+        Some(subject) => format!("{} {} of {}", entry.kind.as_str().replace('_', " "), subject, entry.repo),
+        None => format!("{} of {}", entry.kind.as_str().replace('_', " "), entry.repo),
+    };
+    let link = entry.remote_ids.get("x")
+        .map(|id| format!("https://x.com/i/web/status/{}", id))
+        .or_else(|| URL.find(&entry.text).map(|url| url.as_str().to_string()));
+
+    let mut xml = String::from("  <entry>\n");
+    xml.push_str(&format!("    <title>{}</title>\n", escape(&title)));
+    // Keyed on the announced object, so an edit updates the entry rather than adding one
+    xml.push_str(&format!(
+        "    <id>urn:x-bot:{}</id>\n",
+        escape(&idempotency_key(entry.kind, &entry.repo, &entry.source_id))
+    ));
+    if let Some(link) = link {
+        xml.push_str(&format!("    <link href=\"{}\"/>\n", escape(&link)));
+    }
+    xml.push_str(&format!("    <updated>{}</updated>\n", entry.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)));
+    xml.push_str(&format!("    <category term=\"{}\"/>\n", entry.kind.as_str()));
+    xml.push_str(&format!("    <content type=\"text\">{}</content>\n", escape(&entry.text)));
+    xml.push_str("  </entry>\n");
+    xml
+}
+
+/// Escapes the characters with a meaning in XML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
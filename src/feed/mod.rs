@@ -0,0 +1,3 @@
+pub mod atom;
+pub mod parse;
+pub mod writer;
@@ -0,0 +1,126 @@
+use std::{collections::HashMap, sync::LazyLock};
+use regex::Regex;
+
+/// Elements read from the entries of a feed
+const ELEMENT_NAMES: [&str; 7] = ["guid", "id", "title", "link", "summary", "description", "content"];
+
+static ELEMENTS: LazyLock<HashMap<&'static str, Regex>> = LazyLock::new(|| {
+    ELEMENT_NAMES
+        .into_iter()
+        .map(|name| {
+            let element = Regex::new(&format!(r"(?is)<{0}\b[^>]*>(.*?)</{0}>", name)).expect("valid feed element regex");
+            (name, element)
+        })
+        .collect()
+});
+
+static ENTRY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<(item|entry)\b[^>]*>(.*?)</(?:item|entry)>").expect("valid feed entry regex")
+});
+
+static FEED_TITLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").expect("valid feed title regex")
+});
+
+static LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<link\b([^>]*?)/?>").expect("valid link regex")
+});
+
+static LINK_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(href|rel)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid link attribute regex")
+});
+
+static CDATA: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").expect("valid CDATA regex")
+});
+
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<[^>]*>").expect("valid HTML tag regex")
+});
+
+/// An entry of an RSS or Atom feed, such as a blog post or a release
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedEntry {
+    /// Identifies the entry across fetches: its guid or id, or its link without one
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    /// Summary of the entry as plain text, empty without one
+    pub summary: String,
+}
+
+/// Finds the title of a feed, the first one it lists before its entries.
+pub fn feed_title(xml: &str) -> Option<String> {
+    let head = ENTRY.find(xml).map_or(xml, |entry| &xml[..entry.start()]);
+    FEED_TITLE.captures(head).map(|title| text(&title[1])).filter(|title| !title.is_empty())
+}
+
+/// Extracts the entries of an RSS or Atom feed, in the order the feed lists them.
+///
+/// Entries without an id or a link can't be told apart across fetches and are left out.
+pub fn entries(xml: &str) -> Vec<FeedEntry> {
+    ENTRY
+        .captures_iter(xml)
+        .filter_map(|entry| {
+            let body = &entry[2];
+            let url = link(body).unwrap_or_default();
+            let id = element(body, &["guid", "id"]).filter(|id| !id.is_empty()).unwrap_or_else(|| url.clone());
+            if id.is_empty() {
+                return None;
+            }
+            Some(FeedEntry {
+                id,
+                title: element(body, &["title"]).unwrap_or_default(),
+                url,
+                summary: element(body, &["summary", "description", "content"]).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the text of the first of `names` found in an entry.
+fn element(body: &str, names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        ELEMENTS[name].captures(body).map(|captures| text(&captures[1]))
+    })
+}
+
+/// Returns the link of an entry: the text of an RSS `<link>`, or the `href` of the Atom
+/// `<link>` to the entry itself.
+fn link(body: &str) -> Option<String> {
+    if let Some(url) = element(body, &["link"]).filter(|url| !url.is_empty()) {
+        return Some(url);
+    }
+
+    LINK.captures_iter(body).find_map(|tag| {
+        let mut href = None;
+        let mut alternate = true;
+        for attribute in LINK_ATTRIBUTE.captures_iter(&tag[1]) {
+            let value = attribute.get(2).or_else(|| attribute.get(3)).map_or("", |value| value.as_str());
+            match attribute[1].to_ascii_lowercase().as_str() {
+                "href" => href = Some(unescape(value)),
+                _ => alternate = value.eq_ignore_ascii_case("alternate"),
+            }
+        }
+        href.filter(|href| alternate && !href.is_empty())
+    })
+}
+
+/// Converts the content of an element to plain text, without CDATA sections, markup or entities.
+fn text(content: &str) -> String {
+    let content = CDATA.replace_all(content, "$1");
+    // Escaped HTML, as in RSS descriptions, is unescaped before its tags are stripped
+    let content = unescape(&content);
+    let content = HTML_TAG.replace_all(&content, " ");
+    content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Replaces the XML entities of a text.
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,77 @@
+use super::atom;
+use crate::{
+    audit::log::{AuditEntry, AuditLog, AuditStatus},
+    config::env::AtomFeedConfig};
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::broadcast::{self, error::RecvError};
+use anyhow::{Context, Result};
+use tracing::{error, info, warn};
+
+/// Writes the Atom feed of the announcements to disk, for a static web server to serve.
+///
+/// The feed is written at startup, then rewritten whenever an announcement is posted, edited
+/// or retracted, replacing the previous file atomically.
+pub struct AtomFeedWriter {
+    path: PathBuf,
+    audit_log: Arc<AuditLog>,
+    config: AtomFeedConfig,
+}
+
+impl AtomFeedWriter {
+    /// Creates a new instance of `AtomFeedWriter`.
+    ///
+    /// # Arguments
+    /// * `path` - Where the feed is written.
+    /// * `audit_log` - The audit log the announcements are read from.
+    /// * `config` - The title, public URL and length of the feed.
+    pub fn new(path: &str, audit_log: Arc<AuditLog>, config: AtomFeedConfig) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            audit_log,
+            config,
+        }
+    }
+
+    /// Writes the feed, then again after every change of the announcements, until the audit log is dropped.
+    ///
+    /// # Arguments
+    /// * `recorded` - The entries recorded in the audit log, see [AuditLog::subscribe].
+    pub async fn run(self, mut recorded: broadcast::Receiver<AuditEntry>) {
+        info!("Writing the Atom feed of the announcements to {}", self.path.display());
+        loop {
+            if let Err(e) = self.write().await {
+                error!("Failed to write the Atom feed: {:?}", e);
+            }
+
+            // Missed entries don't matter, the whole feed is rendered from the audit log
+            loop {
+                match recorded.recv().await {
+                    Ok(entry) if matches!(entry.status, AuditStatus::Posted | AuditStatus::Edited | AuditStatus::Retracted) => break,
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("Atom feed writer fell behind by {} entries", missed);
+                        break;
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        }
+    }
+
+    async fn write(&self) -> Result<()> {
+        let announcements = self.audit_log.announcements(None, None).await;
+        let feed = atom::render(&self.config, &announcements);
+
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let temporary = self.path.with_extension("tmp");
+        tokio::fs::write(&temporary, feed)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
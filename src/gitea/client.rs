@@ -0,0 +1,272 @@
+use crate::{
+    config::{env::{GiteaConfig, HttpConfig}, tls},
+    github::{checks::CheckRun, repository::RepoName, types::Release},
+    source::Source};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration};
+use tokio::{sync::RwLock, time::sleep};
+use reqwest::Certificate;
+use serde::{de::DeserializeOwned, Deserialize};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+/// Commits listed per page, the most Gitea allows by default
+const COMMITS_PER_PAGE: usize = 50;
+
+/// Time between two attempts at gathering the contributors
+const BOOTSTRAP_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct ListedCommit {
+    commit: ListedCommitDetails,
+    /// The user the commit is linked to, unset when its email matches no account
+    author: Option<ListedUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedCommitDetails {
+    author: ListedCommitAuthor,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedCommitAuthor {
+    date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedUser {
+    login: String,
+    #[serde(default)]
+    website: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedRelease {
+    #[serde(default)]
+    assets: Vec<ListedAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedAsset {
+    name: String,
+}
+
+/// Status of a CI job reported on a commit, such as a Gitea Actions or Woodpecker job
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    context: String,
+    /// `pending`, `success`, `error`, `failure` or `warning`
+    status: String,
+}
+
+/// Contributors of a branch, gathered from its commits
+#[derive(Default)]
+struct BranchContributors {
+    ready: AtomicBool,
+    /// Date of the first commit of every contributor, by username
+    first_commits: RwLock<HashMap<String, DateTime<Utc>>>,
+}
+
+/// A client of the API of a Gitea or Forgejo instance, scoped to the watched repository
+pub struct GiteaClient {
+    http: reqwest::Client,
+    /// Base URL of the API, such as `https://codeberg.org/api/v1`
+    api_url: String,
+    repo: RepoName,
+    token: Option<String>,
+    // Contributors are tracked separately for every monitored branch
+    contributors: HashMap<String, BranchContributors>,
+}
+
+impl GiteaClient {
+    /// Creates a new instance of `GiteaClient`.
+    ///
+    /// # Arguments
+    /// * `token` - The access token for API authentication, if the repository is private.
+    /// * `config` - The URL of the instance and the repository.
+    /// * `branches` - The branches whose contributors are tracked.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if the repository is invalid or the extra root
+    /// certificates cannot be read.
+    pub fn new(token: Option<String>, config: &GiteaConfig, branches: &[String], http_config: &HttpConfig) -> Result<Self> {
+        let repo = RepoName::from_full_name(&config.repo)
+            .ok_or_else(|| anyhow!("Invalid Gitea repository: {}", config.repo))?;
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        info!("Gitea Api Client initialized for {} on {}", repo, config.url);
+
+        Ok(Self {
+            http: http.build()?,
+            api_url: format!("{}/api/v1", config.url.trim_end_matches('/')),
+            repo,
+            token,
+            contributors: branches.iter().map(|branch| (branch.clone(), BranchContributors::default())).collect(),
+        })
+    }
+
+    /// Returns the watched repository.
+    pub fn repo(&self) -> &RepoName {
+        &self.repo
+    }
+
+    /// Reads a resource of the API.
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+        let mut request = self.http.get(format!("{}{}", self.api_url, path)).query(query);
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("token {}", token));
+        }
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+
+    /// Returns the route of a resource of the repository, e.g. `/repos/owner/name/releases`.
+    fn repo_route(&self, path: &str) -> String {
+        format!("/repos/{}/{}{}", self.repo.owner, self.repo.name, path)
+    }
+
+    /// Gathers the contributors of every monitored branch from their commits, retrying until
+    /// it succeeds. Contributions to a branch are checked once its contributors are gathered.
+    pub async fn bootstrap_contributors(&self) {
+        for (branch, contributors) in &self.contributors {
+            loop {
+                match self.list_first_commits(branch).await {
+                    Ok(first_commits) => {
+                        info!("Gathered {} contributors of {} on {}", first_commits.len(), self.repo, branch);
+                        *contributors.first_commits.write().await = first_commits;
+                        contributors.ready.store(true, Ordering::Release);
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("Failed to gather the contributors of {} on {}, retrying: {:?}", self.repo, branch, e);
+                        sleep(BOOTSTRAP_RETRY_DELAY).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lists every commit of a branch, keeping the date of the first commit of every contributor.
+    async fn list_first_commits(&self, branch: &str) -> Result<HashMap<String, DateTime<Utc>>> {
+        let route = self.repo_route("/commits");
+        let limit = COMMITS_PER_PAGE.to_string();
+        let mut first_commits: HashMap<String, DateTime<Utc>> = HashMap::new();
+
+        for page in 1u32.. {
+            let commits: Vec<ListedCommit> = self
+                .get(&route, &[
+                    ("sha", branch),
+                    ("limit", &limit),
+                    ("page", &page.to_string()),
+                    ("stat", "false"),
+                    ("verification", "false"),
+                    ("files", "false"),
+                ])
+                .await?;
+            let last_page = commits.len() < COMMITS_PER_PAGE;
+
+            for commit in commits {
+                let Some(author) = commit.author else {
+                    continue;
+                };
+                let date = commit.commit.author.date;
+                first_commits
+                    .entry(author.login)
+                    .and_modify(|first| *first = (*first).min(date))
+                    .or_insert(date);
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(first_commits)
+    }
+
+    /// Records a contribution to a branch, returning whether it is the first of its contributor.
+    ///
+    /// # Arguments
+    /// * `branch` - The name of a monitored branch.
+    /// * `username` - The login of the contributor.
+    /// * `committed_at` - When the contribution was made, now if unknown.
+    pub async fn record_contribution(&self, branch: &str, username: &str, committed_at: Option<DateTime<Utc>>) -> bool {
+        let Some(contributors) = self.contributors.get(branch) else {
+            return false;
+        };
+
+        // A redelivered push is the first contribution as long as nothing older is known
+        let committed_at = committed_at.unwrap_or_else(Utc::now);
+        let mut first_commits = contributors.first_commits.write().await;
+        let first = first_commits.entry(username.to_string()).or_insert(committed_at);
+        *first = (*first).min(committed_at);
+        *first >= committed_at
+    }
+}
+
+#[async_trait]
+impl Source for GiteaClient {
+    fn name(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn contributors_ready(&self, branch: &str) -> bool {
+        self.contributors.get(branch).is_none_or(|contributors| contributors.ready.load(Ordering::Acquire))
+    }
+
+    async fn list_release_assets(&self, release: &Release) -> Result<Vec<String>> {
+        let listed: ListedRelease = self.get(&self.repo_route(&format!("/releases/{}", release.id)), &[]).await?;
+        Ok(listed.assets.into_iter().map(|asset| asset.name).collect())
+    }
+
+    async fn list_tag_check_runs(&self, tag: &str) -> Result<Vec<CheckRun>> {
+        let tag: String = form_urlencoded::byte_serialize(tag.as_bytes()).collect();
+        let statuses: Vec<CommitStatus> = self
+            .get(&self.repo_route(&format!("/commits/{}/statuses", tag)), &[("limit", "50")])
+            .await?;
+
+        // Statuses are listed newest first, only the latest one of every job counts
+        let mut latest: Vec<CommitStatus> = Vec::new();
+        for status in statuses {
+            if !latest.iter().any(|seen| seen.context == status.context) {
+                latest.push(status);
+            }
+        }
+
+        Ok(latest.into_iter().map(|status| {
+            let conclusion = match status.status.as_str() {
+                "success" => Some("success"),
+                "warning" => Some("neutral"),
+                "error" | "failure" => Some("failure"),
+                _ => None,
+            };
+            CheckRun {
+                name: status.context,
+                status: if conclusion.is_some() { "completed" } else { "in_progress" }.to_string(),
+                conclusion: conclusion.map(str::to_string),
+            }
+        }).collect())
+    }
+
+    // Gitea profiles have no X field, only a website that may point to X
+    async fn x_handle(&self, username: &str) -> Result<Option<String>> {
+        let user: ListedUser = self.get(&format!("/users/{}", username), &[]).await?;
+        let Ok(url) = reqwest::Url::parse(&user.website) else {
+            return Ok(None);
+        };
+        if !matches!(url.host_str(), Some("x.com" | "www.x.com" | "twitter.com" | "www.twitter.com")) {
+            return Ok(None);
+        }
+        Ok(url.path_segments()
+            .and_then(|mut segments| segments.find(|segment| !segment.is_empty()))
+            .map(|handle| handle.trim_start_matches('@').to_string()))
+    }
+}
@@ -0,0 +1,29 @@
+use super::client::GiteaClient;
+use crate::github::first_contribution::{Contribution, FirstContributionStrategy};
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// The first commit of the contributor on the branch of the Gitea repository.
+///
+/// The contributors gathered from the commits of the branch at startup are kept up to date
+/// with every push, so checking a contribution also records it.
+pub struct FirstCommit {
+    gitea_client: Arc<GiteaClient>,
+}
+
+impl FirstCommit {
+    /// Creates the strategy, looking up previous commits with `gitea_client`.
+    pub fn new(gitea_client: Arc<GiteaClient>) -> Self {
+        Self { gitea_client }
+    }
+}
+
+#[async_trait]
+impl FirstContributionStrategy for FirstCommit {
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool> {
+        Ok(self.gitea_client
+            .record_contribution(contribution.branch, contribution.username, contribution.committed_at)
+            .await)
+    }
+}
@@ -0,0 +1,168 @@
+use crate::github::project::GraphQlError;
+use std::collections::HashMap;
+use serde::Deserialize;
+use chrono::{DateTime, Utc};
+
+/// Commits of each branch looked at per query, newer ones than the last query are announced
+pub const ACTIVITY_COMMITS: usize = 50;
+
+/// Releases looked at per query
+pub const ACTIVITY_RELEASES: usize = 10;
+
+/// Builds the query of the recent activity of a repository, gathering in a single request what
+/// takes a request per commit over REST: the latest commits of every monitored branch, each
+/// with the account of its author and the merged pull request that brought it in, and the
+/// latest releases.
+///
+/// The branches are queried under the aliases `branch0`, `branch1` and so on, from the
+/// `$branch0`, `$branch1` variables holding their qualified names.
+///
+/// # Arguments
+/// * `branches` - The number of monitored branches.
+pub fn activity_query(branches: usize) -> String {
+    let variables: String = (0..branches).map(|index| format!(", $branch{}: String!", index)).collect();
+    let refs: String = (0..branches)
+        .map(|index| format!("\n    branch{0}: ref(qualifiedName: $branch{0}) {{ ...RecentCommits }}", index))
+        .collect();
+    format!(r#"
+query($owner: String!, $name: String!{variables}) {{
+  repository(owner: $owner, name: $name) {{{refs}
+    releases(first: {ACTIVITY_RELEASES}, orderBy: {{field: CREATED_AT, direction: DESC}}) {{
+      nodes {{ databaseId tagName name url description isDraft isPrerelease }}
+    }}
+  }}
+}}
+
+fragment RecentCommits on Ref {{
+  target {{
+    ... on Commit {{
+      history(first: {ACTIVITY_COMMITS}) {{
+        nodes {{
+          oid
+          message
+          url
+          committedDate
+          author {{ name email user {{ login }} }}
+          associatedPullRequests(first: 1) {{
+            nodes {{ number mergedAt author {{ login }} }}
+          }}
+        }}
+      }}
+    }}
+  }}
+}}
+"#)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityResponse {
+    pub data: Option<ActivityData>,
+    #[serde(default)]
+    pub errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityData {
+    pub repository: Option<ActivityRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityRepository {
+    pub releases: Nodes<ActivityRelease>,
+    /// Branches under their alias, unset when the branch doesn't exist
+    #[serde(flatten)]
+    pub branches: HashMap<String, Option<ActivityRef>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nodes<T> {
+    pub nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityRef {
+    pub target: Option<ActivityTarget>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivityTarget {
+    pub history: Option<Nodes<ActivityCommit>>,
+}
+
+/// A commit of a branch, newest first
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityCommit {
+    pub oid: String,
+    pub message: String,
+    pub url: String,
+    pub committed_date: Option<DateTime<Utc>>,
+    pub author: Option<ActivityCommitAuthor>,
+    pub associated_pull_requests: Option<Nodes<ActivityPullRequest>>,
+}
+
+impl ActivityCommit {
+    /// Returns the merged pull request that brought the commit in, if any
+    pub fn merged_pull(&self) -> Option<&ActivityPullRequest> {
+        self.associated_pull_requests.as_ref()?
+            .nodes
+            .iter()
+            .find(|pull| pull.merged_at.is_some())
+    }
+
+    /// Returns the login of the author of the commit.
+    ///
+    /// The account GitHub matched the email of the commit with comes first, like in push
+    /// events. Commits made with an email linked to no account fall back to the author of
+    /// the pull request that brought them in, rather than going unannounced.
+    pub fn login(&self) -> Option<&str> {
+        self.author.as_ref()
+            .and_then(|author| author.user.as_ref())
+            .map(|user| user.login.as_str())
+            .or_else(|| self.merged_pull()?.author.as_ref().map(|author| author.login.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActivityCommitAuthor {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub user: Option<ActivityUser>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActivityUser {
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPullRequest {
+    pub number: u64,
+    pub merged_at: Option<DateTime<Utc>>,
+    /// Unset when the account was deleted
+    pub author: Option<ActivityUser>,
+}
+
+/// A release, newest first
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityRelease {
+    /// Id of the release in the REST API, which release events carry
+    pub database_id: Option<u64>,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub url: String,
+    pub description: Option<String>,
+    pub is_draft: bool,
+    pub is_prerelease: bool,
+}
+
+/// Recent activity of a repository, see [activity_query]
+#[derive(Debug)]
+pub struct RepositoryActivity {
+    /// Latest commits of each monitored branch, newest first, missing for branches that don't exist
+    pub commits: HashMap<String, Vec<ActivityCommit>>,
+    /// Latest releases, newest first
+    pub releases: Vec<ActivityRelease>,
+}
@@ -0,0 +1,66 @@
+use serde::Deserialize;
+
+/// Check runs of a commit, as returned by the checks API
+#[derive(Debug, Deserialize)]
+pub(crate) struct CheckRunsResponse {
+    pub total_count: u64,
+    #[serde(default)]
+    pub check_runs: Vec<CheckRun>,
+}
+
+/// A CI job reported on a commit, such as a GitHub Actions job
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckRun {
+    pub name: String,
+    /// `queued`, `in_progress` or `completed`
+    pub status: String,
+    /// Set once completed, e.g. `success` or `failure`
+    pub conclusion: Option<String>,
+}
+
+/// Where the CI of a commit stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CiStatus {
+    /// No check run is reported yet
+    Missing,
+    /// Some check runs haven't completed yet
+    Running,
+    /// Every check run completed successfully, or was skipped
+    Passed,
+    /// These check runs didn't succeed
+    Failed(Vec<String>),
+}
+
+impl CiStatus {
+    /// Sums up the check runs of a commit.
+    ///
+    /// # Arguments
+    /// * `check_runs` - Every check run of the commit.
+    /// * `required` - Names of the check runs to consider, or every check run when empty.
+    pub fn of(check_runs: &[CheckRun], required: &[String]) -> Self {
+        let runs: Vec<&CheckRun> = check_runs
+            .iter()
+            .filter(|run| required.is_empty() || required.iter().any(|name| name.eq_ignore_ascii_case(&run.name)))
+            .collect();
+        if runs.is_empty() {
+            return CiStatus::Missing;
+        }
+
+        let failed: Vec<String> = runs
+            .iter()
+            .filter(|run| run.status == "completed")
+            .filter(|run| !matches!(run.conclusion.as_deref(), Some("success" | "neutral" | "skipped")))
+            .map(|run| run.name.clone())
+            .collect();
+        if !failed.is_empty() {
+            CiStatus::Failed(failed)
+        } else if runs.iter().any(|run| run.status != "completed") {
+            CiStatus::Running
+        } else if required.iter().any(|name| !runs.iter().any(|run| name.eq_ignore_ascii_case(&run.name))) {
+            // Required checks that haven't started yet
+            CiStatus::Running
+        } else {
+            CiStatus::Passed
+        }
+    }
+}
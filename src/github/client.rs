@@ -1,66 +1,1110 @@
-use super::contributor::{ContributorManager, ContributorInfo};
-use octocrab::Octocrab;
-use anyhow::Result;
-use tracing::info;
+use super::{
+    activity::{activity_query, ActivityCommit, ActivityResponse, RepositoryActivity},
+    checks::{CheckRun, CheckRunsResponse},
+    contributor::{ContributorManager, ContributorInfo},
+    downloads::{ListedReleaseAssets, ReleaseDownloads},
+    project::{ProjectItem, ProjectItemsResponse, PROJECT_ITEMS_QUERY},
+    pull_association::PullAssociations,
+    repository::{RepoLocation, RepoName},
+    sponsors::{SponsorCountResponse, SPONSOR_COUNT_QUERY},
+    traffic::{ClonesResponse, Referrer, ViewsResponse, WeeklyTraffic},
+    types::{Commit, CommitAuthor, Release, ReleaseAction, ReleaseEvent, Repository}};
+use crate::{
+    config::{env::HttpConfig, tls},
+    scheduler::{request_budget::RequestBudget, retry_budget::RetryBudget},
+    source::Source};
+use std::{collections::{HashMap, HashSet}, path::Path, sync::{Arc, Mutex}};
+use tokio::sync::RwLock;
+use octocrab::{
+    auth::AppAuth,
+    models::{pulls::PullRequest, AppId, AuthorAssociation, InstallationId, Milestone, StarGazer},
+    params::{pulls::Sort, Direction, State},
+    service::middleware::{
+        auth_header::AuthHeaderLayer,
+        base_uri::BaseUriLayer,
+        extra_headers::ExtraHeadersLayer,
+        retry::RetryConfig},
+    AuthState, Octocrab, OctocrabBuilder};
+use http::{header::USER_AGENT, HeaderValue, Uri};
+use hyper_rustls::HttpsConnectorBuilder;
+use hyper_timeout::TimeoutConnector;
+use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+use chrono::{DateTime, NaiveDate, Utc};
+use ipnet::IpNet;
+use tower::{retry::RetryLayer, timeout::TimeoutLayer};
+use tower_http::follow_redirect::FollowRedirectLayer;
+use serde::{de::IgnoredAny, Deserialize};
+use jsonwebtoken::EncodingKey;
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+#[derive(Debug, Deserialize)]
+struct GitHubMeta {
+    hooks: Vec<IpNet>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedRelease {
+    created_at: DateTime<Utc>,
+    /// Unset for drafts
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedCommit {
+    sha: String,
+    html_url: String,
+    commit: ListedCommitDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedCommitDetails {
+    message: String,
+    author: Option<ListedCommitAuthor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedCommitAuthor {
+    name: String,
+    #[serde(default)]
+    email: String,
+    date: Option<DateTime<Utc>>,
+}
+
+/// A repository of an organization, as listed by the organization repositories API
+#[derive(Debug, Deserialize)]
+pub struct OrganizationRepository {
+    pub name: String,
+    pub full_name: String,
+    #[serde(default)]
+    pub fork: bool,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// A link on a user's profile, as listed by the social accounts API
+#[derive(Debug, Deserialize)]
+struct SocialAccount {
+    provider: String,
+    url: String,
+}
+
+/// Credentials of the GitHub API
+#[derive(Clone)]
+pub enum GitHubAuth {
+    /// Personal access token
+    Token(String),
+    /// GitHub App acting as one of its installations, whose tokens are minted as they expire and
+    /// come with higher rate limits than personal access tokens
+    App {
+        app_id: u64,
+        /// Private key of the app, in PEM
+        private_key: String,
+        /// Installation the bot acts as, the one on the repository unless set
+        installation_id: Option<u64>,
+    },
+}
+
+/// Commits whose merged pull request is remembered at most, the oldest ones are forgotten beyond
+const MAX_MERGED_PULLS: usize = 1000;
+
+/// The merged pull request a commit came in with
+#[derive(Debug, Clone)]
+struct MergedPull {
+    number: u64,
+    /// Login of the author, unset when the account was deleted
+    author: Option<String>,
+}
 
 pub struct GitHubClient {
     client: Octocrab,
-    repo_owner: String,
-    repo_name: String,
-    contributor_manager: ContributorManager,
+    repo: RepoLocation,
+    // Contributors are tracked separately for every monitored branch
+    contributor_managers: HashMap<String, ContributorManager>,
+    retry_budget: Arc<RetryBudget>,
+    // Paces the requests of background scans, which go through unpaced without one
+    request_budget: Option<Arc<RequestBudget>>,
+    // Merged pull request of the commits read by `recent_activity`, or none, by SHA
+    merged_pulls: Mutex<HashMap<String, Option<MergedPull>>>,
+    // Association of the authors of pull requests while they were open
+    pull_associations: PullAssociations,
 }
 
 impl GitHubClient {
-    /// Creates a new instance of `GitHubClient` with the specified token and repository details.
+    /// Creates a new instance of `GitHubClient` with the specified credentials and repository details.
     ///
     /// # Arguments
-    /// * `token` - A string containing the personal access token for GitHub API authentication.
+    /// * `auth` - The personal access token or the GitHub App to authenticate with.
     /// * `repo_owner` - A string containing the owner of the repository.
     /// * `repo_name` - A string containing the name of the repository.
+    /// * `branches` - The branches whose contributors are tracked.
+    /// * `state_dir` - The directory where the contributors of every branch and the associations
+    ///   of the open pull requests are persisted.
+    /// * `http` - The API endpoint, timeouts and extra root certificates to use.
     ///
     /// # Returns
-    /// A result containing the initialized `GitHubClient` or an error if initialization fails.
-    pub async fn new(token: String, repo_owner: String, repo_name: String) -> Result<Self> {
-        let client = Octocrab::builder()
-            .personal_token(token)
-            .build()?;
+    /// A result containing the initialized `GitHubClient` or an error if initialization fails,
+    /// such as when the GitHub App isn't installed on the repository.
+    pub async fn new(
+        auth: GitHubAuth,
+        repo_owner: String,
+        repo_name: String,
+        branches: Vec<String>,
+        state_dir: &Path,
+        http: &HttpConfig,
+    ) -> Result<Self> {
+        let client = match auth {
+            GitHubAuth::Token(token) => build_octocrab(Some(&token), AuthState::None, http)?,
+            GitHubAuth::App { app_id, private_key, installation_id } => {
+                let key = EncodingKey::from_rsa_pem(private_key.as_bytes())
+                    .context("The private key of the GitHub App is not a PEM encoded RSA key")?;
+                let app = build_octocrab(None, AuthState::App(AppAuth { app_id: AppId(app_id), key }), http)?;
+                let installation_id = match installation_id {
+                    Some(installation_id) => InstallationId(installation_id),
+                    None => app.apps().get_repository_installation(&repo_owner, &repo_name)
+                        .await
+                        .with_context(|| format!("GitHub App {} is not installed on {}/{}", app_id, repo_owner, repo_name))?
+                        .id,
+                };
+                info!("Authenticating as installation {} of GitHub App {}", installation_id, app_id);
+                // The installation tokens are minted on the first request, and again once expired
+                app.installation(installation_id)?
+            }
+        };
+
+        let repo = Arc::new(RwLock::new(RepoName::new(repo_owner, repo_name)));
+        let contributor_managers = contributor_managers(&client, &repo, branches, state_dir);
 
-        let contributor_manager = ContributorManager::new(
-            client.clone(),
-            repo_owner.clone(),
-            repo_name.clone(),
-            300, // 5 minutes cache TTL
-        );
-        
         info!("Github Api Client initialized");
 
         Ok(Self {
             client,
-            repo_owner,
-            repo_name,
-            contributor_manager,
+            repo,
+            contributor_managers,
+            retry_budget: Arc::new(RetryBudget::new(0)),
+            request_budget: None,
+            merged_pulls: Mutex::new(HashMap::new()),
+            pull_associations: PullAssociations::new(state_dir.join("pull_associations.json")),
         })
     }
 
-    /// Checks if the specified user is making their first contribution to the repository.
+    /// Creates a client of another repository, sharing the connection and the budgets of this one.
+    ///
+    /// # Arguments
+    /// * `repo` - The owner and name of the repository.
+    /// * `branches` - The branches whose contributors are tracked.
+    /// * `state_dir` - The directory where the contributors of every branch and the associations
+    ///   of the open pull requests are persisted.
+    pub fn for_repository(&self, repo: RepoName, branches: Vec<String>, state_dir: &Path) -> Self {
+        let repo = Arc::new(RwLock::new(repo));
+        let contributor_managers = contributor_managers(&self.client, &repo, branches, state_dir);
+        Self {
+            client: self.client.clone(),
+            repo,
+            contributor_managers,
+            retry_budget: Arc::clone(&self.retry_budget),
+            request_budget: self.request_budget.clone(),
+            merged_pulls: Mutex::new(HashMap::new()),
+            pull_associations: PullAssociations::new(state_dir.join("pull_associations.json")),
+        }
+    }
+
+    /// Spends the shared retry budget on retrying to gather the contributors.
+    pub fn with_retry_budget(mut self, retry_budget: Arc<RetryBudget>) -> Self {
+        self.retry_budget = retry_budget;
+        self
+    }
+
+    /// Paces the requests of background scans with the shared request budget.
+    pub fn with_request_budget(mut self, request_budget: Arc<RequestBudget>) -> Self {
+        self.request_budget = Some(request_budget);
+        self
+    }
+
+    /// Waits for the turn of a background request, see [RequestBudget::pace].
+    async fn pace(&self, site: &str) {
+        if let Some(request_budget) = &self.request_budget {
+            request_budget.pace(&self.client, site).await;
+        }
+    }
+
+    /// Returns the current location of the monitored repository.
+    pub async fn repo(&self) -> RepoName {
+        self.repo.read().await.clone()
+    }
+
+    /// Points the client at the new location of a renamed or transferred repository.
+    ///
+    /// # Arguments
+    /// * `new_location` - The repository's new owner and name.
+    ///
+    /// # Returns
+    /// The previous location if it changed, or `None` if the client already used `new_location`.
+    pub async fn follow_repository(&self, new_location: RepoName) -> Option<RepoName> {
+        let mut repo = self.repo.write().await;
+        if *repo == new_location {
+            return None;
+        }
+
+        warn!("Repository {} moved to {}, update REPO_OWNER and REPO_NAME accordingly", repo, new_location);
+        Some(std::mem::replace(&mut *repo, new_location))
+    }
+
+    /// Looks up the repository and follows it if GitHub reports it under a different name.
+    ///
+    /// The API transparently redirects requests for renamed repositories, so this picks up
+    /// the new location before GitHub stops redirecting the old one.
+    ///
+    /// # Returns
+    /// A result containing the previous location if the repository moved, or `None` otherwise.
+    pub async fn sync_repository_location(&self) -> Result<Option<RepoName>> {
+        let repo = self.repo().await;
+        let details = self.client.repos(&repo.owner, &repo.name).get().await?;
+
+        let current = match (details.owner, details.full_name) {
+            (_, Some(full_name)) => RepoName::from_full_name(&full_name),
+            (Some(owner), None) => Some(RepoName::new(owner.login, details.name)),
+            (None, None) => None,
+        };
+
+        match current {
+            Some(current) => Ok(self.follow_repository(current).await),
+            None => Ok(None),
+        }
+    }
+
+    /// Lists the names of the assets attached to a release.
+    ///
+    /// # Arguments
+    /// * `release_id` - The ID of the release.
+    ///
+    /// # Returns
+    /// A result containing the asset names, or an error if the release cannot be read.
+    pub async fn list_release_assets(&self, release_id: u64) -> Result<Vec<String>> {
+        let repo = self.repo().await;
+        let release = self.client
+            .repos(&repo.owner, &repo.name)
+            .releases()
+            .get(release_id)
+            .await?;
+
+        Ok(release.assets.into_iter().map(|asset| asset.name).collect())
+    }
+
+    /// Lists the check runs of the commit a tag points to, such as its CI workflow jobs.
     ///
     /// # Arguments
+    /// * `tag` - The name of the tag, e.g. `v1.0.0`.
+    ///
+    /// # Returns
+    /// A result containing the check runs, or an error if the request fails.
+    pub async fn list_tag_check_runs(&self, tag: &str) -> Result<Vec<CheckRun>> {
+        let repo = self.repo().await;
+        let tag: String = form_urlencoded::byte_serialize(tag.as_bytes()).collect();
+        let route = format!("/repos/{}/{}/commits/{}/check-runs", repo.owner, repo.name, tag);
+        let mut check_runs = Vec::new();
+
+        for page in 1u32.. {
+            let response: CheckRunsResponse = self.client
+                .get(&route, Some(&[("per_page", "100"), ("page", &page.to_string())]))
+                .await?;
+            let last_page = response.check_runs.len() < 100;
+            check_runs.extend(response.check_runs);
+            if last_page || check_runs.len() as u64 >= response.total_count {
+                break;
+            }
+        }
+
+        Ok(check_runs)
+    }
+
+    /// Looks up a release by its tag, as the event GitHub sends when it is published.
+    ///
+    /// # Arguments
+    /// * `tag` - The tag of the release, e.g. `v1.0.0`.
+    ///
+    /// # Returns
+    /// A result containing the `published` release event, or an error if there is no such release.
+    pub async fn published_release_event(&self, tag: &str) -> Result<ReleaseEvent> {
+        let repo = self.repo().await;
+        let tag: String = form_urlencoded::byte_serialize(tag.as_bytes()).collect();
+        let repository: Repository = self.client
+            .get(format!("/repos/{}/{}", repo.owner, repo.name), None::<&()>)
+            .await?;
+        let release: Release = self.client
+            .get(format!("/repos/{}/{}/releases/tags/{}", repo.owner, repo.name, tag), None::<&()>)
+            .await?;
+
+        Ok(ReleaseEvent {
+            action: ReleaseAction::Published,
+            release,
+            repository,
+            changes: None,
+        })
+    }
+
+    /// Checks whether a tag has a published release.
+    ///
+    /// # Arguments
+    /// * `tag` - The name of the tag, e.g. `v1.0.0`.
+    ///
+    /// # Returns
+    /// A result containing `true` if a release of the tag is published.
+    pub async fn has_release(&self, tag: &str) -> Result<bool> {
+        let repo = self.repo().await;
+        let tag: String = form_urlencoded::byte_serialize(tag.as_bytes()).collect();
+        let route = format!("/repos/{}/{}/releases/tags/{}", repo.owner, repo.name, tag);
+        match self.client.get::<Release, _, _>(route, None::<&()>).await {
+            Ok(_) => Ok(true),
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code == http::StatusCode::NOT_FOUND => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lists the open milestones of the repository, soonest due first.
+    ///
+    /// # Returns
+    /// A result containing the open milestones, or an error if the request fails.
+    pub async fn list_open_milestones(&self) -> Result<Vec<Milestone>> {
+        let repo = self.repo().await;
+        self.pace("listing the milestones").await;
+        let route = format!("/repos/{}/{}/milestones", repo.owner, repo.name);
+        let milestones = self.client
+            .get(route, Some(&[
+                ("state", "open"),
+                ("sort", "due_on"),
+                ("direction", "asc"),
+                ("per_page", "100"),
+            ]))
+            .await?;
+
+        Ok(milestones)
+    }
+
+    /// Lists the pull requests merged since the given time, most recently updated first.
+    ///
+    /// # Arguments
+    /// * `since` - Pull requests merged before this time are left out.
+    ///
+    /// # Returns
+    /// A result containing the merged pull requests, or an error if the request fails.
+    pub async fn list_merged_pulls(&self, since: DateTime<Utc>) -> Result<Vec<PullRequest>> {
+        let repo = self.repo().await;
+        let mut merged = Vec::new();
+
+        for page in 1u32.. {
+            self.pace("listing the merged pull requests").await;
+            let pulls = self.client
+                .pulls(&repo.owner, &repo.name)
+                .list()
+                .state(State::Closed)
+                .sort(Sort::Updated)
+                .direction(Direction::Descending)
+                .per_page(100)
+                .page(page)
+                .send()
+                .await?;
+
+            // A pull request is updated when merged, so older pages can't hold newer merges
+            let exhausted = pulls.next.is_none()
+                || pulls.items.last().and_then(|pull| pull.updated_at).is_none_or(|updated| updated < since);
+            merged.extend(
+                pulls.items
+                    .into_iter()
+                    .filter(|pull| pull.merged_at.is_some_and(|merged_at| merged_at >= since)),
+            );
+            if exhausted {
+                break;
+            }
+        }
+
+        Ok(merged)
+    }
+
+    /// Counts the closed issues of the repository, leaving pull requests out.
+    ///
+    /// # Returns
+    /// A result containing the number of closed issues, or an error if the search fails.
+    pub async fn count_closed_issues(&self) -> Result<u64> {
+        let repo = self.repo().await;
+        self.count_search_results(&format!("repo:{} is:issue is:closed", repo.full_name())).await
+    }
+
+    /// Counts the stars of the repository.
+    ///
+    /// # Returns
+    /// A result containing the number of stargazers, or an error if the repository can't be read.
+    pub async fn count_stars(&self) -> Result<u64> {
+        let repo = self.repo().await;
+        let details = self.client.repos(&repo.owner, &repo.name).get().await?;
+        details.stargazers_count
+            .map(u64::from)
+            .ok_or_else(|| anyhow!("{} has no stargazer count", repo.full_name()))
+    }
+
+    /// Fetches the creation date of the repository.
+    ///
+    /// # Returns
+    /// A result containing the creation date, or an error if the repository can't be read.
+    pub async fn created_at(&self) -> Result<DateTime<Utc>> {
+        let repo = self.repo().await;
+        let details = self.client.repos(&repo.owner, &repo.name).get().await?;
+        details.created_at
+            .ok_or_else(|| anyhow!("{} has no creation date", repo.full_name()))
+    }
+
+    /// Fetches the networks GitHub delivers webhooks from, as published by the meta API.
+    ///
+    /// # Returns
+    /// A result containing the webhook networks, or an error if the request fails.
+    pub async fn webhook_networks(&self) -> Result<Vec<IpNet>> {
+        let meta: GitHubMeta = self.client.get("/meta", None::<&()>).await?;
+        Ok(meta.hooks)
+    }
+
+    /// Lists the stargazers who starred the repository since the given time, oldest first.
+    ///
+    /// # Arguments
+    /// * `since` - Stars given before this time are left out.
+    ///
+    /// # Returns
+    /// A result containing the recent stargazers, or an error if the request fails.
+    pub async fn list_recent_stargazers(&self, since: DateTime<Utc>) -> Result<Vec<StarGazer>> {
+        let repo = self.repo().await;
+        let handler = self.client.repos(&repo.owner, &repo.name);
+        self.pace("listing the stargazers").await;
+        let first = handler.list_stargazers().per_page(100).send().await?;
+
+        // Stargazers are listed oldest first, so the recent ones are on the last pages
+        let mut recent = Vec::new();
+        let mut page = first.number_of_pages().unwrap_or(1);
+        let mut first = Some(first);
+        loop {
+            let stargazers = match first.take() {
+                Some(stargazers) if page <= 1 => stargazers,
+                _ => {
+                    self.pace("listing the stargazers").await;
+                    handler.list_stargazers().per_page(100).page(page).send().await?
+                }
+            };
+            let exhausted = stargazers.items
+                .first()
+                .and_then(|stargazer| stargazer.starred_at)
+                .is_none_or(|starred_at| starred_at < since);
+            recent.extend(
+                stargazers.items
+                    .into_iter()
+                    .rev()
+                    .filter(|stargazer| stargazer.starred_at.is_some_and(|starred_at| starred_at >= since)),
+            );
+            if exhausted || page <= 1 {
+                break;
+            }
+            page -= 1;
+        }
+
+        recent.reverse();
+        Ok(recent)
+    }
+
+    /// Counts the followers of a GitHub user.
+    ///
+    /// # Arguments
+    /// * `username` - The login of the user.
+    ///
+    /// # Returns
+    /// A result containing the number of followers, or an error if the user cannot be read.
+    pub async fn count_followers(&self, username: &str) -> Result<u64> {
+        self.pace("counting the followers of a stargazer").await;
+        Ok(self.client.users(username).profile().await?.followers)
+    }
+
+    /// Finds the X handle of a GitHub user, from the X username or the social links of their profile.
+    ///
+    /// # Arguments
+    /// * `username` - The login of the user.
+    ///
+    /// # Returns
+    /// A result containing the handle without the `@`, `None` if the profile doesn't link to X,
+    /// or an error if the profile cannot be read.
+    pub async fn x_handle(&self, username: &str) -> Result<Option<String>> {
+        let profile = self.client.users(username).profile().await?;
+        if let Some(handle) = profile.twitter_username.filter(|handle| !handle.is_empty()) {
+            return Ok(Some(handle.trim_start_matches('@').to_string()));
+        }
+
+        // GitHub lists links to x.com under the provider it used before the rename
+        let accounts: Vec<SocialAccount> = self.client
+            .get(format!("/users/{}/social_accounts", username), None::<&()>)
+            .await?;
+        Ok(accounts
+            .into_iter()
+            .filter(|account| account.provider == "twitter")
+            .find_map(|account| {
+                let url = reqwest::Url::parse(&account.url).ok()?;
+                let handle = url.path_segments()?.find(|segment| !segment.is_empty())?;
+                Some(handle.trim_start_matches('@').to_string())
+            }))
+    }
+
+    /// Finds the latest commit a user authored on a branch, as it appears in push events.
+    ///
+    /// # Arguments
+    /// * `branch` - The branch the commit is on.
+    /// * `username` - The login of the author.
+    ///
+    /// # Returns
+    /// A result containing the commit, `None` if the user has no commits on the branch,
+    /// or an error if the request fails.
+    pub async fn latest_commit_by(&self, branch: &str, username: &str) -> Result<Option<Commit>> {
+        let repo = self.repo().await;
+        let route = format!("/repos/{}/{}/commits", repo.owner, repo.name);
+        let commits: Vec<ListedCommit> = self.client
+            .get(route, Some(&[("sha", branch), ("author", username), ("per_page", "1")]))
+            .await?;
+
+        Ok(commits.into_iter().next().map(|listed| {
+            let author = listed.commit.author;
+            Commit {
+                id: listed.sha,
+                message: listed.commit.message,
+                timestamp: author.as_ref().and_then(|author| author.date),
+                author: CommitAuthor {
+                    name: author.as_ref().map_or_else(|| username.to_string(), |author| author.name.clone()),
+                    email: author.map(|author| author.email).unwrap_or_default(),
+                    username: Some(username.to_string()),
+                },
+                url: listed.html_url,
+            }
+        }))
+    }
+
+    /// Counts the pull requests merged in the repository between two dates.
+    ///
+    /// # Arguments
+    /// * `from` - The first day, included.
+    /// * `to` - The last day, included.
+    ///
+    /// # Returns
+    /// A result containing the number of merged pull requests, or an error if the search fails.
+    pub async fn count_merged_pulls_between(&self, from: NaiveDate, to: NaiveDate) -> Result<u64> {
+        let repo = self.repo().await;
+        self.pace("counting the merged pull requests").await;
+        self.count_search_results(&format!("repo:{} is:pr is:merged merged:{}..{}", repo.full_name(), from, to)).await
+    }
+
+    /// Counts the releases published in the repository in a period.
+    ///
+    /// # Arguments
+    /// * `since` - The start of the period, included.
+    /// * `until` - The end of the period, excluded.
+    ///
+    /// # Returns
+    /// A result containing the number of published releases, or an error if the request fails.
+    pub async fn count_releases_between(&self, since: DateTime<Utc>, until: DateTime<Utc>) -> Result<u64> {
+        let repo = self.repo().await;
+        let route = format!("/repos/{}/{}/releases", repo.owner, repo.name);
+        let mut count = 0;
+
+        for page in 1u32.. {
+            self.pace("listing the releases").await;
+            let releases: Vec<ListedRelease> = self.client
+                .get(&route, Some(&[("per_page", "100"), ("page", &page.to_string())]))
+                .await?;
+            count += releases
+                .iter()
+                .filter(|release| release.published_at.is_some_and(|published_at| published_at >= since && published_at < until))
+                .count() as u64;
+
+            // Releases are listed newest first, and are published after they are created
+            if releases.len() < 100 || releases.last().is_some_and(|release| release.created_at < since) {
+                break;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Lists the downloads of the assets of every published release, newest first.
+    ///
+    /// # Returns
+    /// A result containing the downloads of each release, or an error if the request fails.
+    pub async fn list_release_downloads(&self) -> Result<Vec<ReleaseDownloads>> {
+        let repo = self.repo().await;
+        let route = format!("/repos/{}/{}/releases", repo.owner, repo.name);
+        let mut downloads = Vec::new();
+
+        for page in 1u32.. {
+            self.pace("listing the release downloads").await;
+            let releases: Vec<ListedReleaseAssets> = self.client
+                .get(&route, Some(&[("per_page", "100"), ("page", &page.to_string())]))
+                .await?;
+            let last_page = releases.len() < 100;
+            downloads.extend(releases.into_iter().filter(|release| !release.draft).map(ReleaseDownloads::from));
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(downloads)
+    }
+
+    /// Reads the recent activity of the repository in a single GraphQL query, see [activity_query].
+    ///
+    /// The merged pull requests of the commits are remembered, so the first contribution
+    /// strategies don't look them up again.
+    ///
+    /// # Arguments
+    /// * `branches` - The monitored branches.
+    ///
+    /// # Returns
+    /// A result containing the latest commits of each branch and the latest releases, or an error if the query fails.
+    pub async fn recent_activity(&self, branches: &[String]) -> Result<RepositoryActivity> {
+        let repo = self.repo().await;
+        let mut variables = serde_json::json!({ "owner": repo.owner, "name": repo.name });
+        for (index, branch) in branches.iter().enumerate() {
+            variables[format!("branch{}", index)] = format!("refs/heads/{}", branch).into();
+        }
+        self.pace("reading the recent activity").await;
+        let response: ActivityResponse = self.client
+            .graphql(&serde_json::json!({
+                "query": activity_query(branches.len()),
+                "variables": variables,
+            }))
+            .await?;
+
+        if let Some(error) = response.errors.first() {
+            return Err(anyhow!("GraphQL error reading the activity of {}: {}", repo, error.message));
+        }
+        let repository = response.data
+            .and_then(|data| data.repository)
+            .ok_or_else(|| anyhow!("Repository {} not found", repo))?;
+
+        let mut commits = HashMap::new();
+        for (alias, branch_ref) in repository.branches {
+            let Some(branch) = alias.strip_prefix("branch")
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| branches.get(index)) else {
+                continue;
+            };
+            if let Some(history) = branch_ref.and_then(|branch_ref| branch_ref.target).and_then(|target| target.history) {
+                commits.insert(branch.clone(), history.nodes);
+            }
+        }
+        self.remember_merged_pulls(commits.values().flatten());
+
+        Ok(RepositoryActivity { commits, releases: repository.releases.nodes })
+    }
+
+    /// Remembers the merged pull request of the commits, forgetting the previous ones once too many are remembered
+    fn remember_merged_pulls<'a>(&self, commits: impl Iterator<Item = &'a ActivityCommit>) {
+        let mut merged_pulls = self.merged_pulls.lock().unwrap();
+        if merged_pulls.len() > MAX_MERGED_PULLS {
+            merged_pulls.clear();
+        }
+        for commit in commits {
+            let merged_pull = commit.merged_pull().map(|pull| MergedPull {
+                number: pull.number,
+                author: pull.author.as_ref().map(|author| author.login.clone()),
+            });
+            merged_pulls.insert(commit.oid.clone(), merged_pull);
+        }
+    }
+
+    /// Lists the public repositories of an organization.
+    ///
+    /// # Arguments
+    /// * `organization` - The login of the organization.
+    ///
+    /// # Returns
+    /// A result containing the repositories, or an error if the request fails.
+    pub async fn list_organization_repositories(&self, organization: &str) -> Result<Vec<OrganizationRepository>> {
+        let route = format!("/orgs/{}/repos", organization);
+        let mut repositories = Vec::new();
+
+        for page in 1u32.. {
+            self.pace("listing the repositories of the organization").await;
+            let listed: Vec<OrganizationRepository> = self.client
+                .get(&route, Some(&[("type", "public"), ("per_page", "100"), ("page", &page.to_string())]))
+                .await?;
+            let last_page = listed.len() < 100;
+            repositories.extend(listed);
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(repositories)
+    }
+
+    /// Counts the contributors of the repository, from the commits of its default branch.
+    ///
+    /// # Returns
+    /// A result containing the number of contributors, or an error if the request fails.
+    pub async fn count_contributors(&self) -> Result<u64> {
+        let repo = self.repo().await;
+        let route = format!("/repos/{}/{}/contributors", repo.owner, repo.name);
+        let mut count = 0;
+
+        for page in 1u32.. {
+            self.pace("listing the contributors").await;
+            let contributors: Vec<IgnoredAny> = self.client
+                .get(&route, Some(&[("per_page", "100"), ("page", &page.to_string())]))
+                .await?;
+            count += contributors.len() as u64;
+            if contributors.len() < 100 {
+                break;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Counts the merged pull requests opened by a user in the repository.
+    ///
+    /// # Arguments
+    /// * `username` - The login of the user.
+    ///
+    /// # Returns
+    /// A result containing the number of merged pull requests, or an error if the search fails.
+    pub async fn count_merged_pulls_by(&self, username: &str) -> Result<u64> {
+        let repo = self.repo().await;
+        self.count_search_results(&format!("repo:{} is:pr is:merged author:{}", repo.full_name(), username)).await
+    }
+
+    /// Counts the issues and pull requests a user opened or commented on in the repository.
+    ///
+    /// # Arguments
+    /// * `username` - The login of the user.
+    ///
+    /// # Returns
+    /// A result containing the number of interactions, or an error if the search fails.
+    pub async fn count_interactions(&self, username: &str) -> Result<u64> {
+        let repo = self.repo().await;
+        let authored = self.count_search_results(&format!("repo:{} author:{}", repo.full_name(), username)).await?;
+        let commented = self.count_search_results(&format!("repo:{} commenter:{}", repo.full_name(), username)).await?;
+        Ok(authored + commented)
+    }
+
+    /// Checks whether a commit was brought in by a merged pull request.
+    ///
+    /// # Arguments
+    /// * `sha` - The SHA of the commit.
+    ///
+    /// # Returns
+    /// A result containing `true` if a merged pull request contains the commit.
+    pub async fn is_merged_pull_commit(&self, sha: &str) -> Result<bool> {
+        if let Some(merged_pull) = self.merged_pulls.lock().unwrap().get(sha) {
+            return Ok(merged_pull.is_some());
+        }
+        let repo = self.repo().await;
+        let route = format!("/repos/{}/{}/commits/{}/pulls", repo.owner, repo.name, sha);
+        let pulls: Vec<PullRequest> = self.client.get(route, None::<&()>).await?;
+        Ok(pulls.iter().any(|pull| pull.merged_at.is_some()))
+    }
+
+    /// Remembers how GitHub relates the author of an open pull request to the repository, as
+    /// reported by its events, see [PullAssociations].
+    ///
+    /// # Arguments
+    /// * `number` - The number of the pull request.
+    /// * `author` - The login of its author.
+    /// * `association` - The association reported by the event, such as `FIRST_TIME_CONTRIBUTOR`.
+    ///
+    /// # Returns
+    /// A result indicating whether the association could be persisted.
+    pub async fn remember_open_pull_association(&self, number: u64, author: &str, association: AuthorAssociation) -> Result<()> {
+        self.pull_associations.record(number, author, association).await
+    }
+
+    /// Looks up how GitHub related the author of the merged pull request that brought in a
+    /// commit to the repository while the pull request was open, such as `FIRST_TIME_CONTRIBUTOR`.
+    ///
+    /// # Arguments
+    /// * `sha` - The SHA of the commit.
+    /// * `username` - The login of the author of the pull request.
+    ///
+    /// # Returns
+    /// A result containing the association, or `None` if no merged pull request of the user
+    /// contains the commit, or none of its events was received while it was open.
+    pub async fn merged_pull_association(&self, sha: &str, username: &str) -> Result<Option<AuthorAssociation>> {
+        let cached = self.merged_pulls.lock().unwrap().get(sha).cloned();
+        let merged_pull = match cached {
+            Some(merged_pull) => merged_pull,
+            None => {
+                let repo = self.repo().await;
+                let route = format!("/repos/{}/{}/commits/{}/pulls", repo.owner, repo.name, sha);
+                let pulls: Vec<PullRequest> = self.client.get(route, None::<&()>).await?;
+                pulls
+                    .into_iter()
+                    .filter(|pull| pull.merged_at.is_some())
+                    .find(|pull| pull.user.as_ref().is_some_and(|user| user.login.eq_ignore_ascii_case(username)))
+                    .map(|pull| MergedPull { number: pull.number, author: pull.user.map(|user| user.login) })
+            }
+        };
+
+        match merged_pull {
+            Some(MergedPull { number, author: Some(author) }) if author.eq_ignore_ascii_case(username) => {
+                Ok(self.pull_associations.get(number, username).await)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn count_search_results(&self, query: &str) -> Result<u64> {
+        let results = self.client
+            .search()
+            .issues_and_pull_requests(query)
+            .per_page(1)
+            .send()
+            .await?;
+
+        results.total_count.ok_or_else(|| anyhow!("Search {} returned no total count", query))
+    }
+
+    /// Reads the views, clones and referrers of the repository over the past two weeks.
+    ///
+    /// The traffic API requires push access to the repository.
+    ///
+    /// # Returns
+    /// A result containing the traffic of the past week and the week before, or an error if
+    /// the traffic cannot be read.
+    pub async fn weekly_traffic(&self) -> Result<WeeklyTraffic> {
+        let repo = self.repo().await;
+        let route = format!("/repos/{}/{}/traffic", repo.owner, repo.name);
+        self.pace("reading the traffic").await;
+        let views: ViewsResponse = self.client
+            .get(format!("{}/views", route), Some(&[("per", "day")]))
+            .await?;
+        self.pace("reading the traffic").await;
+        let clones: ClonesResponse = self.client
+            .get(format!("{}/clones", route), Some(&[("per", "day")]))
+            .await?;
+        self.pace("reading the traffic").await;
+        let referrers: Vec<Referrer> = self.client
+            .get(format!("{}/popular/referrers", route), None::<&()>)
+            .await?;
+        Ok(WeeklyTraffic::new(&views.views, &clones.clones, &referrers, Utc::now()))
+    }
+
+    /// Lists every item of a Projects (v2) board through the GraphQL API.
+    ///
+    /// # Arguments
+    /// * `owner` - The organization or user owning the project.
+    /// * `number` - The number of the project.
+    /// * `status_field` - The name of the single select field holding the item status.
+    ///
+    /// # Returns
+    /// A result containing the items of the project, or an error if the project cannot be read.
+    pub async fn list_project_items(&self, owner: &str, number: u64, status_field: &str) -> Result<Vec<ProjectItem>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let response: ProjectItemsResponse = self.client
+                .graphql(&serde_json::json!({
+                    "query": PROJECT_ITEMS_QUERY,
+                    "variables": {
+                        "owner": owner,
+                        "number": number,
+                        "field": status_field,
+                        "cursor": cursor,
+                    },
+                }))
+                .await?;
+
+            if let Some(error) = response.errors.first() {
+                return Err(anyhow!("GraphQL error reading project {}/{}: {}", owner, number, error.message));
+            }
+            let project = response.data
+                .and_then(|data| data.repository_owner)
+                .and_then(|owner| owner.project_v2)
+                .ok_or_else(|| anyhow!("Project {} of {} not found", number, owner))?;
+
+            items.extend(project.items.nodes.into_iter().map(ProjectItem::from));
+            if !project.items.page_info.has_next_page {
+                break;
+            }
+            cursor = project.items.page_info.end_cursor;
+        }
+
+        Ok(items)
+    }
+
+    /// Counts the active sponsors of a GitHub Sponsors account through the GraphQL API.
+    ///
+    /// # Arguments
+    /// * `login` - The sponsored organization or user.
+    ///
+    /// # Returns
+    /// A result containing the number of sponsors, or an error if the sponsorships cannot be read.
+    pub async fn count_sponsors(&self, login: &str) -> Result<u64> {
+        let response: SponsorCountResponse = self.client
+            .graphql(&serde_json::json!({
+                "query": SPONSOR_COUNT_QUERY,
+                "variables": { "login": login },
+            }))
+            .await?;
+
+        if let Some(error) = response.errors.first() {
+            return Err(anyhow!("GraphQL error counting the sponsors of {}: {}", login, error.message));
+        }
+        response.data
+            .and_then(|data| data.repository_owner)
+            .and_then(|owner| owner.sponsorships_as_maintainer)
+            .map(|sponsorships| sponsorships.total_count)
+            .ok_or_else(|| anyhow!("Sponsorships of {} not found", login))
+    }
+
+    /// Gathers the contributors of every monitored branch, see [ContributorManager::bootstrap].
+    pub async fn bootstrap_contributors(&self) {
+        for manager in self.contributor_managers.values() {
+            manager.bootstrap(&self.retry_budget, self.request_budget.as_deref()).await;
+        }
+    }
+
+    /// Returns true once the contributors of a branch were gathered, so first contributions can be checked without waiting.
+    ///
+    /// # Arguments
+    /// * `branch` - A string slice containing the name of a monitored branch.
+    pub fn contributors_ready(&self, branch: &str) -> bool {
+        self.contributor_managers.get(branch).is_none_or(ContributorManager::is_ready)
+    }
+
+    /// Checks if the specified user is making their first contribution to a branch of the repository.
+    ///
+    /// # Arguments
+    /// * `branch` - A string slice containing the name of a monitored branch.
     /// * `username` - A string slice containing the username of the contributor.
     ///
     /// # Returns
     /// A result containing `true` if this is the user's first contribution, or `false` otherwise.
-    pub async fn is_first_contribution(&self, username: &str) -> Result<bool> {
-        self.contributor_manager.is_first_contribution(username).await
+    pub async fn is_first_contribution(&self, branch: &str, username: &str) -> Result<bool> {
+        self.contributor_manager(branch)?.is_first_contribution(username).await
     }
 
-    /// Gets detailed information about a contributor.
+    /// Gets detailed information about a contributor to a branch.
     ///
     /// # Arguments
+    /// * `branch` - A string slice containing the name of a monitored branch.
     /// * `username` - A string slice containing the username of the contributor.
     ///
     /// # Returns
     /// A result containing an optional `ContributorInfo` if the contributor exists.
-    pub async fn get_contributor_info(&self, username: &str) -> Result<Option<ContributorInfo>> {
-        self.contributor_manager.get_contributor_info(username).await
+    pub async fn get_contributor_info(&self, branch: &str, username: &str) -> Result<Option<ContributorInfo>> {
+        self.contributor_manager(branch)?.get_contributor_info(username).await
+    }
+
+    /// Counts the unique contributors across every monitored branch, as known from their commits.
+    ///
+    /// # Returns
+    /// A result containing the number of contributors, or an error if the contributors cannot be refreshed.
+    pub async fn count_known_contributors(&self) -> Result<u64> {
+        let mut usernames = HashSet::new();
+        for manager in self.contributor_managers.values() {
+            usernames.extend(manager.usernames().await?);
+        }
+        Ok(usernames.len() as u64)
+    }
+
+    fn contributor_manager(&self, branch: &str) -> Result<&ContributorManager> {
+        self.contributor_managers
+            .get(branch)
+            .ok_or_else(|| anyhow!("Branch {} is not monitored", branch))
+    }
+}
+
+#[async_trait]
+impl Source for GitHubClient {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn contributors_ready(&self, branch: &str) -> bool {
+        GitHubClient::contributors_ready(self, branch)
     }
+
+    async fn list_release_assets(&self, release: &Release) -> Result<Vec<String>> {
+        GitHubClient::list_release_assets(self, release.id).await
+    }
+
+    async fn list_tag_check_runs(&self, tag: &str) -> Result<Vec<CheckRun>> {
+        GitHubClient::list_tag_check_runs(self, tag).await
+    }
+
+    async fn x_handle(&self, username: &str) -> Result<Option<String>> {
+        GitHubClient::x_handle(self, username).await
+    }
+}
+
+/// Name of the file persisting the contributors of a branch, e.g. `contributors-v1.x.json`
+/// Creates the contributor managers of the branches of a repository
+fn contributor_managers(
+    client: &Octocrab,
+    repo: &RepoLocation,
+    branches: Vec<String>,
+    state_dir: &Path,
+) -> HashMap<String, ContributorManager> {
+    branches
+        .into_iter()
+        .map(|branch| {
+            let manager = ContributorManager::new(
+                client.clone(),
+                Arc::clone(repo),
+                branch.clone(),
+                state_dir.join(contributor_state_file(&branch)),
+                300, // 5 minutes cache TTL
+            );
+            (branch, manager)
+        })
+        .collect()
+}
+
+fn contributor_state_file(branch: &str) -> String {
+    let branch: String = branch
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    format!("contributors-{}.json", branch)
+}
+
+/// Builds the GitHub API client, trusting the extra root certificates and applying the
+/// timeouts of the configuration
+fn build_octocrab(token: Option<&str>, auth: AuthState, http: &HttpConfig) -> Result<Octocrab> {
+    let extra_roots = tls::extra_root_certificates(http.ca_bundle.as_deref())?;
+    let timeout = &http.timeout.github;
+
+    // The default client neither trusts extra roots nor bounds whole requests, so the
+    // service stack is assembled by hand, with the same retries and redirects
+    let connector = HttpsConnectorBuilder::new()
+        .with_tls_config(tls::client_config(&extra_roots)?)
+        .https_or_http()
+        .enable_http1()
+        .build();
+    let mut connector = TimeoutConnector::new(connector);
+    connector.set_connect_timeout(Some(timeout.connect()));
+    connector.set_read_timeout(Some(timeout.read()));
+    connector.set_write_timeout(Some(timeout.write()));
+    let service = Client::builder(TokioExecutor::new()).build(connector);
+
+    let (base_uri, upload_uri): (Uri, Uri) = match &http.github_api_url {
+        Some(url) => (url.parse()?, url.parse()?),
+        None => ("https://api.github.com".parse()?, "https://uploads.github.com".parse()?),
+    };
+    let headers = vec![(USER_AGENT, HeaderValue::from_static("octocrab"))];
+    // GitHub Apps authenticate every request through the auth state instead
+    let authorization = token.map(|token| HeaderValue::from_str(&format!("Bearer {}", token))).transpose()?;
+
+    let client = OctocrabBuilder::new_empty()
+        .with_service(service)
+        .with_layer(&RetryLayer::new(RetryConfig::Simple(3)))
+        .with_layer(&FollowRedirectLayer::new())
+        .with_layer(&TimeoutLayer::new(timeout.total()))
+        .with_layer(&ExtraHeadersLayer::new(Arc::new(headers)))
+        .with_layer(&BaseUriLayer::new(base_uri.clone()))
+        .with_layer(&AuthHeaderLayer::new(authorization, base_uri, upload_uri))
+        .with_auth(auth)
+        .build()?;
+    Ok(client)
 }
@@ -1,11 +1,17 @@
-use std::{collections::HashMap,sync::Arc};
-use tokio::sync::RwLock;
-use anyhow::Result;
-use tracing::info;
+use super::repository::RepoLocation;
+use crate::scheduler::{request_budget::RequestBudget, retry_budget::RetryBudget};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use tokio::{
+    sync::{watch, RwLock},
+    time::{sleep, Duration}};
+use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use tracing::{debug, error, info, warn};
 use chrono::{DateTime, Utc};
+use octocrab::models::repos::RepoCommit;
 
 /// Represents a contributor's information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ContributorInfo {
     pub username: String,
     pub total_commits: usize,
@@ -13,11 +19,26 @@ pub struct ContributorInfo {
     pub latest_contribution_date: DateTime<Utc>,
 }
 
-/// Manages contributor information with caching
+/// Contributors of a branch as persisted between runs
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ContributorState {
+    /// SHA of the branch head when the contributors were last reconciled
+    head: Option<String>,
+    contributors: HashMap<String, ContributorInfo>,
+}
+
+/// Manages contributor information of a branch with caching
+///
+/// The contributors are persisted, so only the first run scans the whole history of the
+/// branch. Later runs load them back and only go through the commits pushed since.
 pub struct ContributorManager {
     client: octocrab::Octocrab,
-    repo_owner: String,
-    repo_name: String,
+    repo: RepoLocation,
+    branch: String,
+    // Where the contributors of the branch are persisted
+    state_path: PathBuf,
+    // Branch head the cache was reconciled with, None until the cache is loaded
+    head: Arc<RwLock<Option<String>>>,
     
     // Cache of contributor information
     // The HashMap structure is used here because:
@@ -29,28 +50,74 @@ pub struct ContributorManager {
     cache_ttl: u64,
     // Last cache refresh timestamp
     last_refresh: Arc<RwLock<DateTime<Utc>>>,
+    // Set once the contributors were gathered for the first time
+    ready: watch::Sender<bool>,
 }
 
 impl ContributorManager {
     /// Creates a new ContributorManager
+    ///
+    /// The contributors are loaded from `state_path`, or gathered from the whole history of the
+    /// branch when it doesn't exist yet, by [bootstrap](ContributorManager::bootstrap).
     pub fn new(
         client: octocrab::Octocrab,
-        repo_owner: String,
-        repo_name: String,
+        repo: RepoLocation,
+        branch: String,
+        state_path: PathBuf,
         cache_ttl: u64,
     ) -> Self {
         Self {
             client,
-            repo_owner,
-            repo_name,
+            repo,
+            branch,
+            state_path,
+            head: Arc::new(RwLock::new(None)),
             contributors_cache: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl,
-            last_refresh: Arc::new(RwLock::new(Utc::now())),
+            // Refreshed by the bootstrap
+            last_refresh: Arc::new(RwLock::new(DateTime::<Utc>::MIN_UTC)),
+            ready: watch::Sender::new(false),
+        }
+    }
+
+    /// Gathers the contributors of the branch, retrying until it succeeds.
+    ///
+    /// Lookups wait until this is done, which only takes long on the first run over a
+    /// large repository, when the whole history of the branch is scanned.
+    ///
+    /// # Arguments
+    /// * `retry_budget` - The budget every retry is spent from.
+    /// * `request_budget` - The planner pacing the scan, if any.
+    pub async fn bootstrap(&self, retry_budget: &RetryBudget, request_budget: Option<&RequestBudget>) {
+        loop {
+            match self.refresh_cache(request_budget).await {
+                Ok(()) => break,
+                Err(e) => {
+                    error!("Failed to gather contributors of branch {}, retrying in a minute: {:?}", self.branch, e);
+                    sleep(Duration::from_secs(60)).await;
+                    retry_budget.acquire("gathering the contributors").await;
+                }
+            }
         }
+
+        info!("Contributors of branch {} ready", self.branch);
+        self.ready.send_replace(true);
+    }
+
+    /// Returns true once the contributors were gathered by [bootstrap](ContributorManager::bootstrap).
+    pub fn is_ready(&self) -> bool {
+        *self.ready.borrow()
+    }
+
+    async fn wait_until_ready(&self) {
+        let mut ready = self.ready.subscribe();
+        // The sender lives as long as self, so this can't fail
+        let _ = ready.wait_for(|ready| *ready).await;
     }
 
     /// Checks if a user is making their first contribution
     pub async fn is_first_contribution(&self, username: &str) -> Result<bool> {
+        self.wait_until_ready().await;
         self.refresh_cache_if_needed().await?;
         
         let cache = self.contributors_cache.read().await;
@@ -59,74 +126,177 @@ impl ContributorManager {
 
     /// Gets detailed information about a contributor
     pub async fn get_contributor_info(&self, username: &str) -> Result<Option<ContributorInfo>> {
+        self.wait_until_ready().await;
         self.refresh_cache_if_needed().await?;
         
         let cache = self.contributors_cache.read().await;
         Ok(cache.get(username).cloned())
     }
 
+    /// Lists the usernames of every contributor to the branch
+    pub async fn usernames(&self) -> Result<Vec<String>> {
+        self.wait_until_ready().await;
+        self.refresh_cache_if_needed().await?;
+
+        let cache = self.contributors_cache.read().await;
+        Ok(cache.keys().cloned().collect())
+    }
+
     /// Refreshes the cache if it's expired
     async fn refresh_cache_if_needed(&self) -> Result<()> {
         let now = Utc::now();
         let last_refresh = *self.last_refresh.read().await;
         
         if (now - last_refresh).num_seconds() as u64 > self.cache_ttl {
-            self.refresh_cache().await?;
+            self.refresh_cache(None).await?;
         }
         
         Ok(())
     }
 
     /// Refreshes the contributor cache
-    async fn refresh_cache(&self) -> Result<()> {
-        info!("Refreshing contributor cache for {}/{}", self.repo_owner, self.repo_name);
-        
+    ///
+    /// Goes through the commits pushed since the last refresh, loading the persisted
+    /// contributors first if the cache is empty. When the previous head is no longer part of
+    /// the branch, e.g. after a force push, the whole history is scanned again.
+    /// The pages are paced by `request_budget` when given, for scans running in the background.
+    async fn refresh_cache(&self, request_budget: Option<&RequestBudget>) -> Result<()> {
+        let repo = self.repo.read().await.clone();
+        info!("Refreshing contributor cache for {} on branch {}", repo, self.branch);
+
         let mut cache = self.contributors_cache.write().await;
-        let mut new_cache: HashMap<String, ContributorInfo> = HashMap::new();
-
-        // Get all commits
-        let commits = self.client
-            .repos(&self.repo_owner, &self.repo_name)
-            .list_commits()
-            .per_page(100) // Maximum allowed per page
-            .send()
-            .await?;
-
-        for commit in commits.items {
-            if let Some(author) = commit.author {
-                let username = author.login;
-                // Safely access the commit date through the commit author
-                if let Some(commit_author) = &commit.commit.author {
-                    if let Some(date) = commit_author.date {
-                        match new_cache.get_mut(&username) {
-                            Some(info) => {
-                                info.total_commits += 1;
-                                if date < info.first_contribution_date {
-                                    info.first_contribution_date = date;
-                                }
-                                if date > info.latest_contribution_date {
-                                    info.latest_contribution_date = date;
-                                }
-                            }
-                            None => {
-                                new_cache.insert(username.clone(), ContributorInfo {
-                                    username,
-                                    total_commits: 1,
-                                    first_contribution_date: date,
-                                    latest_contribution_date: date,
-                                });
-                            }
-                        }
-                    }
+        let mut head = self.head.write().await;
+        if head.is_none() {
+            if let Some(state) = self.load_state().await {
+                info!("Loaded {} contributors of branch {} from {}", state.contributors.len(), self.branch, self.state_path.display());
+                *cache = state.contributors;
+                *head = state.head;
+            }
+        }
+
+        // Commits are listed newest first, so the scan stops at the previous head
+        let mut new_commits = Vec::new();
+        let mut reached_head = false;
+        for page in 1u32.. {
+            if let Some(request_budget) = request_budget {
+                request_budget.pace(&self.client, "gathering the contributors").await;
+            }
+            let commits = self.client
+                .repos(&repo.owner, &repo.name)
+                .list_commits()
+                .sha(self.branch.as_str())
+                .per_page(100) // Maximum allowed per page
+                .page(page)
+                .send()
+                .await?;
+
+            let last_page = commits.next.is_none();
+            for commit in commits.items {
+                if head.as_deref() == Some(commit.sha.as_str()) {
+                    reached_head = true;
+                    break;
                 }
+                new_commits.push(commit);
             }
+            if reached_head || last_page {
+                break;
+            }
+        }
+
+        if head.is_some() && !reached_head {
+            warn!("Previous head of branch {} not found, rebuilding its contributors from the full history", self.branch);
         }
+        if !reached_head {
+            cache.clear();
+        }
+        debug!("{} new commits on branch {}", new_commits.len(), self.branch);
 
-        // Update the cache
-        *cache = new_cache;
+        if let Some(newest) = new_commits.first() {
+            *head = Some(newest.sha.clone());
+        }
+        for commit in &new_commits {
+            add_commit(&mut cache, commit);
+        }
         *self.last_refresh.write().await = Utc::now();
-        
+
+        if !new_commits.is_empty() || !reached_head {
+            let state = ContributorState {
+                head: head.clone(),
+                contributors: cache.clone(),
+            };
+            if let Err(e) = self.save_state(&state).await {
+                warn!("Failed to persist contributors of branch {}: {:?}", self.branch, e);
+            }
+        }
+
         info!("Successfully refreshed contributor cache with {} contributors", cache.len());
         Ok(())
     }
+
+    /// Loads the persisted contributors, if any.
+    async fn load_state(&self) -> Option<ContributorState> {
+        let contents = match tokio::fs::read_to_string(&self.state_path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Failed to read {}, scanning the full history: {}", self.state_path.display(), e);
+                return None;
+            }
+        };
+
+        match serde_json::from_str(&contents) {
+            Ok(state) => Some(state),
+            Err(e) => {
+                warn!("Ignoring malformed {}, scanning the full history: {}", self.state_path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Persists the contributors, replacing the previous file atomically.
+    async fn save_state(&self, state: &ContributorState) -> Result<()> {
+        if let Some(parent) = self.state_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let temporary = self.state_path.with_extension("tmp");
+        tokio::fs::write(&temporary, serde_json::to_string(state)?)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.state_path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.state_path.display()))?;
+        Ok(())
+    }
+}
+
+/// Adds a commit to the contributor it was authored by.
+fn add_commit(cache: &mut HashMap<String, ContributorInfo>, commit: &RepoCommit) {
+    let Some(author) = &commit.author else {
+        return;
+    };
+    // Safely access the commit date through the commit author
+    let Some(date) = commit.commit.author.as_ref().and_then(|commit_author| commit_author.date) else {
+        return;
+    };
+
+    match cache.get_mut(&author.login) {
+        Some(info) => {
+            info.total_commits += 1;
+            if date < info.first_contribution_date {
+                info.first_contribution_date = date;
+            }
+            if date > info.latest_contribution_date {
+                info.latest_contribution_date = date;
+            }
+        }
+        None => {
+            cache.insert(author.login.clone(), ContributorInfo {
+                username: author.login.clone(),
+                total_commits: 1,
+                first_contribution_date: date,
+                latest_contribution_date: date,
+            });
+        }
+    }
 }
@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+/// A release with its assets, as returned by the releases API
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListedReleaseAssets {
+    pub id: u64,
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub assets: Vec<ListedAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListedAsset {
+    pub download_count: u64,
+}
+
+/// Downloads of the assets of a published release
+#[derive(Debug, Clone)]
+pub struct ReleaseDownloads {
+    pub id: u64,
+    pub tag: String,
+    pub url: String,
+    /// Downloads summed over every asset of the release
+    pub downloads: u64,
+}
+
+impl From<ListedReleaseAssets> for ReleaseDownloads {
+    fn from(release: ListedReleaseAssets) -> Self {
+        Self {
+            id: release.id,
+            tag: release.tag_name,
+            url: release.html_url,
+            downloads: release.assets.iter().map(|asset| asset.download_count).sum(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use super::client::GitHubClient;
+use crate::config::env::FirstContributionPolicy;
+use std::sync::Arc;
+use octocrab::models::AuthorAssociation;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// A contribution pushed to a monitored branch
+#[derive(Debug, Clone)]
+pub struct Contribution<'a> {
+    /// Name of the branch, without the `refs/heads/` prefix
+    pub branch: &'a str,
+    pub username: &'a str,
+    /// SHA of the contributor's earliest commit in the push
+    pub sha: &'a str,
+    /// When the contributor's earliest commit in the push was made
+    pub committed_at: Option<DateTime<Utc>>,
+}
+
+/// Decides whether a contribution is the first one of its contributor.
+#[async_trait]
+pub trait FirstContributionStrategy: Send + Sync {
+    /// Returns true if `contribution` is the first contribution of its contributor.
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool>;
+}
+
+/// Creates the strategy implementing a policy.
+///
+/// # Arguments
+/// * `policy` - What counts as a first contribution.
+/// * `github_client` - The client used to look up previous contributions.
+pub fn strategy(policy: FirstContributionPolicy, github_client: Arc<GitHubClient>) -> Box<dyn FirstContributionStrategy> {
+    match policy {
+        FirstContributionPolicy::FirstCommit => Box::new(FirstCommit { github_client }),
+        FirstContributionPolicy::FirstMergedPr => Box::new(FirstMergedPr { github_client }),
+        FirstContributionPolicy::FirstInteraction => Box::new(FirstInteraction {
+            first_commit: FirstCommit { github_client: Arc::clone(&github_client) },
+            github_client,
+        }),
+        FirstContributionPolicy::PrAuthorAssociation => Box::new(PrAuthorAssociation {
+            first_commit: FirstCommit { github_client: Arc::clone(&github_client) },
+            github_client,
+        }),
+    }
+}
+
+/// The first commit of the contributor on the branch.
+pub struct FirstCommit {
+    github_client: Arc<GitHubClient>,
+}
+
+#[async_trait]
+impl FirstContributionStrategy for FirstCommit {
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool> {
+        let info = self.github_client
+            .get_contributor_info(contribution.branch, contribution.username)
+            .await?;
+
+        // The known contributors may already include the pushed commits, which only
+        // count as the first contribution when nothing older is known
+        Ok(match (info, contribution.committed_at) {
+            (None, _) => true,
+            (Some(info), Some(committed_at)) => info.first_contribution_date >= committed_at,
+            (Some(_), None) => false,
+        })
+    }
+}
+
+/// The first merged pull request of the contributor.
+pub struct FirstMergedPr {
+    github_client: Arc<GitHubClient>,
+}
+
+#[async_trait]
+impl FirstContributionStrategy for FirstMergedPr {
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool> {
+        if !self.github_client.is_merged_pull_commit(contribution.sha).await? {
+            return Ok(false);
+        }
+
+        // The search index may not include the pull request that was just merged yet
+        Ok(self.github_client.count_merged_pulls_by(contribution.username).await? <= 1)
+    }
+}
+
+/// The first interaction of the contributor with the repository, of any type.
+pub struct FirstInteraction {
+    github_client: Arc<GitHubClient>,
+    first_commit: FirstCommit,
+}
+
+#[async_trait]
+impl FirstContributionStrategy for FirstInteraction {
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool> {
+        if !self.first_commit.is_first_contribution(contribution).await? {
+            return Ok(false);
+        }
+
+        // The pull request that brought the commit in is part of the same contribution
+        let own_pull = u64::from(self.github_client.is_merged_pull_commit(contribution.sha).await?);
+        Ok(self.github_client.count_interactions(contribution.username).await? <= own_pull)
+    }
+}
+
+/// The pull request GitHub marked as coming from a first-time contributor while it was open.
+///
+/// Unlike commits, the author of a pull request doesn't depend on the emails the commits
+/// were made with, nor on how the pull request was merged. The association is the one the
+/// pull request events reported before the merge, since GitHub reports the author as a
+/// contributor once it's merged. Commits pushed without a pull request, or with one whose
+/// events weren't received while it was open, fall back to the first commit on the branch.
+pub struct PrAuthorAssociation {
+    github_client: Arc<GitHubClient>,
+    first_commit: FirstCommit,
+}
+
+#[async_trait]
+impl FirstContributionStrategy for PrAuthorAssociation {
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool> {
+        match self.github_client.merged_pull_association(contribution.sha, contribution.username).await? {
+            Some(AuthorAssociation::FirstTimeContributor | AuthorAssociation::FirstTimer) => Ok(true),
+            Some(_) => Ok(false),
+            None => self.first_commit.is_first_contribution(contribution).await,
+        }
+    }
+}
@@ -1,3 +1,12 @@
+pub mod activity;
+pub mod checks;
 pub mod client;
 pub mod contributor;
+pub mod downloads;
+pub mod first_contribution;
+pub mod project;
+pub mod pull_association;
+pub mod repository;
+pub mod sponsors;
+pub mod traffic;
 pub mod types;
\ No newline at end of file
@@ -0,0 +1,120 @@
+use serde::Deserialize;
+
+/// Lists the items of a Projects (v2) board with the value of a single select field.
+///
+/// The owner may be an organization or a user, hence the two identical fragments.
+pub const PROJECT_ITEMS_QUERY: &str = r#"
+query($owner: String!, $number: Int!, $field: String!, $cursor: String) {
+  repositoryOwner(login: $owner) {
+    ... on Organization { projectV2(number: $number) { ...ProjectItems } }
+    ... on User { projectV2(number: $number) { ...ProjectItems } }
+  }
+}
+
+fragment ProjectItems on ProjectV2 {
+  items(first: 100, after: $cursor) {
+    pageInfo { hasNextPage endCursor }
+    nodes {
+      id
+      fieldValueByName(name: $field) {
+        ... on ProjectV2ItemFieldSingleSelectValue { name }
+      }
+      content {
+        ... on Issue { title url }
+        ... on PullRequest { title url }
+        ... on DraftIssue { title }
+      }
+    }
+  }
+}
+"#;
+
+/// An item of a Projects (v2) board
+#[derive(Debug, Clone)]
+pub struct ProjectItem {
+    /// GraphQL node id of the item
+    pub id: String,
+    /// Value of the status field, if set
+    pub status: Option<String>,
+    pub title: String,
+    /// Link to the underlying issue or pull request; draft items have none
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectItemsResponse {
+    pub data: Option<ProjectItemsData>,
+    #[serde(default)]
+    pub errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQlError {
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectItemsData {
+    pub repository_owner: Option<ProjectOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectOwner {
+    pub project_v2: Option<Project>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Project {
+    pub items: ProjectItemConnection,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectItemConnection {
+    pub page_info: PageInfo,
+    pub nodes: Vec<ProjectItemNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectItemNode {
+    pub id: String,
+    pub field_value_by_name: Option<FieldValue>,
+    pub content: Option<ItemContent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FieldValue {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemContent {
+    pub title: Option<String>,
+    pub url: Option<String>,
+}
+
+impl From<ProjectItemNode> for ProjectItem {
+    fn from(node: ProjectItemNode) -> Self {
+        let (title, url) = match node.content {
+            Some(content) => (content.title.unwrap_or_default(), content.url),
+            None => (String::new(), None),
+        };
+
+        Self {
+            id: node.id,
+            status: node.field_value_by_name.and_then(|value| value.name),
+            title,
+            url,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use octocrab::models::AuthorAssociation;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Context, Result};
+use tracing::warn;
+
+/// Pull requests whose association is remembered at most, the oldest ones are forgotten beyond
+const MAX_PULLS: usize = 1000;
+
+/// How GitHub related the author of an open pull request to the repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OpenPull {
+    number: u64,
+    /// Login of the author
+    author: String,
+    association: AuthorAssociation,
+    /// When the association was last reported
+    seen_at: DateTime<Utc>,
+}
+
+/// Associations of the open pull requests, as persisted between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PullAssociationState {
+    pulls: Vec<OpenPull>,
+}
+
+/// Remembers how GitHub relates the authors of pull requests to the repository while the pull
+/// requests are open.
+///
+/// GitHub computes `author_association` when it's asked for, so the author of a first pull
+/// request is already a `CONTRIBUTOR` once it's merged. The association reported by the events
+/// of the open pull request is kept instead, and persisted since pull requests stay open across
+/// restarts.
+pub struct PullAssociations {
+    path: PathBuf,
+    state: Mutex<Option<PullAssociationState>>,
+}
+
+impl PullAssociations {
+    /// Creates a new store of associations.
+    ///
+    /// # Arguments
+    /// * `path` - The file the associations are persisted in.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Remembers the association of the author of an open pull request, replacing the one
+    /// reported by an earlier event.
+    ///
+    /// # Arguments
+    /// * `number` - The number of the pull request.
+    /// * `author` - The login of its author.
+    /// * `association` - The association reported by the event.
+    ///
+    /// # Returns
+    /// A result indicating whether the association could be persisted.
+    pub async fn record(&self, number: u64, author: &str, association: AuthorAssociation) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if state.is_none() {
+            *state = Some(self.load().await);
+        }
+        let state = state.as_mut().expect("pull associations are loaded");
+
+        state.pulls.retain(|pull| pull.number != number);
+        state.pulls.push(OpenPull {
+            number,
+            author: author.to_owned(),
+            association,
+            seen_at: Utc::now(),
+        });
+        if state.pulls.len() > MAX_PULLS {
+            let forgotten = state.pulls.len() - MAX_PULLS;
+            state.pulls.drain(..forgotten);
+        }
+        self.save(state).await
+    }
+
+    /// Returns the association of the author of a pull request, as last reported while it was open.
+    ///
+    /// # Arguments
+    /// * `number` - The number of the pull request.
+    /// * `author` - The login of its author, the association of another author is ignored.
+    pub async fn get(&self, number: u64, author: &str) -> Option<AuthorAssociation> {
+        let mut state = self.state.lock().await;
+        if state.is_none() {
+            *state = Some(self.load().await);
+        }
+        state.as_ref()?
+            .pulls
+            .iter()
+            .find(|pull| pull.number == number && pull.author.eq_ignore_ascii_case(author))
+            .map(|pull| pull.association.clone())
+    }
+
+    /// Loads the persisted associations, if any.
+    async fn load(&self) -> PullAssociationState {
+        let contents = match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return PullAssociationState::default(),
+            Err(e) => {
+                warn!("Failed to read {}, starting without pull request associations: {}", self.path.display(), e);
+                return PullAssociationState::default();
+            }
+        };
+
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Ignoring malformed {}, starting without pull request associations: {}", self.path.display(), e);
+            PullAssociationState::default()
+        })
+    }
+
+    /// Persists the associations, replacing the previous file atomically.
+    async fn save(&self, state: &PullAssociationState) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let temporary = self.path.with_extension("tmp");
+        tokio::fs::write(&temporary, serde_json::to_string(state)?)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
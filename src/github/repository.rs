@@ -0,0 +1,47 @@
+use std::{
+    fmt::{Display, Formatter},
+    sync::Arc};
+use tokio::sync::RwLock;
+
+/// Owner and name identifying a GitHub repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoName {
+    pub owner: String,
+    pub name: String,
+}
+
+impl RepoName {
+    pub fn new(owner: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            owner: owner.into(),
+            name: name.into(),
+        }
+    }
+
+    /// Parses a `owner/name` full name.
+    pub fn from_full_name(full_name: &str) -> Option<Self> {
+        let (owner, name) = full_name.split_once('/')?;
+        if owner.is_empty() || name.is_empty() {
+            return None;
+        }
+        Some(Self::new(owner, name))
+    }
+
+    /// Returns the repository as `owner/name`.
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+impl Display for RepoName {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+/// Shared, updatable location of the monitored repository.
+///
+/// Repositories can be renamed or transferred while the bot is running, so every
+/// component that talks to the GitHub API reads the current location from here
+/// instead of keeping its own copy of the configured owner and name.
+pub type RepoLocation = Arc<RwLock<RepoName>>;
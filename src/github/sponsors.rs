@@ -0,0 +1,39 @@
+use crate::github::project::GraphQlError;
+use serde::Deserialize;
+
+/// Counts the active sponsorships of a GitHub Sponsors account, private ones included.
+///
+/// The account may be an organization or a user, hence the two identical fragments.
+pub const SPONSOR_COUNT_QUERY: &str = r#"
+query($login: String!) {
+  repositoryOwner(login: $login) {
+    ... on Organization { sponsorshipsAsMaintainer(activeOnly: true) { totalCount } }
+    ... on User { sponsorshipsAsMaintainer(activeOnly: true) { totalCount } }
+  }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+pub struct SponsorCountResponse {
+    pub data: Option<SponsorCountData>,
+    #[serde(default)]
+    pub errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorCountData {
+    pub repository_owner: Option<SponsorableOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorableOwner {
+    pub sponsorships_as_maintainer: Option<SponsorshipConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorshipConnection {
+    pub total_count: u64,
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+use chrono::{DateTime, Duration, Utc};
+
+/// Daily views of the repository, as returned by the traffic API
+#[derive(Debug, Deserialize)]
+pub(crate) struct ViewsResponse {
+    #[serde(default)]
+    pub views: Vec<DailyCount>,
+}
+
+/// Daily clones of the repository, as returned by the traffic API
+#[derive(Debug, Deserialize)]
+pub(crate) struct ClonesResponse {
+    #[serde(default)]
+    pub clones: Vec<DailyCount>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DailyCount {
+    pub timestamp: DateTime<Utc>,
+    pub count: u64,
+}
+
+/// A site sending visitors to the repository, as returned by the traffic API
+#[derive(Debug, Deserialize)]
+pub(crate) struct Referrer {
+    pub referrer: String,
+    pub count: u64,
+}
+
+/// Traffic of the repository over the past week and the week before.
+///
+/// GitHub only keeps the last 14 days of traffic, which is exactly enough for a
+/// week-over-week comparison.
+#[derive(Debug, Clone)]
+pub struct WeeklyTraffic {
+    pub views: u64,
+    pub previous_views: u64,
+    pub clones: u64,
+    pub previous_clones: u64,
+    /// The site that sent the most visitors over the past 14 days
+    pub top_referrer: Option<String>,
+}
+
+impl WeeklyTraffic {
+    /// Sums the daily counts into the past week and the week before.
+    pub(crate) fn new(views: &[DailyCount], clones: &[DailyCount], referrers: &[Referrer], now: DateTime<Utc>) -> Self {
+        let (views, previous_views) = weekly_sums(views, now);
+        let (clones, previous_clones) = weekly_sums(clones, now);
+        let top_referrer = referrers
+            .iter()
+            .max_by_key(|referrer| referrer.count)
+            .map(|referrer| referrer.referrer.clone());
+        Self {
+            views,
+            previous_views,
+            clones,
+            previous_clones,
+            top_referrer,
+        }
+    }
+}
+
+/// Returns the sum of the counts of the past 7 days, and of the 7 days before.
+fn weekly_sums(days: &[DailyCount], now: DateTime<Utc>) -> (u64, u64) {
+    let week_start = now - Duration::days(7);
+    let previous_week_start = now - Duration::days(14);
+    days.iter().fold((0, 0), |(current, previous), day| {
+        if day.timestamp >= week_start {
+            (current + day.count, previous)
+        } else if day.timestamp >= previous_week_start {
+            (current, previous + day.count)
+        } else {
+            (current, previous)
+        }
+    })
+}
+
+/// Formats the change between two weekly counts, e.g. `+12%`, or an empty string without a
+/// previous week to compare to.
+pub fn format_change(current: u64, previous: u64) -> String {
+    if previous == 0 {
+        return String::new();
+    }
+    let change = (current as f64 - previous as f64) / previous as f64 * 100.0;
+    format!("{:+.0}%", change)
+}
@@ -1,26 +1,364 @@
-use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use serde_path_to_error::Segment;
+use octocrab::models::AuthorAssociation;
+use chrono::{DateTime, Utc};
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
+// Payloads are parsed leniently since GitHub keeps adding fields and actions: unknown
+// fields are ignored, and actions that aren't known yet are kept in an `Other` variant
+// instead of failing the whole delivery.
+
+/// Declares the action of an event, keeping unknown actions in `Other`
+macro_rules! action_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($variant,)*
+            /// An action the bot doesn't know about
+            Other(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Other(action) => action,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(action: String) -> Self {
+                match action.as_str() {
+                    $($value => Self::$variant,)*
+                    _ => Self::Other(action),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(action: $name) -> Self {
+                action.as_str().to_owned()
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+action_enum! {
+    /// Action of a release event
+    ReleaseAction {
+        Published => "published",
+        Created => "created",
+        Edited => "edited",
+        Deleted => "deleted",
+        Prereleased => "prereleased",
+        Released => "released",
+        Unpublished => "unpublished",
+    }
+}
+
+action_enum! {
+    /// Action of a repository event
+    RepositoryAction {
+        Renamed => "renamed",
+        Transferred => "transferred",
+        Archived => "archived",
+        Unarchived => "unarchived",
+        Publicized => "publicized",
+        Privatized => "privatized",
+        Created => "created",
+        Deleted => "deleted",
+        Edited => "edited",
+    }
+}
+
+action_enum! {
+    /// Action of a member event
+    MemberAction {
+        Added => "added",
+        Edited => "edited",
+        Removed => "removed",
+    }
+}
+
+action_enum! {
+    /// Action of a sponsorship event
+    SponsorshipAction {
+        Created => "created",
+        Cancelled => "cancelled",
+        Edited => "edited",
+        TierChanged => "tier_changed",
+        PendingCancellation => "pending_cancellation",
+        PendingTierChange => "pending_tier_change",
+    }
+}
+
+action_enum! {
+    /// Action of a pull request event
+    PullRequestAction {
+        Opened => "opened",
+        Closed => "closed",
+        Reopened => "reopened",
+        Edited => "edited",
+        Synchronize => "synchronize",
+        Labeled => "labeled",
+        Unlabeled => "unlabeled",
+        ReadyForReview => "ready_for_review",
+        ConvertedToDraft => "converted_to_draft",
+        ReviewRequested => "review_requested",
+        Assigned => "assigned",
+        Unassigned => "unassigned",
+    }
+}
+
+action_enum! {
+    /// Action of an issues event
+    IssuesAction {
+        Opened => "opened",
+        Closed => "closed",
+        Reopened => "reopened",
+        Edited => "edited",
+        Deleted => "deleted",
+        Labeled => "labeled",
+        Unlabeled => "unlabeled",
+        Milestoned => "milestoned",
+        Demilestoned => "demilestoned",
+        Assigned => "assigned",
+        Unassigned => "unassigned",
+        Transferred => "transferred",
+        Pinned => "pinned",
+        Unpinned => "unpinned",
+        Locked => "locked",
+        Unlocked => "unlocked",
+    }
+}
+
+action_enum! {
+    /// Action of a star event
+    StarAction {
+        Created => "created",
+        Deleted => "deleted",
+    }
+}
+
+action_enum! {
+    /// Action of a watch event, which GitHub sends when a repository is starred
+    WatchAction {
+        Started => "started",
+    }
+}
+
+action_enum! {
+    /// Action of a discussion event
+    DiscussionAction {
+        Created => "created",
+        Edited => "edited",
+        Deleted => "deleted",
+        Answered => "answered",
+        Unanswered => "unanswered",
+        CategoryChanged => "category_changed",
+        Labeled => "labeled",
+        Unlabeled => "unlabeled",
+        Locked => "locked",
+        Unlocked => "unlocked",
+        Pinned => "pinned",
+        Unpinned => "unpinned",
+        Transferred => "transferred",
+    }
+}
+
+action_enum! {
+    /// Action of a workflow run event
+    WorkflowRunAction {
+        Requested => "requested",
+        InProgress => "in_progress",
+        Completed => "completed",
+    }
+}
+
+action_enum! {
+    /// Action of a package event
+    PackageAction {
+        Published => "published",
+        Updated => "updated",
+    }
+}
+
+/// A webhook delivery, typed by its `X-GitHub-Event` header.
+///
+/// The one model of the events announced: the webhooks of GitLab and Gitea, the GraphQL
+/// poll and the message brokers are converted to it, and
+/// [WebhookHandler::handle](crate::webhook::handler::WebhookHandler::handle) alone decides
+/// what is announced for it.
+#[derive(Debug)]
 pub enum WebhookEvent {
+    Ping(PingEvent),
     Push(PushEvent),
     Release(ReleaseEvent),
-    Ping(PingEvent),
+    Repository(RepositoryEvent),
+    Member(MemberEvent),
+    Public(PublicEvent),
+    Create(CreateEvent),
+    Sponsorship(SponsorshipEvent),
+    DeploymentStatus(DeploymentStatusEvent),
+    PageBuild(PageBuildEvent),
+    PullRequest(PullRequestEvent),
+    Issues(IssuesEvent),
+    Star(StarEvent),
+    Watch(WatchEvent),
+    Fork(ForkEvent),
+    Discussion(DiscussionEvent),
+    WorkflowRun(WorkflowRunEvent),
+    Package(PackageEvent),
+}
+
+impl WebhookEvent {
+    /// Parses the payload of a webhook delivery.
+    ///
+    /// # Arguments
+    /// * `event_type` - The value of the `X-GitHub-Event` header.
+    /// * `payload` - The JSON payload of the delivery.
+    ///
+    /// # Returns
+    /// `None` for event types the bot doesn't handle, otherwise the parsed event or the parse error.
+    pub fn parse(event_type: &str, payload: &str) -> Option<Result<Self, PayloadError>> {
+        let parse: fn(&Value) -> Result<Self, serde_path_to_error::Error<serde_json::Error>> = match event_type {
+            "ping" => |value| typed(value).map(Self::Ping),
+            "push" => |value| typed(value).map(Self::Push),
+            "release" => |value| typed(value).map(Self::Release),
+            "repository" => |value| typed(value).map(Self::Repository),
+            "member" => |value| typed(value).map(Self::Member),
+            "public" => |value| typed(value).map(Self::Public),
+            "create" => |value| typed(value).map(Self::Create),
+            "sponsorship" => |value| typed(value).map(Self::Sponsorship),
+            "deployment_status" => |value| typed(value).map(Self::DeploymentStatus),
+            "page_build" => |value| typed(value).map(Self::PageBuild),
+            "pull_request" => |value| typed(value).map(Self::PullRequest),
+            "issues" => |value| typed(value).map(Self::Issues),
+            "star" => |value| typed(value).map(Self::Star),
+            "watch" => |value| typed(value).map(Self::Watch),
+            "fork" => |value| typed(value).map(Self::Fork),
+            "discussion" => |value| typed(value).map(Self::Discussion),
+            "workflow_run" => |value| typed(value).map(Self::WorkflowRun),
+            "package" => |value| typed(value).map(Self::Package),
+            _ => return None,
+        };
+
+        // Parsed as a value first, so the offending value of a field can be looked up
+        let value: Value = match serde_json::from_str(payload) {
+            Ok(value) => value,
+            Err(e) => return Some(Err(PayloadError::Syntax(e))),
+        };
+        Some(parse(&value).map_err(|e| PayloadError::field(e, &value)))
+    }
+
+    /// Returns the repository the event is about, as `owner/name`, `None` for events about
+    /// the webhook or an account, such as pings and sponsorships.
+    pub fn repository(&self) -> Option<&str> {
+        let repository = match self {
+            Self::Ping(_) | Self::Sponsorship(_) => return None,
+            Self::Public(event) => return Some(&event.repository.full_name),
+            Self::Package(event) => event.repository.as_ref()?,
+            Self::Push(event) => &event.repository,
+            Self::Release(event) => &event.repository,
+            Self::Repository(event) => &event.repository,
+            Self::Member(event) => &event.repository,
+            Self::Create(event) => &event.repository,
+            Self::DeploymentStatus(event) => &event.repository,
+            Self::PageBuild(event) => &event.repository,
+            Self::PullRequest(event) => &event.repository,
+            Self::Issues(event) => &event.repository,
+            Self::Star(event) => &event.repository,
+            Self::Watch(event) => &event.repository,
+            Self::Fork(event) => &event.repository,
+            Self::Discussion(event) => &event.repository,
+            Self::WorkflowRun(event) => &event.repository,
+        };
+        Some(&repository.full_name)
+    }
+}
+
+/// Deserializes a value, tracking the path of the field that fails
+fn typed<T: DeserializeOwned>(value: &Value) -> Result<T, serde_path_to_error::Error<serde_json::Error>> {
+    serde_path_to_error::deserialize(value)
+}
+
+/// Error parsing the payload of a webhook delivery
+#[derive(Debug)]
+pub enum PayloadError {
+    /// The payload isn't valid JSON
+    Syntax(serde_json::Error),
+    /// A field of the payload doesn't have the expected shape
+    Field {
+        /// Path of the field, such as `commits[0].author.email`
+        path: String,
+        /// The offending value, or for a missing field the object it is missing from
+        value: Option<Value>,
+        source: serde_json::Error,
+    },
+}
+
+impl PayloadError {
+    fn field(error: serde_path_to_error::Error<serde_json::Error>, payload: &Value) -> Self {
+        let mut value = Some(payload);
+        for segment in error.path() {
+            value = value.and_then(|value| match segment {
+                Segment::Seq { index } => value.get(index),
+                Segment::Map { key } => value.get(key),
+                Segment::Enum { .. } | Segment::Unknown => None,
+            });
+        }
+        Self::Field {
+            path: error.path().to_string(),
+            value: value.cloned(),
+            source: error.into_inner(),
+        }
+    }
+}
+
+impl Display for PayloadError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            PayloadError::Syntax(e) => write!(f, "{}", e),
+            PayloadError::Field { path, source, .. } => write!(f, "{}: {}", path, source),
+        }
+    }
+}
+
+impl std::error::Error for PayloadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PayloadError::Syntax(e) => Some(e),
+            PayloadError::Field { source, .. } => Some(source),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PingEvent {
+    #[serde(default)]
     pub zen: String,
     pub hook_id: u64,
     pub hook: WebhookInfo,
 }
 
+/// Organization and app hooks leave out the repository hook URLs
 #[derive(Debug, Deserialize, Serialize)]
 pub struct WebhookInfo {
-    pub url: String,
-    pub test_url: String,
-    pub ping_url: String,
+    pub url: Option<String>,
+    pub test_url: Option<String>,
+    pub ping_url: Option<String>,
     pub id: u64,
+    #[serde(default)]
     pub active: bool,
 }
 
@@ -37,6 +375,7 @@ pub struct PushEvent {
 pub struct Commit {
     pub id: String,
     pub message: String,
+    pub timestamp: Option<DateTime<Utc>>,
     pub author: CommitAuthor,
     pub url: String,
 }
@@ -44,6 +383,7 @@ pub struct Commit {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CommitAuthor {
     pub name: String,
+    #[serde(default)]
     pub email: String,
     pub username: Option<String>,
 }
@@ -52,6 +392,37 @@ pub struct CommitAuthor {
 pub struct Repository {
     pub full_name: String,
     pub owner: GitHubUser,
+    pub html_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RepositoryEvent {
+    pub action: RepositoryAction,
+    pub repository: Repository,
+    /// Previous name or owner for `renamed` and `transferred` actions
+    pub changes: Option<RepositoryChanges>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RepositoryChanges {
+    pub repository: Option<RepositoryNameChange>,
+    pub owner: Option<RepositoryOwnerChange>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RepositoryNameChange {
+    pub name: ChangedValue,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RepositoryOwnerChange {
+    pub from: PreviousOwner,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PreviousOwner {
+    pub user: Option<GitHubUser>,
+    pub organization: Option<GitHubUser>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -62,14 +433,320 @@ pub struct GitHubUser {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ReleaseEvent {
-    pub action: String,
+    pub action: ReleaseAction,
     pub release: Release,
     pub repository: Repository,
+    /// Previous values of the fields changed by an `edited` action
+    pub changes: Option<ReleaseChanges>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Release {
+    pub id: u64,
     pub tag_name: String,
     pub name: Option<String>,
     pub html_url: String,
+    /// Release notes, in markdown
+    pub body: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReleaseChanges {
+    pub name: Option<ChangedValue>,
+    pub body: Option<ChangedValue>,
+    pub tag_name: Option<ChangedValue>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChangedValue {
+    pub from: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeploymentStatusEvent {
+    pub deployment_status: DeploymentStatus,
+    pub deployment: Deployment,
+    pub repository: Repository,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeploymentStatus {
+    pub state: String,
+    pub environment_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Deployment {
+    pub id: u64,
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub environment: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PageBuildEvent {
+    pub build: PageBuild,
+    pub repository: Repository,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PageBuild {
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemberEvent {
+    pub action: MemberAction,
+    pub member: GitHubUser,
+    pub repository: Repository,
+    pub changes: Option<MemberChanges>,
+}
+
+/// A repository was made public
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PublicEvent {
+    pub repository: PublicizedRepository,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PublicizedRepository {
+    pub id: u64,
+    pub name: String,
+    pub full_name: String,
+    pub html_url: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    pub language: Option<String>,
+}
+
+/// A branch or tag was created
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateEvent {
+    /// Name of the branch or tag, without the `refs/` prefix
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub ref_type: RefType,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefType {
+    Branch,
+    Tag,
+}
+
+/// A GitHub Sponsors sponsorship changed, for webhooks of the sponsored account
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SponsorshipEvent {
+    pub action: SponsorshipAction,
+    pub sponsorship: Sponsorship,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Sponsorship {
+    pub node_id: String,
+    pub created_at: DateTime<Utc>,
+    /// The sponsored user or organization
+    pub sponsorable: GitHubUser,
+    pub sponsor: GitHubUser,
+    pub privacy_level: SponsorshipPrivacy,
+    pub tier: SponsorshipTier,
+}
+
+/// Whether the sponsor chose to be listed publicly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SponsorshipPrivacy {
+    Public,
+    Private,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SponsorshipTier {
+    pub name: String,
+    pub monthly_price_in_dollars: u64,
+    #[serde(default)]
+    pub is_one_time: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MemberChanges {
+    pub permission: Option<PermissionChange>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PermissionChange {
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PullRequestEvent {
+    pub action: PullRequestAction,
+    pub number: u64,
+    pub pull_request: PullRequest,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub user: GitHubUser,
+    /// How GitHub relates the author to the repository when the event is sent
+    #[serde(default)]
+    pub author_association: Option<AuthorAssociation>,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub merged: bool,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub base: PullRequestBranch,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PullRequestBranch {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssuesEvent {
+    pub action: IssuesAction,
+    pub issue: Issue,
+    /// The label added or removed by a `labeled` or `unlabeled` action
+    pub label: Option<Label>,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub user: GitHubUser,
+    pub state: String,
+    /// Why a closed issue was closed, `completed` or `not_planned`
+    pub state_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StarEvent {
+    pub action: StarAction,
+    /// Not set when a star is removed
+    pub starred_at: Option<DateTime<Utc>>,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WatchEvent {
+    pub action: WatchAction,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ForkEvent {
+    pub forkee: Forkee,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Forkee {
+    pub full_name: String,
+    pub html_url: String,
+    pub owner: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DiscussionEvent {
+    pub action: DiscussionAction,
+    pub discussion: Discussion,
+    pub repository: Repository,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Discussion {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    pub user: GitHubUser,
+    pub category: DiscussionCategory,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DiscussionCategory {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkflowRunEvent {
+    pub action: WorkflowRunAction,
+    pub workflow_run: WorkflowRun,
+    pub repository: Repository,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkflowRun {
+    pub id: u64,
+    pub name: Option<String>,
+    pub head_branch: Option<String>,
+    pub head_sha: String,
+    /// Event that triggered the run, such as `push` or `release`
+    pub event: String,
+    pub status: Option<String>,
+    /// Set once the run completed, e.g. `success` or `failure`
+    pub conclusion: Option<String>,
+    pub html_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PackageEvent {
+    pub action: PackageAction,
+    pub package: Package,
+    /// Not set for packages that don't belong to a repository
+    pub repository: Option<Repository>,
+    pub sender: GitHubUser,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Package {
+    pub name: String,
+    pub package_type: String,
+    pub html_url: Option<String>,
+    pub package_version: Option<PackageVersion>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PackageVersion {
+    pub version: String,
+    pub html_url: Option<String>,
 }
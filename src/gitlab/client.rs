@@ -0,0 +1,214 @@
+use crate::{
+    config::{env::{GitLabConfig, HttpConfig}, tls},
+    github::{checks::CheckRun, types::Release},
+    source::Source};
+use reqwest::Certificate;
+use serde::{de::DeserializeOwned, Deserialize};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+struct ListedUser {
+    id: u64,
+    username: String,
+}
+
+/// A user's public profile, with the links it shows
+#[derive(Debug, Deserialize)]
+struct UserProfile {
+    #[serde(default)]
+    twitter: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedCommit {
+    author_email: String,
+    committed_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedRelease {
+    #[serde(default)]
+    assets: ListedReleaseAssets,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListedReleaseAssets {
+    #[serde(default)]
+    links: Vec<ListedReleaseLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedReleaseLink {
+    name: String,
+}
+
+/// Status of a CI job reported on a commit
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    name: String,
+    /// `pending`, `running`, `success`, `failed`, `canceled`, `skipped` or `manual`
+    status: String,
+}
+
+/// A client of the GitLab REST API, scoped to the watched project
+pub struct GitLabClient {
+    http: reqwest::Client,
+    /// Base URL of the API, without a trailing slash
+    api_url: String,
+    /// Path of the project, such as `delta-io/delta-rs`
+    project: String,
+    token: String,
+}
+
+impl GitLabClient {
+    /// Creates a new instance of `GitLabClient`.
+    ///
+    /// # Arguments
+    /// * `token` - The access token for GitLab API authentication.
+    /// * `config` - The API URL and the path of the project.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if the extra root certificates cannot be read.
+    pub fn new(token: String, config: &GitLabConfig, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        info!("GitLab Api Client initialized for {}", config.project);
+
+        Ok(Self {
+            http: http.build()?,
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            project: config.project.clone(),
+            token,
+        })
+    }
+
+    /// Returns the path of the watched project.
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    /// Reads a resource of the API.
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+        let response = self.http
+            .get(format!("{}{}", self.api_url, path))
+            .header("PRIVATE-TOKEN", &self.token)
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Returns the route of a resource of the project, e.g. `/projects/group%2Fproject/releases`.
+    fn project_route(&self, path: &str) -> String {
+        format!("/projects/{}{}", encode(&self.project), path)
+    }
+
+    /// Finds the user who made an email public on their profile.
+    ///
+    /// # Arguments
+    /// * `email` - The email, e.g. of the author of a commit.
+    ///
+    /// # Returns
+    /// A result containing the username, `None` if no profile shows the email, or an error if the
+    /// users cannot be searched.
+    pub async fn username_by_email(&self, email: &str) -> Result<Option<String>> {
+        let users: Vec<ListedUser> = self.get("/users", &[("search", email)]).await?;
+        Ok(users.into_iter().next().map(|user| user.username))
+    }
+
+    /// Checks whether the author of a commit committed to a branch before it.
+    ///
+    /// # Arguments
+    /// * `branch` - The name of the branch.
+    /// * `sha` - The SHA of the commit.
+    ///
+    /// # Returns
+    /// A result containing true if an older commit of the same author is on the branch, or an
+    /// error if the commits cannot be listed.
+    pub async fn has_earlier_commits(&self, branch: &str, sha: &str) -> Result<bool> {
+        let commit: ListedCommit = self
+            .get(&self.project_route(&format!("/repository/commits/{}", encode(sha))), &[])
+            .await?;
+        let until = (commit.committed_date - chrono::Duration::seconds(1)).to_rfc3339();
+
+        let earlier: Vec<serde_json::Value> = self
+            .get(&self.project_route("/repository/commits"), &[
+                ("ref_name", branch),
+                ("author", &commit.author_email),
+                ("until", &until),
+                ("per_page", "1"),
+            ])
+            .await?;
+        Ok(!earlier.is_empty())
+    }
+}
+
+#[async_trait]
+impl Source for GitLabClient {
+    fn name(&self) -> &'static str {
+        "gitlab"
+    }
+
+    // Contributors are looked up on every push rather than gathered upfront
+    fn contributors_ready(&self, _branch: &str) -> bool {
+        true
+    }
+
+    async fn list_release_assets(&self, release: &Release) -> Result<Vec<String>> {
+        let listed: ListedRelease = self
+            .get(&self.project_route(&format!("/releases/{}", encode(&release.tag_name))), &[])
+            .await?;
+        Ok(listed.assets.links.into_iter().map(|link| link.name).collect())
+    }
+
+    async fn list_tag_check_runs(&self, tag: &str) -> Result<Vec<CheckRun>> {
+        let statuses: Vec<CommitStatus> = self
+            .get(
+                &self.project_route(&format!("/repository/commits/{}/statuses", encode(tag))),
+                &[("ref", tag), ("per_page", "100")],
+            )
+            .await?;
+
+        Ok(statuses.into_iter().map(|status| {
+            let conclusion = match status.status.as_str() {
+                "success" => Some("success"),
+                "skipped" | "manual" => Some("skipped"),
+                "failed" => Some("failure"),
+                "canceled" => Some("cancelled"),
+                _ => None,
+            };
+            CheckRun {
+                name: status.name,
+                status: if conclusion.is_some() { "completed" } else { "in_progress" }.to_string(),
+                conclusion: conclusion.map(str::to_string),
+            }
+        }).collect())
+    }
+
+    async fn x_handle(&self, username: &str) -> Result<Option<String>> {
+        let users: Vec<ListedUser> = self.get("/users", &[("username", username)]).await?;
+        let Some(user) = users.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let profile: UserProfile = self.get(&format!("/users/{}", user.id), &[]).await?;
+        Ok(profile.twitter
+            .filter(|handle| !handle.is_empty())
+            .map(|handle| handle.trim_start_matches('@').to_string()))
+    }
+}
+
+/// Encodes a project path or ref as a single segment of a route
+fn encode(segment: &str) -> String {
+    form_urlencoded::byte_serialize(segment.as_bytes()).collect()
+}
@@ -0,0 +1,27 @@
+use super::client::GitLabClient;
+use crate::github::first_contribution::{Contribution, FirstContributionStrategy};
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// The first commit of the contributor on the branch of the GitLab project.
+///
+/// Unlike GitHub, the contributors of the project aren't gathered upfront: the commits of
+/// the author are searched on every push, by the email of the pushed commit.
+pub struct FirstCommit {
+    gitlab_client: Arc<GitLabClient>,
+}
+
+impl FirstCommit {
+    /// Creates the strategy, looking up previous commits with `gitlab_client`.
+    pub fn new(gitlab_client: Arc<GitLabClient>) -> Self {
+        Self { gitlab_client }
+    }
+}
+
+#[async_trait]
+impl FirstContributionStrategy for FirstCommit {
+    async fn is_first_contribution(&self, contribution: &Contribution<'_>) -> Result<bool> {
+        Ok(!self.gitlab_client.has_earlier_commits(contribution.branch, contribution.sha).await?)
+    }
+}
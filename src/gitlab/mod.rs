@@ -0,0 +1,3 @@
+pub mod client;
+pub mod first_contribution;
+pub mod types;
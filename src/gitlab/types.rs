@@ -0,0 +1,158 @@
+use crate::github::types::{
+    Commit,
+    CommitAuthor,
+    GitHubUser,
+    PushEvent,
+    Release,
+    ReleaseAction,
+    ReleaseAsset,
+    ReleaseEvent,
+    Repository};
+use std::collections::HashMap;
+use serde::Deserialize;
+use chrono::{DateTime, Utc};
+
+/// Payload of a `Push Hook`, sent when commits are pushed to a branch of the project
+#[derive(Debug, Deserialize)]
+pub struct PushHook {
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    pub user_id: u64,
+    pub user_username: String,
+    /// Email of the pusher, `[REDACTED]` unless they made it public
+    #[serde(default)]
+    pub user_email: Option<String>,
+    pub project: Project,
+    /// At most the 20 latest commits of the push, oldest first
+    pub commits: Vec<HookCommit>,
+}
+
+/// Payload of a `Release Hook`, sent when a release of the project is created, updated or deleted
+#[derive(Debug, Deserialize)]
+pub struct ReleaseHook {
+    pub id: u64,
+    pub tag: String,
+    pub name: Option<String>,
+    /// Release notes, in markdown
+    pub description: Option<String>,
+    /// URL of the release page
+    pub url: String,
+    /// `create`, `update` or `delete`
+    pub action: String,
+    pub project: Project,
+    #[serde(default)]
+    pub assets: HookReleaseAssets,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Project {
+    pub path_with_namespace: String,
+    pub web_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HookCommit {
+    pub id: String,
+    pub message: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub url: String,
+    pub author: HookAuthor,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HookAuthor {
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct HookReleaseAssets {
+    #[serde(default)]
+    pub links: Vec<ReleaseLink>,
+}
+
+/// A file linked from a release, GitLab's equivalent of a release asset
+#[derive(Debug, Deserialize)]
+pub struct ReleaseLink {
+    pub name: String,
+}
+
+impl Project {
+    /// Converts the project to a GitHub repository, owned by its namespace.
+    fn into_repository(self) -> Repository {
+        let namespace = self.path_with_namespace
+            .rsplit_once('/')
+            .map_or("", |(namespace, _)| namespace)
+            .to_string();
+        Repository {
+            full_name: self.path_with_namespace,
+            // GitLab doesn't report the id of the namespace, which nothing reads
+            owner: GitHubUser { login: namespace, id: 0 },
+            html_url: Some(self.web_url),
+        }
+    }
+}
+
+impl PushHook {
+    /// Converts the push to the GitHub push event contributors are announced from.
+    ///
+    /// GitLab only reports the name and email of commit authors, so their usernames are
+    /// looked up beforehand.
+    ///
+    /// # Arguments
+    /// * `usernames` - The usernames of the commit authors, by lowercased email.
+    pub fn into_event(self, usernames: &HashMap<String, String>) -> PushEvent {
+        let commits = self.commits
+            .into_iter()
+            .map(|commit| Commit {
+                id: commit.id,
+                message: commit.message,
+                timestamp: commit.timestamp,
+                author: CommitAuthor {
+                    username: usernames.get(&commit.author.email.to_lowercase()).cloned(),
+                    name: commit.author.name,
+                    email: commit.author.email,
+                },
+                url: commit.url,
+            })
+            .collect();
+
+        PushEvent {
+            git_ref: self.git_ref,
+            commits,
+            repository: self.project.into_repository(),
+            sender: GitHubUser { login: self.user_username, id: self.user_id },
+        }
+    }
+}
+
+impl ReleaseHook {
+    /// Converts the release to the GitHub release event releases are announced from.
+    ///
+    /// GitLab has no pre-release flag, so pre-releases are only told apart by their tag, and
+    /// it doesn't report what an update changed.
+    pub fn into_event(self) -> ReleaseEvent {
+        let action = match self.action.as_str() {
+            "create" => ReleaseAction::Published,
+            "update" => ReleaseAction::Edited,
+            "delete" => ReleaseAction::Deleted,
+            _ => ReleaseAction::Other(self.action),
+        };
+
+        ReleaseEvent {
+            action,
+            release: Release {
+                id: self.id,
+                tag_name: self.tag,
+                name: self.name,
+                html_url: self.url,
+                body: self.description,
+                prerelease: false,
+                assets: self.assets.links.into_iter().map(|link| ReleaseAsset { name: link.name }).collect(),
+            },
+            repository: self.project.into_repository(),
+            changes: None,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use super::client::{IrcClient, IrcConnection};
+use crate::{
+    announce::{Announcement, Announcer},
+    audit::log::AuditStatus,
+    config::env::IrcConfig};
+use std::collections::VecDeque;
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    time::{interval, sleep, sleep_until, Duration, Instant, MissedTickBehavior}};
+use async_trait::async_trait;
+use anyhow::{anyhow, Result};
+use tracing::{info, warn};
+
+/// Lines waiting to be sent, beyond which the oldest ones are dropped
+const MAX_PENDING_LINES: usize = 100;
+
+/// Longest text of a message, leaving room in the 512 bytes of a line for the command,
+/// the channel and the prefix the server adds when relaying it
+const MAX_MESSAGE_BYTES: usize = 350;
+
+/// Time without hearing from the server before pinging it, and then before giving up on it
+const IDLE_TIMEOUT: Duration = Duration::from_secs(240);
+
+/// Delay before reconnecting, doubled after each failed attempt
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(300);
+
+/// Relays the announcements to an IRC channel, for communities coordinating there or over its bridges.
+///
+/// The bot stays in the channel between announcements, through a connection kept in the
+/// background. Each line of an announcement is a message of its own, sent at most once per
+/// `IRC_MESSAGE_INTERVAL_MS` so the server doesn't disconnect the bot for flooding. A lost
+/// connection is reopened, and the messages waiting are sent once back in.
+pub struct IrcAnnouncer {
+    config: IrcConfig,
+    /// Messages of the announcements, sent over the connection
+    messages: UnboundedSender<Vec<String>>,
+}
+
+impl IrcAnnouncer {
+    /// Creates a new instance of `IrcAnnouncer`, joining the channel in the background.
+    ///
+    /// # Arguments
+    /// * `client` - The client joining the channel.
+    /// * `config` - The channel, the kinds of announcements relayed to it and the pace of the messages.
+    pub fn start(client: IrcClient, config: IrcConfig) -> Self {
+        let (messages, waiting) = mpsc::unbounded_channel();
+        let relay = Relay {
+            client,
+            channel: config.channel.clone(),
+            message_interval: Duration::from_millis(config.message_interval_ms),
+            waiting,
+            pending: VecDeque::new(),
+        };
+        info!("Relaying announcements to IRC channel {} on {}", config.channel, config.server);
+        tokio::spawn(relay.run());
+        Self { config, messages }
+    }
+}
+
+#[async_trait]
+impl Announcer for IrcAnnouncer {
+    fn name(&self) -> &str {
+        "irc"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        // Edits and retractions change posts the channel doesn't have
+        let posted = matches!(announcement.status, AuditStatus::Posted | AuditStatus::Corrected | AuditStatus::FollowUp);
+        posted && !announcement.text.is_empty() && self.config.posts(announcement.kind)
+    }
+
+    /// Queues the messages of the announcement, which are sent at the pace of the channel.
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        self.messages.send(split_message(&announcement.text))
+            .map_err(|_| anyhow!("The connection to IRC stopped"))?;
+        Ok(None)
+    }
+}
+
+/// The connection to the server, sending the messages of the announcements in turn
+struct Relay {
+    client: IrcClient,
+    channel: String,
+    message_interval: Duration,
+    /// Messages of the announcements not queued yet
+    waiting: UnboundedReceiver<Vec<String>>,
+    /// Messages queued for their turn
+    pending: VecDeque<String>,
+}
+
+impl Relay {
+    /// Keeps the bot in the channel and sends the messages, until the announcer is dropped.
+    async fn run(mut self) {
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        loop {
+            let mut connection = match self.client.connect().await {
+                Ok(connection) => {
+                    delay = INITIAL_RECONNECT_DELAY;
+                    connection
+                }
+                Err(e) => {
+                    warn!("Failed to connect to IRC, retrying in {}s: {:?}", delay.as_secs(), e);
+                    sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    continue;
+                }
+            };
+
+            match self.relay(&mut connection).await {
+                Ok(()) => {
+                    let _ = connection.send("QUIT :Shutting down").await;
+                    return;
+                }
+                Err(e) => {
+                    warn!("Lost the connection to IRC, reconnecting in {}s: {:?}", delay.as_secs(), e);
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Sends the messages over a connection, answering the server along the way.
+    ///
+    /// # Returns
+    /// A result that is `Ok` once the announcer is dropped, or an error if the connection is lost.
+    async fn relay(&mut self, connection: &mut IrcConnection) -> Result<()> {
+        let mut pace = interval(self.message_interval);
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut idle_until = Instant::now() + IDLE_TIMEOUT;
+        let mut pinged = false;
+
+        loop {
+            tokio::select! {
+                message = connection.read_message() => {
+                    let message = message?;
+                    idle_until = Instant::now() + IDLE_TIMEOUT;
+                    pinged = false;
+                    match message.command.as_str() {
+                        "PING" => connection.pong(&message).await?,
+                        "KICK" if message.params.get(1) == Some(&connection.nickname) => {
+                            return Err(anyhow!("Kicked from {}: {}", self.channel, message.trailing()));
+                        }
+                        // Cannot send to the channel, such as when it's moderated
+                        "404" => warn!("Message not relayed to {}: {}", self.channel, message.trailing()),
+                        "ERROR" => return Err(anyhow!("The IRC server closed the connection: {}", message.trailing())),
+                        _ => {}
+                    }
+                }
+                messages = self.waiting.recv() => match messages {
+                    Some(messages) => self.queue(messages),
+                    None => return Ok(()),
+                },
+                _ = pace.tick(), if !self.pending.is_empty() => {
+                    if let Some(text) = self.pending.front() {
+                        connection.privmsg(&self.channel, text).await?;
+                        self.pending.pop_front();
+                    }
+                }
+                _ = sleep_until(idle_until) => {
+                    if pinged {
+                        return Err(anyhow!("The IRC server stopped answering"));
+                    }
+                    connection.send("PING :x-bot").await?;
+                    idle_until = Instant::now() + IDLE_TIMEOUT;
+                    pinged = true;
+                }
+            }
+        }
+    }
+
+    /// Queues the messages of an announcement, dropping the oldest ones waiting when too many are
+    fn queue(&mut self, messages: Vec<String>) {
+        let mut dropped = 0;
+        for message in messages {
+            if self.pending.len() == MAX_PENDING_LINES {
+                self.pending.pop_front();
+                dropped += 1;
+            }
+            self.pending.push_back(message);
+        }
+        if dropped > 0 {
+            warn!("Too many messages waiting for IRC, {} were dropped", dropped);
+        }
+    }
+}
+
+/// Splits the text of an announcement into messages, one per line of text, with long lines
+/// wrapped at spaces and words too long for a message, such as URLs, cut.
+fn split_message(text: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    for line in text.lines() {
+        let mut message = String::new();
+        // Control characters would end or garble the line
+        let line: String = line.chars().filter(|c| !c.is_control()).collect();
+        for mut word in line.split_whitespace() {
+            while word.len() > MAX_MESSAGE_BYTES {
+                if !message.is_empty() {
+                    messages.push(std::mem::take(&mut message));
+                }
+                let mut cut = MAX_MESSAGE_BYTES;
+                while !word.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                messages.push(word[..cut].to_string());
+                word = &word[cut..];
+            }
+            if !message.is_empty() && message.len() + 1 + word.len() > MAX_MESSAGE_BYTES {
+                messages.push(std::mem::take(&mut message));
+            }
+            if !message.is_empty() {
+                message.push(' ');
+            }
+            message.push_str(word);
+        }
+        // Empty lines can't be sent
+        if !message.is_empty() {
+            messages.push(message);
+        }
+    }
+    messages
+}
@@ -0,0 +1,237 @@
+use crate::config::{env::{HttpConfig, IrcConfig}, tls};
+use std::sync::Arc;
+use rustls::pki_types::ServerName;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufStream},
+    net::TcpStream,
+    time::{timeout, Duration}};
+use tokio_rustls::TlsConnector;
+use anyhow::{anyhow, Context, Result};
+use tracing::{debug, info};
+
+/// Time allowed for connecting to the server or for sending it a line
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time allowed for registering the nickname and joining the channel
+const JOIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Replies refusing to join the channel: no such channel, too many channels, full,
+/// invite only, banned, wrong key, or registered users only
+const JOIN_ERRORS: [&str; 7] = ["403", "405", "471", "473", "474", "475", "477"];
+
+/// A connection to the server, in plain text or TLS
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+/// A message received from the server, such as `:irc.libera.chat 001 x-bot :Welcome`
+#[derive(Debug)]
+pub struct Message {
+    /// Command or numeric reply, in upper case
+    pub command: String,
+    /// Parameters, the trailing one last
+    pub params: Vec<String>,
+}
+
+impl Message {
+    /// Parses a line received from the server, leaving out its tags and its prefix.
+    fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+        if rest.starts_with('@') {
+            rest = rest.split_once(' ')?.1;
+        }
+        if rest.starts_with(':') {
+            rest = rest.split_once(' ')?.1;
+        }
+        let (middle, trailing) = match rest.split_once(" :") {
+            Some((middle, trailing)) => (middle, Some(trailing)),
+            None => (rest, None),
+        };
+
+        let mut words = middle.split(' ').filter(|word| !word.is_empty());
+        let command = words.next()?.to_ascii_uppercase();
+        let mut params: Vec<String> = words.map(str::to_string).collect();
+        params.extend(trailing.map(str::to_string));
+        Some(Self { command, params })
+    }
+
+    /// Returns the last parameter, the human readable text of most replies
+    pub fn trailing(&self) -> &str {
+        self.params.last().map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// A client joining an IRC channel, speaking just enough of the protocol to relay
+/// announcements: registration with an optional server password, and `PRIVMSG`.
+pub struct IrcClient {
+    server: String,
+    port: u16,
+    /// Connector for TLS connections, plain text without it
+    tls: Option<TlsConnector>,
+    nickname: String,
+    channel: String,
+    password: Option<String>,
+}
+
+impl IrcClient {
+    /// Creates a new instance of `IrcClient`.
+    ///
+    /// # Arguments
+    /// * `config` - The server, the nickname and the channel to join.
+    /// * `password` - The password of the server, checked against the account of the nickname on most networks.
+    /// * `http_config` - The extra root certificates to trust.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if no root certificates are usable.
+    pub fn new(config: &IrcConfig, password: Option<&str>, http_config: &HttpConfig) -> Result<Self> {
+        let tls = if config.tls {
+            let extra = tls::extra_root_certificates(http_config.ca_bundle.as_deref())?;
+            Some(TlsConnector::from(Arc::new(tls::client_config(&extra)?)))
+        } else {
+            None
+        };
+        Ok(Self {
+            server: config.server.clone(),
+            port: config.port,
+            tls,
+            nickname: config.nickname.clone(),
+            channel: config.channel.clone(),
+            password: password.map(str::to_owned),
+        })
+    }
+
+    /// Connects to the server, registers the nickname and joins the channel.
+    ///
+    /// The nickname is taken with trailing underscores while it's in use, such as by the
+    /// previous connection of the bot the server hasn't noticed is gone yet.
+    ///
+    /// # Returns
+    /// A result containing the connection once in the channel, or an error if the server refused the bot.
+    pub async fn connect(&self) -> Result<IrcConnection> {
+        let tcp = timeout(IO_TIMEOUT, TcpStream::connect((self.server.as_str(), self.port)))
+            .await
+            .context("Timed out connecting to the IRC server")??;
+        let stream: Box<dyn Stream> = match &self.tls {
+            Some(tls) => {
+                let server_name = ServerName::try_from(self.server.clone())
+                    .map_err(|_| anyhow!("Invalid IRC server {}", self.server))?;
+                Box::new(timeout(IO_TIMEOUT, tls.connect(server_name, tcp))
+                    .await
+                    .context("Timed out negotiating TLS with the IRC server")??)
+            }
+            None => Box::new(tcp),
+        };
+        let mut connection = IrcConnection {
+            stream: BufStream::new(stream),
+            buffer: Vec::new(),
+            nickname: self.nickname.clone(),
+        };
+
+        timeout(JOIN_TIMEOUT, self.join(&mut connection))
+            .await
+            .context("Timed out joining the IRC channel")??;
+        info!("Joined {} on {} as {}", self.channel, self.server, connection.nickname);
+        Ok(connection)
+    }
+
+    async fn join(&self, connection: &mut IrcConnection) -> Result<()> {
+        if let Some(password) = &self.password {
+            connection.send(&format!("PASS {}", password)).await?;
+        }
+        connection.send(&format!("NICK {}", connection.nickname)).await?;
+        connection.send(&format!("USER {} 0 * :x-bot announcements", self.nickname)).await?;
+        loop {
+            let message = connection.read_message().await?;
+            match message.command.as_str() {
+                "PING" => connection.pong(&message).await?,
+                // Welcome
+                "001" => break,
+                // Nickname in use
+                "433" => {
+                    connection.nickname.push('_');
+                    connection.send(&format!("NICK {}", connection.nickname)).await?;
+                }
+                // Invalid nickname, or banned from the server
+                "432" | "465" => return Err(anyhow!("The IRC server refused the bot: {}", message.trailing())),
+                "ERROR" => return Err(anyhow!("The IRC server closed the connection: {}", message.trailing())),
+                _ => {}
+            }
+        }
+
+        connection.send(&format!("JOIN {}", self.channel)).await?;
+        loop {
+            let message = connection.read_message().await?;
+            match message.command.as_str() {
+                "PING" => connection.pong(&message).await?,
+                // End of the names in the channel, sent once joined
+                "366" => return Ok(()),
+                error if JOIN_ERRORS.contains(&error) => {
+                    return Err(anyhow!("Cannot join {}: {}", self.channel, message.trailing()));
+                }
+                "ERROR" => return Err(anyhow!("The IRC server closed the connection: {}", message.trailing())),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A connection registered with the server
+pub struct IrcConnection {
+    stream: BufStream<Box<dyn Stream>>,
+    /// Bytes of the line being read
+    buffer: Vec<u8>,
+    /// Nickname the server registered, which may have trailing underscores
+    pub nickname: String,
+}
+
+impl IrcConnection {
+    /// Reads the next message from the server.
+    ///
+    /// This is cancel safe, a line read in part is kept for the next call.
+    ///
+    /// # Returns
+    /// A result containing the message, or an error if the connection is lost.
+    pub async fn read_message(&mut self) -> Result<Message> {
+        loop {
+            let read = self.stream.read_until(b'\n', &mut self.buffer).await?;
+            if read == 0 {
+                return Err(anyhow!("The IRC server closed the connection"));
+            }
+            if !self.buffer.ends_with(b"\n") {
+                continue;
+            }
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+            self.buffer.clear();
+            debug!("IRC: {}", line.trim_end());
+            if let Some(message) = Message::parse(&line) {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Sends a line to the server.
+    pub async fn send(&mut self, line: &str) -> Result<()> {
+        timeout(IO_TIMEOUT, async {
+            self.stream.write_all(line.as_bytes()).await?;
+            self.stream.write_all(b"\r\n").await?;
+            self.stream.flush().await
+        })
+        .await
+        .context("Timed out sending to the IRC server")??;
+        Ok(())
+    }
+
+    /// Sends a message to a channel.
+    ///
+    /// # Arguments
+    /// * `channel` - The channel, such as `#delta-rs`.
+    /// * `text` - The text of the message, on a single line.
+    pub async fn privmsg(&mut self, channel: &str, text: &str) -> Result<()> {
+        self.send(&format!("PRIVMSG {} :{}", channel, text)).await
+    }
+
+    /// Answers a ping of the server.
+    pub async fn pong(&mut self, ping: &Message) -> Result<()> {
+        self.send(&format!("PONG :{}", ping.trailing())).await
+    }
+}
@@ -1,4 +1,22 @@
+pub mod announce;
+pub mod audit;
+pub mod broker;
+pub mod cli;
 pub mod config;
+pub mod email;
+pub mod feed;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
+pub mod irc;
+pub mod linkedin;
+pub mod matrix;
+pub mod packages;
+pub mod registry;
+pub mod release;
+pub mod scheduler;
+pub mod source;
+pub mod teams;
+pub mod template;
 pub mod webhook;
 pub mod x;
\ No newline at end of file
@@ -0,0 +1,239 @@
+use crate::{
+    announce::{Announcement, Announcer},
+    config::{env::{HttpConfig, LinkedInConfig, Secrets}, tls}};
+use std::sync::atomic::{AtomicU64, Ordering};
+use reqwest::{Certificate, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::{sync::Mutex, time::{Duration, Instant}};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+/// Endpoint exchanging the refresh token for an access token
+const TOKEN_URL: &str = "https://www.linkedin.com/oauth/v2/accessToken";
+
+/// Endpoint of the UGC Posts API
+const UGC_POSTS_URL: &str = "https://api.linkedin.com/v2/ugcPosts";
+
+/// Access tokens are refreshed this long before they expire, so a post never races the expiry
+const EXPIRY_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostResponse {
+    id: Option<String>,
+}
+
+/// The access token in use, and when it expires when known
+struct AccessToken {
+    token: String,
+    expires_at: Option<Instant>,
+}
+
+/// A client posting to a LinkedIn organization page through the UGC Posts API.
+///
+/// Access tokens of LinkedIn expire after 60 days, so they are obtained from the refresh
+/// token of the OAuth2 application, again whenever they expire or are rejected.
+pub struct LinkedInClient {
+    http: reqwest::Client,
+    /// URN of the organization posting, such as `urn:li:organization:123456`
+    author: String,
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    access_token: Mutex<Option<AccessToken>>,
+    dry_run: bool,
+    dry_run_posts: AtomicU64,
+}
+
+impl LinkedInClient {
+    /// Creates a new instance of `LinkedInClient`.
+    ///
+    /// # Arguments
+    /// * `config` - The organization page and the OAuth2 application posting to it.
+    /// * `secrets` - The client secret and refresh token of the application, and optionally an access token to start with.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if the extra root certificates cannot be read.
+    pub fn new(config: &LinkedInConfig, secrets: &Secrets, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        info!("LinkedIn client initialized for organization {}", config.organization_id);
+
+        Ok(Self {
+            http: http.build()?,
+            author: format!("urn:li:organization:{}", config.organization_id),
+            client_id: config.client_id.clone(),
+            client_secret: secrets.linkedin_client_secret().unwrap_or_default().to_string(),
+            refresh_token: secrets.linkedin_refresh_token().unwrap_or_default().to_string(),
+            access_token: Mutex::new(secrets.linkedin_access_token().map(|token| AccessToken {
+                token: token.to_string(),
+                expires_at: None,
+            })),
+            dry_run: false,
+            dry_run_posts: AtomicU64::new(0),
+        })
+    }
+
+    /// Prints the posts to stdout instead of making them.
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Posts to the organization page, visible to the public.
+    ///
+    /// # Arguments
+    /// * `text` - The commentary of the post.
+    ///
+    /// # Returns
+    /// A result containing the URN of the post, or an error if LinkedIn rejected it.
+    pub async fn post(&self, text: &str) -> Result<String> {
+        if self.dry_run {
+            let id = format!("dry-run-{}", self.dry_run_posts.fetch_add(1, Ordering::Relaxed) + 1);
+            println!("[linkedin] Post ({}):\n{}\n", id, text);
+            return Ok(id);
+        }
+
+        let body = json!({
+            "author": self.author,
+            "lifecycleState": "PUBLISHED",
+            "specificContent": {
+                "com.linkedin.ugc.ShareContent": {
+                    "shareCommentary": { "text": text },
+                    "shareMediaCategory": "NONE",
+                },
+            },
+            "visibility": { "com.linkedin.ugc.MemberNetworkVisibility": "PUBLIC" },
+        });
+
+        let mut refreshed = false;
+        loop {
+            let token = self.token(refreshed).await?;
+            let response = self.http
+                .post(UGC_POSTS_URL)
+                .bearer_auth(&token)
+                .header("X-Restli-Protocol-Version", "2.0.0")
+                .json(&body)
+                .send()
+                .await?;
+
+            // A token revoked or expired early is refreshed once
+            if response.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                warn!("LinkedIn rejected the access token, refreshing it");
+                refreshed = true;
+                continue;
+            }
+
+            let response = response.error_for_status()?;
+            let header_id = response.headers()
+                .get("x-restli-id")
+                .and_then(|id| id.to_str().ok())
+                .map(str::to_owned);
+            let post: PostResponse = response.json().await.unwrap_or(PostResponse { id: None });
+            return header_id
+                .or(post.id)
+                .ok_or_else(|| anyhow!("LinkedIn didn't return the id of the post"));
+        }
+    }
+
+    /// Deletes a post of the organization page.
+    ///
+    /// # Arguments
+    /// * `post_id` - The URN of the post, as returned by [post](Self::post).
+    ///
+    /// # Returns
+    /// A result indicating success, or an error if LinkedIn refused to delete the post.
+    pub async fn delete(&self, post_id: &str) -> Result<()> {
+        if self.dry_run {
+            println!("[linkedin] Delete {}\n", post_id);
+            return Ok(());
+        }
+
+        // URNs go in the path with their colons encoded
+        let url = format!("{}/{}", UGC_POSTS_URL, post_id.replace(':', "%3A"));
+        let mut refreshed = false;
+        loop {
+            let token = self.token(refreshed).await?;
+            let response = self.http
+                .delete(&url)
+                .bearer_auth(&token)
+                .header("X-Restli-Protocol-Version", "2.0.0")
+                .send()
+                .await?;
+
+            if response.status() == StatusCode::UNAUTHORIZED && !refreshed {
+                warn!("LinkedIn rejected the access token, refreshing it");
+                refreshed = true;
+                continue;
+            }
+            // Already gone, which is what deleting it was for
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(());
+            }
+
+            response.error_for_status()?;
+            return Ok(());
+        }
+    }
+
+    /// Returns a valid access token, refreshing it when it expires soon or when `force` is set.
+    async fn token(&self, force: bool) -> Result<String> {
+        let mut access_token = self.access_token.lock().await;
+        if let Some(current) = access_token.as_ref() {
+            let expired = current.expires_at.is_some_and(|expires_at| Instant::now() + EXPIRY_MARGIN >= expires_at);
+            if !force && !expired {
+                return Ok(current.token.clone());
+            }
+        }
+
+        let response: TokenResponse = self.http
+            .post(TOKEN_URL)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", self.refresh_token.as_str()),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        info!("Refreshed the LinkedIn access token, valid for {} days", response.expires_in / 86_400);
+
+        let token = response.access_token.clone();
+        *access_token = Some(AccessToken {
+            token: response.access_token,
+            expires_at: Some(Instant::now() + Duration::from_secs(response.expires_in)),
+        });
+        Ok(token)
+    }
+}
+
+#[async_trait]
+impl Announcer for LinkedInClient {
+    fn name(&self) -> &str {
+        "linkedin"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        announcement.awaits("linkedin")
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        self.post(announcement.text_for("linkedin")).await.map(Some)
+    }
+}
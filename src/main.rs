@@ -1,38 +1,127 @@
 use x_bot::{
-    config::env::Config,
+    announce::{console::ConsoleAnnouncer, dispatcher::Dispatcher},
+    audit::log::AuditLog,
+    broker::{nats::{NatsPublisher, NatsSubscriber}, webhooks::WebhookPublisher},
+    cli::{
+        args::Args,
+        bench,
+        edit_post,
+        exit::{exit_code, is_github_auth_error, Failure},
+        history,
+        post_contributor,
+        post_release,
+        simulate},
+    config::env::{Announcer, Config},
+    email::smtp::SmtpClient,
+    feed::writer::AtomFeedWriter,
+    scheduler::{
+        anniversary::Anniversary,
+        archive::Archiver,
+        backoff_state::BackoffStore,
+        contributor_milestones::ContributorMilestones,
+        digest::WeeklyDigest,
+        download_milestones::DownloadMilestones,
+        email_digest::EmailDigest,
+        feeds::FeedWatcher,
+        images::ImageWatcher,
+        issues::IssueMilestones,
+        monthly_stats::MonthlyStats,
+        organization::OrganizationWatcher,
+        packages::PackageWatcher,
+        poll::PollMonitor,
+        request_budget::RequestBudget,
+        retry_budget::RetryBudget,
+        runtime::RuntimeMonitor,
+        project::ProjectWatcher,
+        star_milestones::StarMilestones,
+        stargazers::StargazerWatcher,
+        supervisor::Supervisor,
+        teaser::MilestoneTeaser},
     github::client::GitHubClient,
-    webhook::handler::{
-        WebhookHandler,
-        AppState,
-        handle_webhook,
-        health_check, 
-        call_back},
-    x::client::XClient};
-use std::sync::Arc;
-use tokio::net::TcpListener;
+    gitea::{client::GiteaClient, first_contribution::FirstCommit as GiteaFirstCommit},
+    gitlab::{client::GitLabClient, first_contribution::FirstCommit as GitLabFirstCommit},
+    source::{events::{self, EventSource}, graphql::GraphQlPoller, Source},
+    webhook::{
+        api::{atom_feed, latest_release_badge, list_announcements},
+        gitea::{handle_gitea_webhook, GiteaWebhooks},
+        gitlab::{handle_gitlab_webhook, GitLabWebhooks},
+        handler::{
+            WebhookHandler,
+            AppState,
+            handle_webhook,
+            health_check, 
+            call_back,
+            confirm_member,
+            export_history},
+        logging::log_requests,
+        rate_limit::{limit_requests, RateLimiter},
+        security,
+        server},
+    linkedin::client::LinkedInClient,
+    irc::{announcer::IrcAnnouncer, client::IrcClient},
+    matrix::{announcer::MatrixAnnouncer, client::MatrixClient},
+    packages::client::PackageClient,
+    teams::announcer::TeamsAnnouncer,
+    registry::client::RegistryClient,
+    x::{client::XClient, follows::Follows, outbox::Outbox}};
+use std::{collections::HashMap, path::Path, process::ExitCode, sync::{Arc, RwLock}, time::Duration};
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
+    middleware,
     routing::{post, get}};
-use anyhow::Result;
-use tracing::{info, debug};
+use anyhow::{Context, Result, anyhow};
+use tracing::{info, debug, warn};
 use tracing_subscriber::{
     layer::SubscriberExt, 
-    util::SubscriberInitExt};
+    util::SubscriberInitExt,
+    Layer};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            exit_code(&e)
+        }
+    }
+}
+
+async fn run() -> Result<()> {
+    // Run a subcommand instead of the server when one is given
+    let mut args = std::env::args().skip(1);
+    if let Some(command) = args.next() {
+        return match command.as_str() {
+            "history" => history::run(Args::new(args)).await,
+            "simulate" => simulate::run(Args::new(args)).await,
+            "bench-webhook" => bench::run(Args::new(args)).await,
+            "post-release" => post_release::run(Args::new(args)).await,
+            "post-contributor" => post_contributor::run(Args::new(args)).await,
+            "edit-post" => edit_post::run(Args::new(args)).await,
+            _ => Err(anyhow!(
+                "Unknown command: {}\nUsage:\n  {}\n  {}\n  {}\n  {}\n  {}\n  {}",
+                command, history::USAGE, simulate::USAGE, bench::USAGE, post_release::USAGE, post_contributor::USAGE,
+                edit_post::USAGE
+            ))
+                .context(Failure::Usage),
+        };
+    }
     
     // Clear the terminal
     std::process::Command::new("clear").status().unwrap();println!("\n");
     
     // Load configuration
-    let config = Config::from_env()?;
+    let config = Config::from_env().context(Failure::Config)?;
         
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
+    // The log level only filters the logs, tokio-console gets every task event it needs
+    let registry = tracing_subscriber::registry();
+    #[cfg(feature = "tokio-console")]
+    let registry = registry.with(console_subscriber::spawn());
+    registry
+        .with(tracing_subscriber::fmt::layer().with_target(true).with_filter(tracing_subscriber::EnvFilter::new(
             std::env::var("LOG_LEVEL").unwrap_or_else(|_| "debug".to_string())
-        ))
-        .with(tracing_subscriber::fmt::layer().with_target(true))
+        )))
         .init();
 
     info!("Starting X Bot with log level: {}", config.log_level);
@@ -49,45 +138,576 @@ async fn main() -> Result<()> {
     // Get webhook URL
     println!("Webhook URL: {}", config.webhook_url());
     
+    // Retries against GitHub and X are paced together, so an outage of both doesn't end in a retry storm
+    let retry_budget = Arc::new(RetryBudget::new(config.retry.budget_per_minute));
+
+    // Background scans are spread over GitHub's rate limit, keeping a reserve for the webhooks
+    let request_budget = config.request_budget.pacing.then(|| Arc::new(RequestBudget::new(config.request_budget.reserve)));
+
     // Initialize GitHub client
     let github_client = GitHubClient::new(
-        config.secrets.github_token().to_owned(),
+        config.github_auth(),
         config.repo_owner.clone(),
-        config.repo_name.clone()
-    ).await?;
+        config.repo_name.clone(),
+        config.branches.iter().map(|branch| branch.name.clone()).collect(),
+        Path::new(&config.contributor.state_dir),
+        &config.http,
+    ).await.context(Failure::Config)?.with_retry_budget(Arc::clone(&retry_budget));
+    let github_client = Arc::new(match &request_budget {
+        Some(request_budget) => github_client.with_request_budget(Arc::clone(request_budget)),
+        None => github_client,
+    });
+
+    // Initialize a GitHub client for each of the other repositories, whose contributors are kept
+    // in a directory of their own
+    let mut repositories = Vec::new();
+    for repository in &config.repositories {
+        let client = GitHubClient::new(
+            config.github_auth(),
+            repository.owner.clone(),
+            repository.name.clone(),
+            repository.branches.iter().map(|branch| branch.name.clone()).collect(),
+            &Path::new(&config.contributor.state_dir).join(&repository.owner).join(&repository.name),
+            &config.http,
+        ).await.context(Failure::Config)?.with_retry_budget(Arc::clone(&retry_budget));
+        let client = Arc::new(match &request_budget {
+            Some(request_budget) => client.with_request_budget(Arc::clone(request_budget)),
+            None => client,
+        });
+        repositories.push((repository, client));
+    }
 
     // Initialize X client
-    let x_client = Arc::new(XClient::new(
+    let x_client = XClient::new(
         config.secrets.x_api_key().to_owned(),
         config.secrets.x_api_secret().to_owned(),
         config.secrets.x_access_token().to_owned(),
-        config.secrets.x_access_secret().to_owned()
-    ).await?);
+        config.secrets.x_access_secret().to_owned(),
+        config.queue.clone(),
+        config.fallback.clone(),
+        &config.http,
+    ).await.context(Failure::Config)?
+    .with_retry_budget(Arc::clone(&retry_budget))
+    .with_edit_access(config.edit.clone());
+    let console = Arc::new(ConsoleAnnouncer::new());
+    let x_client = Arc::new(match config.announcer {
+        Announcer::X => x_client,
+        Announcer::Console => {
+            warn!("Announcing on the console, nothing is posted on X");
+            x_client.console(Arc::clone(&console))
+        }
+    });
     
+    // Open the audit log of announcements, kept in memory on the console so switching to X
+    // later announces them for real
+    let audit_log = AuditLog::open(&config.audit.path).await.context(Failure::StateStore)?;
+    let audit_log = Arc::new(match config.announcer {
+        Announcer::X => audit_log,
+        Announcer::Console => audit_log.in_memory(),
+    });
+
+    // Pick up the rate limit window where it was before a restart
+    x_client.restore_rate_limit(&audit_log).await;
+
+    // Follow the repositories if they were renamed or transferred while the bot was down
+    for client in std::iter::once(&github_client).chain(repositories.iter().map(|(_, client)| client)) {
+        match client.sync_repository_location().await {
+            Ok(Some(previous)) => {
+                let current = client.repo().await;
+                audit_log.rename_repo(&previous.full_name(), &current.full_name()).await.context(Failure::StateStore)?;
+            }
+            Ok(None) => debug!("Location of {} verified", client.repo().await),
+            // Retrying won't help with a rejected token, unlike GitHub being unreachable
+            Err(e) if is_github_auth_error(&e) => return Err(e).context(Failure::Auth),
+            Err(e) => warn!("Could not verify the location of {}: {:?}", client.repo().await, e),
+        }
+    }
+
+    // Relay the announcements recorded from now on to the other targets, none of which is set
+    // up on the console so nothing is announced for real
+    let live = config.announcer == Announcer::X;
+    let mut dispatcher = Dispatcher::new();
+    if let Some(url) = config.secrets.nats_url().filter(|_| live) {
+        dispatcher.add(NatsPublisher::new(url, &config.broker).context(Failure::Config)?);
+    }
+    for url in config.broker.webhook_urls.iter().filter(|_| live) {
+        dispatcher.add(WebhookPublisher::new(url, config.secrets.outbound_webhook_secret(), &config.http)
+            .context(Failure::Config)?);
+    }
+    if let Some(access_token) = config.secrets.matrix_access_token().filter(|_| live && config.matrix.enabled) {
+        let client = MatrixClient::new(&config.matrix, access_token, &config.http).context(Failure::Config)?;
+        dispatcher.add(MatrixAnnouncer::new(client, config.matrix.clone()));
+    }
+    if let Some(webhook_url) = config.secrets.teams_webhook_url().filter(|_| live && config.teams.enabled) {
+        dispatcher.add(TeamsAnnouncer::new(webhook_url, config.teams.clone(), &config.http).context(Failure::Config)?);
+    }
+    if live && config.irc.enabled {
+        let client = IrcClient::new(&config.irc, config.secrets.irc_password(), &config.http).context(Failure::Config)?;
+        dispatcher.add(IrcAnnouncer::start(client, config.irc.clone()));
+    }
+    if !dispatcher.is_empty() {
+        tokio::spawn(dispatcher.run(Arc::clone(&audit_log)));
+    }
+
+    // Keep the Atom feed of the announcements up to date on disk
+    if let Some(path) = &config.atom_feed.path {
+        let writer = AtomFeedWriter::new(path, Arc::clone(&audit_log), config.atom_feed.clone());
+        tokio::spawn(writer.run(audit_log.subscribe()));
+    }
+
+    // Gather the contributors in the background, contributor announcements wait for it
+    // while everything else is served right away
+    for client in std::iter::once(&github_client).chain(repositories.iter().map(|(_, client)| client)) {
+        let client = Arc::clone(client);
+        tokio::spawn(async move { client.bootstrap_contributors().await });
+    }
+
+    // Background tasks are restarted when they stop, and stop the bot if they keep stopping
+    let mut supervisor = Supervisor::new(config.poll_backoff.clone());
+
+    // Post the releases of the channels routed to LinkedIn to the organization page, or print
+    // them on the console
+    let linkedin_client = config.linkedin.enabled
+        .then(|| LinkedInClient::new(&config.linkedin, &config.secrets, &config.http))
+        .transpose()
+        .context(Failure::Config)?
+        .map(|client| Arc::new(if live { client } else { client.dry_run() }));
+
+    // Post the announcements queued in the audit log, starting with those left by a restart,
+    // on X, or on the console in its place. The X client retries the posts on its own, and
+    // follows the announced contributors once their announcement is posted.
+    let mut targets = Dispatcher::new().with_max_attempts(1);
+    match config.announcer {
+        Announcer::X => targets.add_shared(x_client.clone()),
+        Announcer::Console => targets.add_shared(console),
+    }
+    let outbox = Outbox::new(targets, Arc::clone(&audit_log), config.poll_backoff.clone())
+        .with_linkedin(linkedin_client.clone())
+        .with_follows(Arc::clone(&x_client), Follows::for_contributors(&config.contributor));
+    let outbox = Arc::new(outbox);
+    supervisor.spawn("outbox", move || Arc::clone(&outbox).run());
+
+    // Watch the runtime for tasks blocking its worker threads
+    let runtime = Arc::new(RuntimeMonitor::new(tokio::runtime::Handle::current()));
+    supervisor.spawn("runtime_monitor", {
+        let runtime = Arc::clone(&runtime);
+        move || Arc::clone(&runtime).run()
+    });
+
+    // Limit the requests every client can make, except from GitHub's own webhook networks
+    let rate_limiter = Arc::new(RateLimiter::new(&config.rate_limit));
+    if config.rate_limit.allow_github_hooks {
+        let rate_limiter = Arc::clone(&rate_limiter);
+        let github_client = Arc::clone(&github_client);
+        supervisor.spawn("github_networks", move || {
+            Arc::clone(&rate_limiter).refresh_github_networks(Arc::clone(&github_client))
+        });
+    }
+
+    // Scheduled polls back off while they fail, their health is reported by /health
+    let polls = Arc::new(PollMonitor::new(config.poll_backoff.clone(), Arc::clone(&retry_budget)));
+
+    // Keep backing off where the bot was before a restart, and save where it is from now on
+    let backoff_state = Arc::new(BackoffStore::new(
+        Path::new(&config.contributor.state_dir).join("backoff.json"),
+        Arc::clone(&retry_budget),
+        Arc::clone(&polls),
+        Arc::clone(&x_client),
+    ));
+    backoff_state.restore().await;
+    supervisor.spawn("backoff_state", {
+        let backoff_state = Arc::clone(&backoff_state);
+        move || Arc::clone(&backoff_state).run()
+    });
+
+    // Start the polls of every repository, the ones of the other repositories are reported
+    // under their name, as in `delta-io/delta-kernel-rs:star_milestones`
+    let main_repository = (Arc::clone(&github_client), Arc::clone(&polls), String::new());
+    let other_repositories = repositories.iter().map(|(repository, client)| {
+        let name = repository.full_name();
+        (Arc::clone(client), polls.scoped(&name), format!("{}:", name))
+    });
+    for (github_client, polls, prefix) in std::iter::once(main_repository).chain(other_repositories) {
+        let task = |name: &str| format!("{}{}", prefix, name);
+
+        // Start the milestone teaser scheduler
+        if config.teaser.enabled {
+            let teaser = Arc::new(MilestoneTeaser::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.teaser.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("milestone_teaser"), move || Arc::clone(&teaser).run(Arc::clone(&polls)));
+        }
+
+        // Start the weekly merged pull request digest
+        if config.digest.enabled {
+            let digest = Arc::new(WeeklyDigest::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.digest.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("weekly_digest"), move || Arc::clone(&digest).run(Arc::clone(&polls)));
+        }
+
+        // Start the monthly repository stats
+        if config.monthly_stats.enabled {
+            let stats = Arc::new(MonthlyStats::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.monthly_stats.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("monthly_stats"), move || Arc::clone(&stats).run(Arc::clone(&polls)));
+        }
+
+        // Start the repository anniversary
+        if config.anniversary.enabled {
+            let anniversary = Arc::new(Anniversary::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.anniversary.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("anniversary"), move || Arc::clone(&anniversary).run(Arc::clone(&polls)));
+        }
+
+        // Start the closed issue milestone watcher
+        if config.issue_milestones.enabled {
+            let milestones = Arc::new(IssueMilestones::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.issue_milestones.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("issue_milestones"), move || Arc::clone(&milestones).run(Arc::clone(&polls)));
+        }
+
+        // Start the star count milestone watcher
+        if config.star_milestones.enabled {
+            let milestones = Arc::new(StarMilestones::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.star_milestones.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("star_milestones"), move || Arc::clone(&milestones).run(Arc::clone(&polls)));
+        }
+
+        // Start the release download milestone watcher
+        if config.download_milestones.enabled {
+            let milestones = Arc::new(DownloadMilestones::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.download_milestones.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("download_milestones"), move || Arc::clone(&milestones).run(Arc::clone(&polls)));
+        }
+
+        // Start the contributor count milestone watcher
+        if config.contributor_milestones.enabled {
+            let milestones = Arc::new(ContributorMilestones::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.contributor_milestones.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("contributor_milestones"), move || Arc::clone(&milestones).run(Arc::clone(&polls)));
+        }
+
+        // Start the notable stargazer watcher
+        if config.stargazers.enabled {
+            let watcher = Arc::new(StargazerWatcher::new(
+                Arc::clone(&github_client),
+                Arc::clone(&audit_log),
+                config.stargazers.clone(),
+            ));
+            let polls = Arc::clone(&polls);
+            supervisor.spawn(task("stargazers"), move || Arc::clone(&watcher).run(Arc::clone(&polls)));
+        }
+    }
+
+    // Start the container image watcher
+    if config.images.enabled {
+        let registry_client = RegistryClient::new(&config.images, config.secrets.registry_token(), &config.http)
+            .context(Failure::Config)?;
+        let watcher = Arc::new(ImageWatcher::new(
+            registry_client,
+            Arc::clone(&audit_log),
+            config.images.clone(),
+        ).context(Failure::Config)?);
+        let polls = Arc::clone(&polls);
+        supervisor.spawn("container_images", move || Arc::clone(&watcher).run(Arc::clone(&polls)));
+    }
+
+    // Start the RSS and Atom feed watcher
+    if config.feeds.enabled {
+        let watcher = Arc::new(FeedWatcher::new(
+            Arc::clone(&audit_log),
+            config.feeds.clone(),
+            &config.http,
+        ).context(Failure::Config)?);
+        let polls = Arc::clone(&polls);
+        supervisor.spawn("feeds", move || Arc::clone(&watcher).run(Arc::clone(&polls)));
+    }
+
+    // Start the email digest of the announcements, which aren't made for real on the console
+    if live && config.email_digest.enabled {
+        let smtp_client = SmtpClient::new(&config.email_digest, config.secrets.smtp_password(), &config.http)
+            .context(Failure::Config)?;
+        let digest = Arc::new(EmailDigest::new(
+            smtp_client,
+            Arc::clone(&audit_log),
+            config.email_digest.clone(),
+            Path::new(&config.contributor.state_dir).join("email_digest.json"),
+        ));
+        let polls = Arc::clone(&polls);
+        supervisor.spawn("email_digest", move || Arc::clone(&digest).run(Arc::clone(&polls)));
+    }
+
+    // Start the npm and PyPI package release watchers
+    for (name, packages) in [("npm_packages", &config.npm), ("pypi_packages", &config.pypi)] {
+        if !packages.enabled {
+            continue;
+        }
+        let package_client = PackageClient::new(packages, &config.http).context(Failure::Config)?;
+        let watcher = Arc::new(PackageWatcher::new(
+            package_client,
+            Arc::clone(&audit_log),
+            packages.clone(),
+        ));
+        let polls = Arc::clone(&polls);
+        supervisor.spawn(name, move || Arc::clone(&watcher).run(Arc::clone(&polls)));
+    }
+
+    // Start the project board watcher
+    if config.project.enabled {
+        let watcher = Arc::new(ProjectWatcher::new(
+            Arc::clone(&github_client),
+            Arc::clone(&audit_log),
+            config.project.clone(),
+        ));
+        let polls = Arc::clone(&polls);
+        supervisor.spawn("project", move || Arc::clone(&watcher).run(Arc::clone(&polls)));
+    }
+
+    // Start archiving the audit log and state
+    if config.archive.enabled {
+        let archiver = Arc::new(Archiver::new(
+            config.archive.clone(),
+            config.secrets.archive_secret_access_key().unwrap_or_default(),
+            Path::new(&config.audit.path),
+            Path::new(&config.contributor.state_dir),
+            &config.http,
+        ).context(Failure::Config)?);
+        let polls = Arc::clone(&polls);
+        supervisor.spawn("archive", move || Arc::clone(&archiver).run(Arc::clone(&polls)));
+    }
+
+    // Announce the pushes and releases of the GitLab project with a handler of its own
+    let gitlab = match config.secrets.gitlab_token().filter(|_| config.gitlab.enabled) {
+        Some(token) => {
+            let gitlab_client = Arc::new(GitLabClient::new(token.to_owned(), &config.gitlab, &config.http).context(Failure::Config)?);
+            let first_contribution = Box::new(GitLabFirstCommit::new(Arc::clone(&gitlab_client)));
+            let handler = WebhookHandler::new(
+                Arc::clone(&github_client),
+                Arc::clone(&x_client),
+                Arc::clone(&audit_log),
+                config.branches.clone(),
+                config.release.clone(),
+                config.contributor.clone(),
+                config.member.clone(),
+            )?
+            .with_source(Arc::clone(&gitlab_client) as Arc<dyn Source>, first_contribution)
+            .with_opengraph_images(&config.http)?
+            .with_linkedin(linkedin_client.clone(), config.linkedin.clone());
+            Some(GitLabWebhooks {
+                gitlab_client,
+                handler: Arc::new(handler),
+                secret: config.secrets.gitlab_webhook_secret().unwrap_or_default().to_owned(),
+            })
+        }
+        None => None,
+    };
+
+    // Announce the pushes and releases of the Gitea repository with a handler of its own
+    let gitea = match config.secrets.gitea_webhook_secret().filter(|_| config.gitea.enabled) {
+        Some(secret) => {
+            let branches: Vec<String> = config.branches.iter().map(|branch| branch.name.clone()).collect();
+            let gitea_client = Arc::new(GiteaClient::new(
+                config.secrets.gitea_token().map(str::to_owned),
+                &config.gitea,
+                &branches,
+                &config.http,
+            ).context(Failure::Config)?);
+            tokio::spawn({
+                let gitea_client = Arc::clone(&gitea_client);
+                async move { gitea_client.bootstrap_contributors().await }
+            });
+            let first_contribution = Box::new(GiteaFirstCommit::new(Arc::clone(&gitea_client)));
+            let handler = WebhookHandler::new(
+                Arc::clone(&github_client),
+                Arc::clone(&x_client),
+                Arc::clone(&audit_log),
+                config.branches.clone(),
+                config.release.clone(),
+                config.contributor.clone(),
+                config.member.clone(),
+            )?
+            .with_source(Arc::clone(&gitea_client) as Arc<dyn Source>, first_contribution)
+            .with_opengraph_images(&config.http)?
+            .with_linkedin(linkedin_client.clone(), config.linkedin.clone());
+            Some(GiteaWebhooks {
+                gitea_client,
+                handler: Arc::new(handler),
+                secret: secret.to_owned(),
+            })
+        }
+        None => None,
+    };
+
     // Create webhook handler
     let webhook_handler = WebhookHandler::new(
-        github_client,
+        Arc::clone(&github_client),
         Arc::clone(&x_client),
-    );
+        audit_log,
+        config.branches.clone(),
+        config.release.clone(),
+        config.contributor.clone(),
+        config.member.clone(),
+    )?
+    .with_opengraph_images(&config.http)?
+    .with_public_repos(config.public_repo.clone())
+    .with_tags(config.tags.clone())?
+    .with_sponsors(config.sponsors.clone())
+    .with_breaking_changes(config.breaking_changes.clone())
+    .with_issue_labels(config.issue_labels.clone())
+    .with_long_awaited_fixes(config.long_awaited_fixes.clone())
+    .with_linkedin(linkedin_client, config.linkedin.clone());
+
+    // Announce the other repositories with a handler of their own, with the same settings
+    let mut repository_handlers = HashMap::new();
+    for (repository, client) in &repositories {
+        let handler = webhook_handler.for_repository(Arc::clone(client), repository.branches.clone())?;
+        repository_handlers.insert(repository.full_name().to_lowercase(), Arc::new(handler));
+    }
 
     // Create app state
     let state = Arc::new(AppState {
-        webhook_handler,
+        webhook_handler: Arc::new(webhook_handler),
+        repositories: RwLock::new(repository_handlers),
+        ignored_repositories: Default::default(),
+        member_confirm_token: config.secrets.member_confirm_token().map(str::to_owned),
+        history_token: config.secrets.history_token().map(str::to_owned),
+        polls,
+        runtime,
+        parse_failures: Default::default(),
+        retry_budget,
+        request_budget,
+        accept_form_payloads: config.server.accept_form_payloads,
+        webhook_secret: config.server.verify_signatures
+            .then(|| config.secrets.webhook_secret().map(str::to_owned))
+            .flatten(),
+        gitlab,
+        gitea,
+        atom_feed: config.atom_feed.clone(),
     });
 
-    // Build router
-    let app = Router::new()
-        .route("/webhook", post(handle_webhook))
+    // Consume GitHub events forwarded to NATS alongside the webhook route
+    if let Some(url) = config.secrets.nats_url() {
+        let parse_failures = Arc::clone(&state.parse_failures);
+        if let Some(subscriber) = NatsSubscriber::new(url, &config.broker, parse_failures).context(Failure::Config)? {
+            let subscriber: Arc<dyn EventSource> = Arc::new(subscriber);
+            let state = Arc::clone(&state);
+            supervisor.spawn("nats_ingest", move || events::feed(Arc::clone(&subscriber), Arc::clone(&state)));
+        }
+    }
+
+    // Poll the commits and releases of every repository alongside the webhook route, the polls
+    // of the other repositories are reported under their name
+    if config.github_poll.enabled {
+        let interval = Duration::from_secs(config.github_poll.interval_seconds);
+        let main_repository = (Arc::clone(&github_client), &config.branches, Arc::clone(&state.polls), String::new());
+        let other_repositories = repositories.iter().map(|(repository, client)| {
+            let name = repository.full_name();
+            (Arc::clone(client), &repository.branches, state.polls.scoped(&name), format!("{}:", name))
+        });
+        for (github_client, branches, polls, prefix) in std::iter::once(main_repository).chain(other_repositories) {
+            let branches = branches.iter().map(|branch| branch.name.clone()).collect();
+            let poller: Arc<dyn EventSource> = Arc::new(GraphQlPoller::new(github_client, branches, polls, interval));
+            let state = Arc::clone(&state);
+            supervisor.spawn(format!("{}github_poll", prefix), move || events::feed(Arc::clone(&poller), Arc::clone(&state)));
+        }
+    }
+
+    // Announce the repositories of the organization, including the ones created from now on
+    if config.organization.enabled {
+        let watcher = Arc::new(OrganizationWatcher::new(
+            github_client,
+            Arc::clone(&state),
+            config.branches.clone(),
+            Path::new(&config.contributor.state_dir),
+            config.organization.clone(),
+        ).context(Failure::Config)?);
+        let polls = Arc::clone(&state.polls);
+        supervisor.spawn("organization", move || Arc::clone(&watcher).run(Arc::clone(&polls)));
+    }
+
+    // Build router, the security headers only apply to the admin routes GitHub doesn't call
+    let mut admin = Router::new()
         .route("/health", get(health_check))
         .route("/callback", get(call_back))
+        .route("/members/:username/confirm", post(confirm_member))
+        .route("/history", get(export_history));
+    if config.public_api.enabled {
+        // Anyone can call the public API, so it is rate limited like the webhook route
+        admin = admin.merge(
+            Router::new()
+                .route("/api/announcements", get(list_announcements))
+                .route("/badge/latest-release.json", get(latest_release_badge))
+                .route("/feed.atom", get(atom_feed))
+                .layer(middleware::from_fn_with_state(Arc::clone(&rate_limiter), limit_requests)),
+        );
+    }
+    let admin = security::secure(admin, &config.security)?;
+    let mut app = Router::new()
+        .route(
+            "/webhook",
+            post(handle_webhook)
+                .layer(DefaultBodyLimit::max(config.server.max_body_bytes))
+                .layer(middleware::from_fn_with_state(Arc::clone(&rate_limiter), limit_requests)),
+        );
+    if config.gitlab.enabled {
+        app = app.route(
+            "/webhook/gitlab",
+            post(handle_gitlab_webhook)
+                .layer(DefaultBodyLimit::max(config.server.max_body_bytes))
+                .layer(middleware::from_fn_with_state(Arc::clone(&rate_limiter), limit_requests)),
+        );
+    }
+    if config.gitea.enabled {
+        app = app.route(
+            "/webhook/gitea",
+            post(handle_gitea_webhook)
+                .layer(DefaultBodyLimit::max(config.server.max_body_bytes))
+                .layer(middleware::from_fn_with_state(rate_limiter, limit_requests)),
+        );
+    }
+    let app = app
+        .merge(admin)
+        .layer(middleware::from_fn(log_requests))
         .with_state(state);
 
-    // Start server
-    let addr = format!("{}:{}", config.server.host, config.server.port);
-    info!("Listening on {}", addr);
-    
-    let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
-
-    Ok(())
+    // Start server, which only returns once it is told to shut down or a task is given up on
+    let result = tokio::select! {
+        result = server::serve(&config.server.listen, app) => result,
+        result = supervisor.wait() => result.context(Failure::Fatal),
+    };
+    if let Err(e) = backoff_state.save().await {
+        warn!("Failed to save the backoff state: {:?}", e);
+    }
+    result
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use super::client::MatrixClient;
+use crate::{
+    announce::{Announcement, Announcer},
+    audit::log::AuditStatus,
+    config::env::MatrixConfig};
+use std::sync::LazyLock;
+use regex::Regex;
+use async_trait::async_trait;
+use anyhow::Result;
+use tracing::debug;
+
+/// Links in the text of an announcement
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"https?://[^\s<>"]+"#).unwrap());
+
+/// Posts the announcements to a Matrix room, for communities coordinating there.
+///
+/// Messages carry an HTML body with clickable links alongside the plain text one.
+pub struct MatrixAnnouncer {
+    client: MatrixClient,
+    config: MatrixConfig,
+}
+
+impl MatrixAnnouncer {
+    /// Creates a new instance of `MatrixAnnouncer`.
+    ///
+    /// # Arguments
+    /// * `client` - The client posting to the room.
+    /// * `config` - The kinds of announcements posted to the room.
+    pub fn new(client: MatrixClient, config: MatrixConfig) -> Self {
+        Self { client, config }
+    }
+}
+
+#[async_trait]
+impl Announcer for MatrixAnnouncer {
+    fn name(&self) -> &str {
+        "matrix"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        // Edits and retractions change posts the room doesn't have
+        let posted = matches!(announcement.status, AuditStatus::Posted | AuditStatus::Corrected | AuditStatus::FollowUp);
+        posted && !announcement.text.is_empty() && self.config.posts(announcement.kind)
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        let transaction_id = format!("{}-{}", announcement.idempotency_key, announcement.status.as_str());
+        let html = to_html(&announcement.text);
+        let event_id = self.client.send_message(&transaction_id, &announcement.text, &html).await?;
+        debug!("Posted {} announcement {} to Matrix as {}", announcement.status.as_str(), announcement.idempotency_key, event_id);
+        Ok(None)
+    }
+}
+
+/// Formats the text of an announcement as HTML, escaping it, linking its URLs and keeping its line breaks
+fn to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+    for link in URL.find_iter(text) {
+        html.push_str(&escape(&text[last..link.start()]));
+        let url = escape(link.as_str());
+        html.push_str(&format!("<a href=\"{}\">{}</a>", url, url));
+        last = link.end();
+    }
+    html.push_str(&escape(&text[last..]));
+    html.replace('\n', "<br>")
+}
+
+/// Escapes the characters with a meaning in HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
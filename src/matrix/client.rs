@@ -0,0 +1,87 @@
+use crate::config::{env::{HttpConfig, MatrixConfig}, tls};
+use reqwest::{Certificate, Url};
+use serde::Deserialize;
+use serde_json::json;
+use anyhow::{anyhow, Context, Result};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+struct SendResponse {
+    event_id: String,
+}
+
+/// A client posting messages to a Matrix room through the client-server API
+pub struct MatrixClient {
+    http: reqwest::Client,
+    /// `/send/m.room.message` endpoint of the room, to which the transaction id is appended
+    send_url: Url,
+    access_token: String,
+}
+
+impl MatrixClient {
+    /// Creates a new instance of `MatrixClient`.
+    ///
+    /// # Arguments
+    /// * `config` - The homeserver and the room to post to.
+    /// * `access_token` - The access token of the account posting, which has joined the room.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if the homeserver URL or the extra root certificates are invalid.
+    pub fn new(config: &MatrixConfig, access_token: &str, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        // Room ids hold `!` and `:`, which have to be escaped in the path
+        let mut send_url = Url::parse(&config.homeserver_url).context("MATRIX_HOMESERVER_URL must be a URL")?;
+        send_url
+            .path_segments_mut()
+            .map_err(|_| anyhow!("MATRIX_HOMESERVER_URL cannot be a base URL"))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3", "rooms", &config.room_id, "send", "m.room.message"]);
+
+        info!("Matrix client initialized for room {} on {}", config.room_id, config.homeserver_url);
+
+        Ok(Self {
+            http: http.build()?,
+            send_url,
+            access_token: access_token.to_string(),
+        })
+    }
+
+    /// Posts a message to the room.
+    ///
+    /// # Arguments
+    /// * `transaction_id` - Identifies the message, the homeserver ignores a retry with the same id.
+    /// * `text` - The plain text body, shown by clients without HTML support.
+    /// * `html` - The HTML body.
+    ///
+    /// # Returns
+    /// A result containing the id of the event posted, or an error if the homeserver rejected it.
+    pub async fn send_message(&self, transaction_id: &str, text: &str, html: &str) -> Result<String> {
+        let mut url = self.send_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| anyhow!("Invalid Matrix send URL"))?
+            .push(transaction_id);
+
+        let response: SendResponse = self.http
+            .put(url)
+            .bearer_auth(&self.access_token)
+            .json(&json!({
+                "msgtype": "m.text",
+                "body": text,
+                "format": "org.matrix.custom.html",
+                "formatted_body": html,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.event_id)
+    }
+}
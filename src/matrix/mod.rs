@@ -0,0 +1,2 @@
+pub mod announcer;
+pub mod client;
@@ -0,0 +1,95 @@
+use crate::config::{env::{Ecosystem, HttpConfig, PackageConfig}, tls};
+use reqwest::Certificate;
+use serde::Deserialize;
+use anyhow::{anyhow, Result};
+use tracing::info;
+
+#[derive(Debug, Deserialize)]
+struct NpmDistTags {
+    latest: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PyPiProject {
+    info: PyPiInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct PyPiInfo {
+    version: String,
+}
+
+/// A client looking up the latest version of a package, on npm or PyPI
+pub struct PackageClient {
+    http: reqwest::Client,
+    ecosystem: Ecosystem,
+    /// Name of the package, such as `deltalake`
+    package: String,
+}
+
+impl PackageClient {
+    /// Creates a new instance of `PackageClient`.
+    ///
+    /// # Arguments
+    /// * `config` - The ecosystem and the name of the package.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if the extra root certificates cannot be read.
+    pub fn new(config: &PackageConfig, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        info!("Package client initialized for {} on {}", config.package, config.ecosystem.as_str());
+
+        Ok(Self {
+            http: http.build()?,
+            ecosystem: config.ecosystem,
+            package: config.package.clone(),
+        })
+    }
+
+    /// Returns the name of the package.
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    /// Returns the page of a version of the package.
+    pub fn url(&self, version: &str) -> String {
+        match self.ecosystem {
+            Ecosystem::Npm => format!("https://www.npmjs.com/package/{}/v/{}", self.package, version),
+            Ecosystem::PyPi => format!("https://pypi.org/project/{}/{}/", self.package, version),
+        }
+    }
+
+    /// Returns the command installing a version of the package.
+    pub fn install(&self, version: &str) -> String {
+        match self.ecosystem {
+            Ecosystem::Npm => format!("npm install {}@{}", self.package, version),
+            Ecosystem::PyPi => format!("pip install {}=={}", self.package, version),
+        }
+    }
+
+    /// Looks up the latest version of the package, pre-releases excluded.
+    ///
+    /// # Returns
+    /// A result containing the version, or an error if the registry cannot be read.
+    pub async fn latest_version(&self) -> Result<String> {
+        match self.ecosystem {
+            Ecosystem::Npm => {
+                let url = format!("https://registry.npmjs.org/-/package/{}/dist-tags", self.package);
+                let tags: NpmDistTags = self.http.get(&url).send().await?.error_for_status()?.json().await?;
+                tags.latest.ok_or_else(|| anyhow!("{} has no latest version on npm", self.package))
+            }
+            Ecosystem::PyPi => {
+                let url = format!("https://pypi.org/pypi/{}/json", self.package);
+                let project: PyPiProject = self.http.get(&url).send().await?.error_for_status()?.json().await?;
+                Ok(project.info.version)
+            }
+        }
+    }
+}
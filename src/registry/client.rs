@@ -0,0 +1,182 @@
+use crate::config::{env::{HttpConfig, ImageConfig, Registry}, tls};
+use reqwest::Certificate;
+use serde::Deserialize;
+use anyhow::Result;
+use tracing::info;
+
+/// Tags listed per page, the most Docker Hub allows
+const DOCKER_HUB_PAGE_SIZE: usize = 100;
+
+/// Tags listed per page of the GitHub Container Registry
+const GHCR_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct DockerHubLogin {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerHubTags {
+    results: Vec<DockerHubTag>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DockerHubTag {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryToken {
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+/// A client listing the tags of a container image, on Docker Hub or the GitHub Container Registry
+pub struct RegistryClient {
+    http: reqwest::Client,
+    registry: Registry,
+    /// Name of the image, such as `delta-io/delta-rs`
+    image: String,
+    /// Username and token to sign in with, anonymous without them
+    credentials: Option<(String, String)>,
+}
+
+impl RegistryClient {
+    /// Creates a new instance of `RegistryClient`.
+    ///
+    /// # Arguments
+    /// * `config` - The registry, the image and the username to sign in with.
+    /// * `token` - The access token paired with the username.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the client, or an error if the extra root certificates cannot be read.
+    pub fn new(config: &ImageConfig, token: Option<&str>, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        info!("Registry client initialized for {}", reference(config.registry, &config.image));
+
+        Ok(Self {
+            http: http.build()?,
+            registry: config.registry,
+            image: config.image.clone(),
+            credentials: config.username.clone().zip(token.map(str::to_owned)),
+        })
+    }
+
+    /// Returns the reference images are pulled with, such as `ghcr.io/delta-io/delta-rs`.
+    pub fn reference(&self) -> String {
+        reference(self.registry, &self.image)
+    }
+
+    /// Returns the page listing the tags of the image.
+    pub fn url(&self) -> String {
+        match self.registry {
+            Registry::DockerHub => format!("https://hub.docker.com/r/{}/tags", self.image),
+            Registry::Ghcr => format!("https://ghcr.io/{}", self.image),
+        }
+    }
+
+    /// Lists the tags of the image.
+    ///
+    /// # Returns
+    /// A result containing the tags, or an error if the registry cannot be read.
+    pub async fn list_tags(&self) -> Result<Vec<String>> {
+        match self.registry {
+            Registry::DockerHub => self.list_docker_hub_tags().await,
+            Registry::Ghcr => self.list_ghcr_tags().await,
+        }
+    }
+
+    /// Lists the tags of an image on Docker Hub, following its pages.
+    async fn list_docker_hub_tags(&self) -> Result<Vec<String>> {
+        let token = match &self.credentials {
+            Some((username, password)) => {
+                let login: DockerHubLogin = self.http
+                    .post("https://hub.docker.com/v2/users/login")
+                    .json(&serde_json::json!({ "username": username, "password": password }))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                Some(login.token)
+            }
+            None => None,
+        };
+
+        let mut tags = Vec::new();
+        let mut url = Some(format!(
+            "https://hub.docker.com/v2/repositories/{}/tags?page_size={}",
+            self.image, DOCKER_HUB_PAGE_SIZE
+        ));
+        while let Some(page_url) = url {
+            let mut request = self.http.get(&page_url);
+            if let Some(token) = &token {
+                request = request.bearer_auth(token);
+            }
+            let page: DockerHubTags = request.send().await?.error_for_status()?.json().await?;
+            tags.extend(page.results.into_iter().map(|tag| tag.name));
+            url = page.next;
+        }
+
+        Ok(tags)
+    }
+
+    /// Lists the tags of an image on the GitHub Container Registry, with a token scoped to pulling it.
+    async fn list_ghcr_tags(&self) -> Result<Vec<String>> {
+        let scope = format!("repository:{}:pull", self.image);
+        let mut request = self.http.get("https://ghcr.io/token").query(&[("scope", &scope)]);
+        if let Some((username, password)) = &self.credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        let token: RegistryToken = request.send().await?.error_for_status()?.json().await?;
+
+        // Tags are listed in lexical order, the next page starting after the last tag listed
+        let url = format!("https://ghcr.io/v2/{}/tags/list", self.image);
+        let page_size = GHCR_PAGE_SIZE.to_string();
+        let mut tags: Vec<String> = Vec::new();
+        loop {
+            let mut query = vec![("n", page_size.as_str())];
+            if let Some(last) = tags.last() {
+                query.push(("last", last.as_str()));
+            }
+            let page: TagList = self.http
+                .get(&url)
+                .query(&query)
+                .bearer_auth(&token.token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let page = page.tags.unwrap_or_default();
+            let last_page = page.len() < GHCR_PAGE_SIZE;
+            tags.extend(page);
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(tags)
+    }
+}
+
+/// Returns the reference images of a registry are pulled with
+fn reference(registry: Registry, image: &str) -> String {
+    match registry {
+        Registry::DockerHub => image.to_string(),
+        Registry::Ghcr => format!("ghcr.io/{}", image),
+    }
+}
@@ -0,0 +1,43 @@
+use regex::Regex;
+use anyhow::{Context, Result};
+
+/// Assets a release must have before it is announced
+pub struct AssetRequirement {
+    min_assets: usize,
+    patterns: Vec<Regex>,
+}
+
+impl AssetRequirement {
+    /// Creates a requirement, compiling the asset name patterns.
+    ///
+    /// # Arguments
+    /// * `min_assets` - Minimum number of assets attached to the release.
+    /// * `patterns` - Regexes that must each match the name of at least one asset.
+    ///
+    /// # Returns
+    /// A result containing the `AssetRequirement` or an error if a pattern is not a valid regex.
+    pub fn new(min_assets: usize, patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid asset pattern: {}", pattern)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            min_assets,
+            patterns,
+        })
+    }
+
+    /// Returns true if announcements have to wait for assets at all.
+    pub fn is_enabled(&self) -> bool {
+        self.min_assets > 0 || !self.patterns.is_empty()
+    }
+
+    /// Returns true if the release assets named `names` fulfill the requirement.
+    pub fn is_satisfied_by(&self, names: &[String]) -> bool {
+        names.len() >= self.min_assets
+            && self.patterns
+                .iter()
+                .all(|pattern| names.iter().any(|name| pattern.is_match(name)))
+    }
+}
@@ -0,0 +1,110 @@
+use crate::config::env::ChannelConfig;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr};
+use serde::{Deserialize, Serialize};
+use regex::Regex;
+use anyhow::{Context, Result};
+
+/// Release channel a published release belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl ReleaseChannel {
+    /// All channels, from the most to the least stable
+    pub const ALL: [ReleaseChannel; 3] = [
+        ReleaseChannel::Stable,
+        ReleaseChannel::Beta,
+        ReleaseChannel::Nightly,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseChannel::Stable => "stable",
+            ReleaseChannel::Beta => "beta",
+            ReleaseChannel::Nightly => "nightly",
+        }
+    }
+}
+
+impl Display for ReleaseChannel {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for ReleaseChannel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stable" => Ok(ReleaseChannel::Stable),
+            "beta" => Ok(ReleaseChannel::Beta),
+            "nightly" => Ok(ReleaseChannel::Nightly),
+            _ => Err(anyhow::anyhow!("Invalid release channel: {}", s)),
+        }
+    }
+}
+
+/// Assigns releases to channels using the configured tag patterns
+pub struct ChannelClassifier {
+    channels: Vec<(ChannelConfig, Option<Regex>)>,
+}
+
+impl ChannelClassifier {
+    /// Creates a classifier from the channel settings, compiling their tag patterns.
+    ///
+    /// # Arguments
+    /// * `channels` - Settings of every release channel.
+    ///
+    /// # Returns
+    /// A result containing the `ChannelClassifier` or an error if a tag pattern is not a valid regex.
+    pub fn new(channels: &[ChannelConfig]) -> Result<Self> {
+        let channels = channels
+            .iter()
+            .map(|config| {
+                let pattern = config
+                    .tag_pattern
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .with_context(|| format!("Invalid tag pattern for the {} release channel", config.channel))?;
+                Ok((config.clone(), pattern))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { channels })
+    }
+
+    /// Classifies a release by its tag and prerelease flag.
+    ///
+    /// Tag patterns are checked from the least stable channel up, so a tag matching
+    /// the nightly pattern is a nightly even if it also looks like a beta. Releases
+    /// flagged as prereleases on GitHub are at least beta.
+    pub fn classify(&self, tag: &str, prerelease: bool) -> ReleaseChannel {
+        let matched = ReleaseChannel::ALL.iter().rev().copied().find(|channel| {
+            self.channels
+                .iter()
+                .any(|(config, pattern)| config.channel == *channel && pattern.as_ref().is_some_and(|p| p.is_match(tag)))
+        });
+
+        match matched {
+            Some(channel) => channel,
+            None if prerelease => ReleaseChannel::Beta,
+            None => ReleaseChannel::Stable,
+        }
+    }
+
+    /// Returns the settings of a channel.
+    pub fn config(&self, channel: ReleaseChannel) -> Option<&ChannelConfig> {
+        self.channels
+            .iter()
+            .map(|(config, _)| config)
+            .find(|config| config.channel == channel)
+    }
+}
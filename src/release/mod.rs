@@ -0,0 +1,5 @@
+pub mod assets;
+pub mod channel;
+pub mod notes;
+pub mod opengraph;
+pub mod version;
\ No newline at end of file
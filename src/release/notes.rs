@@ -0,0 +1,71 @@
+use std::{collections::HashMap, sync::LazyLock};
+use regex::Regex;
+
+static MARKDOWN_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[([^\]]*)\]\([^)]*\)").expect("valid markdown link regex")
+});
+
+/// Extracts the first bullet points of release notes as plain text highlights.
+///
+/// # Arguments
+/// * `notes` - The markdown body of the release.
+/// * `max` - The maximum number of highlights to return.
+///
+/// # Returns
+/// The highlights, in the order they appear in the notes.
+pub fn highlights(notes: &str, max: usize) -> Vec<String> {
+    notes
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .or_else(|| line.strip_prefix("+ "))
+        })
+        .map(|item| {
+            let item = MARKDOWN_LINK.replace_all(item, "$1");
+            item.replace("**", "").replace('`', "").trim().to_string()
+        })
+        .filter(|item| !item.is_empty())
+        .take(max)
+        .collect()
+}
+
+/// Shortens `text` to at most `max_chars` characters, ending with an ellipsis when cut.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Measures how much of the release notes changed, as the percentage of their non-blank lines
+/// that were added or removed. Reordered lines and whitespace changes don't count.
+///
+/// # Arguments
+/// * `before` - The notes as previously published.
+/// * `after` - The notes as edited.
+///
+/// # Returns
+/// The changed percentage, from 0 when nothing changed to 100 when every line is new.
+pub fn changed_percent(before: &str, after: &str) -> u32 {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    let mut total = 0;
+    for line in before.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        *counts.entry(line).or_default() += 1;
+        total += 1;
+    }
+    for line in after.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        *counts.entry(line).or_default() -= 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0;
+    }
+
+    let changed: u64 = counts.values().map(|count| count.unsigned_abs()).sum();
+    (changed * 100 / total) as u32
+}
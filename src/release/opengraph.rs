@@ -0,0 +1,91 @@
+use crate::config::{env::HttpConfig, tls};
+use std::sync::LazyLock;
+use reqwest::{header::CONTENT_TYPE, Certificate};
+use regex::Regex;
+use anyhow::{Result, anyhow};
+use tracing::debug;
+
+// GitHub's social cards are well under X's 5 MB limit on images
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+static META_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<meta\s[^>]*>").expect("valid meta tag regex")
+});
+
+static META_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(property|name|content)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid meta attribute regex")
+});
+
+/// Fetches the OpenGraph image of web pages, such as the social card GitHub generates
+/// for every release.
+#[derive(Clone)]
+pub struct OpenGraphImages {
+    http: reqwest::Client,
+}
+
+impl OpenGraphImages {
+    /// Creates a new fetcher.
+    ///
+    /// # Arguments
+    /// * `http_config` - The extra root certificates to trust, and the GitHub timeouts.
+    pub fn new(http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+        Ok(Self { http: http.build()? })
+    }
+
+    /// Downloads the image a page advertises in its `og:image` meta tag.
+    ///
+    /// # Arguments
+    /// * `page_url` - The URL of the page, e.g. the `html_url` of a release.
+    ///
+    /// # Returns
+    /// A result containing the raw bytes of the image, or an error if the page has no
+    /// OpenGraph image or it cannot be downloaded.
+    pub async fn fetch(&self, page_url: &str) -> Result<Vec<u8>> {
+        let page = self.http.get(page_url).send().await?.error_for_status()?.text().await?;
+        let image_url = image_url(&page).ok_or_else(|| anyhow!("{} has no og:image meta tag", page_url))?;
+        let image_url = reqwest::Url::parse(page_url)?.join(&image_url)?;
+
+        debug!("Downloading OpenGraph image {}", image_url);
+        let response = self.http.get(image_url.clone()).send().await?.error_for_status()?;
+        let content_type = response.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !content_type.starts_with("image/") {
+            return Err(anyhow!("{} is not an image but {:?}", image_url, content_type));
+        }
+        if response.content_length().is_some_and(|length| length as usize > MAX_IMAGE_BYTES) {
+            return Err(anyhow!("{} is larger than {} bytes", image_url, MAX_IMAGE_BYTES));
+        }
+
+        let image = response.bytes().await?;
+        if image.len() > MAX_IMAGE_BYTES {
+            return Err(anyhow!("{} is larger than {} bytes", image_url, MAX_IMAGE_BYTES));
+        }
+        Ok(image.to_vec())
+    }
+}
+
+/// Finds the URL in the `og:image` meta tag of an HTML page.
+fn image_url(html: &str) -> Option<String> {
+    META_TAG.find_iter(html).find_map(|tag| {
+        let mut is_image = false;
+        let mut content = None;
+        for attribute in META_ATTRIBUTE.captures_iter(tag.as_str()) {
+            let value = attribute.get(2).or_else(|| attribute.get(3)).map_or("", |value| value.as_str());
+            match attribute[1].to_ascii_lowercase().as_str() {
+                "content" => content = Some(value),
+                _ => is_image |= value.eq_ignore_ascii_case("og:image"),
+            }
+        }
+        content
+            .filter(|content| is_image && !content.is_empty())
+            .map(|content| content.replace("&amp;", "&"))
+    })
+}
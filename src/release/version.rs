@@ -0,0 +1,56 @@
+use std::sync::LazyLock;
+use regex::Regex;
+
+// Matches the first `major.minor[.patch][-pre]` found in a tag, so prefixed tags
+// like `v1.2.3` or `python-v0.22.0` are understood as well
+static VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?(?:-([0-9A-Za-z.-]+))?").expect("valid version regex")
+});
+
+/// Semantic version parsed from a release tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifier such as `rc.1`
+    pub pre: Option<String>,
+}
+
+impl Version {
+    /// Parses the version contained in a release tag.
+    ///
+    /// # Arguments
+    /// * `tag` - The release tag, e.g. `v1.2.3` or `rust-v0.22.0`.
+    ///
+    /// # Returns
+    /// The parsed `Version`, or `None` if the tag does not contain one.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let captures = VERSION.captures(tag)?;
+        let number = |index: usize| captures.get(index).map_or(Some(0), |m| m.as_str().parse().ok());
+
+        Some(Self {
+            major: number(1)?,
+            minor: number(2)?,
+            patch: number(3)?,
+            pre: captures.get(4).map(|m| m.as_str().to_string()),
+        })
+    }
+
+    /// Returns true if this version introduces a new major version, e.g. `2.0.0`.
+    ///
+    /// # Arguments
+    /// * `zero_minor_is_major` - Treat `0.y.0` as major, as semver does for
+    ///   pre-1.0 crates where a minor bump is a breaking change.
+    pub fn is_major(&self, zero_minor_is_major: bool) -> bool {
+        if self.pre.is_some() || self.patch != 0 {
+            return false;
+        }
+
+        match (self.major, self.minor) {
+            (0, 0) => false,
+            (0, _) => zero_minor_is_major,
+            (_, minor) => minor == 0,
+        }
+    }
+}
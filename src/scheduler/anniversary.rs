@@ -0,0 +1,117 @@
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::AnniversaryConfig,
+    github::client::GitHubClient,
+    scheduler::poll::PollMonitor,
+    release::notes::truncate,
+    template::render::render};
+use std::sync::Arc;
+use tokio::{sync::OnceCell, time::Duration};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+use tracing::{debug, error, info};
+
+/// Maximum length of a tweet, in characters
+const MAX_TWEET_CHARS: usize = 280;
+
+/// Minutes between two checks of whether the anniversary is due
+const CHECK_INTERVAL_MINUTES: u64 = 30;
+
+/// Posts on the anniversary of the creation of the repository, with the releases and the
+/// contributors it gathered since.
+///
+/// The anniversary is posted once per year, on the day the repository was created from the
+/// configured hour on. Repositories created on February 29 celebrate on February 28 outside
+/// of leap years.
+pub struct Anniversary {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: AnniversaryConfig,
+    /// Creation date of the repository, read once
+    created_at: OnceCell<DateTime<Utc>>,
+}
+
+impl Anniversary {
+    /// Creates a new instance of `Anniversary`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to read the creation date and gather the counts.
+    /// * `audit_log` - The audit log recording which anniversaries were posted.
+    /// * `config` - The anniversary settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: AnniversaryConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+            created_at: OnceCell::new(),
+        }
+    }
+
+    /// Checks whether the anniversary is due periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!("Repository anniversary enabled, posting from {}:00 UTC", self.config.hour);
+
+        let mut poller = polls.poller("anniversary", Duration::from_secs(CHECK_INTERVAL_MINUTES * 60));
+        loop {
+            poller.wait().await;
+            let result = self.post_anniversary().await;
+            if let Err(e) = &result {
+                error!("Failed to post the repository anniversary: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Posts the anniversary if it is due and wasn't posted yet this year.
+    async fn post_anniversary(&self) -> Result<()> {
+        let created_at = *self.created_at
+            .get_or_try_init(|| self.github_client.created_at())
+            .await?;
+        let now = Utc::now();
+        let years = now.year() - created_at.year();
+        if years < 1 || now.date_naive() != anniversary_date(created_at, now.year()) || now.hour() < self.config.hour {
+            return Ok(());
+        }
+
+        let repo = self.github_client.repo().await.full_name();
+        let source_id = now.year().to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::Anniversary, &repo, &source_id).await else {
+            debug!("Anniversary of {} already handled", source_id);
+            return Ok(());
+        };
+
+        let releases = self.github_client.count_releases_between(created_at, now).await?;
+        let contributors = self.github_client.count_contributors().await?;
+
+        let locale = self.config.locale;
+        let tweet = render(&self.config.template, &[
+            ("years", &years.to_string()),
+            ("since", &created_at.year().to_string()),
+            ("releases", &locale.format_count(releases)),
+            ("contributors", &locale.format_count(contributors)),
+            ("repo", &repo),
+        ]);
+        let tweet = truncate(tweet.trim_end(), MAX_TWEET_CHARS);
+
+        info!("Posting repository anniversary: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::Anniversary, AuditStatus::Queued, &repo, &source_id, &tweet)
+                .with_subject(&source_id)
+        ).await;
+
+        Ok(())
+    }
+}
+
+/// Returns the day of `year` the anniversary of a creation date falls on.
+fn anniversary_date(created_at: DateTime<Utc>, year: i32) -> NaiveDate {
+    let created = created_at.date_naive();
+    created
+        .with_year(year)
+        .or_else(|| NaiveDate::from_ymd_opt(year, created.month(), created.day() - 1))
+        .unwrap_or(created)
+}
@@ -0,0 +1,124 @@
+use super::{
+    poll::{PollHealth, PollMonitor},
+    retry_budget::{RetryBudget, RetryBudgetSnapshot}};
+use crate::x::client::XClient;
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use tokio::time::{interval, Duration, MissedTickBehavior};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use anyhow::{Context, Result};
+use tracing::{debug, warn};
+
+/// How often the backoff state is saved while the bot runs
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Backoff state, as persisted between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BackoffState {
+    #[serde(default)]
+    retry_budget: Option<RetryBudgetSnapshot>,
+    /// Health of the failing polls, keyed by name
+    #[serde(default)]
+    polls: BTreeMap<String, PollHealth>,
+    /// Until when posts skip the v2 tweets endpoint
+    #[serde(default)]
+    x_v2_down_until: Option<DateTime<Utc>>,
+}
+
+/// Persists the state of the retry budget, of the failing polls and of the fallback from the
+/// v2 tweets endpoint, so a restart during an outage keeps backing off where it was instead
+/// of retrying everything at once. The rate limit window of X is restored from the audit log.
+pub struct BackoffStore {
+    path: PathBuf,
+    retry_budget: Arc<RetryBudget>,
+    polls: Arc<PollMonitor>,
+    x_client: Arc<XClient>,
+}
+
+impl BackoffStore {
+    /// Creates a new instance of `BackoffStore`.
+    ///
+    /// # Arguments
+    /// * `path` - The file the state is persisted in.
+    /// * `retry_budget` - The budget shared by the retries against GitHub and X.
+    /// * `polls` - The monitor of the scheduled polls.
+    /// * `x_client` - The client falling back from the v2 tweets endpoint.
+    pub fn new(path: PathBuf, retry_budget: Arc<RetryBudget>, polls: Arc<PollMonitor>, x_client: Arc<XClient>) -> Self {
+        Self {
+            path,
+            retry_budget,
+            polls,
+            x_client,
+        }
+    }
+
+    /// Restores the state saved before a restart, before the polls are started.
+    pub async fn restore(&self) {
+        let state = match self.load().await {
+            Ok(Some(state)) => state,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to restore the backoff state, starting afresh: {:?}", e);
+                return;
+            }
+        };
+
+        if let Some(snapshot) = &state.retry_budget {
+            self.retry_budget.restore(snapshot);
+        }
+        self.polls.restore(state.polls);
+        if let Some(until) = state.x_v2_down_until {
+            self.x_client.restore_v2_down_until(until);
+        }
+        debug!("Restored the backoff state from {}", self.path.display());
+    }
+
+    /// Saves the state periodically, forever.
+    pub async fn run(self: Arc<Self>) {
+        let mut ticks = interval(SAVE_INTERVAL);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            if let Err(e) = self.save().await {
+                warn!("Failed to save the backoff state: {:?}", e);
+            }
+        }
+    }
+
+    /// Saves the current state, such as when the bot shuts down.
+    pub async fn save(&self) -> Result<()> {
+        let state = BackoffState {
+            retry_budget: Some(self.retry_budget.snapshot()),
+            polls: self.polls.health().into_iter().filter(|(_, health)| health.failure_streak > 0).collect(),
+            x_v2_down_until: self.x_client.v2_down_until(),
+        };
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let temporary = self.path.with_extension("tmp");
+        tokio::fs::write(&temporary, serde_json::to_string(&state)?)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Loads the saved state, `None` before the first run.
+    async fn load(&self) -> Result<Option<BackoffState>> {
+        let contents = match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        };
+        match serde_json::from_str(&contents) {
+            Ok(state) => Ok(Some(state)),
+            Err(e) => {
+                warn!("Ignoring malformed {}, starting afresh: {}", self.path.display(), e);
+                Ok(None)
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use super::{poll::PollMonitor, threshold::highest_reached};
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::ContributorMilestoneConfig,
+    github::client::GitHubClient,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Announces the number of unique contributors crossing milestones, such as "500 contributors".
+///
+/// Each milestone is announced at most once, even across restarts or when the count drops
+/// below it and crosses it again. The first time the contributors are counted, the milestone
+/// already reached is only recorded, so enabling the watcher doesn't celebrate a milestone
+/// that was passed long ago. Milestones crossed together between two counts are announced
+/// as the highest of them.
+pub struct ContributorMilestones {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: ContributorMilestoneConfig,
+}
+
+impl ContributorMilestones {
+    /// Creates a new instance of `ContributorMilestones`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to count the contributors.
+    /// * `audit_log` - The audit log recording which milestones were announced.
+    /// * `config` - The contributor milestone settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: ContributorMilestoneConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Counts the contributors periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing contributor milestones {:?}, checking every {} minutes",
+            self.config.milestones, self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("contributor_milestones", Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check contributor milestones: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the highest milestone reached, unless it was already handled.
+    async fn check(&self) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let contributors = self.github_client.count_known_contributors().await?;
+        let threshold = highest_reached(contributors, &self.config.milestones).unwrap_or_default();
+        let source_id = threshold.to_string();
+
+        // The baseline is recorded even before the first milestone is reached, so that
+        // reaching it later is announced
+        if !self.audit_log.has_any(AnnouncementKind::ContributorMilestone, &repo).await {
+            info!("First count of contributors, recording {} without announcing it", contributors);
+            let entry = AuditEntry::new(AnnouncementKind::ContributorMilestone, AuditStatus::Skipped, &repo, &source_id, "");
+            self.record(entry).await;
+            return Ok(());
+        }
+        if threshold == 0 {
+            debug!("{} contributors, no milestone reached yet", contributors);
+            return Ok(());
+        }
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::ContributorMilestone, &repo, &source_id).await else {
+            debug!("Milestone of {} contributors already handled", threshold);
+            return Ok(());
+        };
+
+        let count = self.config.locale.format_count(threshold);
+        let tweet = render(&self.config.template, &[
+            ("count", &count),
+            ("repo", &repo),
+        ]);
+
+        info!("Posting contributor milestone: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::ContributorMilestone, AuditStatus::Queued, &repo, &source_id, &tweet)
+                .with_subject(&count)
+        ).await;
+
+        Ok(())
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::DigestConfig,
+    github::{client::GitHubClient, traffic::{format_change, WeeklyTraffic}},
+    scheduler::poll::PollMonitor,
+    release::notes::truncate,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration as TokioDuration;
+use anyhow::Result;
+use chrono::{Datelike, Duration, Timelike, Utc};
+use octocrab::models::pulls::PullRequest;
+use tracing::{debug, error, info, warn};
+
+/// Maximum length of a tweet, in characters
+const MAX_TWEET_CHARS: usize = 280;
+
+/// Minutes between two checks of whether the digest is due
+const CHECK_INTERVAL_MINUTES: u64 = 30;
+
+/// Posts a weekly summary of the pull requests merged in the repository.
+///
+/// The digest is posted once per ISO week, on the configured day from the configured
+/// hour on, and lists the titles of the most notable pull requests by label.
+pub struct WeeklyDigest {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: DigestConfig,
+}
+
+impl WeeklyDigest {
+    /// Creates a new instance of `WeeklyDigest`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to list the merged pull requests.
+    /// * `audit_log` - The audit log recording which weeks were summarized.
+    /// * `config` - The digest settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: DigestConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Checks whether the digest is due periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Weekly digest enabled, posting on {} from {}:00 UTC",
+            self.config.weekday, self.config.hour
+        );
+
+        let mut poller = polls.poller("weekly_digest", TokioDuration::from_secs(CHECK_INTERVAL_MINUTES * 60));
+        loop {
+            poller.wait().await;
+            let result = self.post_digest().await;
+            if let Err(e) = &result {
+                error!("Failed to post the weekly digest: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Posts the digest of the past week if it is due and wasn't posted yet.
+    async fn post_digest(&self) -> Result<()> {
+        let now = Utc::now();
+        if now.weekday() != self.config.weekday || now.hour() < self.config.hour {
+            return Ok(());
+        }
+
+        let repo = self.github_client.repo().await.full_name();
+        let week = now.format("%G-W%V").to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::WeeklyDigest, &repo, &week).await else {
+            debug!("Weekly digest for {} already handled", week);
+            return Ok(());
+        };
+
+        let merged = self.github_client.list_merged_pulls(now - Duration::days(7)).await?;
+        if merged.is_empty() {
+            info!("No pull requests merged this week, skipping the weekly digest");
+            let entry = AuditEntry::new(AnnouncementKind::WeeklyDigest, AuditStatus::Skipped, &repo, &week, "");
+            self.record(entry).await;
+            return Ok(());
+        }
+
+        let traffic = self.traffic().await;
+        let tweet = self.digest_tweet(&merged, traffic.as_ref(), &repo, &week);
+        info!("Posting weekly digest: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::WeeklyDigest, AuditStatus::Queued, &repo, &week, &tweet)
+                .with_subject(&week)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Reads the traffic of the past week when enabled. The digest is posted without it when
+    /// it can't be read, e.g. because the token lacks push access.
+    async fn traffic(&self) -> Option<WeeklyTraffic> {
+        if !self.config.traffic {
+            return None;
+        }
+        match self.github_client.weekly_traffic().await {
+            Ok(traffic) => Some(traffic),
+            Err(e) => {
+                warn!("Failed to read the repository traffic, posting the digest without it: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Formats the digest, listing as many notable pull requests as fit in a tweet.
+    fn digest_tweet(&self, merged: &[PullRequest], traffic: Option<&WeeklyTraffic>, repo: &str, week: &str) -> String {
+        let locale = self.config.locale;
+        let count = locale.format_count(merged.len() as u64);
+        let date = locale.format_date(Utc::now());
+        // The traffic placeholders are left empty without traffic, rather than claiming no views
+        let views = traffic.map(|traffic| locale.format_count(traffic.views)).unwrap_or_default();
+        let views_change = traffic.map(|traffic| format_change(traffic.views, traffic.previous_views)).unwrap_or_default();
+        let clones = traffic.map(|traffic| locale.format_count(traffic.clones)).unwrap_or_default();
+        let clones_change = traffic.map(|traffic| format_change(traffic.clones, traffic.previous_clones)).unwrap_or_default();
+        let top_referrer = traffic.and_then(|traffic| traffic.top_referrer.clone()).unwrap_or_default();
+        let mut notable = self.notable_titles(merged);
+
+        loop {
+            let lines: Vec<String> = notable.iter().map(|title| format!("• {}", title)).collect();
+            let tweet = render(&self.config.template, &[
+                ("count", &count),
+                ("notable", &lines.join("\n")),
+                ("week", week),
+                ("date", &date),
+                ("repo", repo),
+                ("views", &views),
+                ("views_change", &views_change),
+                ("clones", &clones),
+                ("clones_change", &clones_change),
+                ("top_referrer", &top_referrer),
+            ]);
+            if tweet.chars().count() <= MAX_TWEET_CHARS || notable.is_empty() {
+                return truncate(tweet.trim_end(), MAX_TWEET_CHARS);
+            }
+            notable.pop();
+        }
+    }
+
+    /// Picks the titles of the notable pull requests, in the order of the notable labels.
+    fn notable_titles(&self, merged: &[PullRequest]) -> Vec<String> {
+        let mut titles: Vec<String> = Vec::new();
+        for label in &self.config.notable_labels {
+            for pull in merged {
+                let labelled = pull.labels
+                    .iter()
+                    .flatten()
+                    .any(|pull_label| pull_label.name.eq_ignore_ascii_case(label));
+                let Some(title) = pull.title.as_ref().filter(|_| labelled) else {
+                    continue;
+                };
+                if titles.len() < self.config.max_notable && !titles.contains(title) {
+                    titles.push(title.clone());
+                }
+            }
+        }
+        titles
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::{poll::PollMonitor, threshold::highest_reached};
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::DownloadMilestoneConfig,
+    github::{client::GitHubClient, downloads::ReleaseDownloads},
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Announces the downloads of the assets of a release crossing milestones, such as
+/// "v1.0.0 downloaded 10,000 times".
+///
+/// Each milestone of each release is announced at most once, even across restarts. The first
+/// time the downloads are counted, the milestones already reached by every release are only
+/// recorded, so enabling the watcher doesn't celebrate milestones that were passed long ago.
+/// Milestones crossed together between two counts are announced as the highest of them.
+pub struct DownloadMilestones {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: DownloadMilestoneConfig,
+}
+
+impl DownloadMilestones {
+    /// Creates a new instance of `DownloadMilestones`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to count the downloads.
+    /// * `audit_log` - The audit log recording which milestones were announced.
+    /// * `config` - The download milestone settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: DownloadMilestoneConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Counts the downloads periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing release download milestones {:?}, checking every {} minutes",
+            self.config.milestones, self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("download_milestones", Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check download milestones: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the highest milestone reached by each release, unless it was already handled.
+    async fn check(&self) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let releases = self.github_client.list_release_downloads().await?;
+
+        if !self.audit_log.has_any(AnnouncementKind::DownloadMilestone, &repo).await {
+            info!("First count of release downloads, recording the milestones reached without announcing them");
+            for release in &releases {
+                let threshold = highest_reached(release.downloads, &self.config.milestones).unwrap_or_default();
+                let source_id = milestone_id(release, threshold);
+                let entry = AuditEntry::new(AnnouncementKind::DownloadMilestone, AuditStatus::Skipped, &repo, &source_id, "");
+                self.record(entry).await;
+            }
+            return Ok(());
+        }
+
+        for release in &releases {
+            let Some(threshold) = highest_reached(release.downloads, &self.config.milestones) else {
+                continue;
+            };
+            self.announce(&repo, release, threshold).await;
+        }
+
+        Ok(())
+    }
+
+    /// Announces a milestone of a release, unless it was already handled.
+    async fn announce(&self, repo: &str, release: &ReleaseDownloads, threshold: u64) {
+        let source_id = milestone_id(release, threshold);
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::DownloadMilestone, repo, &source_id).await else {
+            debug!("Milestone of {} downloads of {} already handled", threshold, release.tag);
+            return;
+        };
+
+        let count = self.config.locale.format_count(threshold);
+        let tweet = render(&self.config.template, &[
+            ("tag", &release.tag),
+            ("count", &count),
+            ("repo", repo),
+            ("url", &release.url),
+        ]);
+
+        info!("Posting download milestone of {}: {}", release.tag, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::DownloadMilestone, AuditStatus::Queued, repo, &source_id, &tweet)
+                .with_subject(&release.tag)
+        ).await;
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
+
+/// Identifies a milestone of a release in the audit log, e.g. `123456:10000`
+fn milestone_id(release: &ReleaseDownloads, threshold: u64) -> String {
+    format!("{}:{}", release.id, threshold)
+}
@@ -0,0 +1,173 @@
+use super::poll::PollMonitor;
+use crate::{
+    audit::log::{AuditEntry, AuditLog},
+    config::env::EmailDigestConfig,
+    email::smtp::{Email, SmtpClient},
+    template::render::render};
+use std::{path::PathBuf, sync::Arc};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use tokio::time::Duration as TokioDuration;
+use anyhow::{Context, Result};
+use tracing::{debug, error, info, warn};
+
+/// Minutes between two checks of whether the digest is due
+const CHECK_INTERVAL_MINUTES: u64 = 15;
+
+/// The last digest sent, as persisted between runs
+#[derive(Debug, Serialize, Deserialize)]
+struct DigestState {
+    /// The digest covers the announcements made after this time
+    last_sent: DateTime<Utc>,
+}
+
+/// Emails a digest of the announcements made since the previous one to a mailing list.
+///
+/// The digest is sent daily, or weekly on the configured day, from the configured hour on.
+/// When the last digest was sent is persisted, so a restart neither skips nor repeats
+/// announcements. The first run only starts the period, nothing made before is sent.
+pub struct EmailDigest {
+    smtp_client: SmtpClient,
+    audit_log: Arc<AuditLog>,
+    config: EmailDigestConfig,
+    state_path: PathBuf,
+}
+
+impl EmailDigest {
+    /// Creates a new instance of `EmailDigest`.
+    ///
+    /// # Arguments
+    /// * `smtp_client` - The client sending the digest.
+    /// * `audit_log` - The audit log the announcements are read from.
+    /// * `config` - The digest settings.
+    /// * `state_path` - The file when the last digest was sent is persisted in.
+    pub fn new(smtp_client: SmtpClient, audit_log: Arc<AuditLog>, config: EmailDigestConfig, state_path: PathBuf) -> Self {
+        Self {
+            smtp_client,
+            audit_log,
+            config,
+            state_path,
+        }
+    }
+
+    /// Checks whether the digest is due periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        match self.config.weekday {
+            Some(weekday) => info!("Email digest enabled, sending on {} from {}:00 UTC", weekday, self.config.hour),
+            None => info!("Email digest enabled, sending daily from {}:00 UTC", self.config.hour),
+        }
+
+        let mut poller = polls.poller("email_digest", TokioDuration::from_secs(CHECK_INTERVAL_MINUTES * 60));
+        loop {
+            poller.wait().await;
+            let result = self.send_digest().await;
+            if let Err(e) = &result {
+                error!("Failed to send the email digest: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Sends the digest of the announcements made since the last one, if it is due.
+    async fn send_digest(&self) -> Result<()> {
+        let now = Utc::now();
+        let Some(state) = self.load().await? else {
+            info!("First run of the email digest, starting its period now");
+            return self.save(&DigestState { last_sent: now }).await;
+        };
+
+        // Sent at most once a day, however long the hour lasts
+        let due = now.hour() >= self.config.hour
+            && self.config.weekday.is_none_or(|weekday| now.weekday() == weekday)
+            && now - state.last_sent > Duration::hours(12);
+        if !due {
+            return Ok(());
+        }
+
+        // Oldest first, the order they were made in
+        let mut announcements: Vec<AuditEntry> = self.audit_log.announcements(None, None).await
+            .into_iter()
+            .filter(|entry| entry.timestamp > state.last_sent && !entry.text.is_empty())
+            .collect();
+        announcements.reverse();
+        if announcements.is_empty() {
+            debug!("No announcements since {}, not sending the email digest", state.last_sent);
+            return self.save(&DigestState { last_sent: now }).await;
+        }
+
+        let email = self.email(&announcements, state.last_sent);
+        let message_id = self.smtp_client.send(&email).await?;
+        info!("Sent the email digest of {} announcements as {}", announcements.len(), message_id);
+        self.save(&DigestState { last_sent: now }).await
+    }
+
+    /// Renders the digest with the templates of the settings.
+    fn email(&self, announcements: &[AuditEntry], since: DateTime<Utc>) -> Email {
+        let count = announcements.len().to_string();
+        let since = since.format("%Y-%m-%d").to_string();
+
+        let text_list: Vec<String> = announcements
+            .iter()
+            .map(|entry| format!("- {}", entry.text.replace('\n', "\n  ")))
+            .collect();
+        let html_list: Vec<String> = announcements
+            .iter()
+            .map(|entry| format!("<li>{}</li>", escape(&entry.text).replace('\n', "<br>")))
+            .collect();
+        let html_list = format!("<ul>\n{}\n</ul>", html_list.join("\n"));
+
+        Email {
+            subject: render(&self.config.subject, &[("count", &count), ("since", &since)]),
+            text: render(&self.config.text_template, &[
+                ("count", &count),
+                ("since", &since),
+                ("announcements", &text_list.join("\n\n")),
+            ]),
+            html: render(&self.config.html_template, &[
+                ("count", &count),
+                ("since", &since),
+                ("announcements", &html_list),
+            ]),
+        }
+    }
+
+    /// Loads when the last digest was sent, `None` before the first run.
+    async fn load(&self) -> Result<Option<DigestState>> {
+        let contents = match tokio::fs::read_to_string(&self.state_path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", self.state_path.display())),
+        };
+        match serde_json::from_str(&contents) {
+            Ok(state) => Ok(Some(state)),
+            Err(e) => {
+                warn!("Ignoring malformed {}, starting the email digest period over: {}", self.state_path.display(), e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Persists when the last digest was sent, replacing the previous file atomically.
+    async fn save(&self, state: &DigestState) -> Result<()> {
+        if let Some(parent) = self.state_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let temporary = self.state_path.with_extension("tmp");
+        tokio::fs::write(&temporary, serde_json::to_string(state)?)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.state_path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.state_path.display()))?;
+        Ok(())
+    }
+}
+
+/// Escapes the characters with a meaning in HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,142 @@
+use super::poll::PollMonitor;
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::{env::{FeedConfig, HttpConfig}, tls},
+    feed::parse::{entries, feed_title, FeedEntry},
+    release::notes::truncate,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use reqwest::Certificate;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Maximum length of a tweet, in characters
+const MAX_TWEET_CHARS: usize = 280;
+
+/// Maximum length of the `{summary}` placeholder, in characters
+const MAX_SUMMARY_CHARS: usize = 140;
+
+/// Announces the new entries of RSS and Atom feeds, such as the posts of a blog.
+///
+/// Feeds don't count against the GitHub API quota, so the Atom feed of the releases of a
+/// repository can stand in for the release webhooks. Each entry is announced at most once,
+/// even across restarts. The first time a feed is fetched, its entries are only recorded,
+/// so adding a feed doesn't announce its whole history.
+pub struct FeedWatcher {
+    http: reqwest::Client,
+    audit_log: Arc<AuditLog>,
+    config: FeedConfig,
+}
+
+impl FeedWatcher {
+    /// Creates a new instance of `FeedWatcher`.
+    ///
+    /// # Arguments
+    /// * `audit_log` - The audit log recording which entries were announced.
+    /// * `config` - The feed settings.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the watcher, or an error if the extra root certificates cannot be read.
+    pub fn new(
+        audit_log: Arc<AuditLog>,
+        config: FeedConfig,
+        http_config: &HttpConfig,
+    ) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        Ok(Self {
+            http: http.build()?,
+            audit_log,
+            config,
+        })
+    }
+
+    /// Fetches the feeds periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing the new entries of {} feeds, checking every {} minutes",
+            self.config.urls.len(), self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("feeds", Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check the feeds: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Fetches every feed, failing if one of them can't be read once all were tried.
+    async fn check(&self) -> Result<()> {
+        let mut result = Ok(());
+        for url in &self.config.urls {
+            if let Err(e) = self.check_feed(url).await {
+                error!("Failed to check feed {}: {:?}", url, e);
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    /// Announces the entries of a feed that weren't handled yet, oldest first.
+    async fn check_feed(&self, url: &str) -> Result<()> {
+        let xml = self.http.get(url).send().await?.error_for_status()?.text().await?;
+        let feed = feed_title(&xml).unwrap_or_else(|| url.to_string());
+        let entries = entries(&xml);
+
+        if !self.audit_log.has_any(AnnouncementKind::FeedEntry, url).await {
+            info!("First fetch of feed {}, recording {} entries without announcing them", url, entries.len());
+            for entry in &entries {
+                let record = AuditEntry::new(AnnouncementKind::FeedEntry, AuditStatus::Skipped, url, &entry.id, "");
+                self.record(record).await;
+            }
+            return Ok(());
+        }
+
+        // Feeds list their latest entries first
+        for entry in entries.iter().rev() {
+            self.announce(url, &feed, entry).await;
+        }
+
+        Ok(())
+    }
+
+    /// Announces an entry of a feed, unless it was already handled.
+    async fn announce(&self, url: &str, feed: &str, entry: &FeedEntry) {
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::FeedEntry, url, &entry.id).await else {
+            debug!("Entry {} of {} already handled", entry.id, url);
+            return;
+        };
+
+        let summary = truncate(&entry.summary, MAX_SUMMARY_CHARS);
+        let tweet = render(&self.config.template, &[
+            ("title", &entry.title),
+            ("url", &entry.url),
+            ("summary", &summary),
+            ("feed", feed),
+        ]);
+        let tweet = truncate(tweet.trim_end(), MAX_TWEET_CHARS);
+
+        info!("Posting new entry of {}: {}", feed, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::FeedEntry, AuditStatus::Queued, url, &entry.id, &tweet)
+                .with_subject(&entry.title)
+        ).await;
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::poll::PollMonitor;
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::ImageConfig,
+    registry::client::RegistryClient,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use regex::Regex;
+use tracing::{debug, error, info};
+
+/// Announces the new tags of a container image, such as "The delta-io/delta-rs:1.0.0 image is out!".
+///
+/// Each tag is announced at most once, even across restarts. The first time the tags are
+/// listed, they are only recorded, so enabling the watcher doesn't announce every image
+/// ever published.
+pub struct ImageWatcher {
+    registry_client: RegistryClient,
+    audit_log: Arc<AuditLog>,
+    config: ImageConfig,
+    tag_pattern: Regex,
+}
+
+impl ImageWatcher {
+    /// Creates a new instance of `ImageWatcher`.
+    ///
+    /// # Arguments
+    /// * `registry_client` - The client used to list the tags of the image.
+    /// * `audit_log` - The audit log recording which tags were announced.
+    /// * `config` - The container image settings.
+    ///
+    /// # Returns
+    /// A result containing the watcher, or an error if the tag pattern is invalid.
+    pub fn new(
+        registry_client: RegistryClient,
+        audit_log: Arc<AuditLog>,
+        config: ImageConfig,
+    ) -> Result<Self> {
+        let tag_pattern = Regex::new(&config.tag_pattern)?;
+        Ok(Self {
+            registry_client,
+            audit_log,
+            config,
+            tag_pattern,
+        })
+    }
+
+    /// Lists the tags periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing new tags of {}, checking every {} minutes",
+            self.registry_client.reference(), self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("container_images", Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check the tags of {}: {:?}", self.registry_client.reference(), e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the tags matching the pattern that weren't handled yet.
+    async fn check(&self) -> Result<()> {
+        let image = self.registry_client.reference();
+        let tags: Vec<String> = self.registry_client
+            .list_tags()
+            .await?
+            .into_iter()
+            .filter(|tag| self.tag_pattern.is_match(tag))
+            .collect();
+
+        if !self.audit_log.has_any(AnnouncementKind::ContainerImage, &image).await {
+            info!("First listing of the tags of {}, recording {} tags without announcing them", image, tags.len());
+            for tag in &tags {
+                let entry = AuditEntry::new(AnnouncementKind::ContainerImage, AuditStatus::Skipped, &image, tag, "");
+                self.record(entry).await;
+            }
+            return Ok(());
+        }
+
+        for tag in &tags {
+            self.announce(&image, tag).await;
+        }
+
+        Ok(())
+    }
+
+    /// Announces a tag of the image, unless it was already handled.
+    async fn announce(&self, image: &str, tag: &str) {
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::ContainerImage, image, tag).await else {
+            debug!("Tag {} of {} already handled", tag, image);
+            return;
+        };
+
+        let pull = format!("docker pull {}:{}", image, tag);
+        let tweet = render(&self.config.template, &[
+            ("image", image),
+            ("tag", tag),
+            ("pull", &pull),
+            ("url", &self.registry_client.url()),
+        ]);
+
+        info!("Posting new image {}:{}: {}", image, tag, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::ContainerImage, AuditStatus::Queued, image, tag, &tweet)
+                .with_subject(tag)
+        ).await;
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{poll::PollMonitor, threshold::reached};
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::IssueMilestoneConfig,
+    github::client::GitHubClient,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Announces round numbers of closed issues, such as "1,000 issues closed".
+///
+/// Each threshold is announced at most once, even across restarts. The first time the
+/// issues are counted, the threshold already reached is only recorded, so enabling the
+/// watcher doesn't celebrate a milestone that was passed long ago.
+pub struct IssueMilestones {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: IssueMilestoneConfig,
+}
+
+impl IssueMilestones {
+    /// Creates a new instance of `IssueMilestones`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to count the closed issues.
+    /// * `audit_log` - The audit log recording which thresholds were announced.
+    /// * `config` - The issue milestone settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: IssueMilestoneConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Counts the closed issues periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing every {} closed issues, checking every {} minutes",
+            self.config.every, self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("issue_milestones", Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check closed issue milestones: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the highest threshold reached, unless it was already handled.
+    async fn check(&self) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let closed = self.github_client.count_closed_issues().await?;
+        let threshold = reached(closed, self.config.every).unwrap_or_default();
+        let source_id = threshold.to_string();
+
+        // The baseline is recorded even before the first threshold is reached, so that
+        // reaching it later is announced
+        if !self.audit_log.has_any(AnnouncementKind::IssueMilestone, &repo).await {
+            info!("First count of closed issues, recording {} without announcing it", closed);
+            let entry = AuditEntry::new(AnnouncementKind::IssueMilestone, AuditStatus::Skipped, &repo, &source_id, "");
+            self.record(entry).await;
+            return Ok(());
+        }
+        if threshold == 0 {
+            debug!("{} closed issues, no milestone reached yet", closed);
+            return Ok(());
+        }
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::IssueMilestone, &repo, &source_id).await else {
+            debug!("Milestone of {} closed issues already handled", threshold);
+            return Ok(());
+        };
+
+        let count = self.config.locale.format_count(threshold);
+        let tweet = render(&self.config.template, &[
+            ("count", &count),
+            ("repo", &repo),
+        ]);
+
+        info!("Posting closed issue milestone: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::IssueMilestone, AuditStatus::Queued, &repo, &source_id, &tweet)
+                .with_subject(&count)
+        ).await;
+
+        Ok(())
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
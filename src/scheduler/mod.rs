@@ -0,0 +1,23 @@
+pub mod anniversary;
+pub mod archive;
+pub mod backoff_state;
+pub mod contributor_milestones;
+pub mod digest;
+pub mod download_milestones;
+pub mod email_digest;
+pub mod feeds;
+pub mod images;
+pub mod issues;
+pub mod monthly_stats;
+pub mod organization;
+pub mod packages;
+pub mod poll;
+pub mod project;
+pub mod request_budget;
+pub mod retry_budget;
+pub mod runtime;
+pub mod star_milestones;
+pub mod stargazers;
+pub mod supervisor;
+pub mod teaser;
+pub mod threshold;
\ No newline at end of file
@@ -0,0 +1,117 @@
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::MonthlyStatsConfig,
+    github::client::GitHubClient,
+    scheduler::poll::PollMonitor,
+    release::notes::truncate,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Datelike, Days, TimeZone, Timelike, Utc};
+use tracing::{debug, error, info};
+
+/// Maximum length of a tweet, in characters
+const MAX_TWEET_CHARS: usize = 280;
+
+/// Minutes between two checks of whether the stats are due
+const CHECK_INTERVAL_MINUTES: u64 = 30;
+
+/// Posts the stats of the past month: pull requests merged, releases published, and the
+/// contributors of the repository so far.
+///
+/// The stats are posted once per month, on the configured day from the configured hour on.
+pub struct MonthlyStats {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: MonthlyStatsConfig,
+}
+
+impl MonthlyStats {
+    /// Creates a new instance of `MonthlyStats`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to gather the stats.
+    /// * `audit_log` - The audit log recording which months were posted.
+    /// * `config` - The monthly stats settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: MonthlyStatsConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Checks whether the stats are due periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Monthly stats enabled, posting on day {} from {}:00 UTC",
+            self.config.day, self.config.hour
+        );
+
+        let mut poller = polls.poller("monthly_stats", Duration::from_secs(CHECK_INTERVAL_MINUTES * 60));
+        loop {
+            poller.wait().await;
+            let result = self.post_stats().await;
+            if let Err(e) = &result {
+                error!("Failed to post the monthly stats: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Posts the stats of the past month if they are due and weren't posted yet.
+    async fn post_stats(&self) -> Result<()> {
+        let now = Utc::now();
+        if now.day() != self.config.day || now.hour() < self.config.hour {
+            return Ok(());
+        }
+
+        // The past month runs from its first day to the first day of this month
+        let until = Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow!("Invalid start of month for {}", now))?;
+        let last_day = until - Days::new(1);
+        let since = Utc.with_ymd_and_hms(last_day.year(), last_day.month(), 1, 0, 0, 0)
+            .single()
+            .ok_or_else(|| anyhow!("Invalid start of month for {}", last_day))?;
+
+        let repo = self.github_client.repo().await.full_name();
+        let month = since.format("%Y-%m").to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::MonthlyStats, &repo, &month).await else {
+            debug!("Monthly stats for {} already handled", month);
+            return Ok(());
+        };
+
+        let pulls = self.github_client.count_merged_pulls_between(since.date_naive(), last_day.date_naive()).await?;
+        let releases = self.github_client.count_releases_between(since, until).await?;
+        let contributors = self.github_client.count_contributors().await?;
+
+        let tweet = self.stats_tweet(since, pulls, releases, contributors, &repo);
+        info!("Posting monthly stats: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::MonthlyStats, AuditStatus::Queued, &repo, &month, &tweet)
+                .with_subject(&month)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Formats the stats of the month starting at `since`.
+    fn stats_tweet(&self, since: DateTime<Utc>, pulls: u64, releases: u64, contributors: u64, repo: &str) -> String {
+        let locale = self.config.locale;
+        let tweet = render(&self.config.template, &[
+            ("month", &locale.format_month(since)),
+            ("year", &since.year().to_string()),
+            ("pulls", &locale.format_count(pulls)),
+            ("releases", &locale.format_count(releases)),
+            ("contributors", &locale.format_count(contributors)),
+            ("repo", repo),
+        ]);
+        truncate(tweet.trim_end(), MAX_TWEET_CHARS)
+    }
+}
@@ -0,0 +1,128 @@
+use super::poll::PollMonitor;
+use crate::{
+    config::env::{BranchConfig, OrganizationConfig},
+    github::{client::{GitHubClient, OrganizationRepository}, repository::RepoName},
+    webhook::handler::AppState};
+use std::{path::{Path, PathBuf}, sync::Arc};
+use tokio::time::Duration;
+use regex::Regex;
+use anyhow::{Context, Result};
+use tracing::{debug, error, info, warn};
+
+/// Announces the releases and contributors of every public repository of an organization,
+/// whose events come from its organization webhook.
+///
+/// The repositories are looked up periodically, so the ones created or made public later are
+/// announced without a restart. Each of them gets a handler of its own, tracking its
+/// contributors in a directory of its own, while the repositories left out by the patterns
+/// are ignored instead of being announced as the main repository.
+pub struct OrganizationWatcher {
+    /// Client of the main repository, listing the repositories of the organization
+    github_client: Arc<GitHubClient>,
+    state: Arc<AppState>,
+    /// Monitored branches of the repositories found
+    branches: Vec<BranchConfig>,
+    /// Directory where the contributors of the repositories found are persisted
+    state_dir: PathBuf,
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+    config: OrganizationConfig,
+}
+
+impl OrganizationWatcher {
+    /// Creates a new instance of `OrganizationWatcher`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to list the repositories of the organization.
+    /// * `state` - The state of the webhook route, the repositories found are added to.
+    /// * `branches` - The monitored branches of the repositories found.
+    /// * `state_dir` - The directory where the contributors are persisted, under `owner/name` for each repository.
+    /// * `config` - The organization and the patterns of the repositories announced.
+    ///
+    /// # Returns
+    /// A result containing the watcher, or an error if a pattern is invalid.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        state: Arc<AppState>,
+        branches: Vec<BranchConfig>,
+        state_dir: &Path,
+        config: OrganizationConfig,
+    ) -> Result<Self> {
+        let include = config.include.as_deref()
+            .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid ORG_INCLUDE: {}", pattern)))
+            .transpose()?;
+        let exclude = config.exclude.as_deref()
+            .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid ORG_EXCLUDE: {}", pattern)))
+            .transpose()?;
+        Ok(Self {
+            github_client,
+            state,
+            branches,
+            state_dir: state_dir.to_path_buf(),
+            include,
+            exclude,
+            config,
+        })
+    }
+
+    /// Looks up the repositories of the organization periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing the repositories of {}, looking for new ones every {} minutes",
+            self.config.name, self.config.discovery_interval_minutes
+        );
+
+        let mut poller = polls.poller("organization", Duration::from_secs(self.config.discovery_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.discover().await;
+            if let Err(e) = &result {
+                error!("Failed to list the repositories of {}: {:?}", self.config.name, e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Returns true if the repository is announced, going by the patterns and whether it's a fork
+    fn watches(&self, repository: &OrganizationRepository) -> bool {
+        (self.config.include_forks || !repository.fork)
+            && self.include.as_ref().is_none_or(|include| include.is_match(&repository.name))
+            && !self.exclude.as_ref().is_some_and(|exclude| exclude.is_match(&repository.name))
+    }
+
+    /// Adds the repositories of the organization not known yet.
+    async fn discover(&self) -> Result<()> {
+        let main = self.github_client.repo().await.full_name();
+        let repositories = self.github_client.list_organization_repositories(&self.config.name).await?;
+
+        for repository in repositories {
+            // Archived repositories have no activity left, they are looked at again once unarchived
+            if repository.archived || repository.full_name.eq_ignore_ascii_case(&main) || self.state.knows(&repository.full_name) {
+                continue;
+            }
+            if !self.watches(&repository) {
+                debug!("Leaving out {}, which doesn't match the patterns of the organization", repository.full_name);
+                self.state.ignore_repository(&repository.full_name);
+                continue;
+            }
+            let Some(name) = RepoName::from_full_name(&repository.full_name) else {
+                warn!("Repository of {} has an invalid full name: {}", self.config.name, repository.full_name);
+                continue;
+            };
+
+            let client = Arc::new(self.github_client.for_repository(
+                name.clone(),
+                self.branches.iter().map(|branch| branch.name.clone()).collect(),
+                &self.state_dir.join(&name.owner).join(&name.name),
+            ));
+            let handler = self.state.webhook_handler.for_repository(Arc::clone(&client), self.branches.clone())?;
+            self.state.add_repository(&repository.full_name, handler);
+            info!("Announcing {} of the {} organization", repository.full_name, self.config.name);
+
+            // Contributor announcements of the repository wait for its contributors to be gathered
+            tokio::spawn(async move { client.bootstrap_contributors().await });
+        }
+
+        Ok(())
+    }
+}
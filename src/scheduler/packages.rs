@@ -0,0 +1,111 @@
+use super::poll::PollMonitor;
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::{Ecosystem, PackageConfig},
+    packages::client::PackageClient,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Announces the new versions of a package on npm or PyPI, such as "deltalake 1.0.0 is out on PyPI!".
+///
+/// Each version is announced at most once, even across restarts. The first time the package
+/// is looked up, its latest version is only recorded, so enabling the watcher doesn't announce
+/// a version published long ago.
+pub struct PackageWatcher {
+    package_client: PackageClient,
+    audit_log: Arc<AuditLog>,
+    config: PackageConfig,
+}
+
+impl PackageWatcher {
+    /// Creates a new instance of `PackageWatcher`.
+    ///
+    /// # Arguments
+    /// * `package_client` - The client used to look up the latest version of the package.
+    /// * `audit_log` - The audit log recording which versions were announced.
+    /// * `config` - The package release settings of the ecosystem.
+    pub fn new(
+        package_client: PackageClient,
+        audit_log: Arc<AuditLog>,
+        config: PackageConfig,
+    ) -> Self {
+        Self {
+            package_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Looks up the latest version periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing new versions of {} on {}, checking every {} minutes",
+            self.config.package, self.config.ecosystem.as_str(), self.config.check_interval_minutes
+        );
+
+        let name = match self.config.ecosystem {
+            Ecosystem::Npm => "npm_packages",
+            Ecosystem::PyPi => "pypi_packages",
+        };
+        let mut poller = polls.poller(name, Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check the latest version of {}: {:?}", self.key(), e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Audit log key of the package, such as `pypi:deltalake`.
+    fn key(&self) -> String {
+        format!("{}:{}", self.config.ecosystem.as_str(), self.config.package)
+    }
+
+    /// Announces the latest version, unless it was already handled.
+    async fn check(&self) -> Result<()> {
+        let key = self.key();
+        let version = self.package_client.latest_version().await?;
+
+        if !self.audit_log.has_any(AnnouncementKind::PackageRelease, &key).await {
+            info!("First lookup of {}, recording version {} without announcing it", key, version);
+            let entry = AuditEntry::new(AnnouncementKind::PackageRelease, AuditStatus::Skipped, &key, &version, "");
+            self.record(entry).await;
+            return Ok(());
+        }
+
+        self.announce(&key, &version).await;
+        Ok(())
+    }
+
+    /// Announces a version of the package, unless it was already handled.
+    async fn announce(&self, key: &str, version: &str) {
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::PackageRelease, key, version).await else {
+            debug!("Version {} of {} already handled", version, key);
+            return;
+        };
+
+        let tweet = render(&self.config.template, &[
+            ("package", self.package_client.package()),
+            ("version", version),
+            ("install", &self.package_client.install(version)),
+            ("url", &self.package_client.url(version)),
+        ]);
+
+        info!("Posting new version {} of {}: {}", version, key, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::PackageRelease, AuditStatus::Queued, key, version, &tweet)
+                .with_subject(version)
+        ).await;
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
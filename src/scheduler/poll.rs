@@ -0,0 +1,187 @@
+use super::retry_budget::RetryBudget;
+use crate::config::env::PollBackoffConfig;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex}};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
+
+/// Health of a scheduled poll, also persisted so a failing poll keeps backing off after a restart
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PollHealth {
+    /// Number of polls that failed in a row, 0 while the poll succeeds
+    pub failure_streak: u32,
+    /// When the poll last succeeded
+    pub last_success: Option<DateTime<Utc>>,
+    /// Error of the last failed poll, cleared once the poll succeeds again
+    pub last_error: Option<String>,
+    /// When the failing poll is attempted again
+    #[serde(default)]
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Tracks the health of every scheduled poll and paces them with [Poller].
+pub struct PollMonitor {
+    backoff: PollBackoffConfig,
+    retry_budget: Arc<RetryBudget>,
+    polls: Arc<Mutex<BTreeMap<String, PollHealth>>>,
+    /// Repository the polls are about, prefixed to their names, for the repositories other than the main one
+    scope: Option<String>,
+}
+
+impl PollMonitor {
+    /// Creates a monitor backing off failing polls with the given settings.
+    ///
+    /// # Arguments
+    /// * `backoff` - The delays between the attempts of a failing poll.
+    /// * `retry_budget` - The budget the attempts of failing polls are spent from.
+    pub fn new(backoff: PollBackoffConfig, retry_budget: Arc<RetryBudget>) -> Self {
+        Self {
+            backoff,
+            retry_budget,
+            polls: Arc::new(Mutex::new(BTreeMap::new())),
+            scope: None,
+        }
+    }
+
+    /// Creates a monitor of the polls about another repository, reported alongside these ones
+    /// under names such as `delta-io/delta-kernel-rs:star_milestones`.
+    ///
+    /// # Arguments
+    /// * `repository` - The repository, as `owner/name`.
+    pub fn scoped(&self, repository: &str) -> Arc<Self> {
+        Arc::new(Self {
+            backoff: self.backoff.clone(),
+            retry_budget: Arc::clone(&self.retry_budget),
+            polls: Arc::clone(&self.polls),
+            scope: Some(repository.to_string()),
+        })
+    }
+
+    /// Creates the pacer of a poll, which is reported as healthy until it fails.
+    ///
+    /// # Arguments
+    /// * `name` - The name the poll is reported under.
+    /// * `interval` - The time between two polls while they succeed.
+    pub fn poller(self: &Arc<Self>, name: &'static str, interval: Duration) -> Poller {
+        let name = match &self.scope {
+            Some(repository) => format!("{}:{}", repository, name),
+            None => name.to_string(),
+        };
+        let mut polls = self.polls.lock().unwrap();
+        let health = polls.entry(name.clone()).or_default();
+        // A poll that was failing before a restart waits out its backoff first
+        let next = health.retry_at
+            .and_then(|retry_at| (retry_at - Utc::now()).to_std().ok())
+            .unwrap_or(Duration::ZERO);
+        let retrying = health.failure_streak > 0;
+        drop(polls);
+
+        Poller {
+            name,
+            interval,
+            monitor: Arc::clone(self),
+            next,
+            retrying,
+        }
+    }
+
+    /// Returns the health of every poll, keyed by name.
+    pub fn health(&self) -> BTreeMap<String, PollHealth> {
+        self.polls.lock().unwrap().clone()
+    }
+
+    /// Returns true if no poll is failing.
+    pub fn healthy(&self) -> bool {
+        self.polls.lock().unwrap().values().all(|poll| poll.failure_streak == 0)
+    }
+
+    /// Restores the polls failing before a restart, before their pollers are created, so they
+    /// keep backing off rather than all being attempted right away.
+    ///
+    /// # Arguments
+    /// * `polls` - The health of the polls when the bot stopped, keyed by name.
+    pub fn restore(&self, polls: BTreeMap<String, PollHealth>) {
+        let failing: BTreeMap<String, PollHealth> = polls
+            .into_iter()
+            .filter(|(_, health)| health.failure_streak > 0)
+            .collect();
+        if !failing.is_empty() {
+            info!("{} polls were failing before the restart, still backing them off", failing.len());
+        }
+        self.polls.lock().unwrap().extend(failing);
+    }
+}
+
+/// Returns the delay before retrying something that failed `failures` times in a row.
+///
+/// The delay doubles with every failure up to the maximum, and a random part of up to
+/// half of it is taken off so tasks failing together don't retry in lockstep.
+pub fn backoff(config: &PollBackoffConfig, failures: u32) -> Duration {
+    let doubling = 2u64.saturating_pow(failures.saturating_sub(1));
+    let delay = config.initial_seconds
+        .saturating_mul(doubling)
+        .min(config.max_seconds);
+    let delay = Duration::from_secs(delay);
+    delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+}
+
+/// Paces a scheduled poll: at its interval while it succeeds, backing off while it fails.
+pub struct Poller {
+    name: String,
+    interval: Duration,
+    monitor: Arc<PollMonitor>,
+    next: Duration,
+    retrying: bool,
+}
+
+impl Poller {
+    /// Waits until the next poll is due. The first poll is due right away.
+    ///
+    /// Polls retrying after a failure also wait for the retry budget.
+    pub async fn wait(&mut self) {
+        sleep(self.next).await;
+        if self.retrying {
+            self.monitor.retry_budget.acquire(&self.name).await;
+        }
+    }
+
+    /// Records the outcome of a poll, which sets when the next one is due.
+    ///
+    /// A success resets the failure streak and brings the poll back to its interval, while
+    /// every failure in a row increases the delay before the next attempt.
+    pub fn record<T>(&mut self, result: &anyhow::Result<T>) {
+        let mut polls = self.monitor.polls.lock().unwrap();
+        let health = polls.entry(self.name.clone()).or_default();
+        match result {
+            Ok(_) => {
+                if health.failure_streak > 0 {
+                    info!("Poll {} recovered after {} failures", self.name, health.failure_streak);
+                }
+                *health = PollHealth {
+                    failure_streak: 0,
+                    last_success: Some(Utc::now()),
+                    last_error: None,
+                    retry_at: None,
+                };
+                self.next = self.interval;
+                self.retrying = false;
+            }
+            Err(e) => {
+                health.failure_streak += 1;
+                // Some errors carry a backtrace after their message
+                health.last_error = e.to_string().lines().next().map(str::to_owned);
+                self.next = backoff(&self.monitor.backoff, health.failure_streak);
+                health.retry_at = chrono::Duration::from_std(self.next).ok().map(|next| Utc::now() + next);
+                self.retrying = true;
+                warn!(
+                    "Poll {} failed {} times in a row, retrying in {}s",
+                    self.name, health.failure_streak, self.next.as_secs()
+                );
+            }
+        }
+    }
+}
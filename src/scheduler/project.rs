@@ -0,0 +1,109 @@
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::ProjectConfig,
+    github::client::GitHubClient,
+    scheduler::poll::PollMonitor,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Announces items of a Projects (v2) board as they move to the done status.
+///
+/// The board is polled through the GraphQL API, and every item is announced at most
+/// once. The first time the board is polled, items that are already done are only
+/// recorded, so enabling the watcher doesn't flood the timeline with past progress.
+pub struct ProjectWatcher {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: ProjectConfig,
+}
+
+impl ProjectWatcher {
+    /// Creates a new instance of `ProjectWatcher`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to read the project board.
+    /// * `audit_log` - The audit log recording which items were announced.
+    /// * `config` - The project board settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: ProjectConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Polls the project board periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Watching project {} of {} every {} minutes",
+            self.config.number, self.config.owner, self.config.poll_interval_minutes
+        );
+
+        let mut first_poll = true;
+        let mut poller = polls.poller("project", Duration::from_secs(self.config.poll_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.poll(first_poll).await;
+            match &result {
+                Ok(()) => first_poll = false,
+                Err(e) => error!("Failed to poll project {} of {}: {:?}", self.config.number, self.config.owner, e),
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the items that were completed since the last poll.
+    async fn poll(&self, first_poll: bool) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let items = self.github_client
+            .list_project_items(&self.config.owner, self.config.number, &self.config.status_field)
+            .await?;
+
+        let baseline = first_poll && !self.audit_log.has_any(AnnouncementKind::ProjectItem, &repo).await;
+        if baseline {
+            info!("First poll of project {}, recording completed items without announcing them", self.config.number);
+        }
+
+        for item in items {
+            if item.status.as_deref() != Some(self.config.done_status.as_str()) {
+                continue;
+            }
+            let Some(claim) = self.audit_log.claim(AnnouncementKind::ProjectItem, &repo, &item.id).await else {
+                continue;
+            };
+
+            if baseline {
+                let entry = AuditEntry::new(AnnouncementKind::ProjectItem, AuditStatus::Skipped, &repo, &item.id, &item.title);
+                self.record(entry).await;
+                continue;
+            }
+
+            let url = item.url.as_deref().unwrap_or_default();
+            let tweet = render(&self.config.template, &[
+                ("title", &item.title),
+                ("url", url),
+            ]);
+
+            info!("Posting project item completion: {}", tweet);
+            claim.enqueue(
+                AuditEntry::new(AnnouncementKind::ProjectItem, AuditStatus::Queued, &repo, &item.id, &tweet)
+            ).await;
+        }
+
+        debug!("Project {} polled", self.config.number);
+        Ok(())
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
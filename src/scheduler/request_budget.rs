@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::Serialize;
+use tokio::{
+    sync::Mutex,
+    time::{sleep, Duration, Instant}};
+use chrono::{DateTime, TimeZone, Utc};
+use octocrab::Octocrab;
+use tracing::{debug, info, warn};
+
+/// Seconds before the rate limit is read from GitHub again
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// State of the GitHub request budget, as reported by `/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct RequestBudgetStats {
+    /// Requests left in the current rate limit window, as last read from GitHub
+    pub remaining: Option<u32>,
+    /// Requests kept for handling webhooks, which background scans don't spend
+    pub reserve: u32,
+    /// When the rate limit window resets
+    pub resets_at: Option<DateTime<Utc>>,
+    /// Background requests that had to wait for their turn
+    pub paced: u64,
+}
+
+struct Plan {
+    // Remaining requests and reset of the core rate limit, as last read from GitHub
+    remaining: Option<u32>,
+    resets_at: Option<DateTime<Utc>>,
+    checked: Option<Instant>,
+    // Background requests made since the rate limit was read
+    spent: u32,
+    last_request: Option<Instant>,
+}
+
+/// Plans the requests background scans make against GitHub's core rate limit.
+///
+/// Contributor scans, digests, traffic and stargazer checks ask the planner before every
+/// request. Their requests are spread evenly over what's left of the rate limit window,
+/// minus a reserve kept for the webhooks, so a large scan never exhausts the limit the
+/// announcements of incoming events depend on. Once only the reserve is left, background
+/// requests wait for the window to reset. They go one at a time, in the order they asked.
+pub struct RequestBudget {
+    reserve: u32,
+    plan: Mutex<Plan>,
+    paced: AtomicU64,
+}
+
+impl RequestBudget {
+    /// Creates a planner that hasn't read the rate limit yet.
+    ///
+    /// # Arguments
+    /// * `reserve` - The requests kept for handling webhooks.
+    pub fn new(reserve: u32) -> Self {
+        Self {
+            reserve,
+            plan: Mutex::new(Plan {
+                remaining: None,
+                resets_at: None,
+                checked: None,
+                spent: 0,
+                last_request: None,
+            }),
+            paced: AtomicU64::new(0),
+        }
+    }
+
+    /// Waits for the turn of a background request, and counts it as made.
+    ///
+    /// # Arguments
+    /// * `client` - The client the rate limit is read with, which costs no requests.
+    /// * `site` - What the request is for, for the logs.
+    pub async fn pace(&self, client: &Octocrab, site: &str) {
+        let mut plan = self.plan.lock().await;
+        let mut paced = false;
+        loop {
+            if plan.checked.is_none_or(|checked| checked.elapsed() >= CHECK_INTERVAL) {
+                self.check(client, &mut plan).await;
+            }
+            let (Some(remaining), Some(resets_at)) = (plan.remaining, plan.resets_at) else {
+                // Without a rate limit to plan with, the request goes through unpaced
+                break;
+            };
+
+            let until_reset = (resets_at - Utc::now()).to_std().unwrap_or_default();
+            let available = remaining.saturating_sub(plan.spent).saturating_sub(self.reserve);
+            let wait = if available == 0 {
+                if !paced {
+                    warn!("Only the reserve of GitHub requests is left, {} waits {:?} for the reset", site, until_reset);
+                }
+                // Read the rate limit again once the window reset
+                plan.checked = None;
+                until_reset + Duration::from_secs(1)
+            } else {
+                let spacing = until_reset / available;
+                let next = plan.last_request.map_or_else(Instant::now, |last| last + spacing);
+                let wait = next.saturating_duration_since(Instant::now());
+                if wait.is_zero() {
+                    break;
+                }
+                wait
+            };
+
+            if !paced {
+                paced = true;
+                self.paced.fetch_add(1, Ordering::Relaxed);
+            }
+            debug!("Pacing {} for {:?}", site, wait);
+            sleep(wait).await;
+            if available > 0 {
+                break;
+            }
+        }
+
+        plan.spent += 1;
+        plan.last_request = Some(Instant::now());
+    }
+
+    /// Returns the state of the budget.
+    pub async fn stats(&self) -> RequestBudgetStats {
+        let plan = self.plan.lock().await;
+        RequestBudgetStats {
+            remaining: plan.remaining.map(|remaining| remaining.saturating_sub(plan.spent)),
+            reserve: self.reserve,
+            resets_at: plan.resets_at,
+            paced: self.paced.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reads the core rate limit from GitHub.
+    async fn check(&self, client: &Octocrab, plan: &mut Plan) {
+        plan.checked = Some(Instant::now());
+        match client.ratelimit().get().await {
+            Ok(limit) => {
+                let core = limit.resources.core;
+                if plan.remaining.is_none() {
+                    info!("GitHub allows {} requests an hour, {} are left", core.limit, core.remaining);
+                }
+                plan.remaining = Some(u32::try_from(core.remaining).unwrap_or(u32::MAX));
+                plan.resets_at = Utc.timestamp_opt(core.reset as i64, 0).single();
+                plan.spent = 0;
+            }
+            Err(e) => warn!("Failed to read the GitHub rate limit, not pacing background requests: {:?}", e),
+        }
+    }
+}
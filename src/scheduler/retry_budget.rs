@@ -0,0 +1,129 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Mutex};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Duration, Instant};
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+/// State of the retry budget, as reported by `/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct RetryBudgetStats {
+    /// Retries allowed per minute, 0 when unlimited
+    pub per_minute: u32,
+    /// Retries that can be made right away
+    pub available: u32,
+    /// Retries that had to wait for the budget to refill
+    pub deferred: u64,
+}
+
+/// Retries left in the budget at a given time, as persisted between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryBudgetSnapshot {
+    pub tokens: f64,
+    pub taken_at: DateTime<Utc>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// Limits the retries made against GitHub and X, shared by every call site.
+///
+/// The budget is a token bucket holding a minute of retries, refilled continuously. Once a
+/// widespread outage drains it, retries wait their turn for the bucket to refill instead of
+/// all hitting the APIs the moment they recover and exhausting their rate limits.
+pub struct RetryBudget {
+    per_minute: u32,
+    bucket: Mutex<Bucket>,
+    deferred: AtomicU64,
+}
+
+impl RetryBudget {
+    /// Creates a full budget.
+    ///
+    /// # Arguments
+    /// * `per_minute` - The retries allowed per minute, 0 to never limit them.
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(per_minute),
+                refilled: Instant::now(),
+            }),
+            deferred: AtomicU64::new(0),
+        }
+    }
+
+    /// Waits until the budget allows one more retry, and spends it.
+    ///
+    /// # Arguments
+    /// * `site` - What is being retried, for the logs.
+    pub async fn acquire(&self, site: &str) {
+        if self.per_minute == 0 {
+            return;
+        }
+
+        let mut deferred = false;
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+                self.refill(&mut bucket);
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) * 60.0 / f64::from(self.per_minute))
+            };
+
+            if !deferred {
+                deferred = true;
+                self.deferred.fetch_add(1, Ordering::Relaxed);
+                warn!("Retry budget exhausted, retrying {} once it refills", site);
+            }
+            sleep(wait).await;
+        }
+    }
+
+    /// Returns the state of the budget.
+    pub fn stats(&self) -> RetryBudgetStats {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        RetryBudgetStats {
+            per_minute: self.per_minute,
+            available: bucket.tokens as u32,
+            deferred: self.deferred.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the retries left, to be restored after a restart.
+    pub fn snapshot(&self) -> RetryBudgetSnapshot {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket);
+        RetryBudgetSnapshot {
+            tokens: bucket.tokens,
+            taken_at: Utc::now(),
+        }
+    }
+
+    /// Restores the retries left before a restart, refilled for the time the bot was down, so
+    /// a restart during an outage doesn't start over with a full budget.
+    ///
+    /// # Arguments
+    /// * `snapshot` - The retries left when the bot stopped.
+    pub fn restore(&self, snapshot: &RetryBudgetSnapshot) {
+        let down = (Utc::now() - snapshot.taken_at).to_std().unwrap_or_default();
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.tokens = snapshot.tokens.clamp(0.0, f64::from(self.per_minute));
+        bucket.refilled = Instant::now().checked_sub(down).unwrap_or_else(Instant::now);
+        self.refill(&mut bucket);
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let earned = now.duration_since(bucket.refilled).as_secs_f64() * f64::from(self.per_minute) / 60.0;
+        bucket.tokens = (bucket.tokens + earned).min(f64::from(self.per_minute));
+        bucket.refilled = now;
+    }
+}
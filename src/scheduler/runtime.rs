@@ -0,0 +1,88 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc};
+use serde::Serialize;
+use tokio::{
+    runtime::Handle,
+    time::{sleep, Duration, Instant}};
+use tracing::warn;
+
+/// Interval between two measures of the scheduling lag
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Scheduling lag from which the runtime is considered stalled
+const STALL_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// Snapshot of the async runtime
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeStats {
+    /// Number of worker threads
+    pub workers: usize,
+    /// Number of tasks that haven't finished yet, such as requests and held announcements
+    pub alive_tasks: usize,
+    /// Number of tasks waiting in the global queue for a free worker
+    pub global_queue_depth: usize,
+    /// How late the last measure woke up, in milliseconds
+    pub last_lag_ms: u64,
+    /// How late the latest measure woke up at most, in milliseconds
+    pub max_lag_ms: u64,
+    /// Number of times the runtime stalled
+    pub stalls: u64,
+}
+
+/// Watches the async runtime for stalls.
+///
+/// A task that blocks a worker thread, such as a synchronous sleep or a long computation,
+/// delays every other task scheduled on it. The monitor measures that delay by how late
+/// its own timer wakes up, and logs a warning when it crosses the stall threshold.
+pub struct RuntimeMonitor {
+    handle: Handle,
+    last_lag_ms: AtomicU64,
+    max_lag_ms: AtomicU64,
+    stalls: AtomicU64,
+}
+
+impl RuntimeMonitor {
+    /// Creates a monitor of the runtime behind the given handle.
+    pub fn new(handle: Handle) -> Self {
+        Self {
+            handle,
+            last_lag_ms: AtomicU64::new(0),
+            max_lag_ms: AtomicU64::new(0),
+            stalls: AtomicU64::new(0),
+        }
+    }
+
+    /// Measures the scheduling lag periodically, forever.
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            let started = Instant::now();
+            sleep(SAMPLE_INTERVAL).await;
+            let lag = started.elapsed().saturating_sub(SAMPLE_INTERVAL);
+
+            let lag_ms = lag.as_millis() as u64;
+            self.last_lag_ms.store(lag_ms, Ordering::Relaxed);
+            self.max_lag_ms.fetch_max(lag_ms, Ordering::Relaxed);
+            if lag >= STALL_THRESHOLD {
+                self.stalls.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "Runtime stalled for {}ms with {} tasks alive, a task may be blocking a worker thread",
+                    lag_ms, self.handle.metrics().num_alive_tasks()
+                );
+            }
+        }
+    }
+
+    /// Returns the current state of the runtime.
+    pub fn stats(&self) -> RuntimeStats {
+        let metrics = self.handle.metrics();
+        RuntimeStats {
+            workers: metrics.num_workers(),
+            alive_tasks: metrics.num_alive_tasks(),
+            global_queue_depth: metrics.global_queue_depth(),
+            last_lag_ms: self.last_lag_ms.load(Ordering::Relaxed),
+            max_lag_ms: self.max_lag_ms.load(Ordering::Relaxed),
+            stalls: self.stalls.load(Ordering::Relaxed),
+        }
+    }
+}
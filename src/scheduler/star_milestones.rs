@@ -0,0 +1,106 @@
+use super::{poll::PollMonitor, threshold::highest_reached};
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::StarMilestoneConfig,
+    github::client::GitHubClient,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration;
+use anyhow::Result;
+use tracing::{debug, error, info};
+
+/// Announces the star count of the repository crossing milestones, such as "10,000 stars".
+///
+/// Each milestone is announced at most once, even across restarts or when the count drops
+/// below it and crosses it again. The first time the stars are counted, the milestone
+/// already reached is only recorded, so enabling the watcher doesn't celebrate a milestone
+/// that was passed long ago. Milestones crossed together between two counts are announced
+/// as the highest of them.
+pub struct StarMilestones {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: StarMilestoneConfig,
+}
+
+impl StarMilestones {
+    /// Creates a new instance of `StarMilestones`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to count the stars.
+    /// * `audit_log` - The audit log recording which milestones were announced.
+    /// * `config` - The star milestone settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: StarMilestoneConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Counts the stars periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing star milestones {:?}, checking every {} minutes",
+            self.config.milestones, self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("star_milestones", Duration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check().await;
+            if let Err(e) = &result {
+                error!("Failed to check star milestones: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the highest milestone reached, unless it was already handled.
+    async fn check(&self) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let stars = self.github_client.count_stars().await?;
+        let threshold = highest_reached(stars, &self.config.milestones).unwrap_or_default();
+        let source_id = threshold.to_string();
+
+        // The baseline is recorded even before the first milestone is reached, so that
+        // reaching it later is announced
+        if !self.audit_log.has_any(AnnouncementKind::StarMilestone, &repo).await {
+            info!("First count of stars, recording {} without announcing it", stars);
+            let entry = AuditEntry::new(AnnouncementKind::StarMilestone, AuditStatus::Skipped, &repo, &source_id, "");
+            self.record(entry).await;
+            return Ok(());
+        }
+        if threshold == 0 {
+            debug!("{} stars, no milestone reached yet", stars);
+            return Ok(());
+        }
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::StarMilestone, &repo, &source_id).await else {
+            debug!("Milestone of {} stars already handled", threshold);
+            return Ok(());
+        };
+
+        let count = self.config.locale.format_count(threshold);
+        let tweet = render(&self.config.template, &[
+            ("count", &count),
+            ("repo", &repo),
+        ]);
+
+        info!("Posting star milestone: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::StarMilestone, AuditStatus::Queued, &repo, &source_id, &tweet)
+                .with_subject(&count)
+        ).await;
+
+        Ok(())
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    audit::log::{normalize_identity, AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::env::StargazerConfig,
+    github::client::GitHubClient,
+    scheduler::poll::PollMonitor,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration as TokioDuration;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use tracing::{debug, error, info};
+
+/// Announces notable accounts starring the repository.
+///
+/// An account is notable when it has enough followers or is on the allowlist. Each
+/// account is announced at most once, even if it unstars and stars the repository again.
+pub struct StargazerWatcher {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: StargazerConfig,
+}
+
+impl StargazerWatcher {
+    /// Creates a new instance of `StargazerWatcher`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to list the stargazers.
+    /// * `audit_log` - The audit log recording which stargazers were announced.
+    /// * `config` - The stargazer settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: StargazerConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Checks the new stargazers periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Announcing stargazers with at least {} followers, checking every {} minutes",
+            self.config.min_followers, self.config.poll_interval_minutes
+        );
+
+        // After a restart, stars given during the last interval are still picked up
+        let mut since = Utc::now() - Duration::minutes(self.config.poll_interval_minutes as i64);
+        let mut poller = polls.poller("stargazers", TokioDuration::from_secs(self.config.poll_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let checked_at = Utc::now();
+            let result = self.check(since).await;
+            match &result {
+                Ok(()) => since = checked_at,
+                Err(e) => error!("Failed to check new stargazers: {:?}", e),
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Announces the notable accounts among the stargazers since the given time.
+    async fn check(&self, since: DateTime<Utc>) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let stargazers = self.github_client.list_recent_stargazers(since).await?;
+        debug!("{} new stargazers", stargazers.len());
+
+        for user in stargazers.into_iter().filter_map(|stargazer| stargazer.user) {
+            let source_id = normalize_identity(&user.login);
+            let Some(claim) = self.audit_log.claim(AnnouncementKind::Stargazer, &repo, &source_id).await else {
+                continue;
+            };
+
+            let followers = self.github_client.count_followers(&user.login).await?;
+            let allowlisted = self.config.allowlist
+                .iter()
+                .any(|login| login.eq_ignore_ascii_case(&user.login));
+            if !allowlisted && followers < self.config.min_followers {
+                continue;
+            }
+
+            let tweet = render(&self.config.template, &[
+                ("username", &user.login),
+                ("followers", &self.config.locale.format_count(followers)),
+                ("url", user.html_url.as_str()),
+            ]);
+
+            info!("Posting notable stargazer: {}", tweet);
+            claim.enqueue(
+                AuditEntry::new(AnnouncementKind::Stargazer, AuditStatus::Queued, &repo, &source_id, &tweet)
+                    .with_subject(&user.login)
+            ).await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use super::poll::backoff;
+use crate::config::env::PollBackoffConfig;
+use std::future::Future;
+use tokio::{
+    task::JoinSet,
+    time::{sleep, Duration, Instant}};
+use anyhow::{Result, anyhow};
+use tracing::{error, info, warn};
+
+/// Number of restarts in a row after which a task is given up on
+const MAX_RESTARTS: u32 = 5;
+
+/// Time a task has to run for its restarts to no longer count as in a row
+const STABLE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Keeps the background tasks running.
+///
+/// The tasks are meant to run forever, so a task that panics or returns is restarted,
+/// waiting longer with every restart in a row. A task that keeps stopping right after
+/// being restarted is given up on, which stops the bot through [Supervisor::wait].
+pub struct Supervisor {
+    backoff: PollBackoffConfig,
+    tasks: JoinSet<Result<()>>,
+}
+
+impl Supervisor {
+    /// Creates a supervisor restarting tasks with the given backoff.
+    pub fn new(backoff: PollBackoffConfig) -> Self {
+        Self {
+            backoff,
+            tasks: JoinSet::new(),
+        }
+    }
+
+    /// Starts a task and restarts it whenever it stops.
+    ///
+    /// # Arguments
+    /// * `name` - The name the task is logged under.
+    /// * `task` - Creates the future of the task, called again for every restart.
+    pub fn spawn<F, Fut>(&mut self, name: impl Into<String>, task: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        let backoff_config = self.backoff.clone();
+        self.tasks.spawn(async move {
+            let mut restarts = 0;
+            loop {
+                let started = Instant::now();
+                match tokio::spawn(task()).await {
+                    Err(e) if e.is_panic() => error!("Task {} panicked", name),
+                    _ => warn!("Task {} stopped", name),
+                }
+
+                if started.elapsed() >= STABLE_AFTER {
+                    restarts = 0;
+                }
+                restarts += 1;
+                if restarts > MAX_RESTARTS {
+                    return Err(anyhow!("Task {} stopped {} times in a row", name, restarts));
+                }
+
+                let delay = backoff(&backoff_config, restarts);
+                info!("Restarting task {} in {}s", name, delay.as_secs());
+                sleep(delay).await;
+            }
+        });
+    }
+
+    /// Waits until a task is given up on.
+    ///
+    /// # Returns
+    /// The error of the task that kept stopping. Without any task, this never returns.
+    pub async fn wait(&mut self) -> Result<()> {
+        match self.tasks.join_next().await {
+            Some(result) => result?,
+            None => std::future::pending().await,
+        }
+    }
+}
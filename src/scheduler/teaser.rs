@@ -0,0 +1,112 @@
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditLog, AuditStatus},
+    config::{duration::parse_duration, env::TeaserConfig},
+    github::client::GitHubClient,
+    scheduler::poll::PollMonitor,
+    template::render::render};
+use std::sync::Arc;
+use tokio::time::Duration as TokioDuration;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use tracing::{debug, error, info};
+
+/// Posts countdown teasers for upcoming milestones.
+///
+/// Every open milestone with a due date gets one teaser per configured offset, posted
+/// once the due date is closer than the offset. Teasers are recorded in the audit log
+/// so each of them is posted only once, even across restarts.
+pub struct MilestoneTeaser {
+    github_client: Arc<GitHubClient>,
+    audit_log: Arc<AuditLog>,
+    config: TeaserConfig,
+}
+
+impl MilestoneTeaser {
+    /// Creates a new instance of `MilestoneTeaser`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client used to list the milestones of the repository.
+    /// * `audit_log` - The audit log recording which teasers were posted.
+    /// * `config` - The teaser settings.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        audit_log: Arc<AuditLog>,
+        config: TeaserConfig,
+    ) -> Self {
+        Self {
+            github_client,
+            audit_log,
+            config,
+        }
+    }
+
+    /// Checks the milestones periodically, forever.
+    pub async fn run(self: Arc<Self>, polls: Arc<PollMonitor>) {
+        info!(
+            "Milestone teasers enabled, checking every {} minutes",
+            self.config.check_interval_minutes
+        );
+
+        let mut poller = polls.poller("milestone_teaser", TokioDuration::from_secs(self.config.check_interval_minutes * 60));
+        loop {
+            poller.wait().await;
+            let result = self.check_milestones().await;
+            if let Err(e) = &result {
+                error!("Failed to check milestones for teasers: {:?}", e);
+            }
+            poller.record(&result);
+        }
+    }
+
+    /// Posts the teasers that are due for every open milestone.
+    async fn check_milestones(&self) -> Result<()> {
+        let repo = self.github_client.repo().await.full_name();
+        let now = Utc::now();
+
+        for milestone in self.github_client.list_open_milestones().await? {
+            let Some(due_on) = milestone.due_on else {
+                continue;
+            };
+            let remaining = due_on - now;
+            if remaining <= Duration::zero() {
+                continue;
+            }
+
+            // Only the closest offset that was reached is posted, so a milestone created
+            // a day before its due date doesn't get the one-week teaser as well
+            let Some(offset) = self.config.offsets
+                .iter()
+                .filter_map(|offset| parse_duration(offset).ok().map(|duration| (offset, duration)))
+                .filter(|(_, duration)| remaining <= *duration)
+                .min_by_key(|(_, duration)| *duration)
+                .map(|(offset, _)| offset)
+            else {
+                continue;
+            };
+
+            let source_id = format!("{}:{}", milestone.number, offset);
+            let Some(claim) = self.audit_log.claim(AnnouncementKind::MilestoneTeaser, &repo, &source_id).await else {
+                debug!("Teaser {} for milestone {} already posted", offset, milestone.title);
+                continue;
+            };
+
+            let locale = self.config.locale;
+            let open_issues = locale.format_count(milestone.open_issues.unwrap_or_default() as u64);
+            let due_date = locale.format_date(due_on);
+            let tweet = render(&self.config.template, &[
+                ("title", &milestone.title),
+                ("when", &locale.relative(remaining)),
+                ("due_date", &due_date),
+                ("open_issues", &open_issues),
+                ("url", milestone.html_url.as_str()),
+            ]);
+
+            info!("Posting teaser for milestone {}: {}", milestone.title, tweet);
+            claim.enqueue(
+                AuditEntry::new(AnnouncementKind::MilestoneTeaser, AuditStatus::Queued, &repo, &source_id, &tweet)
+            ).await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+/// Returns the highest multiple of `every` that `count` has reached, if any.
+///
+/// # Arguments
+/// * `count` - The current value of the tracked counter.
+/// * `every` - The spacing of the thresholds, e.g. 1000 for 1,000, 2,000, 3,000...
+///
+/// # Returns
+/// The threshold reached, or `None` if `count` hasn't reached the first one yet.
+pub fn reached(count: u64, every: u64) -> Option<u64> {
+    if every == 0 {
+        return None;
+    }
+
+    let threshold = count / every * every;
+    (threshold > 0).then_some(threshold)
+}
+
+/// Returns the highest of the `milestones` that `count` has reached, if any.
+///
+/// # Arguments
+/// * `count` - The current value of the tracked counter.
+/// * `milestones` - The thresholds, in increasing order, e.g. 1000, 5000, 10000.
+///
+/// # Returns
+/// The milestone reached, or `None` if `count` hasn't reached the first one yet.
+pub fn highest_reached(count: u64, milestones: &[u64]) -> Option<u64> {
+    milestones.iter().rev().find(|&&milestone| count >= milestone).copied()
+}
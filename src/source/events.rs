@@ -0,0 +1,74 @@
+use crate::{github::types::WebhookEvent, webhook::handler::AppState};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, error, info};
+
+/// Events received but not handled yet, beyond which the source waits
+const EVENT_BUFFER: usize = 64;
+
+/// An event of a repository, normalized to the GitHub payloads the handler understands
+#[derive(Debug)]
+pub struct RepoEvent {
+    /// Type of the event, as in the `X-GitHub-Event` header, e.g. `release`
+    pub event_type: String,
+    /// Where the event came from, such as a NATS subject, for the logs
+    pub origin: String,
+    pub event: WebhookEvent,
+}
+
+/// A source of repository events other than the webhook routes, such as a message broker.
+///
+/// A source only turns what it receives into [RepoEvent]s, the events of every source go
+/// through the same handling with [feed], which decides what is announced.
+#[async_trait]
+pub trait EventSource: Send + Sync {
+    /// Name of the source in the logs, e.g. `nats`
+    fn name(&self) -> &'static str;
+
+    /// Produces the events of the source, in the order they are received.
+    ///
+    /// # Arguments
+    /// * `events` - The channel the events are sent to.
+    ///
+    /// # Returns
+    /// A result that is `Ok` once the channel is closed, or an error if the source is lost.
+    async fn run(&self, events: mpsc::Sender<RepoEvent>) -> Result<()>;
+}
+
+/// Handles the events of a source until it stops, such as when its connection is lost.
+///
+/// Events are handled in the background like held webhooks, so a slow announcement doesn't
+/// hold up the source.
+///
+/// # Arguments
+/// * `source` - The source of the events.
+/// * `state` - The state of the webhook route, whose handler the events go through.
+pub async fn feed(source: Arc<dyn EventSource>, state: Arc<AppState>) {
+    let name = source.name();
+    let (events, mut received) = mpsc::channel(EVENT_BUFFER);
+    let producing = tokio::spawn(async move { source.run(events).await });
+
+    while let Some(RepoEvent { event_type, origin, event }) = received.recv().await {
+        debug!("Handling {} event from {}", event_type, origin);
+        let Some(handler) = state.handler_of(&event) else {
+            debug!(
+                "Ignoring {} event of {} from {}, left out of the organization",
+                event_type, event.repository().unwrap_or_default(), origin
+            );
+            continue;
+        };
+        tokio::spawn(async move {
+            if let Err(e) = handler.handle(event).await {
+                error!("Error handling {} event from {}: {:?}", event_type, origin, e);
+            }
+        });
+    }
+
+    match producing.await {
+        Ok(Ok(())) => info!("Stopped consuming events from {}", name),
+        Ok(Err(e)) => error!("Stopped consuming events from {}: {:?}", name, e),
+        Err(e) => error!("Consuming events from {} panicked: {:?}", name, e),
+    }
+}
@@ -0,0 +1,210 @@
+use super::events::{EventSource, RepoEvent};
+use crate::{
+    github::{
+        activity::{ActivityCommit, ActivityRelease, RepositoryActivity, ACTIVITY_COMMITS},
+        client::GitHubClient,
+        repository::RepoName,
+        types::{Commit, CommitAuthor, GitHubUser, PushEvent, Release, ReleaseAction, ReleaseEvent, Repository, WebhookEvent}},
+    scheduler::poll::PollMonitor};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex}};
+use tokio::{sync::mpsc, time::Duration};
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::{debug, error, info, warn};
+
+/// Polls the activity of a repository with a single GraphQL query, for bots GitHub can't
+/// deliver webhooks to, such as behind a firewall.
+///
+/// The new commits of each monitored branch are turned into push events, and the new
+/// releases into published release events, so they're announced like the webhooks would
+/// be. The authors of the commits come with the query, along with the merged pull request
+/// each commit came in with, so first contributions are checked without a request per
+/// commit. The first query after a start only records what's there, as nothing tells what
+/// was already announced before.
+pub struct GraphQlPoller {
+    github_client: Arc<GitHubClient>,
+    branches: Vec<String>,
+    polls: Arc<PollMonitor>,
+    interval: Duration,
+    /// What the previous query found, unset before the first one
+    seen: Mutex<Option<Seen>>,
+}
+
+/// Activity found by the previous queries
+struct Seen {
+    /// Head of each branch
+    heads: HashMap<String, String>,
+    /// Ids of the published releases
+    releases: HashSet<u64>,
+}
+
+impl GraphQlPoller {
+    /// Creates a new instance of `GraphQlPoller`.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client of the repository polled.
+    /// * `branches` - The monitored branches.
+    /// * `polls` - The monitor the health of the poll is reported to.
+    /// * `interval` - The time between two queries.
+    pub fn new(github_client: Arc<GitHubClient>, branches: Vec<String>, polls: Arc<PollMonitor>, interval: Duration) -> Self {
+        Self {
+            github_client,
+            branches,
+            polls,
+            interval,
+            seen: Mutex::new(None),
+        }
+    }
+
+    /// Turns what's new since the previous query into events, with their type, and remembers it.
+    async fn changes(&self, activity: RepositoryActivity) -> Vec<(&'static str, WebhookEvent)> {
+        let repo = self.github_client.repo().await;
+        let mut state = self.seen.lock().unwrap();
+        let Some(seen) = state.as_mut() else {
+            info!("First poll of {}, recording its activity without announcing it", repo);
+            *state = Some(Seen {
+                heads: activity.commits.iter()
+                    .filter_map(|(branch, commits)| Some((branch.clone(), commits.first()?.oid.clone())))
+                    .collect(),
+                releases: activity.releases.iter()
+                    .filter(|release| !release.is_draft)
+                    .filter_map(|release| release.database_id)
+                    .collect(),
+            });
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        for (branch, commits) in &activity.commits {
+            let Some(head) = commits.first() else {
+                continue;
+            };
+            let previous = seen.heads.insert(branch.clone(), head.oid.clone());
+            let Some(previous) = previous else {
+                // A branch created since, whose history was pushed to another branch first
+                debug!("Branch {} of {} appeared, recording its head without announcing it", branch, repo);
+                continue;
+            };
+            let new: Vec<&ActivityCommit> = commits.iter().take_while(|commit| commit.oid != previous).collect();
+            if new.is_empty() {
+                continue;
+            }
+            if new.len() == commits.len() {
+                warn!(
+                    "More than {} commits were pushed to {} of {} since the last poll, the older ones are not announced",
+                    ACTIVITY_COMMITS, branch, repo
+                );
+            }
+            events.push(("push", WebhookEvent::Push(push_event(&repo, branch, new))));
+        }
+
+        for release in activity.releases.iter().rev().filter(|release| !release.is_draft) {
+            let Some(id) = release.database_id else {
+                continue;
+            };
+            if seen.releases.insert(id) {
+                events.push(("release", WebhookEvent::Release(release_event(&repo, id, release))));
+            }
+        }
+        events
+    }
+}
+
+#[async_trait]
+impl EventSource for GraphQlPoller {
+    fn name(&self) -> &'static str {
+        "github_poll"
+    }
+
+    async fn run(&self, events: mpsc::Sender<RepoEvent>) -> Result<()> {
+        info!(
+            "Polling the activity of {} every {} seconds",
+            self.github_client.repo().await, self.interval.as_secs()
+        );
+
+        let mut poller = self.polls.poller("github_poll", self.interval);
+        loop {
+            poller.wait().await;
+            let result = self.github_client.recent_activity(&self.branches).await;
+            poller.record(&result);
+            let activity = match result {
+                Ok(activity) => activity,
+                Err(e) => {
+                    error!("Failed to poll the activity of {}: {:?}", self.github_client.repo().await, e);
+                    continue;
+                }
+            };
+
+            let origin = format!("the poll of {}", self.github_client.repo().await);
+            for (event_type, event) in self.changes(activity).await {
+                let event = RepoEvent { event_type: event_type.to_owned(), origin: origin.clone(), event };
+                if events.send(event).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Returns the repository as reported by the events
+fn repository(repo: &RepoName) -> Repository {
+    Repository {
+        full_name: repo.full_name(),
+        owner: GitHubUser { login: repo.owner.clone(), id: 0 },
+        html_url: None,
+    }
+}
+
+/// Converts the new commits of a branch, newest first, to the push event they would have come with
+fn push_event(repo: &RepoName, branch: &str, commits: Vec<&ActivityCommit>) -> PushEvent {
+    let sender = commits.first()
+        .and_then(|commit| commit.login())
+        .unwrap_or(&repo.owner)
+        .to_owned();
+    let commits = commits
+        .into_iter()
+        .rev()
+        .map(|commit| {
+            let author = commit.author.as_ref();
+            Commit {
+                id: commit.oid.clone(),
+                message: commit.message.clone(),
+                timestamp: commit.committed_date,
+                author: CommitAuthor {
+                    name: author.and_then(|author| author.name.clone()).unwrap_or_default(),
+                    email: author.and_then(|author| author.email.clone()).unwrap_or_default(),
+                    username: commit.login().map(str::to_owned),
+                },
+                url: commit.url.clone(),
+            }
+        })
+        .collect();
+
+    PushEvent {
+        git_ref: format!("refs/heads/{}", branch),
+        commits,
+        repository: repository(repo),
+        sender: GitHubUser { login: sender, id: 0 },
+    }
+}
+
+/// Converts a new release to the event of its publication
+fn release_event(repo: &RepoName, id: u64, release: &ActivityRelease) -> ReleaseEvent {
+    ReleaseEvent {
+        action: ReleaseAction::Published,
+        release: Release {
+            id,
+            tag_name: release.tag_name.clone(),
+            name: release.name.clone(),
+            html_url: release.url.clone(),
+            body: release.description.clone(),
+            prerelease: release.is_prerelease,
+            // Looked up by the handler when it waits for them
+            assets: Vec::new(),
+        },
+        repository: repository(repo),
+        changes: None,
+    }
+}
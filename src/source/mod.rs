@@ -0,0 +1,52 @@
+pub mod events;
+pub mod graphql;
+
+use crate::github::{checks::CheckRun, types::Release};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A forge whose events are announced, such as GitHub or GitLab.
+///
+/// Events of every forge are converted to the GitHub payloads the webhook handler
+/// understands, and the handler asks the forge they came from for anything the payloads
+/// lack, such as the assets of a release or the CI of its tag.
+#[async_trait]
+pub trait Source: Send + Sync {
+    /// Name of the forge, e.g. `github`
+    fn name(&self) -> &'static str;
+
+    /// Returns true once the contributors of a branch were gathered, so first contributions
+    /// can be checked without waiting.
+    ///
+    /// # Arguments
+    /// * `branch` - The name of a monitored branch.
+    fn contributors_ready(&self, branch: &str) -> bool;
+
+    /// Lists the names of the assets attached to a release.
+    ///
+    /// # Arguments
+    /// * `release` - The release, as reported by its event.
+    ///
+    /// # Returns
+    /// A result containing the asset names, or an error if the release cannot be read.
+    async fn list_release_assets(&self, release: &Release) -> Result<Vec<String>>;
+
+    /// Lists the CI jobs run on the commit a tag points to.
+    ///
+    /// # Arguments
+    /// * `tag` - The name of the tag, e.g. `v1.0.0`.
+    ///
+    /// # Returns
+    /// A result containing the jobs as check runs, or an error if the request fails.
+    async fn list_tag_check_runs(&self, tag: &str) -> Result<Vec<CheckRun>>;
+
+    /// Finds the X handle a user links to from their profile.
+    ///
+    /// # Arguments
+    /// * `username` - The username of the user on the forge.
+    ///
+    /// # Returns
+    /// A result containing the handle without the `@`, `None` if the profile doesn't link to X,
+    /// or an error if the profile cannot be read.
+    async fn x_handle(&self, username: &str) -> Result<Option<String>>;
+}
@@ -0,0 +1,106 @@
+use crate::{
+    announce::{Announcement, Announcer},
+    audit::log::{AnnouncementKind, AuditStatus},
+    config::{env::{HttpConfig, TeamsConfig}, tls}};
+use std::sync::LazyLock;
+use reqwest::Certificate;
+use serde_json::{json, Value};
+use regex::Regex;
+use async_trait::async_trait;
+use anyhow::Result;
+use tracing::debug;
+
+/// Links in the text of an announcement
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"https?://[^\s<>"]+"#).unwrap());
+
+/// Posts the announcements to a Microsoft Teams channel as Adaptive Cards, through an incoming webhook.
+///
+/// Each card has a title, the text of the announcement, and a button opening its first link,
+/// such as the release notes or the commit of a contributor.
+pub struct TeamsAnnouncer {
+    http: reqwest::Client,
+    webhook_url: String,
+    config: TeamsConfig,
+}
+
+impl TeamsAnnouncer {
+    /// Creates a new instance of `TeamsAnnouncer`.
+    ///
+    /// # Arguments
+    /// * `webhook_url` - The URL of the incoming webhook of the channel.
+    /// * `config` - The kinds of announcements posted to the channel.
+    /// * `http_config` - The extra root certificates to trust, and the timeouts shared with GitHub.
+    ///
+    /// # Returns
+    /// A result containing the announcer, or an error if the extra root certificates cannot be read.
+    pub fn new(webhook_url: &str, config: TeamsConfig, http_config: &HttpConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.github.connect())
+            .timeout(http_config.timeout.github.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
+
+        Ok(Self {
+            http: http.build()?,
+            webhook_url: webhook_url.to_string(),
+            config,
+        })
+    }
+}
+
+#[async_trait]
+impl Announcer for TeamsAnnouncer {
+    fn name(&self) -> &str {
+        "teams"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        // Threads of follow-ups, edits and retractions change posts the channel doesn't have
+        let posted = matches!(announcement.status, AuditStatus::Posted | AuditStatus::Corrected);
+        posted && !announcement.text.is_empty() && self.config.kinds.contains(&announcement.kind)
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        self.http.post(&self.webhook_url).json(&message(announcement)).send().await
+            .and_then(|response| response.error_for_status())
+            // The URL of the webhook is its secret, keep it out of the logs
+            .map_err(|e| e.without_url())?;
+        debug!("Posted {} announcement {} to Teams", announcement.status.as_str(), announcement.idempotency_key);
+        Ok(None)
+    }
+}
+
+/// Builds the message of an announcement, holding its Adaptive Card
+fn message(entry: &Announcement) -> Value {
+    let subject = entry.subject.as_deref().unwrap_or(&entry.repo);
+    let title = match (entry.status, entry.kind) {
+        (AuditStatus::Corrected, _) => format!("Correction: {}", subject),
+        (_, AnnouncementKind::Release) => format!("New release: {}", subject),
+        (_, AnnouncementKind::Contributor) => format!("New contributor: {}", subject),
+        _ => subject.to_string(),
+    };
+
+    let mut card = json!({
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "type": "AdaptiveCard",
+        "version": "1.4",
+        "body": [
+            { "type": "TextBlock", "text": title, "size": "Large", "weight": "Bolder", "wrap": true },
+            { "type": "TextBlock", "text": entry.repo, "isSubtle": true, "spacing": "None", "wrap": true },
+            { "type": "TextBlock", "text": entry.text, "wrap": true },
+        ],
+    });
+    if let Some(url) = URL.find(&entry.text) {
+        card["actions"] = json!([{ "type": "Action.OpenUrl", "title": "Open", "url": url.as_str() }]);
+    }
+
+    json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "contentUrl": null,
+            "content": card,
+        }],
+    })
+}
@@ -0,0 +1,111 @@
+use crate::release::notes::truncate;
+use std::sync::LazyLock;
+use regex::Regex;
+
+static MARKDOWN_IMAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"!\[([^\]]*)\]\([^)]*\)").expect("valid markdown image regex")
+});
+
+static MARKDOWN_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[([^\]]*)\]\([^)]*\)").expect("valid markdown link regex")
+});
+
+// Headings, quotes and bullets at the start of a line
+static MARKDOWN_LINE_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:#{1,6}[ \t]+|>[ \t]?|[-*+][ \t]+)").expect("valid markdown line prefix regex")
+});
+
+// Emphasis markers, leaving underscores inside identifiers such as `snake_case` alone
+static MARKDOWN_EMPHASIS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\*\*|__|~~|\*|`|\b_|_\b").expect("valid markdown emphasis regex")
+});
+
+/// Applies a template helper to the value of a placeholder, as in `{title|truncate(40)}`.
+///
+/// The supported helpers are:
+/// * `truncate(n)` - Shortens the value to at most `n` characters, ending with an ellipsis.
+/// * `first_line` - Keeps the first non-empty line of the value.
+/// * `strip_markdown` - Turns markdown into plain text, keeping the text of links.
+/// * `hashtagify` - Turns the value into a hashtag, e.g. `spark connect` into `#SparkConnect`.
+/// * `shorten_url` - Drops the scheme, `www.` and trailing slash of a URL.
+/// * `emoji` - Replaces a type such as `release` or `bug` with its emoji. With an
+///   argument, as in `emoji(release)`, the argument is used as the type instead.
+///
+/// # Arguments
+/// * `helper` - The helper and its argument, e.g. `truncate(40)`.
+/// * `value` - The value to transform.
+///
+/// # Returns
+/// The transformed value, or `None` if the helper or its argument is unknown.
+pub fn apply(helper: &str, value: &str) -> Option<String> {
+    let helper = helper.trim();
+    let (name, argument) = match helper.split_once('(') {
+        Some((name, rest)) => (name.trim(), Some(rest.strip_suffix(')')?.trim())),
+        None => (helper, None),
+    };
+
+    match (name, argument) {
+        ("truncate", Some(max_chars)) => Some(truncate(value, max_chars.parse().ok()?)),
+        ("first_line", None) => Some(first_line(value).to_string()),
+        ("strip_markdown", None) => Some(strip_markdown(value)),
+        ("hashtagify", None) => Some(hashtagify(value)),
+        ("shorten_url", None) => Some(shorten_url(value).to_string()),
+        ("emoji", kind) => emoji(kind.unwrap_or(value)).map(str::to_string),
+        _ => None,
+    }
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default()
+}
+
+fn strip_markdown(text: &str) -> String {
+    let text = MARKDOWN_IMAGE.replace_all(text, "$1");
+    let text = MARKDOWN_LINK.replace_all(&text, "$1");
+    let text = MARKDOWN_LINE_PREFIX.replace_all(&text, "");
+    MARKDOWN_EMPHASIS.replace_all(&text, "").trim().to_string()
+}
+
+fn hashtagify(text: &str) -> String {
+    let tag: String = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().into_iter().flat_map(char::to_uppercase).chain(chars).collect::<String>()
+        })
+        .collect();
+
+    // X doesn't link hashtags made only of digits
+    if tag.chars().all(|c| c.is_ascii_digit()) {
+        return String::new();
+    }
+    format!("#{}", tag)
+}
+
+fn shorten_url(url: &str) -> &str {
+    let url = url.trim();
+    let url = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")).unwrap_or(url);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    url.strip_suffix('/').unwrap_or(url)
+}
+
+fn emoji(kind: &str) -> Option<&'static str> {
+    let emoji = match kind.trim().to_lowercase().as_str() {
+        "release" => "🚀",
+        "prerelease" => "🧪",
+        "contributor" => "🎉",
+        "star" | "stargazer" => "⭐",
+        "milestone" => "🏁",
+        "digest" => "🛠️",
+        "bug" | "fix" => "🐛",
+        "feature" | "enhancement" => "✨",
+        "docs" | "documentation" => "📚",
+        "performance" => "⚡",
+        "security" => "🔒",
+        "breaking" => "💥",
+        "deprecation" => "🗑️",
+        _ => return None,
+    };
+    Some(emoji)
+}
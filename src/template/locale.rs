@@ -0,0 +1,214 @@
+use super::render::render;
+use std::str::FromStr;
+use chrono::{DateTime, Datelike, Duration, Utc};
+use serde::Deserialize;
+
+/// Language and conventions used to format numbers, dates and relative times in announcements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+// convert string from env var file to Locale, regional variants such as `de-AT` use their language
+impl FromStr for Locale {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "en" => Ok(Locale::En),
+            "de" => Ok(Locale::De),
+            "fr" => Ok(Locale::Fr),
+            "es" => Ok(Locale::Es),
+            _ => Err(anyhow::anyhow!("Unsupported locale: {}", s)),
+        }
+    }
+}
+
+/// Phrases and conventions of a locale. Phrases are templates taking the count as `{n}`.
+struct Catalog {
+    thousands_separator: &'static str,
+    months: [&'static str; 12],
+    /// Template of a date, taking the `{day}` and `{month}` placeholders
+    date: &'static str,
+    today: &'static str,
+    tomorrow: &'static str,
+    yesterday: &'static str,
+    in_days: &'static str,
+    days_ago: &'static str,
+    next_week: &'static str,
+    last_week: &'static str,
+    in_weeks: &'static str,
+    weeks_ago: &'static str,
+    in_months: &'static str,
+    months_ago: &'static str,
+    next_year: &'static str,
+    last_year: &'static str,
+    in_years: &'static str,
+    years_ago: &'static str,
+}
+
+const EN: Catalog = Catalog {
+    thousands_separator: ",",
+    months: [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ],
+    date: "{month} {day}",
+    today: "today",
+    tomorrow: "tomorrow",
+    yesterday: "yesterday",
+    in_days: "in {n} days",
+    days_ago: "{n} days ago",
+    next_week: "next week",
+    last_week: "last week",
+    in_weeks: "in {n} weeks",
+    weeks_ago: "{n} weeks ago",
+    in_months: "in {n} months",
+    months_ago: "{n} months ago",
+    next_year: "next year",
+    last_year: "last year",
+    in_years: "in {n} years",
+    years_ago: "{n} years ago",
+};
+
+const DE: Catalog = Catalog {
+    thousands_separator: ".",
+    months: [
+        "Januar", "Februar", "März", "April", "Mai", "Juni",
+        "Juli", "August", "September", "Oktober", "November", "Dezember",
+    ],
+    date: "{day}. {month}",
+    today: "heute",
+    tomorrow: "morgen",
+    yesterday: "gestern",
+    in_days: "in {n} Tagen",
+    days_ago: "vor {n} Tagen",
+    next_week: "nächste Woche",
+    last_week: "letzte Woche",
+    in_weeks: "in {n} Wochen",
+    weeks_ago: "vor {n} Wochen",
+    in_months: "in {n} Monaten",
+    months_ago: "vor {n} Monaten",
+    next_year: "nächstes Jahr",
+    last_year: "letztes Jahr",
+    in_years: "in {n} Jahren",
+    years_ago: "vor {n} Jahren",
+};
+
+const FR: Catalog = Catalog {
+    // Narrow no-break space, so large numbers aren't split across lines
+    thousands_separator: "\u{202f}",
+    months: [
+        "janvier", "février", "mars", "avril", "mai", "juin",
+        "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+    ],
+    date: "{day} {month}",
+    today: "aujourd'hui",
+    tomorrow: "demain",
+    yesterday: "hier",
+    in_days: "dans {n} jours",
+    days_ago: "il y a {n} jours",
+    next_week: "la semaine prochaine",
+    last_week: "la semaine dernière",
+    in_weeks: "dans {n} semaines",
+    weeks_ago: "il y a {n} semaines",
+    in_months: "dans {n} mois",
+    months_ago: "il y a {n} mois",
+    next_year: "l'année prochaine",
+    last_year: "l'année dernière",
+    in_years: "dans {n} ans",
+    years_ago: "il y a {n} ans",
+};
+
+const ES: Catalog = Catalog {
+    thousands_separator: ".",
+    months: [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio",
+        "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+    ],
+    date: "{day} de {month}",
+    today: "hoy",
+    tomorrow: "mañana",
+    yesterday: "ayer",
+    in_days: "en {n} días",
+    days_ago: "hace {n} días",
+    next_week: "la próxima semana",
+    last_week: "la semana pasada",
+    in_weeks: "en {n} semanas",
+    weeks_ago: "hace {n} semanas",
+    in_months: "en {n} meses",
+    months_ago: "hace {n} meses",
+    next_year: "el próximo año",
+    last_year: "el año pasado",
+    in_years: "en {n} años",
+    years_ago: "hace {n} años",
+};
+
+impl Locale {
+    fn catalog(&self) -> &'static Catalog {
+        match self {
+            Locale::En => &EN,
+            Locale::De => &DE,
+            Locale::Fr => &FR,
+            Locale::Es => &ES,
+        }
+    }
+
+    /// Formats a count with the thousands separator of the locale, e.g. `12,500` or `12.500`.
+    pub fn format_count(&self, count: u64) -> String {
+        let separator = self.catalog().thousands_separator;
+        let digits = count.to_string();
+        let mut formatted = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                formatted.push_str(separator);
+            }
+            formatted.push(digit);
+        }
+        formatted
+    }
+
+    /// Formats the day and month of a date, e.g. `March 5` or `5. März`.
+    pub fn format_date(&self, date: DateTime<Utc>) -> String {
+        let catalog = self.catalog();
+        render(catalog.date, &[
+            ("day", &date.day().to_string()),
+            ("month", catalog.months[date.month0() as usize]),
+        ])
+    }
+
+    /// Names the month of a date, e.g. `March` or `März`.
+    pub fn format_month(&self, date: DateTime<Utc>) -> String {
+        self.catalog().months[date.month0() as usize].to_string()
+    }
+
+    /// Phrases a time relative to now, e.g. `in 3 days`, `next week` or `3 years ago`.
+    ///
+    /// # Arguments
+    /// * `offset` - The time from now, negative for the past. It is rounded to the nearest day,
+    ///   so something due in 6 days and 20 hours is "next week".
+    pub fn relative(&self, offset: Duration) -> String {
+        let catalog = self.catalog();
+        let future = offset >= Duration::zero();
+        let days = (offset.num_hours().abs() + 12) / 24;
+
+        let phrase = |ahead: &'static str, ago: &'static str| if future { ahead } else { ago };
+        let count = |ahead: &'static str, ago: &'static str, n: i64| render(phrase(ahead, ago), &[("n", &n.to_string())]);
+        match days {
+            0 => catalog.today.to_string(),
+            1 => phrase(catalog.tomorrow, catalog.yesterday).to_string(),
+            2..=6 => count(catalog.in_days, catalog.days_ago, days),
+            7..=13 => phrase(catalog.next_week, catalog.last_week).to_string(),
+            14..=44 => count(catalog.in_weeks, catalog.weeks_ago, days / 7),
+            45..=364 => count(catalog.in_months, catalog.months_ago, (days + 15) / 30),
+            365..=729 => phrase(catalog.next_year, catalog.last_year).to_string(),
+            _ => count(catalog.in_years, catalog.years_ago, days / 365),
+        }
+    }
+}
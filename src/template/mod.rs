@@ -0,0 +1,3 @@
+pub mod helpers;
+pub mod locale;
+pub mod render;
\ No newline at end of file
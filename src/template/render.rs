@@ -0,0 +1,65 @@
+use super::helpers;
+
+/// Renders an announcement template by replacing `{name}` placeholders with their values.
+///
+/// Values can be passed through helpers separated by `|`, as in `{title|first_line|truncate(40)}`,
+/// and helpers taking an argument can stand alone, as in `{emoji(release)}`; see
+/// [`helpers::apply`] for the list. Placeholders without a value or with an unknown helper
+/// are left untouched so mistakes are visible in the rendered text, and `{{` / `}}` produce
+/// literal braces.
+///
+/// # Arguments
+/// * `template` - The template text, e.g. `"New contributor {username}!"`.
+/// * `values` - Placeholder names and the values to substitute for them.
+///
+/// # Returns
+/// The rendered text.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        rendered.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            rendered.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+
+        if let Some(end) = tail.find('}').filter(|_| tail.starts_with('{')) {
+            match expand(&tail[1..end], values) {
+                Some(value) => rendered.push_str(&value),
+                None => rendered.push_str(&tail[..=end]),
+            }
+            rest = &tail[end + 1..];
+        } else {
+            rendered.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    rendered.push_str(rest);
+
+    rendered
+}
+
+/// Looks up the value of a placeholder and passes it through its helpers.
+fn expand(placeholder: &str, values: &[(&str, &str)]) -> Option<String> {
+    let mut parts = placeholder.split('|');
+    let name = parts.next()?.trim();
+    let mut value = match values.iter().find(|(key, _)| *key == name) {
+        Some((_, value)) => value.to_string(),
+        None if name.contains('(') => helpers::apply(name, "")?,
+        None => return None,
+    };
+    for helper in parts {
+        value = helpers::apply(helper, &value)?;
+    }
+    Some(value)
+}
+
+/// Turns the `\n` escape sequences of a template read from the environment into newlines.
+pub fn unescape(template: &str) -> String {
+    template.replace("\\n", "\n")
+}
@@ -0,0 +1,177 @@
+use super::handler::AppState;
+use crate::{
+    audit::log::{AnnouncementKind, AuditEntry, AuditStatus},
+    feed::atom};
+use std::sync::Arc;
+use axum::{
+    extract::{Query, State},
+    http::{header::{CACHE_CONTROL, CONTENT_TYPE}, StatusCode},
+    response::IntoResponse,
+    Json};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Announcements per page, unless requested otherwise
+const DEFAULT_PER_PAGE: usize = 20;
+
+/// Maximum announcements per page
+const MAX_PER_PAGE: usize = 100;
+
+/// Time shields.io, feed readers and browsers may cache a badge or the feed for, in seconds
+const BADGE_MAX_AGE_SECONDS: u64 = 300;
+
+/// Filters and page of the announcements
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementsQuery {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    repo: Option<String>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+}
+
+/// A page of announcements
+#[derive(Debug, Serialize)]
+pub struct AnnouncementsPage {
+    pub announcements: Vec<Announcement>,
+    pub page: usize,
+    pub per_page: usize,
+    /// Number of announcements matching the filters, across pages
+    pub total: usize,
+}
+
+/// An announcement as shown to the public
+#[derive(Debug, Serialize)]
+pub struct Announcement {
+    #[serde(rename = "type")]
+    pub kind: AnnouncementKind,
+    /// Repository the announcement is about, as `owner/name`
+    pub repo: String,
+    /// Human readable name of the announced object, such as a release tag
+    pub subject: Option<String>,
+    pub text: String,
+    /// Link to the post on X
+    pub url: Option<String>,
+    /// Whether the post was edited since
+    pub edited: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<AuditEntry> for Announcement {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            kind: entry.kind,
+            url: entry.remote_ids.get("x").map(|id| format!("https://x.com/i/web/status/{}", id)),
+            edited: entry.status == AuditStatus::Edited,
+            repo: entry.repo,
+            subject: entry.subject,
+            text: entry.text,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+// Lists the announcements that are still up, newest first, such as for the project website
+pub async fn list_announcements(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AnnouncementsQuery>,
+) -> Result<Json<AnnouncementsPage>, StatusCode> {
+    let kind = query.kind
+        .map(|kind| kind.parse::<AnnouncementKind>())
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE);
+    if page == 0 || !(1..=MAX_PER_PAGE).contains(&per_page) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let announcements = state.webhook_handler.audit_log().announcements(kind, query.repo.as_deref()).await;
+    let total = announcements.len();
+    Ok(Json(AnnouncementsPage {
+        announcements: announcements
+            .into_iter()
+            .skip((page - 1).saturating_mul(per_page))
+            .take(per_page)
+            .map(Announcement::from)
+            .collect(),
+        page,
+        per_page,
+        total,
+    }))
+}
+
+/// Filters of the Atom feed
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    repo: Option<String>,
+}
+
+// Serves the Atom feed of the announcements that are still up, for feed readers
+pub async fn atom_feed(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let kind = query.kind
+        .map(|kind| kind.parse::<AnnouncementKind>())
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let announcements = state.webhook_handler.audit_log().announcements(kind, query.repo.as_deref()).await;
+    Ok((
+        [
+            (CONTENT_TYPE, "application/atom+xml; charset=utf-8".to_string()),
+            (CACHE_CONTROL, format!("public, max-age={}", BADGE_MAX_AGE_SECONDS)),
+        ],
+        atom::render(&state.atom_feed, &announcements),
+    ))
+}
+
+/// Repository of the badge
+#[derive(Debug, Deserialize)]
+pub struct BadgeQuery {
+    repo: Option<String>,
+}
+
+/// A badge in the shields.io endpoint format, see https://shields.io/badges/endpoint-badge
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    pub schema_version: u8,
+    pub label: &'static str,
+    pub message: String,
+    pub color: &'static str,
+}
+
+// Shows the latest announced release and its date, for READMEs through shields.io
+pub async fn latest_release_badge(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BadgeQuery>,
+) -> impl IntoResponse {
+    let latest = state.webhook_handler.audit_log()
+        .announcements(Some(AnnouncementKind::Release), query.repo.as_deref())
+        .await
+        .into_iter()
+        .next();
+    let badge = match latest {
+        Some(entry) => Badge {
+            schema_version: 1,
+            label: "latest release",
+            message: format!(
+                "{} ({})",
+                entry.subject.as_deref().unwrap_or(&entry.source_id),
+                entry.timestamp.format("%Y-%m-%d")
+            ),
+            color: "blue",
+        },
+        None => Badge {
+            schema_version: 1,
+            label: "latest release",
+            message: "none".to_string(),
+            color: "lightgrey",
+        },
+    };
+    ([(CACHE_CONTROL, format!("public, max-age={}", BADGE_MAX_AGE_SECONDS))], Json(badge))
+}
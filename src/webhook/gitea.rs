@@ -0,0 +1,82 @@
+use crate::{
+    gitea::client::GiteaClient,
+    github::types::{PushEvent, ReleaseAction, ReleaseEvent, WebhookEvent},
+    webhook::{
+        handler::{respond, AppState, WebhookHandler},
+        signature}};
+use std::sync::Arc;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response}};
+use tracing::{debug, error, info, warn};
+
+/// Announcements of the Gitea or Forgejo repository, from the webhooks it sends to `/webhook/gitea`
+pub struct GiteaWebhooks {
+    pub gitea_client: Arc<GiteaClient>,
+    /// Handler announcing the events, backed by the Gitea repository
+    pub handler: Arc<WebhookHandler>,
+    /// Secret the webhooks are signed with in the `X-Gitea-Signature` header
+    pub secret: String,
+}
+
+/// Checks the signature Gitea, or Forgejo, computes over the body of a webhook, in constant time
+fn has_valid_signature(headers: &HeaderMap, body: &str, secret: &str) -> bool {
+    let signature = headers
+        .get("x-gitea-signature")
+        .or_else(|| headers.get("x-forgejo-signature"))
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    signature::verify(secret.as_bytes(), body.as_bytes(), signature)
+}
+
+// Webhook handler of the Gitea repository, whose payloads follow GitHub's
+pub async fn handle_gitea_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, StatusCode> {
+    let Some(gitea) = &state.gitea else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    if !has_valid_signature(&headers, &body, &gitea.secret) {
+        warn!("Rejected Gitea webhook: invalid signature");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let event_type = headers
+        .get("x-gitea-event")
+        .or_else(|| headers.get("x-forgejo-event"))
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    debug!("Received Gitea {} event", event_type);
+
+    match event_type {
+        "push" => {
+            let mut push_event: PushEvent = serde_json::from_str(&body).map_err(|e| {
+                error!("Failed to parse Gitea push event: {}", e);
+                StatusCode::UNPROCESSABLE_ENTITY
+            })?;
+            // Authors without an account are reported with an empty username
+            for commit in &mut push_event.commits {
+                commit.author.username = commit.author.username.take().filter(|username| !username.is_empty());
+            }
+            respond(Arc::clone(&gitea.handler), "Gitea push", WebhookEvent::Push(push_event)).await
+        }
+        "release" => {
+            let mut release_event: ReleaseEvent = serde_json::from_str(&body).map_err(|e| {
+                error!("Failed to parse Gitea release event: {}", e);
+                StatusCode::UNPROCESSABLE_ENTITY
+            })?;
+            // Gitea reports edits as updates, without what changed
+            if release_event.action == ReleaseAction::Other("updated".to_string()) {
+                release_event.action = ReleaseAction::Edited;
+            }
+            respond(Arc::clone(&gitea.handler), "Gitea release", WebhookEvent::Release(release_event)).await
+        }
+        _ => {
+            info!("Nothing is announced for Gitea {} events", event_type);
+            Ok(StatusCode::OK.into_response())
+        }
+    }
+}
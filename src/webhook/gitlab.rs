@@ -0,0 +1,99 @@
+use crate::{
+    github::types::{PushEvent, WebhookEvent},
+    gitlab::{
+        client::GitLabClient,
+        types::{PushHook, ReleaseHook}},
+    webhook::{
+        handler::{respond, AppState, WebhookHandler},
+        signature}};
+use std::{collections::HashMap, sync::Arc};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response}};
+use tracing::{debug, error, info, warn};
+
+/// Announcements of the GitLab project, from the webhooks it sends to `/webhook/gitlab`
+pub struct GitLabWebhooks {
+    pub gitlab_client: Arc<GitLabClient>,
+    /// Handler announcing the converted events, backed by the GitLab project
+    pub handler: Arc<WebhookHandler>,
+    /// Secret token GitLab sends in the `X-Gitlab-Token` header of every webhook
+    pub secret: String,
+}
+
+impl GitLabWebhooks {
+    /// Converts a push to a GitHub push event, looking up the usernames of its commit authors.
+    async fn push_event(&self, hook: PushHook) -> PushEvent {
+        let mut usernames = HashMap::new();
+        for commit in &hook.commits {
+            let email = commit.author.email.to_lowercase();
+            if email.is_empty() || usernames.contains_key(&email) {
+                continue;
+            }
+            if hook.user_email.as_deref().is_some_and(|pusher| pusher.eq_ignore_ascii_case(&email)) {
+                usernames.insert(email, hook.user_username.clone());
+                continue;
+            }
+            match self.gitlab_client.username_by_email(&email).await {
+                Ok(Some(username)) => {
+                    usernames.insert(email, username);
+                }
+                Ok(None) => debug!("No GitLab user shows {} on their profile", commit.author.name),
+                Err(e) => warn!("Failed to look up the GitLab user of {}: {:?}", commit.author.name, e),
+            }
+        }
+        hook.into_event(&usernames)
+    }
+}
+
+// Webhook handler of the GitLab project, announcing its pushes and releases like GitHub's
+pub async fn handle_gitlab_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, StatusCode> {
+    let Some(gitlab) = &state.gitlab else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let token = headers
+        .get("x-gitlab-token")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    if !signature::verify_token(token, &gitlab.secret) {
+        warn!("Rejected GitLab webhook: invalid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let event_type = headers
+        .get("x-gitlab-event")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    debug!("Received GitLab {} event", event_type);
+
+    match event_type {
+        "Push Hook" => {
+            let hook: PushHook = serde_json::from_str(&body).map_err(|e| {
+                error!("Failed to parse GitLab push event: {}", e);
+                StatusCode::UNPROCESSABLE_ENTITY
+            })?;
+            let push_event = gitlab.push_event(hook).await;
+            respond(Arc::clone(&gitlab.handler), "GitLab push", WebhookEvent::Push(push_event)).await
+        }
+        "Release Hook" => {
+            let hook: ReleaseHook = serde_json::from_str(&body).map_err(|e| {
+                error!("Failed to parse GitLab release event: {}", e);
+                StatusCode::UNPROCESSABLE_ENTITY
+            })?;
+            let release_event = hook.into_event();
+            // GitLab gives up on webhooks that take longer than 10 seconds to answer
+            respond(Arc::clone(&gitlab.handler), "GitLab release", WebhookEvent::Release(release_event)).await
+        }
+        _ => {
+            // GitLab disables webhooks that keep failing, so other events are acknowledged
+            info!("Nothing is announced for GitLab {} events", event_type);
+            Ok(StatusCode::OK.into_response())
+        }
+    }
+}
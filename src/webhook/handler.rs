@@ -1,37 +1,357 @@
 use crate::{
+    audit::{
+        export,
+        log::{normalize_identity, AnnouncementKind, AuditEntry, AuditLog, AuditStatus, Claim, PostPlan, ReplyTo}},
+    config::{duration::parse_duration, env::{
+        AtomFeedConfig,
+        BranchConfig,
+        BreakingChangeConfig,
+        ChannelConfig,
+        ContributorConfig,
+        FirstContributionPolicy,
+        HttpConfig,
+        IssueLabelConfig,
+        LinkedInConfig,
+        LongAwaitedFixConfig,
+        MemberConfig,
+        PublicRepoConfig,
+        ReleaseConfig,
+        ReleaseEditedPolicy,
+        SponsorsConfig,
+        TagConfig}},
     github::{
+        checks::CiStatus,
         client::GitHubClient, 
+        first_contribution::{strategy, Contribution, FirstContributionStrategy},
+        repository::RepoName,
         types::{
-            PingEvent, 
+            Commit,
+            CreateEvent,
+            DeploymentStatusEvent,
+            IssuesAction,
+            IssuesEvent,
+            MemberAction,
+            MemberEvent,
+            PageBuildEvent,
+            PayloadError,
+            PublicEvent,
+            PullRequestAction,
+            PullRequestEvent,
             PushEvent, 
-            ReleaseEvent}},
-    x::client::XClient};
-use std::sync::Arc;
+            RefType,
+            Release,
+            ReleaseAction,
+            ReleaseEvent,
+            RepositoryAction,
+            RepositoryEvent,
+            SponsorshipAction,
+            SponsorshipEvent,
+            SponsorshipPrivacy,
+            WebhookEvent}},
+    linkedin::client::LinkedInClient,
+    release::{
+        assets::AssetRequirement,
+        channel::{ChannelClassifier, ReleaseChannel},
+        notes::{changed_percent, highlights, truncate},
+        opengraph::OpenGraphImages,
+        version::Version},
+    scheduler::{
+        poll::PollMonitor,
+        request_budget::RequestBudget,
+        retry_budget::RetryBudget,
+        runtime::RuntimeMonitor,
+        threshold::highest_reached},
+    source::Source,
+    template::{helpers, render::render},
+    webhook::{gitea::GiteaWebhooks, gitlab::GitLabWebhooks, logging::spawn_in_request, signature},
+    x::{
+        client::XClient,
+        follows::is_valid_handle}};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock}};
 use axum::{
-    http::{StatusCode, HeaderMap},
-    extract::State};
+    http::{header::{CONTENT_DISPOSITION, CONTENT_TYPE}, StatusCode, HeaderMap},
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    Json};
+use serde::Deserialize;
+use serde_json::json;
+use regex::Regex;
 use anyhow::Result;
+use ring::digest;
+use chrono::{Duration, Utc};
+use tokio::time::{sleep, Instant};
 use tracing::{debug, error, info, warn};
 
+/// Maximum length of a tweet, in characters
+const MAX_TWEET_CHARS: usize = 280;
+
+/// Time a release follow-up waits for the release announcement beyond its asset wait
+const RELEASE_SEQUENCING_MARGIN: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Maximum length of the offending value reported when a payload fails to parse, in characters
+const MAX_DIAGNOSTIC_VALUE_CHARS: usize = 200;
+
 /// A handler for incoming webhook events from GitHub.
 pub struct WebhookHandler {
-    github_client: GitHubClient,
+    github_client: Arc<GitHubClient>,
+    /// Forge the contributors and releases come from, GitHub unless set with `with_source`
+    source: Arc<dyn Source>,
     x_client: Arc<XClient>,
+    audit_log: Arc<AuditLog>,
+    branches: Vec<BranchConfig>,
+    release_config: ReleaseConfig,
+    release_channels: ChannelClassifier,
+    release_assets: AssetRequirement,
+    contributor_config: ContributorConfig,
+    first_contribution: Box<dyn FirstContributionStrategy>,
+    member_config: MemberConfig,
+    opengraph_images: Option<OpenGraphImages>,
+    public_repo_config: Option<PublicRepoConfig>,
+    /// Settings of the tag announcements, with their compiled pattern
+    tag_config: Option<(TagConfig, Regex)>,
+    sponsors_config: Option<SponsorsConfig>,
+    breaking_change_config: Option<BreakingChangeConfig>,
+    issue_label_config: Option<IssueLabelConfig>,
+    /// Held while counting the labeled issues of the past hour and claiming one, so that
+    /// concurrent labels can't exceed the hourly cap
+    issue_label_cap: tokio::sync::Mutex<()>,
+    long_awaited_fix_config: Option<LongAwaitedFixConfig>,
+    /// Client of the LinkedIn organization page, with its settings, for the release channels routed to it
+    linkedin: Option<(Arc<LinkedInClient>, LinkedInConfig)>,
 }
 
 impl WebhookHandler {
     /// Creates a new instance of [WebhookHandler](WebhookHandler).
     ///
     /// # Arguments
-    /// * `github_client` - An instance of `GitHubClient` for interacting with the GitHub API.
+    /// * `github_client` - An Arc wrapped instance of `GitHubClient` for interacting with the GitHub API.
     /// * `x_client` - An Arc wrapped instance of [XClient](XClient) for thread-safe posting to Twitter.
+    /// * `audit_log` - An Arc wrapped [AuditLog](AuditLog) recording every announcement made.
+    /// * `branches` - The monitored branches and their announcement settings.
+    /// * `release_config` - Settings controlling how release events are announced.
+    /// * `contributor_config` - Settings controlling how new contributors are announced.
+    /// * `member_config` - Settings controlling how new collaborators are announced.
     ///
     /// # Returns
-    /// An instance of [WebhookHandler](WebhookHandler).
-    pub fn new(github_client: GitHubClient, x_client: Arc<XClient>) -> Self {
-        Self {
+    /// A result containing an instance of [WebhookHandler](WebhookHandler), or an error if a pattern of the release settings is invalid.
+    pub fn new(
+        github_client: Arc<GitHubClient>,
+        x_client: Arc<XClient>,
+        audit_log: Arc<AuditLog>,
+        branches: Vec<BranchConfig>,
+        release_config: ReleaseConfig,
+        contributor_config: ContributorConfig,
+        member_config: MemberConfig,
+    ) -> Result<Self> {
+        let release_channels = ChannelClassifier::new(&release_config.channels)?;
+        let release_assets = AssetRequirement::new(
+            release_config.asset_wait.min_assets,
+            &release_config.asset_wait.patterns,
+        )?;
+
+        let first_contribution = strategy(contributor_config.first_contribution, Arc::clone(&github_client));
+
+        Ok(Self {
+            source: Arc::clone(&github_client) as Arc<dyn Source>,
             github_client,
             x_client,
+            audit_log,
+            branches,
+            release_config,
+            release_channels,
+            release_assets,
+            contributor_config,
+            first_contribution,
+            member_config,
+            opengraph_images: None,
+            public_repo_config: None,
+            tag_config: None,
+            sponsors_config: None,
+            breaking_change_config: None,
+            issue_label_config: None,
+            issue_label_cap: tokio::sync::Mutex::new(()),
+            long_awaited_fix_config: None,
+            linkedin: None,
+        })
+    }
+
+    /// Announces the contributors and releases of another forge than GitHub, whose events are
+    /// converted to GitHub payloads.
+    ///
+    /// # Arguments
+    /// * `source` - The forge the events come from.
+    /// * `first_contribution` - How first contributions are told apart on that forge.
+    pub fn with_source(mut self, source: Arc<dyn Source>, first_contribution: Box<dyn FirstContributionStrategy>) -> Self {
+        info!("Announcing the contributors and releases of {}", source.name());
+        self.source = source;
+        self.first_contribution = first_contribution;
+        self
+    }
+
+    /// Fetches the OpenGraph image of release pages when enabled in the release settings.
+    ///
+    /// # Arguments
+    /// * `http_config` - The extra root certificates trusted when fetching release pages.
+    pub fn with_opengraph_images(mut self, http_config: &HttpConfig) -> Result<Self> {
+        if self.release_config.opengraph_image {
+            self.opengraph_images = Some(OpenGraphImages::new(http_config)?);
+        }
+        Ok(self)
+    }
+
+    /// Announces the repositories of the organization that are made public when enabled.
+    ///
+    /// # Arguments
+    /// * `public_repo_config` - Settings controlling how publicized repositories are announced.
+    pub fn with_public_repos(mut self, public_repo_config: PublicRepoConfig) -> Self {
+        self.public_repo_config = Some(public_repo_config).filter(|config| config.enabled);
+        self
+    }
+
+    /// Announces the tags matching the pattern of the tag settings when enabled.
+    ///
+    /// # Arguments
+    /// * `tag_config` - Settings controlling which tags are announced, and how.
+    ///
+    /// # Returns
+    /// A result containing the handler, or an error if the tag pattern is invalid.
+    pub fn with_tags(mut self, tag_config: TagConfig) -> Result<Self> {
+        if tag_config.enabled {
+            let pattern = Regex::new(&tag_config.pattern)?;
+            self.tag_config = Some((tag_config, pattern));
+        }
+        Ok(self)
+    }
+
+    /// Thanks new GitHub Sponsors and announces sponsor count milestones when enabled.
+    ///
+    /// # Arguments
+    /// * `sponsors_config` - Settings controlling how sponsors are thanked.
+    pub fn with_sponsors(mut self, sponsors_config: SponsorsConfig) -> Self {
+        self.sponsors_config = Some(sponsors_config).filter(|config| config.enabled);
+        self
+    }
+
+    /// Gives a heads-up on merged pull requests labeled as breaking changes when enabled.
+    ///
+    /// # Arguments
+    /// * `breaking_change_config` - Settings controlling which pull requests are breaking changes, and how they are announced.
+    pub fn with_breaking_changes(mut self, breaking_change_config: BreakingChangeConfig) -> Self {
+        self.breaking_change_config = Some(breaking_change_config).filter(|config| config.enabled);
+        self
+    }
+
+    /// Announces open issues given one of the configured labels when enabled.
+    ///
+    /// # Arguments
+    /// * `issue_label_config` - Settings controlling which labels are announced, how, and how often.
+    pub fn with_issue_labels(mut self, issue_label_config: IssueLabelConfig) -> Self {
+        self.issue_label_config = Some(issue_label_config).filter(|config| config.enabled);
+        self
+    }
+
+    /// Announces long-standing issues once they are closed as completed when enabled.
+    ///
+    /// # Arguments
+    /// * `long_awaited_fix_config` - Settings controlling how old an issue must be to be announced, and how.
+    pub fn with_long_awaited_fixes(mut self, long_awaited_fix_config: LongAwaitedFixConfig) -> Self {
+        self.long_awaited_fix_config = Some(long_awaited_fix_config).filter(|config| config.enabled);
+        self
+    }
+
+    /// Posts the releases of the channels routed to the `linkedin` sink to a LinkedIn organization page.
+    ///
+    /// # Arguments
+    /// * `client` - The client posting to the page, `None` when LinkedIn is disabled.
+    /// * `config` - Settings controlling how releases are announced on LinkedIn.
+    pub fn with_linkedin(mut self, client: Option<Arc<LinkedInClient>>, config: LinkedInConfig) -> Self {
+        self.linkedin = client.map(|client| (client, config));
+        self
+    }
+
+    /// Creates a handler of another repository announced with the same settings, such as a
+    /// repository found in the organization. Sponsorships are left out, they are announced
+    /// for the account of the main repository.
+    ///
+    /// # Arguments
+    /// * `github_client` - The client of the repository.
+    /// * `branches` - The monitored branches of the repository.
+    ///
+    /// # Returns
+    /// A result containing the handler, or an error if a pattern of the release settings is invalid.
+    pub fn for_repository(&self, github_client: Arc<GitHubClient>, branches: Vec<BranchConfig>) -> Result<Self> {
+        let mut handler = Self::new(
+            github_client,
+            Arc::clone(&self.x_client),
+            Arc::clone(&self.audit_log),
+            branches,
+            self.release_config.clone(),
+            self.contributor_config.clone(),
+            self.member_config.clone(),
+        )?;
+        handler.opengraph_images = self.opengraph_images.clone();
+        handler.public_repo_config = self.public_repo_config.clone();
+        handler.tag_config = self.tag_config.clone();
+        handler.breaking_change_config = self.breaking_change_config.clone();
+        handler.issue_label_config = self.issue_label_config.clone();
+        handler.long_awaited_fix_config = self.long_awaited_fix_config.clone();
+        handler.linkedin = self.linkedin.clone();
+        Ok(handler)
+    }
+
+    /// The audit log the announcements are recorded in.
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit_log
+    }
+
+    /// Handles any event from GitHub right away, without holding it in the background
+    /// like the webhook route does for pushes and releases that have to wait.
+    ///
+    /// # Arguments
+    /// * `event` - The parsed event.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    pub async fn handle(&self, event: WebhookEvent) -> Result<()> {
+        match event {
+            WebhookEvent::Push(event) => self.handle_push(event).await,
+            WebhookEvent::Release(event) => self.handle_release(event).await,
+            WebhookEvent::Repository(event) => self.handle_repository(event).await,
+            WebhookEvent::Member(event) => self.handle_member(event).await,
+            WebhookEvent::Public(event) => self.handle_public(event).await,
+            WebhookEvent::Create(event) => self.handle_create(event).await,
+            WebhookEvent::Sponsorship(event) => self.handle_sponsorship(event).await,
+            WebhookEvent::PullRequest(event) => self.handle_pull_request(event).await,
+            WebhookEvent::Issues(event) => self.handle_issues(event).await,
+            WebhookEvent::DeploymentStatus(event) => self.handle_deployment_status(event).await,
+            WebhookEvent::PageBuild(event) => self.handle_page_build(event).await,
+            _ => {
+                info!("Nothing is announced for this kind of event");
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns true if handling the event involves waiting for something, such as the assets of
+    /// a release or the announcement a follow-up replies to, longer than a webhook is answered in.
+    pub fn holds(&self, event: &WebhookEvent) -> bool {
+        match event {
+            // Contributor announcements are held until the contributors are gathered
+            WebhookEvent::Push(event) => self.waits_for_contributors(event),
+            // Waiting for the assets or the CI, or for the announcement an edit corrects or a deletion retracts
+            WebhookEvent::Release(event) => {
+                self.waits_before_announcing(event)
+                    || (matches!(event.action, ReleaseAction::Edited | ReleaseAction::Deleted)
+                        && self.waits_for_release(&event.repository.full_name))
+            }
+            // The tag is announced once it is known not to have a release
+            WebhookEvent::Create(event) => self.waits_for_tag_release(event),
+            // The docs follow-up is posted once the release it replies to is announced
+            WebhookEvent::DeploymentStatus(event) => self.waits_for_release(&event.repository.full_name),
+            WebhookEvent::PageBuild(event) => self.waits_for_release(&event.repository.full_name),
+            _ => false,
         }
     }
 
@@ -44,29 +364,36 @@ impl WebhookHandler {
     /// A result indicating success or failure.
     /// Key Features
     /// Event Filtering:
-    /// The method only processes pushes to the master or main branches. If the push is to a different branch, it returns early with Ok(()).
+    /// The method only processes pushes to the monitored branches. If the push is to a different branch, it returns early with Ok(()).
     /// Iterating Over Commits:
     /// It iterates through the commits in the push event, checking each commit for the author's username.
     /// First Contribution Check:
-    /// For each commit, it checks if the author is making their first contribution using self.github_client.is_first_contribution(&username).await?.
+    /// For the first commit of every author, it checks if the author is making their first contribution
+    /// using the strategy of the configured `FirstContributionPolicy`.
+    /// Deduplication:
+    /// Every commit is handled once, even when the push is delivered again. A contributor whose login or name was already announced within the configured dedupe window is skipped and recorded as such in the audit log.
     /// Tweet Formatting:
-    /// Constructs a tweet message that includes the contributor's username, commit message, and a link to the commit.
+    /// Renders the branch's contributor template with the contributor's username, commit message, and a link to the commit.
     /// Posting to X (Twitter):
-    /// Uses the self.x_client.post_with_retry(&tweet).await? method to post the tweet to X.
+    /// Queues the tweet in the outbox with claim.enqueue(entry), the [Outbox](crate::x::outbox::Outbox) posts it to X.
     /// Logging:
-    /// Logs the tweet message before posting it.
+    /// Logs the tweet message before queuing it.
     pub async fn handle_push(&self, event: PushEvent) -> Result<()> {
         debug!("Handling push event for ref: {}", event.git_ref);
         
-        // Only handle pushes to master/main branch
-        if !event.git_ref.ends_with("/main") && !event.git_ref.ends_with("/master") {
-            debug!("Ignoring push to non-main branch: {}", event.git_ref);
+        // Only handle pushes to monitored branches
+        let branch_name = event.git_ref.strip_prefix("refs/heads/").unwrap_or(&event.git_ref);
+        let Some(branch) = self.branches.iter().find(|branch| branch.name == branch_name) else {
+            debug!("Ignoring push to unmonitored branch: {}", event.git_ref);
             return Ok(());
-        }
+        };
 
-        info!("Processing push to main branch with {} commits", event.commits.len());
+        info!("Processing push to branch {} with {} commits", branch.name, event.commits.len());
         let repo_owner = &event.repository.owner.login;
+        let repo = &event.repository.full_name;
         
+        // Commits are listed oldest first, only the first one of every contributor is checked
+        let mut checked = HashSet::new();
         for commit in event.commits {
             if let Some(username) = &commit.author.username {
                 // Skip if the committer is the repo owner
@@ -74,24 +401,21 @@ impl WebhookHandler {
                     debug!("Skipping commit from repository owner: {}", username);
                     continue;
                 }
+                if !checked.insert(username.clone()) {
+                    continue;
+                }
 
                 debug!("Checking if {} is a first-time contributor", username);
                 
-                if self.github_client.is_first_contribution(username).await? {
+                let contribution = Contribution {
+                    branch: &branch.name,
+                    username,
+                    sha: &commit.id,
+                    committed_at: commit.timestamp,
+                };
+                if self.first_contribution.is_first_contribution(&contribution).await? {
                     info!("Found first-time contributor: {}", username);
-                    
-                    let tweet = format!(
-                        "Delta got a new contributor {}!\nDetails: {}\nLink: {}",
-                        username,
-                        commit.message,
-                        commit.url
-                    );
-                    
-                    info!("Posting tweet about new contributor: {}", tweet);
-                    match self.x_client.post_with_retry(&tweet).await {
-                        Ok(_) => info!("Successfully posted tweet about new contributor {}", username),
-                        Err(e) => error!("Failed to post tweet about new contributor: {:?}", e),
-                    }
+                    self.announce_contributor(repo, branch, username, &commit).await;
                 } else {
                     debug!("Contributor {} has previous contributions", username);
                 }
@@ -103,6 +427,103 @@ impl WebhookHandler {
         Ok(())
     }
 
+    /// Announces a new contributor, crediting one of their commits.
+    ///
+    /// The announcement is skipped if the commit was already handled, or if the contributor's
+    /// login or name was announced within the configured dedupe window.
+    ///
+    /// # Arguments
+    /// * `repo` - The repository, as `owner/name`.
+    /// * `branch` - The branch the commit is on, whose template is used.
+    /// * `username` - The login of the contributor.
+    /// * `commit` - The commit credited in the announcement.
+    pub async fn announce_contributor(&self, repo: &str, branch: &BranchConfig, username: &str, commit: &Commit) {
+        // Keyed on the commit so a redelivered push isn't announced twice
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::Contributor, repo, &commit.id).await else {
+            debug!("Commit {} of {} was already handled", commit.id, username);
+            return;
+        };
+
+        let identities = vec![normalize_identity(username), normalize_identity(&commit.author.name)];
+        if let Some(previous) = self.recent_contributor_announcement(repo, &identities).await {
+            info!(
+                "Skipping new contributor {}: already announced at {}",
+                username, previous.timestamp
+            );
+            let skipped = AuditEntry::new(
+                AnnouncementKind::Contributor,
+                AuditStatus::Skipped,
+                repo,
+                &commit.id,
+                &format!("Duplicate of the announcement made at {}", previous.timestamp),
+            );
+            self.record(skipped.with_subject(username).with_identities(&identities)).await;
+            return;
+        }
+
+        let tweet = render(&branch.contributor_template, &[
+            ("username", username),
+            ("message", &commit.message),
+            ("url", &commit.url),
+            ("branch", &branch.name),
+        ]);
+
+        // The contributor is followed by the outbox once the announcement is posted
+        let plan = PostPlan {
+            follow: self.contributor_x_handle(username).await,
+            ..PostPlan::default()
+        };
+        info!("Posting tweet about new contributor: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::Contributor, AuditStatus::Queued, repo, &commit.id, &tweet)
+                .with_subject(username)
+                .with_identities(&identities)
+                .with_plan(plan)
+        ).await;
+    }
+
+    /// Looks up the X account of a contributor to follow, when their GitHub profile links to it.
+    async fn contributor_x_handle(&self, username: &str) -> Option<String> {
+        if !self.contributor_config.follow_on_x {
+            return None;
+        }
+
+        match self.source.x_handle(username).await {
+            Ok(Some(handle)) if is_valid_handle(&handle) => Some(handle),
+            Ok(Some(handle)) => {
+                warn!("Not following {}: {:?} is not a valid X handle", username, handle);
+                None
+            }
+            Ok(None) => {
+                debug!("Not following {}: their profile doesn't link to X", username);
+                None
+            }
+            Err(e) => {
+                warn!("Failed to look up the X handle of {}: {:?}", username, e);
+                None
+            }
+        }
+    }
+
+    /// Returns true if handling the push has to wait for the contributors of its branch to be gathered.
+    pub fn waits_for_contributors(&self, event: &PushEvent) -> bool {
+        let branch_name = event.git_ref.strip_prefix("refs/heads/").unwrap_or(&event.git_ref);
+        !self.source.contributors_ready(branch_name)
+    }
+
+    /// Finds an announcement of the same contributor made within the dedupe window.
+    async fn recent_contributor_announcement(&self, repo: &str, identities: &[String]) -> Option<AuditEntry> {
+        let window_hours = self.contributor_config.dedupe_window_hours;
+        if window_hours == 0 {
+            return None;
+        }
+
+        let since = Utc::now() - Duration::hours(window_hours as i64);
+        self.audit_log
+            .find_recent_by_identity(AnnouncementKind::Contributor, repo, identities, since)
+            .await
+    }
+
     /// Handles release events from GitHub.
     ///
     /// # Arguments
@@ -112,117 +533,1575 @@ impl WebhookHandler {
     /// A result indicating success or failure.
     /// Key Features
     /// Event Filtering:
-    /// The method only processes releases that are marked as "published". If the action is not "published", it returns early with Ok(()).
+    /// The method processes releases that are marked as "published", "edited" or "deleted". Any other action returns early with Ok(()).
+    /// Channels:
+    /// Releases are classified as stable, beta or nightly from their tag and prerelease flag, and only announced on the sinks their channel is routed to.
+    /// Pre-releases, of the beta and nightly channels, are announced with their channel's template, or skipped, or announced exclusively, per `PrereleasePolicy`.
+    /// Assets:
+    /// When configured, the announcement is delayed until the release has the required assets, or the wait times out.
+    /// Major Releases:
+    /// Stable major releases use a separate template, can carry an image card, and get a follow-up thread with highlights from the release notes.
     /// Tweet Formatting:
-    /// Constructs a tweet message that includes the version tag and a link to the release notes.
+    /// Renders the channel's template with the version tag and a link to the release notes, followed by the channel's hashtags.
     /// Posting to X (Twitter):
-    /// Uses the self.x_client.send_tweet(&tweet).await? method to post the tweet to X.
+    /// The release is queued in the audit log through its claim, with its sinks, LinkedIn text, image and thread of highlights, and the `Outbox` posts it.
+    /// Deduplication:
+    /// A release is announced once, even when the published event is delivered again.
+    /// Edits:
+    /// Edits of an already announced release are handled according to the configured `ReleaseEditedPolicy`.
+    /// Deletions:
+    /// Deleting an announced release deletes its announcement posts so the timeline doesn't advertise a pulled release.
     /// Logging:
-    /// Logs the tweet message before posting it.
-
+    /// Logs the tweet message before queueing it, the outbox records the outcome in the audit log.
     pub async fn handle_release(&self, event: ReleaseEvent) -> Result<()> {
-        // Only process published releases
-        if event.action != "published" {
-            return Ok(());
+        match event.action {
+            ReleaseAction::Published => self.handle_release_published(&event).await,
+            ReleaseAction::Edited => self.handle_release_edited(&event).await,
+            ReleaseAction::Deleted => self.handle_release_deleted(&event).await,
+            _ => Ok(()),
         }
+    }
 
+    async fn handle_release_published(&self, event: &ReleaseEvent) -> Result<()> {
         let repo_name = &event.repository.full_name;
         let version = &event.release.tag_name;
         // let release_name = event.release.name.unwrap_or_else(|| version.clone());
-        
-        let tweet = format!(
-            "New release ({}) of Delta out! 🎉\nLink to release notes: {}",
-            version,
-            event.release.html_url
-        );
 
-        info!("Posting new release tweet for {}: {}", repo_name, tweet);
-        if let Err(e) = self.x_client.send_tweet(&tweet).await {
-            error!("Failed to post tweet for new release {}: {}", version, e);
+        let Some(channel) = self.release_channel(event) else {
+            return Ok(());
+        };
+        if !self.release_config.prereleases.announces(channel.channel) {
+            info!("Not posting release {} of the {} channel: excluded by RELEASE_PRERELEASES", version, channel.channel);
+            return Ok(());
+        }
+        let to_x = channel.routes_to("x");
+        let to_linkedin = channel.routes_to("linkedin") && self.linkedin.is_some();
+        if !to_x && !to_linkedin {
+            info!("Not posting release {} of the {} channel: not routed to X or LinkedIn", version, channel.channel);
+            return Ok(());
+        }
+
+        // Keyed on the release id so a redelivered event isn't announced twice
+        let release_id = event.release.id.to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::Release, repo_name, &release_id).await else {
+            info!("Release {} was already announced", version);
+            return Ok(());
+        };
+
+        if self.release_assets.is_enabled() {
+            self.wait_for_assets(event).await;
+        }
+        if self.release_config.ci_wait.enabled && !self.wait_for_ci(event).await {
+            // Dropping the claim unrecorded lets a redelivery announce the release once CI is fixed
+            return Ok(());
+        }
+
+        // Queued once for every sink, with the text the announcement is recorded with
+        let major = self.is_major_release(event, channel);
+        let linkedin_post = self.linkedin.as_ref()
+            .filter(|_| to_linkedin)
+            .map(|(_, config)| release_tweet(event, channel, &config.template));
+        let text = if to_x {
+            let template = if major { &self.release_config.major.template } else { &channel.template };
+            release_tweet(event, channel, template)
+        } else {
+            linkedin_post.clone().unwrap_or_default()
+        };
+
+        let mut plan = PostPlan::default();
+        let mut thread = Vec::new();
+        if to_x {
+            plan.sinks.push("x".to_string());
+            let image_path = if major { self.release_config.major.image_path.as_deref() } else { None };
+            plan.media_ids.extend(self.release_image(event, image_path).await);
+            if major {
+                thread = self.release_highlights(event, claim.key());
+            }
+        }
+        if let Some(post) = linkedin_post {
+            plan.sinks.push("linkedin".to_string());
+            plan.texts.insert("linkedin".to_string(), post);
         }
 
+        info!("Posting new release for {}: {}", repo_name, text);
+        claim.enqueue_thread(
+            AuditEntry::new(AnnouncementKind::Release, AuditStatus::Queued, repo_name, &release_id, &text)
+                .with_subject(version)
+                .with_plan(plan),
+            thread,
+        ).await;
+
         Ok(())
     }
 
-}
+    /// Returns true if handling the release event involves waiting for its assets to be uploaded,
+    /// or for the CI of its tag to complete.
+    pub fn waits_before_announcing(&self, event: &ReleaseEvent) -> bool {
+        event.action == ReleaseAction::Published
+            && (self.release_assets.is_enabled() || self.release_config.ci_wait.enabled)
+    }
 
-// App state that will be shared across requests
-pub struct AppState {
-    pub webhook_handler: WebhookHandler,
-}
+    /// Returns true if a release of the repository is being announced, which follow-ups such as
+    /// docs deploys and corrections have to wait for.
+    pub fn waits_for_release(&self, repo_name: &str) -> bool {
+        self.audit_log.is_in_flight(AnnouncementKind::Release, repo_name)
+    }
 
+    /// Waits for the release being announced, if any, until it leaves the outbox, so its
+    /// follow-ups are posted after it.
+    async fn wait_for_release(&self, repo_name: &str) {
+        if !self.waits_for_release(repo_name) {
+            return;
+        }
 
-// Health check endpoint
-pub async fn health_check() -> &'static str {
-    info!("Health check debug message");
-    "Health-Check-OK"
-}
+        // The announcement can wait for its assets and its CI, then retry the post
+        let mut wait_minutes = self.release_config.asset_wait.timeout_minutes;
+        if self.release_config.ci_wait.enabled {
+            wait_minutes += self.release_config.ci_wait.timeout_minutes;
+        }
+        let timeout = std::time::Duration::from_secs(wait_minutes * 60) + RELEASE_SEQUENCING_MARGIN;
+        info!("A release of {} is being announced, waiting for it before following up", repo_name);
+        if !self.audit_log.wait_until_settled(AnnouncementKind::Release, repo_name, timeout).await {
+            warn!("Release of {} still being announced after {:?}, following up anyway", repo_name, timeout);
+        }
+    }
 
-pub async fn call_back() -> &'static str {
-    info!("Call_back debug message");
-    "Callback-OK"
-}
+    /// Waits until the release has the required assets, or the configured timeout expires.
+    async fn wait_for_assets(&self, event: &ReleaseEvent) {
+        let config = &self.release_config.asset_wait;
+        let tag = &event.release.tag_name;
+        let deadline = Instant::now() + std::time::Duration::from_secs(config.timeout_minutes * 60);
+        let mut names: Vec<String> = event.release.assets.iter().map(|asset| asset.name.clone()).collect();
 
+        while !self.release_assets.is_satisfied_by(&names) {
+            if Instant::now() >= deadline {
+                warn!(
+                    "Release {} only has {} assets after {} minutes, announcing it anyway",
+                    tag, names.len(), config.timeout_minutes
+                );
+                return;
+            }
 
-// Webhook handler that uses app state
-pub async fn handle_webhook(
-    State(state): State<Arc<AppState>>,
-    headers: HeaderMap,
-    body: String,
-) -> Result<impl axum::response::IntoResponse, StatusCode> {
-    debug!("Received raw webhook body: {}", body);
-    
-    // Get the event type from headers
-    let event_type = headers
-        .get("x-github-event")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| {
-            error!("Missing x-github-event header");
-            StatusCode::BAD_REQUEST
-        })?;
-    
-    debug!("GitHub Event Type: {}", event_type);
-    
-    // Parse the body based on event type
-    let result = match event_type {
-        "ping" => {
-            debug!("Handling ping event");
-            let _ping_event: PingEvent = serde_json::from_str(&body).map_err(|e| {
-                error!("Failed to parse ping event: {:?}", e);
-                StatusCode::UNPROCESSABLE_ENTITY
-            })?;
-            info!("Received ping event - webhook is configured correctly");
-            Ok(StatusCode::OK)
-        },
-        "push" => {
-            debug!("Handling push event");
-            let push_event: PushEvent = serde_json::from_str(&body).map_err(|e| {
-                error!("Failed to parse push event: {:?}", e);
-                StatusCode::UNPROCESSABLE_ENTITY
-            })?;
-            state.webhook_handler.handle_push(push_event).await.map_err(|e| {
-                error!("Error handling push event: {:?}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
-            Ok(StatusCode::OK)
-        },
-        "release" => {
-            debug!("Handling release event");
-            let release_event: ReleaseEvent = serde_json::from_str(&body).map_err(|e| {
-                error!("Failed to parse release event: {:?}", e);
-                StatusCode::UNPROCESSABLE_ENTITY
-            })?;
-            state.webhook_handler.handle_release(release_event).await.map_err(|e| {
-                error!("Error handling release event: {:?}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
-            Ok(StatusCode::OK)
-        },
-        _ => {
-            error!("Unsupported event type: {}", event_type);
-            Err(StatusCode::NOT_IMPLEMENTED)
+            debug!("Release {} has {} assets, waiting for more", tag, names.len());
+            sleep(std::time::Duration::from_secs(config.poll_interval_seconds)).await;
+            match self.source.list_release_assets(&event.release).await {
+                Ok(current) => names = current,
+                Err(e) => warn!("Failed to check the assets of release {}: {:?}", tag, e),
+            }
         }
-    };
-    
-    result
-}
\ No newline at end of file
+
+        info!("Release {} has all its assets", tag);
+    }
+
+    /// Waits for the check runs of the release tag to complete.
+    ///
+    /// # Returns
+    /// True if the release can be announced: its CI passed, or no CI reported on the tag in
+    /// time. False if a check failed or the CI was still running at the timeout.
+    async fn wait_for_ci(&self, event: &ReleaseEvent) -> bool {
+        let config = &self.release_config.ci_wait;
+        let tag = &event.release.tag_name;
+        let deadline = Instant::now() + std::time::Duration::from_secs(config.timeout_minutes * 60);
+        let mut status = CiStatus::Missing;
+
+        loop {
+            match self.source.list_tag_check_runs(tag).await {
+                Ok(check_runs) => status = CiStatus::of(&check_runs, &config.checks),
+                Err(e) => warn!("Failed to check the CI of release {}: {:?}", tag, e),
+            }
+
+            match &status {
+                CiStatus::Passed => {
+                    info!("CI of release {} passed", tag);
+                    return true;
+                }
+                CiStatus::Failed(checks) => {
+                    warn!("Not posting release {}: CI failed ({})", tag, checks.join(", "));
+                    return false;
+                }
+                CiStatus::Missing | CiStatus::Running => {}
+            }
+
+            if Instant::now() >= deadline {
+                if status == CiStatus::Missing {
+                    warn!("No CI reported on release {} after {} minutes, announcing it anyway", tag, config.timeout_minutes);
+                    return true;
+                }
+                warn!("Not posting release {}: CI still running after {} minutes", tag, config.timeout_minutes);
+                return false;
+            }
+
+            debug!("CI of release {} is {:?}, waiting for it", tag, status);
+            sleep(std::time::Duration::from_secs(config.poll_interval_seconds)).await;
+        }
+    }
+
+    /// Returns true if the release should be announced with the major release flow.
+    fn is_major_release(&self, event: &ReleaseEvent, channel: &ChannelConfig) -> bool {
+        let major = &self.release_config.major;
+        major.enabled
+            && channel.channel == ReleaseChannel::Stable
+            && Version::from_tag(&event.release.tag_name)
+                .is_some_and(|version| version.is_major(major.zero_minor_is_major))
+    }
+
+    /// Threads the highlights of the notes of a major release under its announcement.
+    ///
+    /// # Arguments
+    /// * `event` - The release event.
+    /// * `anchor_key` - The key the announcement of the release is queued under.
+    ///
+    /// # Returns
+    /// The replies to queue along with the announcement, each one replying to the previous one.
+    fn release_highlights(&self, event: &ReleaseEvent, anchor_key: &str) -> Vec<AuditEntry> {
+        let repo_name = &event.repository.full_name;
+        let release_id = event.release.id.to_string();
+        let highlights = highlights(event.release.body.as_deref().unwrap_or_default(), self.release_config.major.highlights);
+
+        let mut reply_to = anchor_key.to_string();
+        highlights.iter().enumerate().map(|(index, highlight)| {
+            let text = truncate(&format!("{}/{} {}", index + 1, highlights.len(), highlight), MAX_TWEET_CHARS);
+            let key = format!("{}:highlight:{}", anchor_key, index + 1);
+            let plan = PostPlan {
+                reply_to: Some(ReplyTo::Announcement(std::mem::replace(&mut reply_to, key.clone()))),
+                posted_as: Some(AuditStatus::FollowUp),
+                ..PostPlan::default()
+            };
+            AuditEntry::new(AnnouncementKind::Release, AuditStatus::Queued, repo_name, &release_id, &text)
+                .with_idempotency_key(&key)
+                .with_plan(plan)
+        }).collect()
+    }
+
+    /// Uploads the image attached to a release announcement.
+    ///
+    /// The image card at `image_path` is used when there is one, otherwise the OpenGraph
+    /// image of the release page if enabled.
+    ///
+    /// # Returns
+    /// The media ID of the uploaded image, or `None` to post the announcement without one.
+    async fn release_image(&self, event: &ReleaseEvent, image_path: Option<&str>) -> Option<String> {
+        let tag = &event.release.tag_name;
+        let card = match image_path {
+            Some(path) => match tokio::fs::read(path).await {
+                Ok(image) => Some(image),
+                Err(e) => {
+                    warn!("Cannot read the image card {} of release {}: {}", path, tag, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let image = match (card, &self.opengraph_images) {
+            (Some(image), _) => image,
+            (None, Some(opengraph_images)) => match opengraph_images.fetch(&event.release.html_url).await {
+                Ok(image) => image,
+                Err(e) => {
+                    warn!("Posting release {} without image, cannot fetch its OpenGraph image: {:?}", tag, e);
+                    return None;
+                }
+            },
+            (None, None) => return None,
+        };
+
+        match self.x_client.upload_media(&image).await {
+            Ok(media_id) => Some(media_id),
+            Err(e) => {
+                warn!("Posting release {} without image, upload failed: {:?}", tag, e);
+                None
+            }
+        }
+    }
+
+    /// Updates the announcement of a release that was edited after being published.
+    async fn handle_release_edited(&self, event: &ReleaseEvent) -> Result<()> {
+        let policy = self.release_config.edited_policy;
+        if policy == ReleaseEditedPolicy::Ignore {
+            debug!("Ignoring edit of release {}", event.release.tag_name);
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let release_id = event.release.id.to_string();
+        self.wait_for_release(repo_name).await;
+        let Some(original) = self.audit_log
+            .find_posted(AnnouncementKind::Release, repo_name, &release_id)
+            .await
+        else {
+            debug!("Release {} was never announced, ignoring edit", event.release.tag_name);
+            return Ok(());
+        };
+
+        let minutes_since_announcement = (Utc::now() - original.timestamp).num_minutes();
+        if minutes_since_announcement > self.release_config.edit_window_minutes as i64 {
+            info!(
+                "Release {} was edited {} minutes after its announcement, outside the edit window",
+                event.release.tag_name, minutes_since_announcement
+            );
+            return Ok(());
+        }
+
+        let Some(note) = correction_note(event, self.release_config.notes_min_change_percent) else {
+            debug!("Edit of release {} does not affect its announcement", event.release.tag_name);
+            return Ok(());
+        };
+        let Some(original_tweet_id) = original.remote_ids.get("x") else {
+            warn!("Announcement of release {} has no tweet to correct", event.release.tag_name);
+            return Ok(());
+        };
+        // Every edit is corrected once, even when its webhook is delivered again
+        let key = correction_key(repo_name, &release_id, &event.release);
+        let Some(claim) = self.audit_log.claim_key(AnnouncementKind::Release, repo_name, &release_id, &key).await else {
+            debug!("Edit of release {} already handled", event.release.tag_name);
+            return Ok(());
+        };
+
+        let tag = &event.release.tag_name;
+        match policy {
+            ReleaseEditedPolicy::Reply => {
+                let tweet = format!("{}\nLink to release notes: {}", note, event.release.html_url);
+                info!("Replying to release announcement {} with correction: {}", original_tweet_id, tweet);
+                let plan = PostPlan {
+                    reply_to: Some(ReplyTo::Post(original_tweet_id.clone())),
+                    posted_as: Some(AuditStatus::Corrected),
+                    ..PostPlan::default()
+                };
+                claim.enqueue(
+                    AuditEntry::new(AnnouncementKind::Release, AuditStatus::Queued, repo_name, &release_id, &tweet)
+                        .with_subject(tag)
+                        .with_plan(plan)
+                ).await;
+            }
+            ReleaseEditedPolicy::Repost => {
+                let Some(channel) = self.release_channel(event) else {
+                    return Ok(());
+                };
+                let template = if self.is_major_release(event, channel) {
+                    &self.release_config.major.template
+                } else {
+                    &channel.template
+                };
+                let tweet = format!("{}\n{}", release_tweet(event, channel, template), note);
+
+                // Accounts with edit access fix the announcement in place while X still allows it,
+                // which counts from the first post so earlier edits aren't edited again
+                if original.status == AuditStatus::Posted && self.x_client.can_edit(original.timestamp) {
+                    info!("Editing release announcement {}: {}", original_tweet_id, tweet);
+                    claim.begin(&tweet).await;
+                    match self.x_client.edit_tweet(AnnouncementKind::Release, original_tweet_id, &tweet).await {
+                        Ok(tweet_id) => {
+                            let entry = AuditEntry::new(AnnouncementKind::Release, AuditStatus::Edited, repo_name, &release_id, &tweet)
+                                .with_remote_id("x", &tweet_id)
+                                .with_subject(tag)
+                                .with_idempotency_key(claim.key());
+                            self.record(entry).await;
+                            return Ok(());
+                        }
+                        Err(e) => warn!("Failed to edit release announcement {}, reposting it instead: {:?}", original_tweet_id, e),
+                    }
+                }
+
+                match self.x_client.delete_tweet(original_tweet_id).await {
+                    Ok(()) => {
+                        let retraction = AuditEntry::new(
+                            AnnouncementKind::Release,
+                            AuditStatus::Retracted,
+                            repo_name,
+                            &release_id,
+                            &format!("Release {} edited", tag),
+                        );
+                        self.record(retraction.with_remote_id("x", original_tweet_id).with_idempotency_key(claim.key())).await;
+                    }
+                    Err(e) => error!("Failed to delete outdated release announcement {}: {:?}", original_tweet_id, e),
+                }
+                info!("Reposting corrected release announcement: {}", tweet);
+                claim.enqueue(
+                    AuditEntry::new(AnnouncementKind::Release, AuditStatus::Queued, repo_name, &release_id, &tweet)
+                        .with_subject(tag)
+                ).await;
+            }
+            ReleaseEditedPolicy::Ignore => {}
+        }
+
+        Ok(())
+    }
+
+    /// Returns the settings of the channel a release belongs to.
+    fn release_channel(&self, event: &ReleaseEvent) -> Option<&ChannelConfig> {
+        let channel = self.release_channels.classify(&event.release.tag_name, event.release.prerelease);
+        debug!("Release {} belongs to the {} channel", event.release.tag_name, channel);
+
+        let config = self.release_channels.config(channel);
+        if config.is_none() {
+            warn!("No settings for the {} release channel", channel);
+        }
+        config
+    }
+
+    /// Retracts the announcement of a release that was deleted.
+    async fn handle_release_deleted(&self, event: &ReleaseEvent) -> Result<()> {
+        if !self.release_config.retract_on_delete {
+            debug!("Ignoring deletion of release {}", event.release.tag_name);
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let release_id = event.release.id.to_string();
+        // A release still waiting in the outbox is retracted once it's posted
+        self.wait_for_release(repo_name).await;
+        let live = self.audit_log
+            .find_live(AnnouncementKind::Release, repo_name, &release_id)
+            .await;
+        if live.is_empty() {
+            debug!("Release {} was never announced, nothing to retract", event.release.tag_name);
+            return Ok(());
+        }
+
+        info!("Release {} was deleted, retracting {} announcement posts", event.release.tag_name, live.len());
+        let mut entry = AuditEntry::new(
+            AnnouncementKind::Release,
+            AuditStatus::Retracted,
+            repo_name,
+            &release_id,
+            &format!("Release {} deleted", event.release.tag_name),
+        );
+        for tweet_id in live.iter().filter_map(|posted| posted.remote_ids.get("x")) {
+            match self.x_client.delete_tweet(tweet_id).await {
+                Ok(()) => entry = entry.with_remote_id("x", tweet_id),
+                Err(e) => error!("Failed to retract announcement {} of release {}: {:?}", tweet_id, event.release.tag_name, e),
+            }
+        }
+        if let Some((client, _)) = &self.linkedin {
+            for post_id in live.iter().filter_map(|posted| posted.remote_ids.get("linkedin")) {
+                match client.delete(post_id).await {
+                    Ok(()) => entry = entry.with_remote_id("linkedin", post_id),
+                    Err(e) => error!("Failed to retract LinkedIn post {} of release {}: {:?}", post_id, event.release.tag_name, e),
+                }
+            }
+        }
+        self.record(entry).await;
+
+        Ok(())
+    }
+
+    /// Handles repository events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `RepositoryEvent` containing the details of the repository event.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Event Filtering:
+    /// Only `renamed` and `transferred` actions of the monitored repository are processed.
+    /// Following the repository:
+    /// The GitHub client is pointed at the new owner/name and the audit log entries of the old name are moved to the new one.
+    pub async fn handle_repository(&self, event: RepositoryEvent) -> Result<()> {
+        if !matches!(event.action, RepositoryAction::Renamed | RepositoryAction::Transferred) {
+            debug!("Ignoring repository event with action: {}", event.action);
+            return Ok(());
+        }
+
+        let Some(new_location) = RepoName::from_full_name(&event.repository.full_name) else {
+            warn!("Repository event has an invalid full name: {}", event.repository.full_name);
+            return Ok(());
+        };
+        let mut previous_location = new_location.clone();
+        if let Some(changes) = &event.changes {
+            if let Some(rename) = &changes.repository {
+                previous_location.name = rename.name.from.clone();
+            }
+            if let Some(transfer) = &changes.owner {
+                if let Some(owner) = transfer.from.user.as_ref().or(transfer.from.organization.as_ref()) {
+                    previous_location.owner = owner.login.clone();
+                }
+            }
+        }
+
+        if self.github_client.repo().await != previous_location {
+            debug!("Ignoring {} event for unmonitored repository {}", event.action, previous_location);
+            return Ok(());
+        }
+
+        if let Some(previous) = self.github_client.follow_repository(new_location.clone()).await {
+            self.audit_log.rename_repo(&previous.full_name(), &new_location.full_name()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles deployment status events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `DeploymentStatusEvent` containing the details of the deployment.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Event Filtering:
+    /// Only successful deployments of the configured docs environment are processed.
+    /// Follow-up:
+    /// The latest release announcement gets a reply linking to the updated docs.
+    pub async fn handle_deployment_status(&self, event: DeploymentStatusEvent) -> Result<()> {
+        let docs = &self.release_config.docs;
+        if !docs.enabled {
+            debug!("Docs deploy announcements disabled, ignoring deployment status");
+            return Ok(());
+        }
+        if event.deployment_status.state != "success" || event.deployment.environment != docs.environment {
+            debug!(
+                "Ignoring {} deployment status for environment {}",
+                event.deployment_status.state, event.deployment.environment
+            );
+            return Ok(());
+        }
+
+        let url = event.deployment_status.environment_url
+            .filter(|url| !url.is_empty())
+            .or_else(|| docs.url.clone());
+        self.announce_docs_deploy(&event.repository.full_name, url).await
+    }
+
+    /// Handles GitHub Pages build events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `PageBuildEvent` containing the details of the Pages build.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Event Filtering:
+    /// Only successful builds are processed, for sites published from a branch rather than by a workflow deployment.
+    /// Follow-up:
+    /// The latest release announcement gets a reply linking to the configured docs URL.
+    pub async fn handle_page_build(&self, event: PageBuildEvent) -> Result<()> {
+        if !self.release_config.docs.enabled {
+            debug!("Docs deploy announcements disabled, ignoring page build");
+            return Ok(());
+        }
+        if event.build.status != "built" {
+            debug!("Ignoring page build with status: {}", event.build.status);
+            return Ok(());
+        }
+
+        self.announce_docs_deploy(&event.repository.full_name, self.release_config.docs.url.clone()).await
+    }
+
+    /// Replies to the latest release announcement with the URL of the freshly deployed docs.
+    ///
+    /// Every release gets at most one docs follow-up, so deploys of unrelated docs changes
+    /// don't repeat it.
+    async fn announce_docs_deploy(&self, repo_name: &str, url: Option<String>) -> Result<()> {
+        let Some(url) = url else {
+            warn!("Docs deployed without a URL, set DOCS_URL to announce it");
+            return Ok(());
+        };
+
+        self.wait_for_release(repo_name).await;
+        let Some(release) = self.audit_log.find_latest_posted(AnnouncementKind::Release, repo_name).await else {
+            debug!("No release announcement to follow up with the docs deploy");
+            return Ok(());
+        };
+        if Utc::now() - release.timestamp > Duration::hours(self.release_config.docs.window_hours as i64) {
+            debug!("Latest release {} was announced too long ago for a docs follow-up", release.source_id);
+            return Ok(());
+        }
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::DocsDeploy, repo_name, &release.source_id).await else {
+            debug!("Docs deploy for release {} already announced", release.source_id);
+            return Ok(());
+        };
+        let Some(release_tweet_id) = release.remote_ids.get("x") else {
+            return Ok(());
+        };
+
+        let tag = release.subject.as_deref().unwrap_or_default();
+        let tweet = render(&self.release_config.docs.template, &[
+            ("tag", tag),
+            ("url", &url),
+        ]);
+
+        info!("Posting docs deploy follow-up for release {}: {}", tag, tweet);
+        let plan = PostPlan {
+            reply_to: Some(ReplyTo::Post(release_tweet_id.clone())),
+            ..PostPlan::default()
+        };
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::DocsDeploy, AuditStatus::Queued, repo_name, &release.source_id, &tweet)
+                .with_subject(tag)
+                .with_plan(plan)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Handles member events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `MemberEvent` containing the details of the member event.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Event Filtering:
+    /// Only `added` actions are processed, and every collaborator is welcomed at most once.
+    /// Confirmation:
+    /// Collaborators on the allowlist are welcomed right away; the welcome of anyone else is held
+    /// in the audit log until it is confirmed through [confirm_member](WebhookHandler::confirm_member).
+    pub async fn handle_member(&self, event: MemberEvent) -> Result<()> {
+        if !self.member_config.enabled {
+            debug!("Collaborator announcements disabled, ignoring member event");
+            return Ok(());
+        }
+        if event.action != MemberAction::Added {
+            debug!("Ignoring member event with action: {}", event.action);
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let username = &event.member.login;
+        let source_id = normalize_identity(username);
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::Member, repo_name, &source_id).await else {
+            debug!("Collaborator {} was already handled", username);
+            return Ok(());
+        };
+        let latest = self.audit_log.find_latest(AnnouncementKind::Member, repo_name, &source_id).await;
+        if latest.is_some_and(|entry| entry.status == AuditStatus::Pending) {
+            debug!("Welcome of collaborator {} is already awaiting confirmation", username);
+            return Ok(());
+        }
+
+        let permission = event.changes
+            .as_ref()
+            .and_then(|changes| changes.permission.as_ref())
+            .and_then(|permission| permission.to.as_deref())
+            .unwrap_or("write");
+        let tweet = render(&self.member_config.template, &[
+            ("username", username),
+            ("permission", permission),
+        ]);
+
+        let allowlisted = self.member_config.allowlist
+            .iter()
+            .any(|login| login.eq_ignore_ascii_case(username));
+        if !allowlisted {
+            info!("Collaborator {} is not allowlisted, holding the welcome until it is confirmed", username);
+            let entry = AuditEntry::new(AnnouncementKind::Member, AuditStatus::Pending, repo_name, &source_id, &tweet)
+                .with_subject(username);
+            self.record(entry).await;
+            return Ok(());
+        }
+
+        self.post_member_welcome(claim, repo_name, &source_id, username, &tweet).await;
+        Ok(())
+    }
+
+    /// Handles public events from GitHub, sent when a repository is made public.
+    ///
+    /// # Arguments
+    /// * `event` - A `PublicEvent` containing the details of the repository.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Introduction:
+    /// Organization webhooks report every repository made public, which is introduced with its
+    /// description and topics. Each repository is introduced once, even if it's made private
+    /// and public again.
+    pub async fn handle_public(&self, event: PublicEvent) -> Result<()> {
+        let Some(config) = &self.public_repo_config else {
+            debug!("Publicized repository announcements disabled, ignoring public event");
+            return Ok(());
+        };
+
+        let repository = &event.repository;
+        let source_id = repository.id.to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::PublicRepo, &repository.full_name, &source_id).await else {
+            debug!("Repository {} was already introduced", repository.full_name);
+            return Ok(());
+        };
+
+        let topics: Vec<String> = repository.topics
+            .iter()
+            .filter_map(|topic| helpers::apply("hashtagify", topic))
+            .filter(|hashtag| !hashtag.is_empty())
+            .collect();
+        let tweet = render(&config.template, &[
+            ("name", &repository.name),
+            ("repo", &repository.full_name),
+            ("description", repository.description.as_deref().unwrap_or_default()),
+            ("topics", &topics.join(" ")),
+            ("language", repository.language.as_deref().unwrap_or_default()),
+            ("url", &repository.html_url),
+        ]);
+        // Lines left empty by a repository without a description or topics are dropped
+        let tweet = truncate(
+            &tweet.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>().join("\n"),
+            MAX_TWEET_CHARS,
+        );
+
+        info!("Posting introduction of publicized repository {}: {}", repository.full_name, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::PublicRepo, AuditStatus::Queued, &repository.full_name, &source_id, &tweet)
+                .with_subject(&repository.name)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Returns true if a created tag is announced once it is known not to have a release,
+    /// which takes longer than GitHub waits for a webhook response.
+    pub fn waits_for_tag_release(&self, event: &CreateEvent) -> bool {
+        self.tag_config.as_ref().is_some_and(|(config, pattern)| {
+            event.ref_type == RefType::Tag && config.release_wait_minutes > 0 && pattern.is_match(&event.git_ref)
+        })
+    }
+
+    /// Handles create events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `CreateEvent` containing the created branch or tag.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Tags Without Releases:
+    /// Tags matching the pattern are announced, unless a release of the tag is published within
+    /// the release wait, as the release is announced instead. Branches are ignored.
+    pub async fn handle_create(&self, event: CreateEvent) -> Result<()> {
+        if event.ref_type != RefType::Tag {
+            debug!("Ignoring creation of branch {}", event.git_ref);
+            return Ok(());
+        }
+        let Some((config, pattern)) = &self.tag_config else {
+            debug!("Tag announcements disabled, ignoring tag {}", event.git_ref);
+            return Ok(());
+        };
+        let tag = &event.git_ref;
+        if !pattern.is_match(tag) {
+            debug!("Tag {} doesn't match TAG_PATTERN, skipping", tag);
+            return Ok(());
+        }
+
+        // Publishing a release creates its tag, give a release made right after the tag time too
+        if config.release_wait_minutes > 0 {
+            debug!("Waiting {} minutes for a release of tag {}", config.release_wait_minutes, tag);
+            sleep(std::time::Duration::from_secs(config.release_wait_minutes * 60)).await;
+        }
+        if self.github_client.has_release(tag).await? {
+            info!("Tag {} has a release, which is announced instead", tag);
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::Tag, repo_name, tag).await else {
+            debug!("Tag {} was already announced", tag);
+            return Ok(());
+        };
+
+        let url = match &event.repository.html_url {
+            Some(html_url) => format!("{}/tree/{}", html_url, tag),
+            None => format!("https://github.com/{}/tree/{}", repo_name, tag),
+        };
+        let tweet = truncate(&render(&config.template, &[
+            ("tag", tag),
+            ("repo", repo_name),
+            ("url", &url),
+        ]), MAX_TWEET_CHARS);
+
+        info!("Posting tag {}: {}", tag, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::Tag, AuditStatus::Queued, repo_name, tag, &tweet)
+                .with_subject(tag)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Handles pull request events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `PullRequestEvent` containing the pull request and its labels.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Breaking Changes:
+    /// Pull requests merged to a monitored branch with one of the breaking change labels get a
+    /// heads-up, whether they were labeled before or after being merged. Each pull request is
+    /// announced once.
+    /// First-Time Contributors:
+    /// With `FIRST_CONTRIBUTION=pr_author_association`, the author association of open pull
+    /// requests is remembered, since GitHub no longer reports a first-time contributor once
+    /// their pull request is merged.
+    pub async fn handle_pull_request(&self, event: PullRequestEvent) -> Result<()> {
+        self.remember_pull_association(&event).await;
+
+        let Some(config) = &self.breaking_change_config else {
+            debug!("Breaking change announcements disabled, ignoring pull request #{}", event.number);
+            return Ok(());
+        };
+        if !matches!(event.action, PullRequestAction::Closed | PullRequestAction::Labeled) {
+            debug!("Ignoring pull request {} event", event.action);
+            return Ok(());
+        }
+
+        let pull_request = &event.pull_request;
+        if !pull_request.merged {
+            debug!("Pull request #{} isn't merged, nothing to announce", event.number);
+            return Ok(());
+        }
+        let branch = &pull_request.base.git_ref;
+        if !self.branches.iter().any(|monitored| &monitored.name == branch) {
+            debug!("Pull request #{} was merged to {}, which isn't monitored", event.number, branch);
+            return Ok(());
+        }
+        let breaking = pull_request.labels.iter().any(|label| {
+            config.labels.iter().any(|breaking| breaking.eq_ignore_ascii_case(&label.name))
+        });
+        if !breaking {
+            debug!("Pull request #{} isn't labeled as a breaking change", event.number);
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let source_id = event.number.to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::BreakingChange, repo_name, &source_id).await else {
+            debug!("Breaking change #{} was already announced", event.number);
+            return Ok(());
+        };
+
+        let tweet = truncate(&render(&config.template, &[
+            ("number", &source_id),
+            ("title", &pull_request.title),
+            ("author", &pull_request.user.login),
+            ("branch", branch),
+            ("repo", repo_name),
+            ("url", &pull_request.html_url),
+        ]), MAX_TWEET_CHARS);
+
+        info!("Posting breaking change #{}: {}", event.number, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::BreakingChange, AuditStatus::Queued, repo_name, &source_id, &tweet)
+                .with_subject(&pull_request.title)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Remembers the author association of an open pull request, for the first contribution
+    /// policy that relies on it.
+    async fn remember_pull_association(&self, event: &PullRequestEvent) {
+        if self.contributor_config.first_contribution != FirstContributionPolicy::PrAuthorAssociation {
+            return;
+        }
+        let open = matches!(
+            event.action,
+            PullRequestAction::Opened
+                | PullRequestAction::Reopened
+                | PullRequestAction::Synchronize
+                | PullRequestAction::Edited
+                | PullRequestAction::ReadyForReview
+        );
+        let pull_request = &event.pull_request;
+        let Some(association) = pull_request.author_association.clone().filter(|_| open && !pull_request.merged) else {
+            return;
+        };
+
+        debug!("Author of pull request #{} is {:?}", event.number, association);
+        if let Err(e) = self.github_client
+            .remember_open_pull_association(event.number, &pull_request.user.login, association)
+            .await
+        {
+            warn!("Failed to remember the author association of pull request #{}: {:?}", event.number, e);
+        }
+    }
+
+    /// Handles issues events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - An `IssuesEvent` containing the issue and the label added to it.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Labeled Issues:
+    /// Open issues given one of the configured labels, such as `good first issue`, are announced
+    /// with the template of the label. Each issue is announced once, even with several labels.
+    /// Rate Limiting:
+    /// At most `max_per_hour` issues are announced per hour. Issues labeled beyond that are not
+    /// announced, so a bulk-labeling session doesn't flood the timeline.
+    /// Long-Awaited Fixes:
+    /// Issues closed as completed at least `min_age_days` after being opened are announced as
+    /// finally fixed. Issues closed as not planned are left out.
+    pub async fn handle_issues(&self, event: IssuesEvent) -> Result<()> {
+        match event.action {
+            IssuesAction::Labeled => self.handle_issue_labeled(&event).await,
+            IssuesAction::Closed => self.handle_issue_closed(&event).await,
+            _ => {
+                debug!("Ignoring issue {} event", event.action);
+                Ok(())
+            }
+        }
+    }
+
+    async fn handle_issue_labeled(&self, event: &IssuesEvent) -> Result<()> {
+        let Some(config) = &self.issue_label_config else {
+            debug!("Labeled issue announcements disabled, ignoring issue #{}", event.issue.number);
+            return Ok(());
+        };
+        let Some(label) = event.label.as_ref().and_then(|added| {
+            config.labels.iter().find(|label| label.name.eq_ignore_ascii_case(&added.name))
+        }) else {
+            debug!("Issue #{} wasn't given an announced label", event.issue.number);
+            return Ok(());
+        };
+        if event.issue.state != "open" {
+            debug!("Issue #{} is {}, not announcing it", event.issue.number, event.issue.state);
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let source_id = event.issue.number.to_string();
+        let cap = self.issue_label_cap.lock().await;
+        let since = Utc::now() - Duration::hours(1);
+        let announced: HashSet<String> = self.audit_log
+            .history(Some(AnnouncementKind::IssueLabel), Some(repo_name), Some(since))
+            .await
+            .into_iter()
+            .filter(|entry| matches!(entry.status, AuditStatus::Queued | AuditStatus::Posting | AuditStatus::Posted))
+            .map(|entry| entry.source_id)
+            .collect();
+        if announced.len() >= config.max_per_hour && !announced.contains(&source_id) {
+            info!("Not announcing issue #{}: {} issues were announced in the past hour", event.issue.number, announced.len());
+            return Ok(());
+        }
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::IssueLabel, repo_name, &source_id).await else {
+            debug!("Issue #{} was already announced", event.issue.number);
+            return Ok(());
+        };
+
+        let tweet = truncate(&render(&label.template, &[
+            ("number", &source_id),
+            ("title", &event.issue.title),
+            ("label", &label.name),
+            ("repo", repo_name),
+            ("url", &event.issue.html_url),
+        ]), MAX_TWEET_CHARS);
+
+        info!("Posting {} issue #{}: {}", label.name, event.issue.number, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::IssueLabel, AuditStatus::Queued, repo_name, &source_id, &tweet)
+                .with_subject(&event.issue.title)
+        ).await;
+        drop(cap);
+
+        Ok(())
+    }
+
+    /// Announces a long-standing issue that was closed as completed.
+    async fn handle_issue_closed(&self, event: &IssuesEvent) -> Result<()> {
+        let Some(config) = &self.long_awaited_fix_config else {
+            debug!("Long-awaited fix announcements disabled, ignoring issue #{}", event.issue.number);
+            return Ok(());
+        };
+        if event.issue.state_reason.as_deref() != Some("completed") {
+            debug!("Issue #{} wasn't closed as completed, not announcing it", event.issue.number);
+            return Ok(());
+        }
+        let age = Utc::now() - event.issue.created_at;
+        if age.num_days() < config.min_age_days as i64 {
+            debug!("Issue #{} was open for {} days, not long enough to announce it", event.issue.number, age.num_days());
+            return Ok(());
+        }
+
+        let repo_name = &event.repository.full_name;
+        let source_id = event.issue.number.to_string();
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::LongAwaitedFix, repo_name, &source_id).await else {
+            debug!("Fix of issue #{} was already announced", event.issue.number);
+            return Ok(());
+        };
+
+        let years = (age.num_days() / 365).to_string();
+        let tweet = truncate(&render(&config.template, &[
+            ("number", &source_id),
+            ("title", &event.issue.title),
+            ("years", &years),
+            ("repo", repo_name),
+            ("url", &event.issue.html_url),
+        ]), MAX_TWEET_CHARS);
+
+        info!("Posting long-awaited fix of issue #{}: {}", event.issue.number, tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::LongAwaitedFix, AuditStatus::Queued, repo_name, &source_id, &tweet)
+                .with_subject(&event.issue.title)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Handles sponsorship events from GitHub.
+    ///
+    /// # Arguments
+    /// * `event` - A `SponsorshipEvent` containing the sponsorship and its tier.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    /// Key Features
+    /// Sponsor Thanks:
+    /// New sponsors are thanked by name if their sponsorship is public, and without naming
+    /// them otherwise. Cancellations and tier changes are ignored.
+    /// Sponsor Milestones:
+    /// Once a sponsor is thanked, the active sponsors are counted and the highest milestone
+    /// reached is announced once. The milestone reached when first enabled isn't announced.
+    pub async fn handle_sponsorship(&self, event: SponsorshipEvent) -> Result<()> {
+        let Some(config) = &self.sponsors_config else {
+            debug!("Sponsor announcements disabled, ignoring sponsorship event");
+            return Ok(());
+        };
+        if event.action != SponsorshipAction::Created {
+            debug!("Ignoring sponsorship {} event", event.action);
+            return Ok(());
+        }
+
+        let sponsorship = &event.sponsorship;
+        let repo = self.github_client.repo().await.full_name();
+        if let Some(claim) = self.audit_log.claim(AnnouncementKind::Sponsor, &repo, &sponsorship.node_id).await {
+            let amount = format!("${}", sponsorship.tier.monthly_price_in_dollars);
+            let (template, sponsor) = match sponsorship.privacy_level {
+                SponsorshipPrivacy::Public => (&config.template, sponsorship.sponsor.login.as_str()),
+                SponsorshipPrivacy::Private => (&config.private_template, ""),
+            };
+            let tweet = truncate(&render(template, &[
+                ("sponsor", sponsor),
+                ("sponsorable", &sponsorship.sponsorable.login),
+                ("tier", &sponsorship.tier.name),
+                ("amount", &amount),
+            ]), MAX_TWEET_CHARS);
+            // Private sponsors are left out of the audit log as well
+            let subject = match sponsorship.privacy_level {
+                SponsorshipPrivacy::Public => sponsor,
+                SponsorshipPrivacy::Private => "private sponsor",
+            };
+
+            info!("Posting thanks to {}: {}", subject, tweet);
+            claim.enqueue(
+                AuditEntry::new(AnnouncementKind::Sponsor, AuditStatus::Queued, &repo, &sponsorship.node_id, &tweet)
+                    .with_subject(subject)
+            ).await;
+        } else {
+            debug!("Sponsorship {} was already thanked", sponsorship.node_id);
+        }
+
+        if config.milestones.is_empty() {
+            return Ok(());
+        }
+        self.announce_sponsor_milestone(config, &repo, &sponsorship.sponsorable.login).await
+    }
+
+    /// Announces the highest sponsor count milestone reached, unless it was already handled.
+    async fn announce_sponsor_milestone(&self, config: &SponsorsConfig, repo: &str, sponsorable: &str) -> Result<()> {
+        let sponsors = self.github_client.count_sponsors(sponsorable).await?;
+        let threshold = highest_reached(sponsors, &config.milestones).unwrap_or_default();
+        let source_id = threshold.to_string();
+
+        // The baseline is recorded even before the first milestone is reached, so that
+        // reaching it later is announced
+        if !self.audit_log.has_any(AnnouncementKind::SponsorMilestone, repo).await {
+            info!("First count of sponsors, recording {} without announcing it", sponsors);
+            let entry = AuditEntry::new(AnnouncementKind::SponsorMilestone, AuditStatus::Skipped, repo, &source_id, "");
+            self.record(entry).await;
+            return Ok(());
+        }
+        if threshold == 0 {
+            debug!("{} sponsors, no milestone reached yet", sponsors);
+            return Ok(());
+        }
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::SponsorMilestone, repo, &source_id).await else {
+            debug!("Milestone of {} sponsors already handled", threshold);
+            return Ok(());
+        };
+
+        let count = config.locale.format_count(threshold);
+        let tweet = truncate(&render(&config.milestone_template, &[
+            ("count", &count),
+            ("sponsorable", sponsorable),
+        ]), MAX_TWEET_CHARS);
+
+        info!("Posting sponsor milestone: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::SponsorMilestone, AuditStatus::Queued, repo, &source_id, &tweet)
+                .with_subject(&count)
+        ).await;
+
+        Ok(())
+    }
+
+    /// Posts the held welcome of a collaborator who is not on the allowlist.
+    ///
+    /// # Arguments
+    /// * `username` - The login of the collaborator.
+    ///
+    /// # Returns
+    /// A result containing `true` if a pending welcome was posted, or `false` if there was none.
+    pub async fn confirm_member(&self, username: &str) -> Result<bool> {
+        let repo_name = self.github_client.repo().await.full_name();
+        let source_id = normalize_identity(username);
+        let Some(claim) = self.audit_log.claim(AnnouncementKind::Member, &repo_name, &source_id).await else {
+            return Ok(false);
+        };
+        let Some(entry) = self.audit_log.find_latest(AnnouncementKind::Member, &repo_name, &source_id).await else {
+            return Ok(false);
+        };
+        if entry.status != AuditStatus::Pending {
+            return Ok(false);
+        }
+
+        info!("Welcome of collaborator {} confirmed", username);
+        self.post_member_welcome(claim, &repo_name, &source_id, username, &entry.text).await;
+        Ok(true)
+    }
+
+    async fn post_member_welcome(&self, claim: Claim<'_>, repo_name: &str, source_id: &str, username: &str, tweet: &str) {
+        info!("Posting collaborator welcome tweet: {}", tweet);
+        claim.enqueue(
+            AuditEntry::new(AnnouncementKind::Member, AuditStatus::Queued, repo_name, source_id, tweet)
+                .with_subject(username)
+        ).await;
+    }
+
+    /// Records an announcement in the audit log, logging rather than propagating failures
+    /// so that an unwritable log never prevents the bot from announcing.
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+}
+
+/// Formats the announcement tweet for a release.
+fn release_tweet(event: &ReleaseEvent, channel: &ChannelConfig, template: &str) -> String {
+    let name = event.release.name.as_deref().unwrap_or(&event.release.tag_name);
+    let mut tweet = render(template, &[
+        ("tag", &event.release.tag_name),
+        ("name", name),
+        ("body", event.release.body.as_deref().unwrap_or_default()),
+        ("url", &event.release.html_url),
+        ("repo", &event.repository.full_name),
+        ("channel", channel.channel.as_str()),
+    ]);
+
+    if !channel.hashtags.is_empty() {
+        let hashtags: Vec<String> = channel.hashtags.iter().map(|hashtag| format!("#{}", hashtag)).collect();
+        tweet.push('\n');
+        tweet.push_str(&hashtags.join(" "));
+    }
+
+    tweet
+}
+
+/// Describes the changes of an edited release that matter to its announcement.
+///
+/// Returns `None` when nothing that followers would notice has changed, including edits of the
+/// release notes that change less than `min_change_percent` of their lines.
+fn correction_note(event: &ReleaseEvent, min_change_percent: u32) -> Option<String> {
+    let changes = event.changes.as_ref()?;
+
+    if let Some(tag_name) = &changes.tag_name {
+        return Some(format!(
+            "Correction: this release is {} (previously announced as {}).",
+            event.release.tag_name, tag_name.from
+        ));
+    }
+    let notes_revised = changes.body.as_ref().is_some_and(|body| {
+        let percent = changed_percent(&body.from, event.release.body.as_deref().unwrap_or_default());
+        debug!("Edit of release {} changed {}% of its notes", event.release.tag_name, percent);
+        percent >= min_change_percent
+    });
+    if notes_revised || changes.name.is_some() {
+        return Some(format!("Update: the release notes for {} have been revised.", event.release.tag_name));
+    }
+
+    None
+}
+
+/// Builds the key a correction is claimed with, one per edit of the release.
+///
+/// Release payloads carry no edit time, so an edit is told apart by what the announcement
+/// shows of the release, which is the same in a redelivered webhook.
+fn correction_key(repo_name: &str, release_id: &str, release: &Release) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    for part in [release.tag_name.as_str(), release.name.as_deref().unwrap_or_default(), release.body.as_deref().unwrap_or_default()] {
+        context.update(part.as_bytes());
+        context.update(&[0]);
+    }
+    let edit: String = context.finish().as_ref()[..8].iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}:correction:{}:{}", repo_name, release_id, edit)
+}
+
+/// Number of payloads that failed to parse, per event type
+pub type ParseFailures = Mutex<BTreeMap<String, u64>>;
+
+// App state that will be shared across requests
+pub struct AppState {
+    /// Handler of the repository of `REPO_OWNER` and `REPO_NAME`
+    pub webhook_handler: Arc<WebhookHandler>,
+    /// Handlers of the other repositories of `REPOSITORIES` and of the ones found in the
+    /// organization, keyed by `owner/name` in lower case
+    pub repositories: RwLock<HashMap<String, Arc<WebhookHandler>>>,
+    /// Repositories of the organization left out by `ORG_INCLUDE` and `ORG_EXCLUDE`, keyed by
+    /// `owner/name` in lower case, whose events are ignored
+    pub ignored_repositories: RwLock<HashSet<String>>,
+    /// Token required to confirm pending collaborator welcomes, which are disabled without one
+    pub member_confirm_token: Option<String>,
+    /// Token required to export the announcement history, which can't be exported without one
+    pub history_token: Option<String>,
+    /// Whether webhooks sent as `application/x-www-form-urlencoded` are accepted
+    pub accept_form_payloads: bool,
+    /// Secret the GitHub webhooks are signed with, unset when their signature isn't checked
+    pub webhook_secret: Option<String>,
+    /// Health of the scheduled polls
+    pub polls: Arc<PollMonitor>,
+    /// State of the async runtime
+    pub runtime: Arc<RuntimeMonitor>,
+    /// Number of payloads that failed to parse, per event type
+    pub parse_failures: Arc<ParseFailures>,
+    /// Retries shared by the GitHub and X clients
+    pub retry_budget: Arc<RetryBudget>,
+    /// Pacing of the background requests to GitHub, when enabled
+    pub request_budget: Option<Arc<RequestBudget>>,
+    /// Announcements of the GitLab project, when one is watched
+    pub gitlab: Option<GitLabWebhooks>,
+    /// Announcements of the Gitea or Forgejo repository, when one is watched
+    pub gitea: Option<GiteaWebhooks>,
+    /// Title and length of the Atom feed of the announcements
+    pub atom_feed: AtomFeedConfig,
+}
+
+impl AppState {
+    /// Returns the handler of the repository an event is about.
+    ///
+    /// # Arguments
+    /// * `repository` - The repository, as `owner/name`, see [WebhookEvent::repository].
+    ///
+    /// # Returns
+    /// The handler of the repository when it has one of its own, otherwise the main one.
+    pub fn handler(&self, repository: Option<&str>) -> Arc<WebhookHandler> {
+        repository
+            .and_then(|repository| self.repositories.read().unwrap().get(&repository.to_lowercase()).cloned())
+            .unwrap_or_else(|| Arc::clone(&self.webhook_handler))
+    }
+
+    /// Returns the handler of the repository an event is about, see [handler](Self::handler), or
+    /// `None` if the repository was left out of the organization and its events are ignored.
+    pub fn handler_of(&self, event: &WebhookEvent) -> Option<Arc<WebhookHandler>> {
+        let repository = event.repository();
+        if repository.is_some_and(|repository| self.ignores(repository)) {
+            return None;
+        }
+        Some(self.handler(repository))
+    }
+
+    /// Returns true if the repository already has a handler of its own, or was left out of the organization
+    pub fn knows(&self, repository: &str) -> bool {
+        let repository = repository.to_lowercase();
+        self.repositories.read().unwrap().contains_key(&repository)
+            || self.ignored_repositories.read().unwrap().contains(&repository)
+    }
+
+    /// Returns true if the events of the repository are ignored, see [OrganizationConfig](crate::config::env::OrganizationConfig).
+    pub fn ignores(&self, repository: &str) -> bool {
+        self.ignored_repositories.read().unwrap().contains(&repository.to_lowercase())
+    }
+
+    /// Announces a repository with a handler of its own from now on.
+    pub fn add_repository(&self, repository: &str, handler: WebhookHandler) {
+        self.repositories.write().unwrap().insert(repository.to_lowercase(), Arc::new(handler));
+    }
+
+    /// Ignores the events of a repository from now on.
+    pub fn ignore_repository(&self, repository: &str) {
+        self.ignored_repositories.write().unwrap().insert(repository.to_lowercase());
+    }
+}
+
+
+// Health check endpoint, reporting the failure streak of every scheduled poll and the state of the runtime.
+// Failing polls leave the bot up and retrying, so they degrade the status without failing the check.
+pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    info!("Health check debug message");
+    let status = if state.polls.healthy() { "ok" } else { "degraded" };
+    let request_budget = match &state.request_budget {
+        Some(request_budget) => Some(request_budget.stats().await),
+        None => None,
+    };
+    Json(json!({
+        "status": status,
+        "polls": state.polls.health(),
+        "runtime": state.runtime.stats(),
+        "parse_failures": *state.parse_failures.lock().unwrap(),
+        "retry_budget": state.retry_budget.stats(),
+        "request_budget": request_budget,
+    }))
+}
+
+pub async fn call_back() -> &'static str {
+    info!("Call_back debug message");
+    "Callback-OK"
+}
+
+
+/// Extracts the JSON payload of a webhook from its body.
+///
+/// GitHub sends payloads either as the JSON body or, with the form content type,
+/// as the `payload` field of a URL encoded form.
+fn json_payload(headers: &HeaderMap, body: String, accept_form: bool) -> Result<String, StatusCode> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.split(';').next())
+        .map(|h| h.trim().to_lowercase())
+        .unwrap_or_default();
+
+    match content_type.as_str() {
+        "application/json" => Ok(body),
+        "application/x-www-form-urlencoded" if accept_form => {
+            form_urlencoded::parse(body.as_bytes())
+                .find(|(key, _)| key == "payload")
+                .map(|(_, payload)| payload.into_owned())
+                .ok_or_else(|| {
+                    error!("Missing payload field in form encoded webhook");
+                    StatusCode::BAD_REQUEST
+                })
+        }
+        _ => {
+            warn!("Rejected webhook with unsupported content type: {:?}", content_type);
+            Err(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+        }
+    }
+}
+
+/// Returns the part of a payload around a line and column reported by serde_json
+fn payload_fragment(body: &str, line: usize, column: usize) -> String {
+    const CONTEXT: usize = 60;
+    let line = body.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+    let position = column.min(line.len());
+    let start = (0..=position.saturating_sub(CONTEXT)).rev().find(|&i| line.is_char_boundary(i)).unwrap_or(0);
+    let end = (position + CONTEXT).min(line.len());
+    let end = (end..=line.len()).find(|&i| line.is_char_boundary(i)).unwrap_or(line.len());
+    line[start..end].to_owned()
+}
+
+// Webhook handler that uses app state
+pub async fn handle_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, StatusCode> {
+    // Signed over the body as sent, before a form payload is decoded
+    if let Some(secret) = &state.webhook_secret {
+        if !has_valid_signature(&headers, &body, secret) {
+            warn!("Rejected webhook: invalid signature");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+    let body = json_payload(&headers, body, state.accept_form_payloads)?;
+    debug!("Received raw webhook body: {}", body);
+    
+    // Get the event type from headers
+    let event_type = headers
+        .get("x-github-event")
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| {
+            error!("Missing x-github-event header");
+            StatusCode::BAD_REQUEST
+        })?;
+    
+    debug!("GitHub Event Type: {}", event_type);
+    
+    // Parse the body based on event type
+    let event = match WebhookEvent::parse(event_type, &body) {
+        Some(Ok(event)) => event,
+        Some(Err(e)) => {
+            // The diagnostics also go in the response, which shows in the delivery log of GitHub
+            *state.parse_failures.lock().unwrap().entry(event_type.to_owned()).or_default() += 1;
+            let diagnostics = match &e {
+                PayloadError::Syntax(source) => {
+                    let near = payload_fragment(&body, source.line(), source.column());
+                    error!("Failed to parse {} event: {} near `{}`", event_type, e, near);
+                    json!({ "error": e.to_string(), "near": near })
+                }
+                PayloadError::Field { path, value, source } => {
+                    let rendered = value.as_ref().map(|value| value.to_string()).unwrap_or_else(|| "none".to_string());
+                    error!(
+                        "Failed to parse {} event at {}: {} (value: {})",
+                        event_type, path, source, truncate(&rendered, MAX_DIAGNOSTIC_VALUE_CHARS)
+                    );
+                    // Large values, such as the object a field is missing from, are cut
+                    let value = match rendered.chars().count() > MAX_DIAGNOSTIC_VALUE_CHARS {
+                        true => json!(truncate(&rendered, MAX_DIAGNOSTIC_VALUE_CHARS)),
+                        false => json!(value),
+                    };
+                    json!({ "error": source.to_string(), "path": path, "value": value })
+                }
+            };
+            return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(diagnostics)).into_response());
+        }
+        None => {
+            error!("Unsupported event type: {}", event_type);
+            return Err(StatusCode::NOT_IMPLEMENTED);
+        }
+    };
+
+    if let WebhookEvent::Ping(_) = event {
+        info!("Received ping event - webhook is configured correctly");
+        return Ok(StatusCode::OK.into_response());
+    }
+    // Events of the other repositories go to their own handler
+    let Some(handler) = state.handler_of(&event) else {
+        debug!("Ignoring {} event of {}, left out of the organization", event_type, event.repository().unwrap_or_default());
+        return Ok(StatusCode::OK.into_response());
+    };
+    respond(handler, event_type, event).await
+}
+
+/// Answers a webhook with the outcome of handling its event, for the routes of every forge.
+///
+/// Events whose handling takes longer than forges wait for an answer, see
+/// [WebhookHandler::holds], are handled in the background and answered with `202 Accepted`.
+///
+/// # Arguments
+/// * `handler` - The handler of the repository the event is about.
+/// * `event_type` - The type of the event, for the logs.
+/// * `event` - The event, converted to the payloads of GitHub.
+pub async fn respond(handler: Arc<WebhookHandler>, event_type: &str, event: WebhookEvent) -> Result<Response, StatusCode> {
+    debug!("Handling {} event", event_type);
+    if handler.holds(&event) {
+        info!("Handling {} event in the background, it waits for something first", event_type);
+        let event_type = event_type.to_owned();
+        spawn_in_request(async move {
+            if let Err(e) = handler.handle(event).await {
+                error!("Error handling {} event: {:?}", event_type, e);
+            }
+        });
+        return Ok(StatusCode::ACCEPTED.into_response());
+    }
+    handler.handle(event).await.map_err(|e| {
+        error!("Error handling {} event: {:?}", event_type, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(StatusCode::OK.into_response())
+}
+
+/// Repository of a confirmed collaborator
+#[derive(Debug, Deserialize)]
+pub struct ConfirmMemberQuery {
+    /// Repository the collaborator was added to, as `owner/name`, the one of `REPO_OWNER` and `REPO_NAME` if unset
+    repo: Option<String>,
+}
+
+// Confirms the pending welcome of a collaborator
+pub async fn confirm_member(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+    Query(query): Query<ConfirmMemberQuery>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    let Some(expected) = state.member_confirm_token.as_deref() else {
+        warn!("Rejected confirmation of collaborator {}: MEMBER_CONFIRM_TOKEN is not set", username);
+        return Err(StatusCode::FORBIDDEN);
+    };
+
+    if !has_bearer_token(&headers, expected) {
+        warn!("Rejected confirmation of collaborator {}: invalid token", username);
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    match state.handler(query.repo.as_deref()).confirm_member(&username).await {
+        Ok(true) => Ok(StatusCode::OK),
+        Ok(false) => {
+            debug!("No pending welcome for collaborator {}", username);
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            error!("Error confirming collaborator {}: {:?}", username, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Filters and format of a history export
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    since: Option<String>,
+    repo: Option<String>,
+    format: Option<String>,
+}
+
+// Exports the announcement history as CSV with the engagement of the posts, or as JSON lines
+pub async fn export_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, StatusCode> {
+    let Some(expected) = state.history_token.as_deref() else {
+        warn!("Rejected history export: HISTORY_TOKEN is not set");
+        return Err(StatusCode::FORBIDDEN);
+    };
+    if !has_bearer_token(&headers, expected) {
+        warn!("Rejected history export: invalid token");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let kind = query.kind
+        .map(|kind| kind.parse::<AnnouncementKind>())
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let since = query.since
+        .map(|since| parse_duration(&since))
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .map(|duration| Utc::now() - duration);
+    let json = match query.format.as_deref() {
+        None | Some("csv") => false,
+        Some("json") => true,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let entries = state.webhook_handler.audit_log.history(kind, query.repo.as_deref(), since).await;
+    if json {
+        let mut body = String::new();
+        for entry in &entries {
+            body.push_str(&serde_json::to_string(entry).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?);
+            body.push('\n');
+        }
+        return Ok(([(CONTENT_TYPE, "application/x-ndjson")], body).into_response());
+    }
+
+    let engagement = export::engagement(&entries, &state.webhook_handler.x_client).await;
+    Ok((
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (CONTENT_DISPOSITION, "attachment; filename=\"announcements.csv\""),
+        ],
+        export::to_csv(&entries, &engagement),
+    ).into_response())
+}
+
+/// Checks the `X-Hub-Signature-256` GitHub computes over the body of a webhook, in constant time
+fn has_valid_signature(headers: &HeaderMap, body: &str, secret: &str) -> bool {
+    let header = headers.get("x-hub-signature-256").and_then(|h| h.to_str().ok());
+    signature::verify_prefixed(secret.as_bytes(), body.as_bytes(), header)
+}
+
+/// Checks the bearer token of a request in constant time
+fn has_bearer_token(headers: &HeaderMap, expected: &str) -> bool {
+    let token = headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    signature::verify_token(token, expected)
+}
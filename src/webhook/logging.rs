@@ -0,0 +1,90 @@
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering}};
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response};
+use chrono::Utc;
+use tokio::time::Instant;
+use tracing::{info, info_span, Instrument};
+
+/// Header carrying the correlation id of a request, reused when a proxy already set one
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+const DELIVERY_HEADER: &str = "x-github-delivery";
+
+static NEXT_REQUEST: AtomicU64 = AtomicU64::new(0);
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Returns the correlation id of the request being handled, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Spawns a task that keeps the correlation id and span of the current request.
+///
+/// # Arguments
+/// * `future` - The work outliving the request, such as a held push event.
+pub fn spawn_in_request<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let future = future.in_current_span();
+    match current_request_id() {
+        Some(request_id) => tokio::spawn(REQUEST_ID.scope(request_id, future)),
+        None => tokio::spawn(future),
+    };
+}
+
+/// Middleware assigning a correlation id to every request and logging its outcome.
+///
+/// The id is recorded on a span wrapping the whole request, so every log line of the
+/// request carries it, and on the audit entries recorded while handling it.
+pub async fn log_requests(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .filter(|id| !id.is_empty())
+        .map(str::to_owned)
+        .unwrap_or_else(generate_request_id);
+    let delivery = request
+        .headers()
+        .get(DELIVERY_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .unwrap_or("-")
+        .to_owned();
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+    let span = info_span!("request", id = %request_id, delivery = %delivery);
+    let started = Instant::now();
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span.clone()))
+        .await;
+
+    span.in_scope(|| {
+        info!(
+            "{} {} -> {} in {}ms",
+            method, path, response.status().as_u16(), started.elapsed().as_millis()
+        );
+    });
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Generates an id unique across requests and restarts
+fn generate_request_id() -> String {
+    format!(
+        "{:x}-{:04x}",
+        Utc::now().timestamp_millis(),
+        NEXT_REQUEST.fetch_add(1, Ordering::Relaxed) & 0xffff
+    )
+}
@@ -1 +1,9 @@
-pub mod handler;
\ No newline at end of file
+pub mod api;
+pub mod gitea;
+pub mod gitlab;
+pub mod handler;
+pub mod logging;
+pub mod rate_limit;
+pub mod security;
+pub mod server;
+pub mod signature;
\ No newline at end of file
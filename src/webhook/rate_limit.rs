@@ -0,0 +1,124 @@
+use crate::{config::env::RateLimitConfig, github::client::GitHubClient};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, RwLock}};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header::RETRY_AFTER, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response}};
+use ipnet::IpNet;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{info, warn};
+
+// GitHub rarely changes its webhook ranges, so they are only refreshed daily
+const GITHUB_NETWORKS_REFRESH: Duration = Duration::from_secs(24 * 60 * 60);
+const GITHUB_NETWORKS_RETRY: Duration = Duration::from_secs(5 * 60);
+
+/// Requests seen from a client in the current window
+struct Window {
+    started: Instant,
+    requests: u32,
+}
+
+/// Limits the requests every client IP can make in a fixed window.
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    allowlist: Vec<IpNet>,
+    github_networks: RwLock<Vec<IpNet>>,
+    clients: Mutex<HashMap<IpAddr, Window>>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter from the configuration.
+    ///
+    /// # Arguments
+    /// * `config` - The rate limit and the networks exempt from it.
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            max_requests: config.max_requests,
+            window: Duration::from_secs(config.window_seconds),
+            allowlist: config.allowlist.clone(),
+            github_networks: RwLock::new(Vec::new()),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request from a client against its limit.
+    ///
+    /// # Arguments
+    /// * `ip` - The address of the client.
+    ///
+    /// # Returns
+    /// `None` if the request is allowed, or how long until the client may retry.
+    pub fn check(&self, ip: IpAddr) -> Option<Duration> {
+        // Dual stack listeners report IPv4 clients as mapped IPv6 addresses
+        let ip = ip.to_canonical();
+        if self.is_allowed(ip) {
+            return None;
+        }
+
+        let now = Instant::now();
+        let mut clients = self.clients.lock().unwrap();
+        // Keep the map from growing with every address ever seen
+        clients.retain(|_, window| now.duration_since(window.started) < self.window);
+
+        let window = clients.entry(ip).or_insert(Window { started: now, requests: 0 });
+        if window.requests >= self.max_requests {
+            return Some(self.window.saturating_sub(now.duration_since(window.started)));
+        }
+        window.requests += 1;
+        None
+    }
+
+    /// Keeps the networks GitHub delivers webhooks from exempt from the limit.
+    ///
+    /// # Arguments
+    /// * `github` - The client used to fetch the networks from the meta API.
+    pub async fn refresh_github_networks(self: Arc<Self>, github: Arc<GitHubClient>) {
+        loop {
+            match github.webhook_networks().await {
+                Ok(networks) => {
+                    info!("Exempting {} GitHub webhook networks from the rate limit", networks.len());
+                    *self.github_networks.write().unwrap() = networks;
+                    sleep(GITHUB_NETWORKS_REFRESH).await;
+                }
+                Err(e) => {
+                    warn!("Failed to fetch the GitHub webhook networks: {:?}", e);
+                    sleep(GITHUB_NETWORKS_RETRY).await;
+                }
+            }
+        }
+    }
+
+    fn is_allowed(&self, ip: IpAddr) -> bool {
+        self.allowlist.iter().any(|network| network.contains(&ip))
+            || self.github_networks.read().unwrap().iter().any(|network| network.contains(&ip))
+    }
+}
+
+/// Middleware rejecting clients over the rate limit with `429 Too Many Requests`.
+///
+/// Requests without a client address, such as those coming through a Unix socket
+/// from a local proxy, aren't limited.
+pub async fn limit_requests(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| address.ip());
+
+    if let Some(retry_after) = client.and_then(|ip| limiter.check(ip)) {
+        warn!("Rate limited request from {:?}", client);
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, retry_after.as_secs().max(1).to_string())],
+        ).into_response();
+    }
+    next.run(request).await
+}
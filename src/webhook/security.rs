@@ -0,0 +1,79 @@
+use crate::config::env::SecurityConfig;
+use axum::{
+    http::{
+        header::{
+            AUTHORIZATION,
+            CONTENT_SECURITY_POLICY,
+            CONTENT_TYPE,
+            REFERRER_POLICY,
+            STRICT_TRANSPORT_SECURITY,
+            X_CONTENT_TYPE_OPTIONS,
+            X_FRAME_OPTIONS},
+        HeaderName, HeaderValue, Method},
+    Router};
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    set_header::SetResponseHeaderLayer};
+use anyhow::{Context, Result};
+
+/// Adds the CORS policy and the standard security headers to every route of an admin router.
+///
+/// Only meant for the routes a browser may reach, the webhook route is called by GitHub
+/// and is left out by merging it in after this.
+///
+/// # Arguments
+/// * `router` - The admin routes.
+/// * `config` - The allowed origins and header values.
+///
+/// # Returns
+/// A result containing the secured router, or an error if a configured value isn't a valid header.
+pub fn secure<S>(router: Router<S>, config: &SecurityConfig) -> Result<Router<S>>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let mut headers: Vec<(HeaderName, HeaderValue)> = vec![
+        (X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        (REFERRER_POLICY, HeaderValue::from_static("no-referrer")),
+        (
+            CONTENT_SECURITY_POLICY,
+            HeaderValue::from_str(&config.content_security_policy).context("Invalid CONTENT_SECURITY_POLICY")?,
+        ),
+    ];
+    if config.hsts_max_age_seconds > 0 {
+        headers.push((
+            STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&format!("max-age={}; includeSubDomains", config.hsts_max_age_seconds))?,
+        ));
+    }
+
+    let mut router = router;
+    for (name, value) in headers {
+        router = router.layer(SetResponseHeaderLayer::if_not_present(name, value));
+    }
+
+    // Without allowed origins, browsers keep refusing cross-origin calls on their own
+    if !config.cors_allowed_origins.is_empty() {
+        router = router.layer(cors(&config.cors_allowed_origins)?);
+    }
+    Ok(router)
+}
+
+/// Builds the CORS policy allowing the given origins
+fn cors(origins: &[String]) -> Result<CorsLayer> {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            origins
+                .iter()
+                .map(|origin| HeaderValue::from_str(origin).with_context(|| format!("Invalid CORS origin: {}", origin)))
+                .collect::<Result<Vec<_>>>()?,
+        )
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([AUTHORIZATION, CONTENT_TYPE]))
+}
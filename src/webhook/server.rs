@@ -0,0 +1,193 @@
+use crate::config::env::BindAddress;
+use std::{
+    env::var,
+    net::SocketAddr,
+    os::fd::{FromRawFd, IntoRawFd, RawFd}};
+use tokio::{
+    net::{TcpListener, UnixListener},
+    signal::unix::{signal, SignalKind},
+    sync::watch,
+    task::JoinSet,
+    time::{timeout, Duration}};
+use axum::Router;
+use hyper::server::conn::http1;
+use hyper_util::{rt::TokioIo, server::graceful::GracefulShutdown, service::TowerToHyperService};
+use anyhow::{Context, Result, anyhow};
+use tracing::{info, debug, warn};
+
+// First file descriptor passed by systemd socket activation
+const SD_LISTEN_FDS_START: RawFd = 3;
+// How long the requests in flight get to finish on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+/// Serves the app on every configured address until the process is told to shut down
+/// with SIGINT or SIGTERM, or one of the listeners fails.
+///
+/// On shutdown the listeners stop accepting connections, and the requests in flight, such as
+/// webhook deliveries, get up to 30 seconds to finish.
+///
+/// # Arguments
+/// * `addresses` - The TCP addresses, Unix sockets or systemd sockets to listen on.
+/// * `app` - The router handling the requests of every listener.
+///
+/// # Returns
+/// A result indicating a clean shutdown, or the error of the listener that failed.
+pub async fn serve(addresses: &[BindAddress], app: Router) -> Result<()> {
+    let mut listeners = Vec::new();
+    for address in addresses {
+        match address {
+            BindAddress::Tcp(address) => {
+                let listener = TcpListener::bind(address)
+                    .await
+                    .with_context(|| format!("Failed to listen on {}", address))?;
+                info!("Listening on {}", listener.local_addr()?);
+                listeners.push(Listener::Tcp(listener));
+            }
+            BindAddress::Unix(path) => {
+                // A socket left behind by a previous run would make the bind fail
+                if tokio::fs::try_exists(path).await? {
+                    tokio::fs::remove_file(path).await?;
+                }
+                let listener = UnixListener::bind(path)
+                    .with_context(|| format!("Failed to listen on Unix socket {}", path))?;
+                info!("Listening on unix:{}", path);
+                listeners.push(Listener::Unix(listener));
+            }
+            BindAddress::Systemd => listeners.extend(systemd_listeners()?),
+        }
+    }
+
+    let (stop, stopping) = watch::channel(false);
+    let mut servers = JoinSet::new();
+    for listener in listeners {
+        let app = app.clone();
+        let stopping = stopping.clone();
+        match listener {
+            Listener::Tcp(listener) => servers.spawn(async move {
+                // The client address is needed to rate limit requests per IP
+                let app = app.into_make_service_with_connect_info::<SocketAddr>();
+                axum::serve(listener, app)
+                    .with_graceful_shutdown(stopped(stopping))
+                    .await
+                    .map_err(anyhow::Error::from)
+            }),
+            Listener::Unix(listener) => servers.spawn(serve_unix(listener, app, stopping)),
+        };
+    }
+
+    let result = tokio::select! {
+        result = servers.join_next() => match result {
+            Some(result) => result.map_err(anyhow::Error::from).and_then(|result| result),
+            None => return Err(anyhow!("No address to listen on")),
+        },
+        result = shutdown_signal() => {
+            info!("Shutting down, waiting for the requests in flight");
+            result
+        }
+    };
+
+    // Stop the other listeners, letting their requests in flight finish
+    stop.send_replace(true);
+    let finished = timeout(SHUTDOWN_TIMEOUT, async {
+        while let Some(stopped) = servers.join_next().await {
+            if let Err(e) = stopped.map_err(anyhow::Error::from).and_then(|result| result) {
+                warn!("Listener failed while shutting down: {:?}", e);
+            }
+        }
+    })
+    .await;
+    if finished.is_err() {
+        warn!("Requests still in flight after {:?}, dropping them", SHUTDOWN_TIMEOUT);
+    }
+    result
+}
+
+/// Waits until the listeners are told to stop
+async fn stopped(mut stopping: watch::Receiver<bool>) {
+    // An error means the sender is gone, which only happens once serving is over
+    let _ = stopping.wait_for(|stop| *stop).await;
+}
+
+/// Waits for SIGINT or SIGTERM
+async fn shutdown_signal() -> Result<()> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result?,
+        _ = terminate.recv() => {}
+    }
+    Ok(())
+}
+
+/// Serves the app on a Unix domain socket, which `axum::serve` doesn't support.
+///
+/// Once told to stop, no connection is accepted anymore and the open ones are closed
+/// as soon as their request in flight is answered.
+async fn serve_unix(listener: UnixListener, app: Router, stopping: watch::Receiver<bool>) -> Result<()> {
+    let graceful = GracefulShutdown::new();
+    let stop = stopped(stopping);
+    tokio::pin!(stop);
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => accepted?.0,
+            _ = &mut stop => break,
+        };
+        let service = TowerToHyperService::new(app.clone());
+        let connection = http1::Builder::new().serve_connection(TokioIo::new(stream), service);
+        let connection = graceful.watch(connection);
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                debug!("Error serving Unix socket connection: {}", e);
+            }
+        });
+    }
+
+    graceful.shutdown().await;
+    Ok(())
+}
+
+/// Takes the listening sockets passed by systemd socket activation.
+fn systemd_listeners() -> Result<Vec<Listener>> {
+    let pid: u32 = var("LISTEN_PID")
+        .context("LISTEN_PID must be set for systemd socket activation")?
+        .parse()
+        .context("LISTEN_PID must be a process ID")?;
+    if pid != std::process::id() {
+        return Err(anyhow!("Sockets passed by systemd belong to process {}", pid));
+    }
+    let count: RawFd = var("LISTEN_FDS")
+        .context("LISTEN_FDS must be set for systemd socket activation")?
+        .parse()
+        .context("LISTEN_FDS must be a positive integer")?;
+
+    let mut listeners = Vec::new();
+    for fd in SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + count {
+        // SAFETY: systemd hands these descriptors over to this process, and each one
+        // is only wrapped once
+        let tcp = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+        match tcp.local_addr() {
+            Ok(address) => {
+                tcp.set_nonblocking(true)?;
+                info!("Listening on {} passed by systemd", address);
+                listeners.push(Listener::Tcp(TcpListener::from_std(tcp)?));
+            }
+            // Not an IP socket, so it's a Unix domain socket
+            Err(_) => {
+                // SAFETY: the descriptor was just released by the TCP listener
+                let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(tcp.into_raw_fd()) };
+                unix.set_nonblocking(true)?;
+                info!("Listening on Unix socket passed by systemd");
+                listeners.push(Listener::Unix(UnixListener::from_std(unix)?));
+            }
+        }
+    }
+
+    if listeners.is_empty() {
+        return Err(anyhow!("No sockets passed by systemd"));
+    }
+    Ok(listeners)
+}
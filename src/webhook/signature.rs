@@ -0,0 +1,93 @@
+use ring::hmac;
+use subtle::ConstantTimeEq;
+
+/// Computes the HMAC-SHA256 of a message, as lowercase hex.
+///
+/// # Arguments
+/// * `key` - The key the message is signed with.
+/// * `message` - The signed message.
+pub fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    let tag = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), message);
+    tag.as_ref().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Checks a hex HMAC-SHA256 signature of a message in constant time, whatever its case.
+///
+/// # Arguments
+/// * `key` - The key the message is signed with.
+/// * `message` - The signed message.
+/// * `signature` - The signature received with the message, without any `sha256=` prefix.
+pub fn verify(key: &[u8], message: &[u8], signature: &str) -> bool {
+    let expected = hmac_sha256_hex(key, message);
+    bool::from(signature.to_lowercase().as_bytes().ct_eq(expected.as_bytes()))
+}
+
+/// Checks the `X-Hub-Signature-256` header of a GitHub webhook, `sha256=` followed by the hex
+/// HMAC-SHA256 of the body, in constant time.
+///
+/// # Arguments
+/// * `key` - The secret of the webhook.
+/// * `message` - The body of the webhook.
+/// * `header` - The value of the header, if it was sent.
+pub fn verify_prefixed(key: &[u8], message: &[u8], header: Option<&str>) -> bool {
+    header
+        .and_then(|header| header.strip_prefix("sha256="))
+        .is_some_and(|signature| verify(key, message, signature))
+}
+
+/// Compares a token received with a request to the expected one in constant time, such as
+/// the `X-Gitlab-Token` of GitLab webhooks.
+///
+/// # Arguments
+/// * `token` - The token received, empty when none was sent.
+/// * `expected` - The token the request must carry.
+pub fn verify_token(token: &str, expected: &str) -> bool {
+    bool::from(token.as_bytes().ct_eq(expected.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example of https://docs.github.com/en/webhooks/using-webhooks/validating-webhook-deliveries
+    const SECRET: &[u8] = b"It's a Secret to Everybody";
+    const PAYLOAD: &[u8] = b"Hello, World!";
+    const SIGNATURE: &str = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    #[test]
+    fn accepts_the_github_docs_example() {
+        assert_eq!(format!("sha256={}", hmac_sha256_hex(SECRET, PAYLOAD)), SIGNATURE);
+        assert!(verify_prefixed(SECRET, PAYLOAD, Some(SIGNATURE)));
+        assert!(verify_prefixed(SECRET, PAYLOAD, Some(&SIGNATURE.to_uppercase().replace("SHA256=", "sha256="))));
+    }
+
+    #[test]
+    fn rejects_a_wrong_digest() {
+        let wrong = SIGNATURE.replace("757107", "757108");
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some(&wrong)));
+        assert!(!verify_prefixed(b"another secret", PAYLOAD, Some(SIGNATURE)));
+        assert!(!verify_prefixed(SECRET, b"Hello, World?", Some(SIGNATURE)));
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some(&SIGNATURE[..SIGNATURE.len() - 2])));
+    }
+
+    #[test]
+    fn rejects_a_missing_or_malformed_prefix() {
+        let digest = SIGNATURE.trim_start_matches("sha256=");
+        assert!(!verify_prefixed(SECRET, PAYLOAD, None));
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some("")));
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some(digest)));
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some(&format!("sha1={}", digest))));
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some(&format!("sha256 ={}", digest))));
+        assert!(!verify_prefixed(SECRET, PAYLOAD, Some("sha256=")));
+    }
+
+    #[test]
+    fn compares_gitlab_tokens() {
+        let expected = "a-gitlab-webhook-secret-of-32-characters";
+        assert!(verify_token(expected, expected));
+        assert!(!verify_token("a-gitlab-webhook-secret-of-32-characterz", expected));
+        assert!(!verify_token("a-gitlab-webhook-secret", expected));
+        assert!(!verify_token("", expected));
+        assert!(!verify_token(&expected.to_uppercase(), expected));
+    }
+}
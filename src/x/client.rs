@@ -1,20 +1,164 @@
-use std::sync::{atomic::{AtomicU64, Ordering}, Arc};
-use tokio::time::{sleep, Duration};
-use twitter_v2::{
-    authorization::Oauth1aToken, 
-    TwitterApi};
+use super::queue::{PostQueue, RATE_LIMIT_WINDOW};
+use crate::{
+    announce::{console::ConsoleAnnouncer, Announcement, Announcer},
+    audit::log::{AnnouncementKind, AuditLog, ReplyTo},
+    config::{
+        env::{EditConfig, FallbackConfig, FallbackEndpoint, HttpConfig, QueueConfig},
+        tls},
+    scheduler::retry_budget::RetryBudget};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex}};
+use tokio::{
+    sync::OnceCell,
+    time::{sleep, Duration}};
+use twitter_v2::authorization::{Authorization, Oauth1aToken};
+use reqwest::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    Certificate, Method, Response};
+use serde::Deserialize;
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use tracing::{info, warn, error, debug};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 const MAX_RETRIES: u32 = 3;
-const RATE_LIMIT_WINDOW: u64 = 15 * 60; // 15 minutes in seconds
-const TWEETS_PER_WINDOW: u64 = 50; // X API allows 50 tweets per 15 minutes
+/// How long posts skip the v2 endpoint once it failed and a fallback endpoint didn't
+const V2_COOLDOWN: chrono::Duration = chrono::Duration::minutes(10);
+const MEDIA_UPLOAD_URL: &str = "https://upload.twitter.com/1.1/media/upload.json";
+const TWEET_LOOKUP_LIMIT: usize = 100;
+
+/// Optional parts of a tweet besides its text
+#[derive(Debug, Clone, Default)]
+pub struct TweetOptions {
+    /// ID of the tweet this one replies to
+    pub in_reply_to: Option<String>,
+    /// IDs of media uploaded with [XClient::upload_media] to attach
+    pub media_ids: Vec<String>,
+    /// Kind of announcement the tweet belongs to, which sets its priority when posts back up
+    pub kind: Option<AnnouncementKind>,
+    /// ID of the tweet this one is an edit of
+    pub edit_of: Option<String>,
+}
+
+impl TweetOptions {
+    /// Options for a standalone tweet of the given kind of announcement.
+    pub fn kind(kind: AnnouncementKind) -> Self {
+        Self {
+            kind: Some(kind),
+            ..Self::default()
+        }
+    }
+
+    /// Options for the post of a queued announcement, as set by its plan.
+    ///
+    /// # Returns
+    /// The options, or an error if the announcement replies to a post that isn't made yet.
+    pub fn of(announcement: &Announcement) -> Result<Self> {
+        let in_reply_to = match &announcement.plan.reply_to {
+            Some(ReplyTo::Post(tweet_id)) => Some(tweet_id.clone()),
+            Some(ReplyTo::Announcement(key)) => return Err(anyhow!("Announcement {} isn't posted yet", key)),
+            None => None,
+        };
+        Ok(Self {
+            in_reply_to,
+            media_ids: announcement.plan.media_ids.clone(),
+            ..Self::kind(announcement.kind)
+        })
+    }
+
+    /// Describes what posting with these options does, for the posts that are printed
+    pub fn action(&self) -> String {
+        let action = match (&self.edit_of, &self.in_reply_to) {
+            (Some(tweet_id), _) => format!("edit {}", tweet_id),
+            (None, Some(tweet_id)) => format!("reply to {}", tweet_id),
+            (None, None) => "post".to_string(),
+        };
+        match self.media_ids.is_empty() {
+            true => action,
+            false => format!("{} with media {}", action, self.media_ids.join(",")),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaUploadResponse {
+    media_id_string: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetResponse {
+    data: PostedTweet,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostedTweet {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserResponse {
+    data: User,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusUpdateResponse {
+    id_str: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetLookupResponse {
+    #[serde(default)]
+    data: Vec<TweetWithMetrics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetWithMetrics {
+    id: String,
+    public_metrics: Engagement,
+}
+
+/// Public engagement counts of a tweet
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Engagement {
+    #[serde(default)]
+    pub like_count: u64,
+    #[serde(default)]
+    pub retweet_count: u64,
+    #[serde(default)]
+    pub reply_count: u64,
+    #[serde(default)]
+    pub quote_count: u64,
+    #[serde(default)]
+    pub impression_count: u64,
+}
 
 pub struct XClient {
-    client: TwitterApi<Oauth1aToken>,
-    tweet_count: Arc<AtomicU64>,
-    window_start: Arc<AtomicU64>,
+    auth: Oauth1aToken,
+    http: reqwest::Client,
+    api_url: String,
+    queue: PostQueue,
+    queue_config: QueueConfig,
+    fallback: FallbackConfig,
+    // Until when the v2 endpoint is skipped for the fallback ones, after it kept failing
+    v2_down_until: Mutex<Option<DateTime<Utc>>>,
+    retry_budget: Arc<RetryBudget>,
+    edit: EditConfig,
+    // ID of the account the bot posts as, looked up on the first follow
+    user_id: OnceCell<String>,
+    // Prints the posts instead of making them
+    dry_run: bool,
+    dry_run_posts: AtomicU64,
+    // Prints the posts on the console instead, as the console announcer does
+    console: Option<Arc<ConsoleAnnouncer>>,
 }
 
 impl XClient {
@@ -25,6 +169,9 @@ impl XClient {
     /// * `api_secret` - The API secret (Consumer Secret)
     /// * `access_token` - The access token
     /// * `access_secret` - The access token secret
+    /// * `queue_config` - The priorities of the posts waiting on the rate limit
+    /// * `fallback` - The endpoints tried when the v2 tweets endpoint keeps failing
+    /// * `http_config` - The API endpoint, timeouts and extra root certificates to use
     ///
     /// # Returns
     /// A result containing the initialized `XClient` or an error if initialization fails.
@@ -33,6 +180,9 @@ impl XClient {
         api_secret: String,
         access_token: String,
         access_secret: String,
+        queue_config: QueueConfig,
+        fallback: FallbackConfig,
+        http_config: &HttpConfig,
     ) -> Result<Self> {
         let auth = Oauth1aToken::new(
             api_key,
@@ -40,25 +190,158 @@ impl XClient {
             access_token,
             access_secret,
         );
-        let client = TwitterApi::new(auth);
+
+        // reqwest has no read or write timeouts, the total one bounds them instead
+        let mut http = reqwest::Client::builder()
+            .connect_timeout(http_config.timeout.x.connect())
+            .timeout(http_config.timeout.x.total());
+        for certificate in tls::extra_root_certificates(http_config.ca_bundle.as_deref())? {
+            http = http.add_root_certificate(Certificate::from_der(&certificate)?);
+        }
         
         info!("X Api Client initialized");
         
         Ok(Self { 
-            client,
-            tweet_count: Arc::new(AtomicU64::new(0)),
-            window_start: Arc::new(AtomicU64::new(Utc::now().timestamp() as u64)),
+            auth,
+            http: http.build()?,
+            api_url: http_config.x_api_url.clone(),
+            queue: PostQueue::new(queue_config.aging_per_minute),
+            queue_config,
+            fallback,
+            v2_down_until: Mutex::new(None),
+            retry_budget: Arc::new(RetryBudget::new(0)),
+            edit: EditConfig {
+                enabled: false,
+                window_minutes: 0,
+            },
+            user_id: OnceCell::new(),
+            dry_run: false,
+            dry_run_posts: AtomicU64::new(0),
+            console: None,
         })
     }
 
-    /// Posts a tweet with retry mechanism and rate limiting
-    pub async fn post_with_retry(&self, text: &str) -> Result<String> {
+    /// Spends the shared retry budget on the retries of failed posts, which aren't limited otherwise.
+    pub fn with_retry_budget(mut self, retry_budget: Arc<RetryBudget>) -> Self {
+        self.retry_budget = retry_budget;
+        self
+    }
+
+    /// Edits posts in place on accounts with edit access, rather than deleting and reposting them.
+    pub fn with_edit_access(mut self, edit: EditConfig) -> Self {
+        self.edit = edit;
+        self
+    }
+
+    /// Returns whether a post made at the given time can still be edited.
+    ///
+    /// # Arguments
+    /// * `posted_at` - When the post was made.
+    pub fn can_edit(&self, posted_at: DateTime<Utc>) -> bool {
+        self.edit.enabled && (Utc::now() - posted_at).num_minutes() < self.edit.window_minutes as i64
+    }
+
+    /// Prints every post, reply, upload and deletion to stdout instead of making it.
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Prints every post, reply, upload and deletion with the console announcer instead of
+    /// making it, so the posts made outside of the outbox are printed alongside the others.
+    ///
+    /// # Arguments
+    /// * `console` - The console announcer the outbox posts with.
+    pub fn console(mut self, console: Arc<ConsoleAnnouncer>) -> Self {
+        self.dry_run = true;
+        self.console = Some(console);
+        self
+    }
+
+    /// Returns true if the posts are printed instead of made.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Prints what would be done in dry-run mode and returns a placeholder ID
+    fn print_dry_run(&self, action: &str, text: &str) -> String {
+        if let Some(console) = &self.console {
+            return console.print(action, text);
+        }
+
+        let id = format!("dry-run-{}", self.dry_run_posts.fetch_add(1, Ordering::Relaxed) + 1);
+        println!("[x] {} ({}):\n{}\n", action, id, text);
+        id
+    }
+
+    /// Restores the rate limit window from the posts recorded in the audit log, so a restart
+    /// during a rate limit pause doesn't exceed the limit again.
+    ///
+    /// # Arguments
+    /// * `audit_log` - The audit log recording the posts made before the restart.
+    pub async fn restore_rate_limit(&self, audit_log: &AuditLog) {
+        let now = Utc::now();
+        let window = chrono::Duration::from_std(RATE_LIMIT_WINDOW).unwrap_or_default();
+        let post_ages: Vec<Duration> = audit_log
+            .post_times_since("x", now - window)
+            .await
+            .into_iter()
+            .filter_map(|posted_at| (now - posted_at).to_std().ok())
+            .collect();
+
+        if !post_ages.is_empty() {
+            info!("{} posts made in the current rate limit window before the restart", post_ages.len());
+        }
+        self.queue.restore(&post_ages);
+    }
+
+    /// Returns until when posts skip the v2 endpoint for the fallback ones, if they do.
+    pub fn v2_down_until(&self) -> Option<DateTime<Utc>> {
+        *self.v2_down_until.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Restores until when posts skip the v2 endpoint, as it was before a restart, so a
+    /// restart during an outage of v2 doesn't go through its retries again.
+    ///
+    /// # Arguments
+    /// * `until` - Until when the v2 endpoint was skipped when the bot stopped.
+    pub fn restore_v2_down_until(&self, until: DateTime<Utc>) {
+        if until > Utc::now() {
+            info!("The v2 tweets endpoint was failing before the restart, posting through the fallback endpoints until {}", until);
+            *self.v2_down_until.lock().unwrap_or_else(|e| e.into_inner()) = Some(until);
+        }
+    }
+
+    /// Posts a tweet with replies or media with retry mechanism and rate limiting.
+    ///
+    /// Once every retry against the v2 tweets endpoint failed, the tweet goes through
+    /// the configured fallback endpoints in order before giving up. Once one of them posted
+    /// it, the following posts go through them first for a while, without waiting on v2.
+    ///
+    /// # Arguments
+    /// * `text` - A string slice containing the text of the tweet.
+    /// * `options` - The optional parts of the tweet.
+    ///
+    /// # Returns
+    /// A result containing the tweet ID as a string if successful, or an error if the posting fails.
+    pub async fn post_with_options(&self, text: &str, options: &TweetOptions) -> Result<String> {
         info!("Attempting to post tweet: {}", text);
-        
+
+        // The v1.1 endpoints can't edit posts
+        let fallback = if options.edit_of.is_some() { &[][..] } else { &self.fallback.chain[..] };
+        let v2_down = !fallback.is_empty() && self.v2_down_until().is_some_and(|until| until > Utc::now());
+        if v2_down {
+            debug!("The v2 tweets endpoint is failing, trying the fallback endpoints first");
+            if let Some(id) = self.post_through_fallback(text, options, fallback).await {
+                return Ok(id);
+            }
+        }
+
         for attempt in 1..=MAX_RETRIES {
-            match self.send_tweet(text).await {
+            match self.send(text, options).await {
                 Ok(id) => {
                     info!("Successfully posted tweet with ID: {}", id);
+                    *self.v2_down_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
                     return Ok(id);
                 }
                 Err(e) => {
@@ -66,55 +349,342 @@ impl XClient {
                     if attempt < MAX_RETRIES {
                         warn!("Retrying in {} seconds...", attempt * 2);
                         sleep(Duration::from_secs(attempt as u64 * 2)).await;
+                        self.retry_budget.acquire("the post to X").await;
                     }
                 }
             }
         }
-        
+
+        if !v2_down {
+            if let Some(id) = self.post_through_fallback(text, options, fallback).await {
+                *self.v2_down_until.lock().unwrap_or_else(|e| e.into_inner()) = Some(Utc::now() + V2_COOLDOWN);
+                return Ok(id);
+            }
+        }
+
+        // Alert the operator, the announcement stays failed in the audit log until it's retried
+        error!(
+            alert = true,
+            "X API unavailable: tweet dropped after {} attempts and {} fallback endpoints: {}",
+            MAX_RETRIES, fallback.len(), text
+        );
         Err(anyhow!("Failed to post tweet after {} attempts", MAX_RETRIES))
     }
 
+    /// Posts a tweet through the fallback endpoints, in order until one of them posts it.
+    ///
+    /// # Returns
+    /// The tweet ID, or `None` if every endpoint failed.
+    async fn post_through_fallback(&self, text: &str, options: &TweetOptions, fallback: &[FallbackEndpoint]) -> Option<String> {
+        for endpoint in fallback {
+            self.retry_budget.acquire("the post to X").await;
+            warn!("Falling back to the {:?} endpoint to post tweet", endpoint);
+            let result = match endpoint {
+                FallbackEndpoint::V1 => self.send_v1(text, options).await,
+            };
+            match result {
+                Ok(id) => {
+                    info!("Successfully posted tweet with ID {} through the {:?} endpoint", id, endpoint);
+                    return Some(id);
+                }
+                Err(e) => error!("Failed to post tweet through the {:?} endpoint: {:?}", endpoint, e),
+            }
+        }
+        None
+    }
+
     /// Posts a tweet with the specified text to Twitter.
     ///
     /// # Arguments
+    /// * `kind` - The kind of announcement the tweet belongs to.
     /// * `text` - A string slice containing the text of the tweet.
     ///
     /// # Returns
     /// A result containing the tweet ID as a string if successful, or an error if the posting fails.
-    pub async fn send_tweet(&self, text: &str) -> Result<String> {
-        debug!("Checking rate limits before sending tweet");
-        
-        // Rate limiting check
-        let now = Utc::now().timestamp() as u64;
-        let window_start = self.window_start.load(Ordering::Relaxed);
-        let tweet_count = self.tweet_count.load(Ordering::Relaxed);
-        
-        if now - window_start >= RATE_LIMIT_WINDOW {
-            debug!("Rate limit window expired, resetting counts");
-            self.window_start.store(now, Ordering::Relaxed);
-            self.tweet_count.store(0, Ordering::Relaxed);
-        } else if tweet_count >= TWEETS_PER_WINDOW {
-            let wait_time = RATE_LIMIT_WINDOW - (now - window_start);
-            warn!("Rate limit reached. Waiting {} seconds", wait_time);
-            sleep(Duration::from_secs(wait_time)).await;
-            self.window_start.store(now, Ordering::Relaxed);
-            self.tweet_count.store(0, Ordering::Relaxed);
+    pub async fn send_tweet(&self, kind: AnnouncementKind, text: &str) -> Result<String> {
+        self.send(text, &TweetOptions::kind(kind)).await
+    }
+
+    /// Edits a tweet in place with retry mechanism and rate limiting.
+    ///
+    /// X keeps the edit history and links the earlier versions to the edited tweet,
+    /// which gets a new ID.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of announcement the tweet belongs to.
+    /// * `tweet_id` - The ID of the tweet to edit.
+    /// * `text` - A string slice containing the new text of the tweet.
+    ///
+    /// # Returns
+    /// A result containing the ID of the edited tweet as a string if successful, or an error if the edit fails.
+    pub async fn edit_tweet(&self, kind: AnnouncementKind, tweet_id: &str, text: &str) -> Result<String> {
+        let options = TweetOptions {
+            edit_of: Some(tweet_id.to_owned()),
+            ..TweetOptions::kind(kind)
+        };
+        self.post_with_options(text, &options).await
+    }
+
+    /// Uploads an image to attach to a tweet.
+    ///
+    /// Media uploads are only available on the v1.1 API, which accepts the same
+    /// OAuth 1.0a credentials as the v2 API used for tweets.
+    ///
+    /// # Arguments
+    /// * `media` - The raw bytes of the image.
+    ///
+    /// # Returns
+    /// A result containing the media ID to pass in [TweetOptions::media_ids], or an error if the upload fails.
+    pub async fn upload_media(&self, media: &[u8]) -> Result<String> {
+        if self.dry_run {
+            return Ok(self.print_dry_run("upload", &format!("{} bytes of media", media.len())));
         }
-        
-        debug!("Sending tweet to X API");
-        match self.client.post_tweet().text(text.to_owned()).send().await {
-            Ok(response) => {
-                info!("Tweet posted successfully");
-                self.tweet_count.fetch_add(1, Ordering::Relaxed);
-                match &response.data {
-                    Some(tweet) => Ok(tweet.id.to_string()),
-                    None => Err(anyhow!("No tweet data in response"))
-                }
-            }
-            Err(e) => {
-                error!("Error from X API: {:?}", e);
-                Err(anyhow!("Failed to post tweet: {:?}", e))
+
+        // Built by hand since the body must stay out of the OAuth signature,
+        // which only multipart bodies allow
+        let boundary = format!("x-bot-{}", Utc::now().timestamp_nanos_opt().unwrap_or_default());
+        let mut body = Vec::with_capacity(media.len() + 256);
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"media\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+            boundary
+        ).as_bytes());
+        body.extend_from_slice(media);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        let request = self.http
+            .post(MEDIA_UPLOAD_URL)
+            .header(CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+            .body(body);
+
+        debug!("Uploading {} bytes of media to X API", media.len());
+        let response = self.execute(request).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            error!("Error from X media API: {} {}", status, message);
+            return Err(anyhow!("Failed to upload media: {} {}", status, message));
+        }
+
+        let uploaded: MediaUploadResponse = response.json().await?;
+        info!("Uploaded media with ID: {}", uploaded.media_id_string);
+        Ok(uploaded.media_id_string)
+    }
+
+    /// Deletes a previously posted tweet.
+    ///
+    /// # Arguments
+    /// * `tweet_id` - The ID of the tweet to delete.
+    ///
+    /// # Returns
+    /// A result indicating success or failure.
+    pub async fn delete_tweet(&self, tweet_id: &str) -> Result<()> {
+        if self.dry_run {
+            self.print_dry_run("delete", tweet_id);
+            return Ok(());
+        }
+        let id = parse_id(tweet_id)?;
+
+        debug!("Deleting tweet {}", tweet_id);
+        let request = self.http.request(Method::DELETE, format!("{}/tweets/{}", self.api_url, id));
+        let response = self.execute(request).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            error!("Error from X API: {} {}", status, message);
+            return Err(anyhow!("Failed to delete tweet {}: {} {}", tweet_id, status, message));
+        }
+
+        info!("Tweet {} deleted successfully", tweet_id);
+        Ok(())
+    }
+
+    /// Follows an account from the bot's account.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle of the account to follow, without the `@`.
+    ///
+    /// # Returns
+    /// A result indicating success, or an error if the account doesn't exist or can't be followed.
+    pub async fn follow(&self, handle: &str) -> Result<()> {
+        if self.dry_run {
+            self.print_dry_run("follow", &format!("@{}", handle));
+            return Ok(());
+        }
+
+        let user_id = self.user_id.get_or_try_init(|| self.lookup_user("/users/me")).await?;
+        let target_id = self.lookup_user(&format!("/users/by/username/{}", handle)).await?;
+
+        debug!("Following @{} ({})", handle, target_id);
+        let request = self.http
+            .post(format!("{}/users/{}/following", self.api_url, user_id))
+            .json(&serde_json::json!({ "target_user_id": target_id }));
+        let response = self.execute(request).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            error!("Error from X API: {} {}", status, message);
+            return Err(anyhow!("Failed to follow @{}: {} {}", handle, status, message));
+        }
+
+        info!("Followed @{}", handle);
+        Ok(())
+    }
+
+    /// Looks up the ID of the user at a users endpoint, such as `/users/me`.
+    async fn lookup_user(&self, path: &str) -> Result<String> {
+        let response = self.execute(self.http.get(format!("{}{}", self.api_url, path))).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            error!("Error from X API: {} {}", status, message);
+            return Err(anyhow!("Failed to look up user {}: {} {}", path, status, message));
+        }
+
+        let user: UserResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("No user data in response: {}", e))?;
+        Ok(user.data.id)
+    }
+
+    /// Looks up the engagement of tweets.
+    ///
+    /// # Arguments
+    /// * `tweet_ids` - The IDs of the tweets.
+    ///
+    /// # Returns
+    /// A result containing the engagement of every tweet still available, keyed by ID,
+    /// or an error if the lookup fails.
+    pub async fn engagement(&self, tweet_ids: &[String]) -> Result<HashMap<String, Engagement>> {
+        let mut engagement = HashMap::new();
+        let ids: Vec<&String> = tweet_ids.iter().filter(|id| parse_id(id).is_ok()).collect();
+
+        // The lookup takes at most 100 tweets at a time
+        for ids in ids.chunks(TWEET_LOOKUP_LIMIT) {
+            let ids = ids.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(",");
+            let request = self.http
+                .get(format!("{}/tweets", self.api_url))
+                .query(&[("ids", ids.as_str()), ("tweet.fields", "public_metrics")]);
+
+            debug!("Looking up the engagement of tweets {}", ids);
+            let response = self.execute(request).await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let message = response.text().await.unwrap_or_default();
+                error!("Error from X API: {} {}", status, message);
+                return Err(anyhow!("Failed to look up tweets: {} {}", status, message));
             }
+
+            let found: TweetLookupResponse = response.json().await?;
+            engagement.extend(found.data.into_iter().map(|tweet| (tweet.id, tweet.public_metrics)));
+        }
+        Ok(engagement)
+    }
+
+    async fn send(&self, text: &str, options: &TweetOptions) -> Result<String> {
+        if self.dry_run {
+            return Ok(self.print_dry_run(&options.action(), text));
+        }
+
+        debug!("Checking rate limits before sending tweet");
+        let priority = options.kind.map_or(0, |kind| self.queue_config.priority(kind));
+        self.queue.acquire(priority).await;
+
+        debug!("Sending tweet to X API");
+        let mut tweet = serde_json::json!({ "text": text });
+        if let Some(tweet_id) = &options.in_reply_to {
+            tweet["reply"] = serde_json::json!({ "in_reply_to_tweet_id": parse_id(tweet_id)?.to_string() });
+        }
+        if let Some(tweet_id) = &options.edit_of {
+            tweet["edit_options"] = serde_json::json!({ "previous_post_id": parse_id(tweet_id)?.to_string() });
         }
+        if !options.media_ids.is_empty() {
+            let media_ids = options.media_ids
+                .iter()
+                .map(|id| parse_id(id).map(|id| id.to_string()))
+                .collect::<Result<Vec<_>>>()?;
+            tweet["media"] = serde_json::json!({ "media_ids": media_ids });
+        }
+
+        let request = self.http
+            .post(format!("{}/tweets", self.api_url))
+            .json(&tweet);
+        let response = self.execute(request).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            error!("Error from X API: {} {}", status, message);
+            return Err(anyhow!("Failed to post tweet: {} {}", status, message));
+        }
+
+        let posted: TweetResponse = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("No tweet data in response: {}", e))?;
+        info!("Tweet posted successfully");
+        Ok(posted.data.id)
+    }
+
+    /// Posts a tweet through the v1.1 `statuses/update` endpoint.
+    async fn send_v1(&self, text: &str, options: &TweetOptions) -> Result<String> {
+        let priority = options.kind.map_or(0, |kind| self.queue_config.priority(kind));
+        self.queue.acquire(priority).await;
+
+        // Parameters go in the query string so the OAuth signature covers them
+        let mut params = vec![("status", text.to_owned())];
+        if let Some(tweet_id) = &options.in_reply_to {
+            params.push(("in_reply_to_status_id", tweet_id.clone()));
+            params.push(("auto_populate_reply_metadata", "true".to_owned()));
+        }
+        if !options.media_ids.is_empty() {
+            params.push(("media_ids", options.media_ids.join(",")));
+        }
+
+        let request = self.http
+            .post(format!("{}/statuses/update.json", self.fallback.v1_base_url))
+            .query(&params);
+
+        debug!("Sending tweet to X v1.1 API");
+        let response = self.execute(request).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            error!("Error from X v1.1 API: {} {}", status, message);
+            return Err(anyhow!("Failed to post tweet: {} {}", status, message));
+        }
+
+        let posted: StatusUpdateResponse = response.json().await?;
+        Ok(posted.id_str)
+    }
+
+    /// Signs a request with the OAuth 1.0a credentials and sends it.
+    async fn execute(&self, request: reqwest::RequestBuilder) -> Result<Response> {
+        let mut request = request.build()?;
+        let authorization = self.auth.header(&request).await?;
+        request.headers_mut().insert(AUTHORIZATION, authorization);
+        Ok(self.http.execute(request).await?)
     }
 }
+
+#[async_trait]
+impl Announcer for XClient {
+    fn name(&self) -> &str {
+        "x"
+    }
+
+    fn accepts(&self, announcement: &Announcement) -> bool {
+        announcement.awaits("x")
+    }
+
+    async fn announce(&self, announcement: &Announcement) -> Result<Option<String>> {
+        let options = TweetOptions::of(announcement)?;
+        self.post_with_options(announcement.text_for("x"), &options).await.map(Some)
+    }
+}
+
+/// Parses a tweet or media ID, which the X API represents as a string of digits
+fn parse_id(id: &str) -> Result<u64> {
+    id
+        .parse()
+        .map_err(|_| anyhow!("Invalid X API ID: {}", id))
+}
\ No newline at end of file
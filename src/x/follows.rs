@@ -0,0 +1,124 @@
+use super::client::XClient;
+use crate::config::env::ContributorConfig;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+
+/// Accounts followed by the bot, as persisted between runs
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FollowState {
+    /// When each account was followed, keyed by lowercase handle
+    followed: Vec<(String, DateTime<Utc>)>,
+}
+
+/// Follows new contributors on X, at most a given number a day so the account isn't flagged as spam.
+///
+/// The follows are persisted, so the cap holds across restarts and nobody is followed twice.
+pub struct Follows {
+    path: PathBuf,
+    daily_cap: u32,
+    state: Mutex<Option<FollowState>>,
+}
+
+impl Follows {
+    /// Creates a new tracker of follows.
+    ///
+    /// # Arguments
+    /// * `path` - The file the follows are persisted in.
+    /// * `daily_cap` - The accounts followed per day at most.
+    pub fn new(path: PathBuf, daily_cap: u32) -> Self {
+        Self {
+            path,
+            daily_cap,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Creates the tracker of the follows of new contributors, if they are followed on X.
+    pub fn for_contributors(config: &ContributorConfig) -> Option<Self> {
+        config.follow_on_x.then(|| Self::new(
+            std::path::Path::new(&config.state_dir).join("x_follows.json"),
+            config.follow_daily_cap,
+        ))
+    }
+
+    /// Follows an account, unless it was already followed or the daily cap is reached.
+    ///
+    /// # Arguments
+    /// * `x_client` - The client following the account.
+    /// * `handle` - The handle of the account, without the `@`.
+    ///
+    /// # Returns
+    /// A result containing `true` if the account was followed, `false` if it was skipped,
+    /// or an error if following it failed.
+    pub async fn follow(&self, x_client: &XClient, handle: &str) -> Result<bool> {
+        let key = handle.to_ascii_lowercase();
+        let mut state = self.state.lock().await;
+        if state.is_none() {
+            *state = Some(self.load().await);
+        }
+        let state = state.as_mut().expect("follows are loaded");
+
+        if state.followed.iter().any(|(followed, _)| *followed == key) {
+            debug!("Already following @{}", handle);
+            return Ok(false);
+        }
+        let since = Utc::now() - Duration::days(1);
+        let today = state.followed.iter().filter(|(_, followed_at)| *followed_at >= since).count();
+        if today >= self.daily_cap as usize {
+            info!("Not following @{}: {} accounts were followed in the last day", handle, today);
+            return Ok(false);
+        }
+
+        x_client.follow(handle).await?;
+        state.followed.push((key, Utc::now()));
+        if x_client.is_dry_run() {
+            return Ok(true);
+        }
+        if let Err(e) = self.save(state).await {
+            warn!("Failed to persist the followed accounts: {:?}", e);
+        }
+        Ok(true)
+    }
+
+    /// Loads the persisted follows, if any.
+    async fn load(&self) -> FollowState {
+        let contents = match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return FollowState::default(),
+            Err(e) => {
+                warn!("Failed to read {}, starting without followed accounts: {}", self.path.display(), e);
+                return FollowState::default();
+            }
+        };
+
+        serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Ignoring malformed {}, starting without followed accounts: {}", self.path.display(), e);
+            FollowState::default()
+        })
+    }
+
+    /// Persists the follows, replacing the previous file atomically.
+    async fn save(&self, state: &FollowState) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let temporary = self.path.with_extension("tmp");
+        tokio::fs::write(&temporary, serde_json::to_string(state)?)
+            .await
+            .with_context(|| format!("Failed to write {}", temporary.display()))?;
+        tokio::fs::rename(&temporary, &self.path)
+            .await
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// Returns true if a handle is a valid X handle: 1 to 15 letters, digits or underscores.
+pub fn is_valid_handle(handle: &str) -> bool {
+    (1..=15).contains(&handle.len()) && handle.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
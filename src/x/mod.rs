@@ -1 +1,4 @@
-pub mod client;
\ No newline at end of file
+pub mod client;
+pub mod follows;
+pub mod outbox;
+pub mod queue;
\ No newline at end of file
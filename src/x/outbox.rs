@@ -0,0 +1,269 @@
+use super::{client::XClient, follows::Follows};
+use crate::{
+    announce::dispatcher::Dispatcher,
+    audit::log::{AuditEntry, AuditLog, AuditStatus, ReplyTo},
+    config::env::PollBackoffConfig,
+    linkedin::client::LinkedInClient,
+    scheduler::poll::backoff};
+use std::{collections::HashSet, sync::{Arc, Mutex}};
+use tokio::{
+    sync::broadcast::error::RecvError,
+    time::{sleep, Duration}};
+use chrono::Utc;
+use anyhow::{anyhow, Result};
+use tracing::{debug, error, info, warn};
+
+/// Failed attempts at posting an announcement before giving up on it
+const MAX_FAILURES: u32 = 5;
+/// How often a reply looks for the post it replies to while that one is still queued
+const REPLY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Posts the announcements queued in the audit log to X, or to whichever targets stand in for
+/// it such as the console, see [Claim::enqueue](crate::audit::log::Claim::enqueue).
+///
+/// Announcements are written to the audit log before anything is posted, so one that is
+/// queued when the bot stops is posted once it's back rather than lost. The outcome is
+/// recorded after posting, which makes delivery at least once: a stop between posting and
+/// recording the outcome posts the announcement again.
+///
+/// An announcement that fails stays queued and is tried again with a growing delay, up to
+/// a few times. It is only tried again on the targets it failed on, and the time of the next
+/// attempt is queued with it, so a restart doesn't retry everything at once. One that none of
+/// the targets posts, such as a release routed to LinkedIn alone without a LinkedIn client, is
+/// recorded as skipped, so the announcements replying to it don't wait for it.
+pub struct Outbox {
+    targets: Dispatcher,
+    audit_log: Arc<AuditLog>,
+    backoff: PollBackoffConfig,
+    // Follows the accounts of the announced contributors once their announcement is posted
+    follows: Option<(Arc<XClient>, Follows)>,
+    // Idempotency keys of the announcements being posted right now
+    delivering: Mutex<HashSet<String>>,
+}
+
+impl Outbox {
+    /// Creates a new instance of `Outbox`.
+    ///
+    /// # Arguments
+    /// * `targets` - The targets the announcements are posted on, such as the X client.
+    /// * `audit_log` - The audit log the announcements are queued in.
+    /// * `backoff` - The delays between the attempts at posting an announcement that fails.
+    pub fn new(targets: Dispatcher, audit_log: Arc<AuditLog>, backoff: PollBackoffConfig) -> Self {
+        Self {
+            targets,
+            audit_log,
+            backoff,
+            follows: None,
+            delivering: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Creates an outbox posting the announcements on X only.
+    ///
+    /// # Arguments
+    /// * `x_client` - The client the announcements are posted with, which retries on its own.
+    /// * `audit_log` - The audit log the announcements are queued in.
+    /// * `backoff` - The delays between the attempts at posting an announcement that fails.
+    pub fn to_x(x_client: Arc<XClient>, audit_log: Arc<AuditLog>, backoff: PollBackoffConfig) -> Self {
+        let mut targets = Dispatcher::new().with_max_attempts(1);
+        targets.add_shared(x_client);
+        Self::new(targets, audit_log, backoff)
+    }
+
+    /// Posts the announcements routed to LinkedIn, such as releases, on the organization page
+    /// as well, see [PostPlan::sinks](crate::audit::log::PostPlan::sinks).
+    pub fn with_linkedin(mut self, client: Option<Arc<LinkedInClient>>) -> Self {
+        if let Some(client) = client {
+            self.targets.add_shared(client);
+        }
+        self
+    }
+
+    /// Follows the accounts the announcements ask for once they are posted, see
+    /// [PostPlan::follow](crate::audit::log::PostPlan::follow).
+    ///
+    /// # Arguments
+    /// * `x_client` - The client following the accounts.
+    /// * `follows` - The accounts followed so far with the daily cap, if accounts are followed.
+    pub fn with_follows(mut self, x_client: Arc<XClient>, follows: Option<Follows>) -> Self {
+        self.follows = follows.map(|follows| (x_client, follows));
+        self
+    }
+
+    /// Posts the announcements as they are queued, forever.
+    ///
+    /// The announcements left queued before a restart are posted first. Each announcement
+    /// is posted in a task of its own, so the post queue still admits them by priority.
+    pub async fn run(self: Arc<Self>) {
+        // Subscribed before looking for queued announcements so none is missed in between
+        let mut recorded = self.audit_log.subscribe();
+        self.resume().await;
+
+        loop {
+            match recorded.recv().await {
+                Ok(entry) if entry.status == AuditStatus::Queued => {
+                    let outbox = Arc::clone(&self);
+                    tokio::spawn(async move { outbox.deliver(entry).await });
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Outbox fell behind by {} entries, looking for queued announcements", missed);
+                    self.resume().await;
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Posts every queued announcement and waits for the outcomes.
+    ///
+    /// Used by the commands that exit once they have announced, where no outbox runs.
+    pub async fn drain(&self) {
+        for entry in self.audit_log.queued().await {
+            self.deliver(entry).await;
+        }
+    }
+
+    /// Posts the queued announcements in the background.
+    async fn resume(self: &Arc<Self>) {
+        let queued = self.audit_log.queued().await;
+        if !queued.is_empty() {
+            info!("Posting {} queued announcements", queued.len());
+        }
+        for entry in queued {
+            let outbox = Arc::clone(self);
+            tokio::spawn(async move { outbox.deliver(entry).await });
+        }
+    }
+
+    /// Posts a queued announcement and records its outcome, unless it is already being posted.
+    async fn deliver(&self, queued: AuditEntry) {
+        let key = queued.idempotency_key.clone();
+        if !self.lock().insert(key.clone()) {
+            return;
+        }
+
+        // The outcome may have been recorded since the entry was looked up, and the latest
+        // entry has the failures of the previous attempts
+        match self.audit_log.find_by_key(&key).await {
+            Some(entry) if entry.status == AuditStatus::Queued => self.post(entry).await,
+            _ => debug!("Queued announcement {} was already handled", key),
+        }
+        // Released after the outcome is recorded, so the announcement is never looked up as queued again
+        self.lock().remove(&key);
+    }
+
+    /// Posts an announcement until it is made on every target or runs out of attempts.
+    async fn post(&self, mut entry: AuditEntry) {
+        let key = entry.idempotency_key.clone();
+        loop {
+            if let Some(delay) = entry.plan.retry_at.and_then(|retry_at| (retry_at - Utc::now()).to_std().ok()) {
+                debug!("Posting {} announcement {} again in {:?}", entry.kind.as_str(), key, delay);
+                sleep(delay).await;
+            }
+            match self.resolve_reply(&mut entry).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    sleep(REPLY_POLL_INTERVAL).await;
+                    continue;
+                }
+                Err(e) => {
+                    error!("Not posting {} announcement {}: {:?}", entry.kind.as_str(), key, e);
+                    entry.status = AuditStatus::Failed;
+                    return self.record(entry).await;
+                }
+            }
+
+            entry.timestamp = Utc::now();
+            let report = self.targets.dispatch(entry.clone()).await;
+            if report.deliveries.is_empty() && entry.remote_ids.is_empty() {
+                // Left queued, it would be looked up again on every restart and hold its replies
+                warn!("Skipping {} announcement {}: none of the targets posts it", entry.kind.as_str(), key);
+                entry.status = AuditStatus::Skipped;
+                entry.plan = Default::default();
+                return self.record(entry).await;
+            }
+            for delivery in &report.deliveries {
+                if let Ok(Some(post_id)) = &delivery.result {
+                    entry = entry.with_remote_id(&delivery.sink, post_id);
+                }
+            }
+
+            if report.failed().next().is_none() {
+                info!("Posted {} announcement {}", entry.kind.as_str(), key);
+                let plan = std::mem::take(&mut entry.plan);
+                entry.status = plan.posted_as.unwrap_or(AuditStatus::Posted);
+                self.record(entry).await;
+                if let Some(handle) = &plan.follow {
+                    self.follow(handle).await;
+                }
+                return;
+            }
+
+            for delivery in report.failed() {
+                if let Err(e) = &delivery.result {
+                    error!("Failed to post {} announcement {} on {}: {:?}", entry.kind.as_str(), key, delivery.target, e);
+                }
+            }
+            entry.plan.failures += 1;
+            if entry.plan.failures >= MAX_FAILURES {
+                error!("Giving up on {} announcement {} after {} attempts", entry.kind.as_str(), key, entry.plan.failures);
+                entry.status = AuditStatus::Failed;
+                return self.record(entry).await;
+            }
+            // Queued again with the time of the next attempt, which a restart waits for as well
+            let delay = backoff(&self.backoff, entry.plan.failures);
+            entry.plan.retry_at = Some(Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default());
+            self.record(entry.clone()).await;
+        }
+    }
+
+    /// Points a reply at the post of the announcement it replies to, once that one is made.
+    ///
+    /// # Returns
+    /// A result containing `false` while the announcement replied to is still queued, or an
+    /// error if it won't be posted on X.
+    async fn resolve_reply(&self, entry: &mut AuditEntry) -> Result<bool> {
+        let Some(ReplyTo::Announcement(parent_key)) = &entry.plan.reply_to else {
+            return Ok(true);
+        };
+        let Some(parent) = self.audit_log.find_by_key(parent_key).await else {
+            return Err(anyhow!("Announcement {} it replies to was never queued", parent_key));
+        };
+        if parent.status == AuditStatus::Queued {
+            return Ok(false);
+        }
+
+        let posted = matches!(
+            parent.status,
+            AuditStatus::Posted | AuditStatus::Corrected | AuditStatus::FollowUp | AuditStatus::Edited
+        );
+        match parent.remote_ids.get("x").filter(|_| posted) {
+            Some(tweet_id) => {
+                entry.plan.reply_to = Some(ReplyTo::Post(tweet_id.clone()));
+                Ok(true)
+            }
+            None => Err(anyhow!("Announcement {} it replies to is {} without a post on X", parent_key, parent.status.as_str())),
+        }
+    }
+
+    /// Follows an account once the announcement asking for it is posted
+    async fn follow(&self, handle: &str) {
+        let Some((x_client, follows)) = &self.follows else {
+            return;
+        };
+        if let Err(e) = follows.follow(x_client, handle).await {
+            warn!("Failed to follow @{}: {:?}", handle, e);
+        }
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.audit_log.record(entry).await {
+            error!("Failed to write audit log entry: {:?}", e);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.delivering.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
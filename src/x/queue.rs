@@ -0,0 +1,170 @@
+use std::sync::Mutex;
+use tokio::{
+    sync::Notify,
+    time::{sleep, Duration, Instant}};
+use tracing::{debug, warn};
+
+pub const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(15 * 60);
+const TWEETS_PER_WINDOW: u64 = 50; // X API allows 50 tweets per 15 minutes
+
+/// Admits posts to the X API within its rate limit, highest priority first.
+///
+/// While the rate limit leaves room, posts go out as soon as they arrive. Once it is
+/// reached, the waiting posts are admitted by priority instead of in arrival order when
+/// the window resets. The priority of a waiting post grows as it ages, so low priority
+/// posts are delayed but never starved.
+pub struct PostQueue {
+    state: Mutex<QueueState>,
+    admitted: Notify,
+    aging_per_minute: u32,
+}
+
+struct QueueState {
+    next_ticket: u64,
+    waiting: Vec<Waiter>,
+    window_start: Instant,
+    posted: u64,
+}
+
+struct Waiter {
+    ticket: u64,
+    priority: u32,
+    enqueued: Instant,
+}
+
+impl Waiter {
+    /// Priority of the waiter once aging is accounted for
+    fn effective_priority(&self, aging_per_minute: u32, now: Instant) -> u64 {
+        let minutes = now.duration_since(self.enqueued).as_secs() / 60;
+        u64::from(self.priority).saturating_add(minutes.saturating_mul(u64::from(aging_per_minute)))
+    }
+}
+
+/// Removes a waiter from the queue if it stops waiting before being admitted.
+struct Ticket<'a> {
+    queue: &'a PostQueue,
+    id: u64,
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.waiting.retain(|waiter| waiter.ticket != self.id);
+        drop(state);
+        self.queue.admitted.notify_waiters();
+    }
+}
+
+impl PostQueue {
+    /// Creates a new instance of `PostQueue`.
+    ///
+    /// # Arguments
+    /// * `aging_per_minute` - How much the priority of a waiting post grows every minute.
+    pub fn new(aging_per_minute: u32) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                next_ticket: 0,
+                waiting: Vec::new(),
+                window_start: Instant::now(),
+                posted: 0,
+            }),
+            admitted: Notify::new(),
+            aging_per_minute,
+        }
+    }
+
+    /// Restores the rate limit window from posts made before a restart.
+    ///
+    /// # Arguments
+    /// * `post_ages` - How long ago each post of the current window was made.
+    pub fn restore(&self, post_ages: &[Duration]) {
+        let Some(oldest) = post_ages.iter().filter(|age| **age < RATE_LIMIT_WINDOW).max() else {
+            return;
+        };
+
+        let mut state = self.lock();
+        state.window_start = Instant::now().checked_sub(*oldest).unwrap_or_else(Instant::now);
+        state.posted = post_ages.iter().filter(|age| **age < RATE_LIMIT_WINDOW).count() as u64;
+        debug!(
+            "Restored rate limit window with {} posts, {} seconds left",
+            state.posted,
+            (RATE_LIMIT_WINDOW - *oldest).as_secs()
+        );
+    }
+
+    /// Waits until a post of the given priority may be sent.
+    ///
+    /// # Arguments
+    /// * `priority` - The base priority of the post, higher goes first.
+    pub async fn acquire(&self, priority: u32) {
+        let ticket = {
+            let mut state = self.lock();
+            let id = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiting.push(Waiter {
+                ticket: id,
+                priority,
+                enqueued: Instant::now(),
+            });
+            Ticket { queue: self, id }
+        };
+
+        loop {
+            // Registered before checking the queue so an admission in between isn't missed
+            let admitted = self.admitted.notified();
+            tokio::pin!(admitted);
+            admitted.as_mut().enable();
+
+            let window_end = {
+                let mut state = self.lock();
+                let now = Instant::now();
+                if now.duration_since(state.window_start) >= RATE_LIMIT_WINDOW {
+                    debug!("Rate limit window expired, resetting counts");
+                    state.window_start = now;
+                    state.posted = 0;
+                }
+
+                if state.posted < TWEETS_PER_WINDOW && self.head(&state, now) == Some(ticket.id) {
+                    state.posted += 1;
+                    state.waiting.retain(|waiter| waiter.ticket != ticket.id);
+                    break;
+                }
+                (state.posted >= TWEETS_PER_WINDOW).then(|| state.window_start + RATE_LIMIT_WINDOW)
+            };
+
+            match window_end {
+                Some(window_end) => {
+                    warn!(
+                        "Rate limit reached. Waiting {} seconds",
+                        window_end.saturating_duration_since(Instant::now()).as_secs()
+                    );
+                    tokio::select! {
+                        _ = sleep(window_end.saturating_duration_since(Instant::now())) => {}
+                        _ = admitted => {}
+                    }
+                }
+                None => admitted.await,
+            }
+        }
+
+        // Admitted, the ticket no longer needs cleaning up but the next waiter needs a turn
+        std::mem::forget(ticket);
+        self.admitted.notify_waiters();
+    }
+
+    /// Returns the waiter to admit next: the highest effective priority, oldest first.
+    fn head(&self, state: &QueueState, now: Instant) -> Option<u64> {
+        state.waiting
+            .iter()
+            .max_by(|a, b| {
+                a.effective_priority(self.aging_per_minute, now)
+                    .cmp(&b.effective_priority(self.aging_per_minute, now))
+                    .then(b.ticket.cmp(&a.ticket))
+            })
+            .map(|waiter| waiter.ticket)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}